@@ -0,0 +1,80 @@
+//! Checks that every shader embedded with `include_str!` exists before the crate compiles, so a
+//! moved or renamed shader fails the build with its name and an explanation rather than a bare
+//! `include_str!` error from deep inside `GraphicsEngine::new` or `GpuAudioAnalyzer::new`.
+
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=shaders");
+
+    let mut sources = Vec::new();
+    collect_sources(Path::new("src"), &mut sources);
+
+    let mut missing = Vec::new();
+    for source in &sources {
+        let Ok(text) = std::fs::read_to_string(source) else { continue };
+        for (line_index, line) in text.lines().enumerate() {
+            for shader in embedded_shaders(line) {
+                // include_str! paths are relative to the including file
+                let path = source.parent().unwrap_or(Path::new("")).join(shader);
+                if !path.is_file() {
+                    missing.push(format!("  {} (included as \"{}\" at {}:{})",
+                                         normalize(&path).display(), shader, source.display(), line_index + 1));
+                }
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        eprintln!("error: shader not found: {} shader file(s) embedded by the source are missing:", missing.len());
+        for entry in &missing {
+            eprintln!("{}", entry);
+        }
+        eprintln!("Shaders live under shaders/ (compute shaders under shaders/compute/); if one was moved \
+                   or renamed, update the include_str! path that embeds it.");
+        std::process::exit(1);
+    }
+}
+
+/// All `.rs` files under `dir`
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_sources(&path, sources);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            sources.push(path);
+        }
+    }
+}
+
+/// `path` with `..` components folded in, for the message (the file doesn't exist to canonicalize)
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The `.wgsl` paths of the `include_str!("...")` calls on a line
+fn embedded_shaders(line: &str) -> Vec<&str> {
+    let mut shaders = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("include_str!(\"") {
+        rest = &rest[start + "include_str!(\"".len()..];
+        let Some(end) = rest.find('"') else { break };
+        if rest[..end].ends_with(".wgsl") {
+            shaders.push(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    shaders
+}
@@ -0,0 +1,25 @@
+// Presents a finished frame from the history texture on the window surface
+
+@group(0) @binding(0)
+var frame: texture_2d<f32>;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+}
+
+@vertex
+fn vs_main(model: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(model.position, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Same size as the surface, so pixels map one to one (premultiplied, blended over the clear colour)
+    return textureLoad(frame, vec2<i32>(in.clip_position.xy), 0);
+}
@@ -28,7 +28,8 @@ struct Uniforms {
     onset_strength: f32,       // Note attacks
     dynamic_range: f32,        // Volume variation
 
-    // Effect weights for dynamic blending
+    // Effect weights for dynamic blending (each 0 to the manager's weight ceiling, and
+    // together never more than it, so the weighted sum below stays bounded)
     plasma_weight: f32,
     kaleidoscope_weight: f32,
     tunnel_weight: f32,
@@ -37,17 +38,96 @@ struct Uniforms {
     spectralizer_weight: f32,
     parametric_weight: f32,
 
+    // Per-effect intensity scalers from the psychedelic manager
+    plasma_intensity: f32,
+    kaleidoscope_intensity: f32,
+    tunnel_intensity: f32,
+    particle_intensity: f32,
+    fractal_intensity: f32,
+    spectralizer_intensity: f32,
+    parametric_intensity: f32,
+
     // 3D projection controls
     projection_mode: f32,  // 0=sphere, 1=cylinder, 2=torus, 3=flat, -1=auto
 
     // Visual controls
     palette_index: f32,    // Current color palette (0-5)
     smoothing_factor: f32, // Global smoothing sensitivity (0.1-2.0)
+
+    // Mouse interaction
+    mouse_x: f32,          // Cursor X, normalized 0-1
+    mouse_y: f32,          // Cursor Y, normalized 0-1 (top = 0)
+    mouse_zoom: f32,       // Scroll zoom factor (1.0 = none)
+
+    // Show control
+    master_intensity: f32, // Master fade multiplier (0 = black, 1 = full)
+
+    // Developer views
+    flux_debug_weight: f32, // Spectral flux debug overlay (0 = off, 1 = full screen)
+
+    // Output
+    tonemap_mode: f32,     // Highlight compression: 0=clip, 1=Reinhard, 2=ACES
+
+    // Beat pulse
+    beat_flash_intensity: f32, // Additive flash brightness this frame (0 = none)
+
+    // Frame feedback
+    feedback: f32,         // Share of the previous frame kept each frame (0 = no trails)
+
+    // Loop region timeline (fractions of the track; loop points < 0 are unset)
+    loop_in: f32,
+    loop_out: f32,
+    loop_position: f32,
+
+    // Kaleidoscope symmetry
+    kaleidoscope_segments: f32,      // Mirrored segments (0 = follow pitch and onsets)
+    kaleidoscope_seam_softness: f32, // Blend width at segment edges, as a share of the segment (0 = hard)
+    kaleidoscope_bpm_sync: f32,      // 1 = scale the segment count with the tempo (120 BPM = as set)
+
+    // Particle swarm
+    particle_spawn_rate: f32, // Onset burst emission (1 at a hit, decaying to 0)
 }
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+// Log-spaced spectrum bars (0-1), packed four per vec4, then the mel spectrum (0-1)
+const MEL_BANDS: i32 = 24;
+
+struct SpectrumBars {
+    num_bars: f32,         // 0 = no spectrum available, spectralizer uses the five bands
+    num_mel_bands: f32,    // 0 = no mel spectrum (CPU analyzer with mel bands off)
+    band_grid: f32,        // 1 = draw the FFT bin grid and band-edge lines over the spectralizer
+    _padding: f32,
+    axis: vec4<f32>,       // Layout across the screen: mode (0 = bands, 1 = log bars, 2 = mel), low Hz, high Hz, Hz per FFT bin
+    band_edges: vec4<f32>, // x (0-1) of the 60/250/2000/8000 Hz band edges, < 0 when off the axis
+    bars: array<vec4<f32>, 16>,
+    mel_bands: array<vec4<f32>, 6>, // MEL_BANDS / 4
+}
+
+@group(0) @binding(1)
+var<uniform> spectrum: SpectrumBars;
+
+// Per-bin spectral flux for the debug view: the last two spectra (0-1 levels) and a ring
+// of flux rows (-1 to 1), 64 log-spaced bins packed four per vec4
+const FLUX_HISTORY_ROWS: i32 = 48;
+
+struct FluxDebug {
+    num_bins: f32,         // 0 = no spectrum available
+    head_row: f32,         // History row holding the newest flux
+    _padding: vec2<f32>,
+    previous: array<vec4<f32>, 16>,
+    current: array<vec4<f32>, 16>,
+    history: array<vec4<f32>, 768>, // FLUX_HISTORY_ROWS rows of 16
+}
+
+@group(0) @binding(2)
+var<uniform> flux_debug: FluxDebug;
+
+// The previous frame (premultiplied, same size as the target), for feedback trails
+@group(1) @binding(0)
+var previous_frame: texture_2d<f32>;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec4<f32>,
@@ -57,20 +137,33 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
-    @location(1) tex_coords: vec2<f32>,
+    @location(1) screen_clip: vec3<f32>, // Clip x, y, w: affine on the plane, so interpolated exactly
     @location(2) world_pos: vec2<f32>,
 }
 
+// Must match CAMERA_OVERSCAN in src/graphics/camera.rs
+const CAMERA_OVERSCAN: f32 = 4.0;
+
+// The full-screen quad is scaled up and viewed through the audio camera (`view_proj`), so the
+// effect plane can roll, tilt and zoom while still covering the window
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    out.clip_position = vec4<f32>(model.position, 1.0);
+    let plane_pos = model.position.xy * CAMERA_OVERSCAN;
+    let clip = uniforms.view_proj * vec4<f32>(plane_pos, 0.0, 1.0);
+    out.clip_position = clip;
     out.color = model.color;
-    out.tex_coords = model.tex_coords;
-    out.world_pos = model.position.xy;
+    out.screen_clip = clip.xyw;
+    out.world_pos = plane_pos;
     return out;
 }
 
+// Window texture coordinates (0-1, top-left origin) of a fragment, whatever the camera does
+fn screen_tex_coords(screen_clip: vec3<f32>) -> vec2<f32> {
+    let ndc = screen_clip.xy / screen_clip.z;
+    return vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+}
+
 // ============================================================================
 // UTILITY FUNCTIONS
 // ============================================================================
@@ -100,6 +193,15 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> vec3<f32> {
     return rgb + vec3<f32>(m);
 }
 
+// Mel band level (0-1), lowest band first; 0 without a mel spectrum
+fn mel_band(index: i32) -> f32 {
+    if (spectrum.num_mel_bands < 1.0) {
+        return 0.0;
+    }
+    let band = clamp(index, 0, MEL_BANDS - 1);
+    return spectrum.mel_bands[band / 4][band % 4];
+}
+
 fn noise2d(pos: vec2<f32>) -> f32 {
     return fract(sin(dot(pos.xy, vec2<f32>(12.9898, 78.233))) * 43758.5453);
 }
@@ -196,6 +298,75 @@ fn apply_dynamic_range(color: vec3<f32>, contrast: f32, saturation_boost: f32) -
     return saturated;
 }
 
+// Highlight compression for bright additive blends (input 0 to ~2+, output 0-1)
+const TONEMAP_WHITE: f32 = 2.0;  // Matches the clip ceiling, so Reinhard only reaches white there
+
+fn tonemap_reinhard(color: vec3<f32>) -> vec3<f32> {
+    // Extended Reinhard: near-linear in the shadows, TONEMAP_WHITE maps to 1.0
+    return color * (1.0 + color / (TONEMAP_WHITE * TONEMAP_WHITE)) / (1.0 + color);
+}
+
+fn tonemap_aces(color: vec3<f32>) -> vec3<f32> {
+    // Narkowicz's fit of the ACES filmic curve
+    return clamp((color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Additive beat pulse, brightest in the centre and softened towards the corners
+fn apply_beat_flash(color: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let flash = clamp(uniforms.beat_flash_intensity, 0.0, 1.0);
+    if flash <= 0.0 {
+        return color;
+    }
+    let vignette = 1.0 - 0.5 * smoothstep(0.2, 0.75, length(uv - vec2<f32>(0.5)));
+    return color + vec3<f32>(flash * vignette);
+}
+
+// Timeline strip along the bottom edge while a loop point is set: the loop region shaded, its
+// ends and the playhead drawn as ticks
+fn apply_loop_markers(color: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let tick = fwidth(uv.x) * 1.5; // About a pixel and a half, whatever the window size
+    if (uniforms.loop_in < 0.0 && uniforms.loop_out < 0.0) || uv.y < 0.985 {
+        return color;
+    }
+
+    let start = max(uniforms.loop_in, 0.0);
+    var marked = color * 0.4;
+    if uniforms.loop_out >= 0.0 && uv.x >= start && uv.x <= uniforms.loop_out {
+        marked = mix(marked, vec3<f32>(0.2, 0.7, 1.0), 0.45);
+    }
+    if (uniforms.loop_in >= 0.0 && abs(uv.x - uniforms.loop_in) < tick)
+        || (uniforms.loop_out >= 0.0 && abs(uv.x - uniforms.loop_out) < tick) {
+        marked = vec3<f32>(0.3, 0.85, 1.0);
+    }
+    if abs(uv.x - uniforms.loop_position) < tick {
+        marked = vec3<f32>(1.0);
+    }
+    return marked;
+}
+
+// Keep a fading copy of the previous frame wherever this one is darker, so motion leaves trails.
+// The small subtraction stops 8-bit rounding from holding faint trails on screen forever.
+fn apply_feedback(color: vec3<f32>, pixel: vec2<f32>) -> vec3<f32> {
+    let amount = clamp(uniforms.feedback, 0.0, 1.0);
+    if amount <= 0.0 {
+        return color;
+    }
+    let previous = textureLoad(previous_frame, vec2<i32>(pixel), 0).rgb;
+    return max(color, max(previous * amount - vec3<f32>(0.004), vec3<f32>(0.0)));
+}
+
+fn apply_tonemap(color: vec3<f32>) -> vec3<f32> {
+    let hdr = max(color, vec3<f32>(0.0));
+    if uniforms.tonemap_mode > 1.5 {
+        return tonemap_aces(hdr);
+    }
+    if uniforms.tonemap_mode > 0.5 {
+        return tonemap_reinhard(min(hdr, vec3<f32>(TONEMAP_WHITE)));
+    }
+    // Off: hard clip at the brightness ceiling (the display clips anything above 1.0)
+    return clamp(color, vec3<f32>(0.0), vec3<f32>(TONEMAP_WHITE));
+}
+
 // Enhanced smoothing and tweening utilities
 fn smooth_step_custom(edge0: f32, edge1: f32, x: f32) -> f32 {
     let t = clamp((x - edge0) / (edge1 - edge0), 0.0, 1.0);
@@ -345,6 +516,19 @@ fn torus_uv_from_screen(screen_pos: vec2<f32>) -> vec3<f32> {
     return vec3<f32>(fract(u_modulated), fract(v_modulated), 1.0 - torus_center_dist / minor_radius);
 }
 
+// Warp screen space towards the cursor and zoom around the center (scroll wheel).
+// A centered cursor with zoom 1.0 leaves coordinates untouched.
+fn apply_mouse_warp(screen_pos: vec2<f32>) -> vec2<f32> {
+    let mouse = vec2<f32>(uniforms.mouse_x * 2.0 - 1.0, 1.0 - uniforms.mouse_y * 2.0);
+    let zoomed = screen_pos / max(uniforms.mouse_zoom, 0.01);
+
+    // Pull nearby space towards the cursor (a soft lens that grows as the cursor leaves
+    // the center) and pan slightly with it
+    let to_mouse = mouse - zoomed;
+    let lens = exp(-dot(to_mouse, to_mouse) * 2.0) * 0.35 * min(length(mouse), 1.0);
+    return zoomed + to_mouse * lens - mouse * 0.25;
+}
+
 fn apply_surface_projection(screen_pos: vec2<f32>, projection_type: i32) -> vec3<f32> {
     // Select projection type based on audio characteristics or manual selection
     if (projection_type == 0) {
@@ -422,6 +606,24 @@ fn llama_plasma(pos: vec2<f32>) -> vec3<f32> {
 }
 
 // Effect 2: Geometric Kaleidoscope - Controlled by BPM and pitch confidence
+// Mirror `angle` into the first half of one of `segments` equal slices: 0 on the mirror line
+// in the middle of a slice, half a slice at its edges. The fold's corners are the visible
+// seams, so within `softness` (a share of the slice) of either one they are rounded off,
+// leaving no crease in the patterns drawn from the folded angle.
+fn kaleidoscope_fold(angle: f32, segments: f32, softness: f32) -> f32 {
+    let segment_angle = (3.14159 * 2.0) / segments;
+    let half_segment = segment_angle * 0.5;
+    let offset = angle - floor(angle / segment_angle) * segment_angle - half_segment; // -half..half
+    let width = max(clamp(softness, 0.0, 1.0) * half_segment * 0.5, 0.0001);
+
+    // Rounded |x|: a parabola within `width` of the corner meeting the line with matching slope
+    let center_distance = abs(offset);
+    let edge_distance = half_segment - center_distance;
+    let soft_center = select(center_distance, (center_distance * center_distance / width + width) * 0.5, center_distance < width);
+    let soft_edge = select(edge_distance, (edge_distance * edge_distance / width + width) * 0.5, edge_distance < width);
+    return select(half_segment - soft_edge, soft_center, center_distance < half_segment * 0.5);
+}
+
 fn geometric_kaleidoscope(pos: vec2<f32>) -> vec3<f32> {
     // Smooth BPM changes to prevent jarring speed shifts
     let smooth_bpm = exponential_smooth(120.0, uniforms.estimated_bpm, 0.1);
@@ -448,9 +650,16 @@ fn geometric_kaleidoscope(pos: vec2<f32>) -> vec3<f32> {
     let smooth_onset = smooth_audio_parameter(uniforms.onset_strength, 1.5);
 
     let segments_base = 6.0 + smooth_pitch * 8.0; // Much more conservative range
-    let segments = segments_base + smooth_onset * 3.0; // Gentle segment changes
-    let segment_angle = (3.14159 * 2.0) / segments;
-    let folded_angle = abs((angle % segment_angle) - segment_angle * 0.5);
+    let audio_segments = segments_base + smooth_onset * 3.0; // Gentle segment changes
+    let set_segments = select(audio_segments, uniforms.kaleidoscope_segments, uniforms.kaleidoscope_segments > 0.0);
+    let tempo_scale = mix(1.0, clamp(bpm_factor, 0.5, 2.0), uniforms.kaleidoscope_bpm_sync);
+    let segments = max(set_segments * tempo_scale, 1.0);
+
+    // Only whole segment counts tile the circle without a seam where atan2 wraps, so a
+    // fractional count crossfades between its two neighbours
+    let folded_low = kaleidoscope_fold(angle, floor(segments), uniforms.kaleidoscope_seam_softness);
+    let folded_high = kaleidoscope_fold(angle, ceil(segments), uniforms.kaleidoscope_seam_softness);
+    let folded_angle = mix(folded_low, folded_high, smoothstep(0.0, 1.0, fract(segments)));
 
     // Smooth ring patterns
     let ring_frequency_base = 8.0 + smooth_onset * 12.0; // Reduced from 40.0
@@ -615,6 +824,32 @@ fn particle_swarm(pos: vec2<f32>) -> vec3<f32> {
         }
     }
 
+    return color + particle_bursts(pos);
+}
+
+// Particles thrown out from the centre on each onset: the spawn rate jumps to 1 on a hit and
+// decays, so a burst starts dense and bright and thins out as it flies outward
+fn particle_bursts(pos: vec2<f32>) -> vec3<f32> {
+    var color = vec3<f32>(0.0);
+    let spawn = clamp(uniforms.particle_spawn_rate, 0.0, 1.0);
+    let burst_count = i32(spawn * 40.0);
+    let travel = (1.0 - spawn) * 1.4 + 0.05;
+
+    for (var i = 0; i < burst_count; i++) {
+        let seed = f32(i) * 0.618034;
+        let angle = fract(seed) * 6.28318 + uniforms.time * 0.2;
+        let speed = 0.6 + fract(seed * 7.31) * 0.8;
+        let particle_pos = vec2<f32>(cos(angle), sin(angle)) * travel * speed;
+
+        let size = 0.012 + spawn * 0.03;
+        let distance_to_particle = length(pos - particle_pos);
+        if (distance_to_particle < size) {
+            let brightness = pow(1.0 - distance_to_particle / size, 0.7) * (0.5 + spawn);
+            let color_t = seed + uniforms.time * 0.3 + uniforms.spectral_centroid * 0.2;
+            color = color + get_current_palette_color(color_t) * brightness * 1.5;
+        }
+    }
+
     return color;
 }
 
@@ -761,15 +996,21 @@ fn mirroring_kaleidoscope(pos: vec2<f32>) -> vec3<f32> {
 
 // Effect 7: Spectralizer - Classic spectrum analyzer bars
 fn spectralizer_bars(pos: vec2<f32>) -> vec3<f32> {
-    // Map position to frequency bands
+    // Map position to the mel bands, else the spectrum bars, else the five frequency bands
     let x_normalized = (pos.x + 1.0) * 0.5; // Convert from [-1,1] to [0,1]
-    let frequency_index = clamp(x_normalized * 5.0, 0.0, 4.99);
+    let use_mel = spectrum.num_mel_bands >= 1.0;
+    let bar_count = select(select(5.0, spectrum.num_bars, spectrum.num_bars >= 1.0), spectrum.num_mel_bands, use_mel);
+    let frequency_index = clamp(x_normalized * bar_count, 0.0, bar_count - 0.01);
     let band_index = i32(frequency_index);
     let band_blend = fract(frequency_index);
 
     // Get frequency band values
     var band_value: f32;
-    if (band_index == 0) {
+    if (use_mel) {
+        band_value = mel_band(band_index);
+    } else if (spectrum.num_bars >= 1.0) {
+        band_value = spectrum.bars[band_index / 4][band_index % 4];
+    } else if (band_index == 0) {
         band_value = mix(uniforms.sub_bass, uniforms.bass, band_blend);
     } else if (band_index == 1) {
         band_value = mix(uniforms.bass, uniforms.mid, band_blend);
@@ -800,14 +1041,57 @@ fn spectralizer_bars(pos: vec2<f32>) -> vec3<f32> {
 
     // Color based on frequency and palette
     let palette_index = calculate_palette_index();
-    let color_t = f32(band_index) * 0.2 + uniforms.time * 0.1 + bar_intensity * 0.3;
+    let color_t = f32(band_index) / bar_count + uniforms.time * 0.1 + bar_intensity * 0.3;
     let base_color = get_current_palette_color(color_t);
 
     // Add glow effect
     let glow = smoothstep(0.0, 0.3, final_intensity) * 0.3;
     let final_color = base_color * (final_intensity + glow);
 
-    return clamp(final_color, vec3<f32>(0.0), vec3<f32>(1.5));
+    let grid = band_grid_overlay(x_normalized) * spectrum.band_grid;
+    return clamp(final_color + grid, vec3<f32>(0.0), vec3<f32>(1.5));
+}
+
+// Frequency the spectralizer draws at `x` (0-1 across), following `spectrum.axis`
+fn spectrum_axis_hz(x: f32) -> f32 {
+    let low = spectrum.axis.y;
+    let high = spectrum.axis.z;
+    if (spectrum.axis.x > 1.5) {
+        // Mel band k is centred on the (k+1)th of MEL_BANDS + 2 evenly spaced mel edges
+        let low_mel = 2595.0 * log(1.0 + low / 700.0) / log(10.0);
+        let high_mel = 2595.0 * log(1.0 + high / 700.0) / log(10.0);
+        let mel = low_mel + (high_mel - low_mel) * (x * f32(MEL_BANDS) + 0.5) / f32(MEL_BANDS + 1);
+        return 700.0 * (pow(10.0, mel / 2595.0) - 1.0);
+    }
+    return low * pow(high / low, x);
+}
+
+// Developer band grid: alternate FFT bins shaded (fading out where they get narrower than a
+// few pixels) and a colored line at each band edge, matching the legend's colors
+fn band_grid_overlay(x: f32) -> vec3<f32> {
+    var overlay = vec3<f32>(0.0);
+    let bin_hz = spectrum.axis.w;
+    if (spectrum.axis.x > 0.5 && bin_hz > 0.0) {
+        let bin = spectrum_axis_hz(x) / bin_hz;
+        let bins_across = (spectrum_axis_hz(x + 0.001) / bin_hz - bin) * 1000.0;
+        let even_bin = fract(floor(bin) * 0.5) < 0.25;
+        let visibility = 1.0 - smoothstep(150.0, 400.0, bins_across);
+        overlay += vec3<f32>(select(0.0, 0.08, even_bin) * visibility);
+    }
+
+    var colors = array<vec3<f32>, 4>(
+        vec3<f32>(1.0, 0.3, 0.3),  // 60 Hz: sub-bass | bass
+        vec3<f32>(1.0, 0.8, 0.2),  // 250 Hz: bass | mid
+        vec3<f32>(0.3, 1.0, 0.4),  // 2 kHz: mid | treble
+        vec3<f32>(0.3, 0.7, 1.0),  // 8 kHz: treble | presence
+    );
+    for (var edge = 0; edge < 4; edge++) {
+        let edge_x = spectrum.band_edges[edge];
+        if (edge_x >= 0.0) {
+            overlay += colors[edge] * (1.0 - smoothstep(0.0015, 0.004, abs(x - edge_x)));
+        }
+    }
+    return overlay;
 }
 
 // Effect 7: Parametric Waves - Mathematical audio-reactive patterns
@@ -899,11 +1183,64 @@ fn parametric_waves(pos: vec2<f32>) -> vec3<f32> {
 // EFFECT BLENDING AND MAIN SHADER
 // ============================================================================
 
+// ============================================================================
+// FLUX DEBUG VIEW
+// ============================================================================
+
+// Flux colour: rises run black -> red -> yellow -> white, falls show as dim blue
+fn flux_color(flux: f32) -> vec3<f32> {
+    if (flux < 0.0) {
+        return vec3<f32>(0.0, 0.1, 0.35) * -flux;
+    }
+    return vec3<f32>(clamp(flux * 3.0, 0.0, 1.0),
+                     clamp(flux * 3.0 - 1.0, 0.0, 1.0),
+                     clamp(flux * 3.0 - 2.0, 0.0, 1.0));
+}
+
+// Scrolling per-bin spectral flux, newest row at the top, with the current (white) and
+// previous (grey) spectra along the bottom. Drawn in screen space, without projection
+// or mouse warp, so it shows exactly what the analysis sees.
+fn flux_debug_view(uv: vec2<f32>) -> vec3<f32> {
+    let num_bins = i32(flux_debug.num_bins);
+    if (num_bins == 0) {
+        return vec3<f32>(0.0);
+    }
+    let bin = clamp(i32(uv.x * f32(num_bins)), 0, num_bins - 1);
+    let strip = 0.2; // Height of the spectrum strip at the bottom
+
+    if (uv.y > 1.0 - strip) {
+        let previous = flux_debug.previous[bin / 4][bin % 4];
+        let current = flux_debug.current[bin / 4][bin % 4];
+        let height = (1.0 - uv.y) / strip;
+        let rise = max(current - previous, 0.0);
+        var color = vec3<f32>(0.0);
+        if (height < previous) {
+            color = vec3<f32>(0.3);
+        }
+        if (height < current) {
+            color = mix(vec3<f32>(0.8), vec3<f32>(1.0, 0.6, 0.1), clamp(rise * 5.0, 0.0, 1.0));
+        }
+        return color;
+    }
+
+    // Row 0 is the newest frame; older rows scroll down the screen
+    let age = clamp(i32(uv.y / (1.0 - strip) * f32(FLUX_HISTORY_ROWS)), 0, FLUX_HISTORY_ROWS - 1);
+    let row = (i32(flux_debug.head_row) - age + FLUX_HISTORY_ROWS) % FLUX_HISTORY_ROWS;
+    let flux = flux_debug.history[row * 16 + bin / 4][bin % 4];
+
+    // Thin dark gaps between bins keep neighbouring bands readable
+    let bin_fraction = fract(uv.x * f32(num_bins));
+    let gap = select(1.0, 0.6, bin_fraction < 0.08);
+    return flux_color(flux) * gap;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let screen_pos = in.world_pos;
+    let screen_pos = apply_mouse_warp(in.world_pos);
+    let tex_coords = screen_tex_coords(in.screen_clip);
 
     // Determine projection type based on manual setting or intelligent selection
+    // (mirrored on the CPU by `Uniforms::resolved_projection`, which rests the camera for flat)
     var projection_type = 3; // Default to flat projection
 
     // Check if manual projection mode is set
@@ -935,13 +1272,14 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let depth_factor = projection_result.z; // Use depth for intensity modulation
 
     // Calculate individual effects with projected coordinates
-    let plasma = llama_plasma(pos) * (1.0 + depth_factor * 0.3);
-    let kaleidoscope = geometric_kaleidoscope(pos) * (1.0 + depth_factor * 0.2);
-    let tunnel = psychedelic_tunnel(pos) * (1.0 + depth_factor * 0.4);
-    let particles = particle_swarm(pos) * (1.0 + depth_factor * 0.5);
-    let fractal = fractal_madness(pos) * (1.0 + depth_factor * 0.25);
-    let spectralizer = spectralizer_bars(pos) * (1.0 + depth_factor * 0.1);
-    let parametric = parametric_waves(pos) * (1.0 + depth_factor * 0.3);
+    // Each effect is scaled by its manager-computed intensity (BPM, onset, flux modulation etc.)
+    let plasma = llama_plasma(pos) * (1.0 + depth_factor * 0.3) * uniforms.plasma_intensity;
+    let kaleidoscope = geometric_kaleidoscope(pos) * (1.0 + depth_factor * 0.2) * uniforms.kaleidoscope_intensity;
+    let tunnel = psychedelic_tunnel(pos) * (1.0 + depth_factor * 0.4) * uniforms.tunnel_intensity;
+    let particles = particle_swarm(pos) * (1.0 + depth_factor * 0.5) * uniforms.particle_intensity;
+    let fractal = fractal_madness(pos) * (1.0 + depth_factor * 0.25) * uniforms.fractal_intensity;
+    let spectralizer = spectralizer_bars(pos) * (1.0 + depth_factor * 0.1) * uniforms.spectralizer_intensity;
+    let parametric = parametric_waves(pos) * (1.0 + depth_factor * 0.3) * uniforms.parametric_intensity;
 
     // Dynamic effect blending using manager-calculated weights
     var final_color = vec3<f32>(0.0);
@@ -980,8 +1318,26 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let luminance = dot(final_color, vec3<f32>(0.299, 0.587, 0.114));
     final_color = mix(vec3<f32>(luminance), final_color, saturation_push);
 
-    // Higher brightness ceiling
-    final_color = clamp(final_color, vec3<f32>(0.0), vec3<f32>(2.0));
+    // Higher brightness ceiling, optionally tonemapped so highlights grade instead of clipping
+    final_color = apply_tonemap(final_color);
+
+    // Trails from the previous frame (read by pixel, so split-screen halves keep their own)
+    final_color = apply_feedback(final_color, in.clip_position.xy);
+
+    // Beat pulse over every effect, after tonemapping so the flash reads at the same strength
+    final_color = apply_beat_flash(final_color, tex_coords);
+
+    // The flux debug view replaces rather than blends, so its colours read as measurements
+    final_color = mix(final_color, flux_debug_view(tex_coords), clamp(uniforms.flux_debug_weight, 0.0, 1.0));
+
+    // Loop markers sit over the visuals but under the master fade, which blacks out everything
+    final_color = apply_loop_markers(final_color, tex_coords);
+
+    // Master fade for show start/stop (applied last so black is truly black)
+    final_color = final_color * clamp(uniforms.master_intensity, 0.0, 1.0);
 
-    return vec4<f32>(final_color, 1.0);
+    // Premultiplied output with brightness as coverage: over the default opaque black clear this
+    // is the plain colour, while a transparent clear lets dark areas show what's behind the window
+    let coverage = clamp(max(final_color.r, max(final_color.g, final_color.b)), 0.0, 1.0);
+    return vec4<f32>(final_color, coverage);
 }
\ No newline at end of file
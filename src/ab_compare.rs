@@ -0,0 +1,210 @@
+use anyhow::Result;
+use clap::Parser;
+use log::info;
+use std::sync::Arc;
+use winit::{
+    event::{ElementState, Event, WindowEvent},
+    event_loop::EventLoop,
+    keyboard::{KeyCode, PhysicalKey},
+    window::WindowBuilder,
+};
+
+use arrvee::{audio, graphics};
+
+use graphics::GraphicsEngine;
+use audio::AudioPlayback;
+
+#[derive(Parser)]
+#[command(name = "arrvee-ab-compare")]
+#[command(about = "Arrvee Music Visualizer - Split-screen A/B comparison of two audio files")]
+struct Args {
+    /// Audio file shown on the left (A)
+    file_a: String,
+
+    /// Audio file shown on the right (B)
+    file_b: String,
+
+    /// Graphics backend: vulkan, metal, dx12, gl, or auto
+    #[arg(long, default_value = "auto")]
+    backend: String,
+
+    /// Start in borderless fullscreen, optionally on a specific monitor (`--fullscreen=1`)
+    #[arg(long, value_name = "MONITOR", num_args = 0..=1, require_equals = true)]
+    fullscreen: Option<Option<usize>>,
+
+    /// Window width in pixels (at least 320; default is the tool's usual size)
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Window height in pixels (at least 240)
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Window title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Playback volume for the audible side (0.0-1.0)
+    #[arg(long, default_value = "0.1")]
+    volume: f32,
+
+    /// Open with the audio paused (visuals idle) until Space, e.g. while a screen recorder gets ready
+    #[arg(long, overrides_with = "autoplay")]
+    paused: bool,
+
+    /// Start playing as soon as the window opens (the default; overrides an earlier `--paused`)
+    #[arg(long, overrides_with = "paused")]
+    autoplay: bool,
+
+    /// Seek both tracks this many seconds in before starting
+    #[arg(long, value_name = "SECONDS")]
+    start_at: Option<f32>,
+}
+
+/// Which side of the split is currently audible
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    A,
+    B,
+}
+
+impl Side {
+    fn other(self) -> Self {
+        match self {
+            Side::A => Side::B,
+            Side::B => Side::A,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+    graphics::crash_report::install("arrvee-ab-compare");
+    graphics::crash_report::set_audio_file(&format!("{} | {}", args.file_a, args.file_b));
+    graphics::crash_report::set_analysis_config("real-time, one analyzer per side (frames shown are side A)".to_string());
+    let backends = graphics::engine::parse_backends(&args.backend)?;
+    let window_size = graphics::engine::window_size(args.width, args.height, (1600, 600))?;
+
+    info!("Starting A/B comparison: {} | {}", args.file_a, args.file_b);
+
+    let event_loop = EventLoop::new()?;
+    let window = Arc::new(WindowBuilder::new()
+        .with_title(args.title.clone().unwrap_or_else(|| format!("Arrvee A/B: {} | {}", args.file_a, args.file_b)))
+        .with_inner_size(window_size)
+        .with_fullscreen(args.fullscreen.map(|monitor| graphics::engine::borderless_fullscreen(&event_loop, monitor)))
+        .build(&event_loop)?);
+
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, backends))?;
+    graphics_engine.enable_split_screen();
+    let mut shutdown_requested = false;
+
+    // Each side gets its own playback + analysis chain; both advance one frame per redraw,
+    // so their playheads stay in step regardless of the files' sample rates
+    let mut playback_a = AudioPlayback::new()?;
+    let mut playback_b = AudioPlayback::new()?;
+    info!("Loading {}...", args.file_a);
+    playback_a.load_file(&args.file_a).await?;
+    info!("Loading {}...", args.file_b);
+    playback_b.load_file(&args.file_b).await?;
+
+    let volume = args.volume.clamp(0.0, 1.0);
+    let mut audible = Side::A;
+    playback_a.set_volume(volume);
+    playback_b.set_volume(0.0);
+
+    let start_paused = args.paused && !args.autoplay;
+    playback_a.start(args.start_at, start_paused)?;
+    playback_b.start(args.start_at, start_paused)?;
+    info!("🎧 Playing A (Tab switches the audible side)");
+
+    let window_clone = Arc::clone(&window);
+    event_loop.run(move |event, elwt| {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CloseRequested => {
+                    info!("Close requested - cleaning up...");
+                    shutdown_requested = true;
+                    playback_a.stop();
+                    playback_b.stop();
+                    graphics_engine.cleanup();
+                    elwt.exit();
+                }
+                WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                    match event.physical_key {
+                        PhysicalKey::Code(KeyCode::Escape) => {
+                            info!("Escape pressed - cleaning up...");
+                            shutdown_requested = true;
+                            playback_a.stop();
+                            playback_b.stop();
+                            graphics_engine.cleanup();
+                            elwt.exit();
+                        }
+                        PhysicalKey::Code(KeyCode::F11) => {
+                            graphics::engine::toggle_fullscreen(&window_clone);
+                        }
+                        PhysicalKey::Code(KeyCode::Tab) => {
+                            audible = audible.other();
+                            let (volume_a, volume_b) = if audible == Side::A { (volume, 0.0) } else { (0.0, volume) };
+                            playback_a.set_volume(volume_a);
+                            playback_b.set_volume(volume_b);
+                            info!("🎧 Playing {}", if audible == Side::A { "A" } else { "B" });
+                        }
+                        // Pause/resume both sides together so the playheads stay aligned
+                        PhysicalKey::Code(KeyCode::Space) => {
+                            if playback_a.is_playing() {
+                                playback_a.pause();
+                                playback_b.pause();
+                                info!("Audio paused");
+                            } else {
+                                playback_a.play();
+                                playback_b.play();
+                                info!("Audio resumed");
+                            }
+                        }
+                        PhysicalKey::Code(KeyCode::KeyF) => {
+                            let frozen = !graphics_engine.is_time_paused();
+                            graphics_engine.set_time_paused(frozen);
+                            info!("{}", if frozen { "❄️ Visuals frozen" } else { "▶️ Visuals resumed" });
+                        }
+                        PhysicalKey::Code(KeyCode::KeyP) => {
+                            graphics_engine.palette_index = (graphics_engine.palette_index + 1.0) % 6.0;
+                            info!("🎨 Palette: {}", graphics_engine.palette_index as i32);
+                        }
+                        _ => {}
+                    }
+                }
+                WindowEvent::CursorMoved { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::MouseInput { .. } => {
+                    graphics_engine.handle_mouse_event(&event);
+                }
+                WindowEvent::Resized(physical_size) => {
+                    graphics_engine.resize(physical_size);
+                }
+                WindowEvent::RedrawRequested => {
+                    if shutdown_requested {
+                        return;
+                    }
+
+                    let frame_a = pollster::block_on(playback_a.get_current_audio_frame());
+                    let frame_b = pollster::block_on(playback_b.get_current_audio_frame());
+
+                    if let Err(e) = graphics_engine.render_split(&frame_a, &frame_b, &window_clone) {
+                        log::error!("Render error: {}", e);
+                    }
+                }
+                _ => {}
+            },
+            Event::AboutToWait => {
+                if playback_a.is_finished() && playback_b.is_finished() {
+                    info!("Both files finished playing");
+                    elwt.exit();
+                }
+                window_clone.request_redraw();
+            }
+            _ => {}
+        }
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use clap::Parser;
+
+use arrvee::audio;
+
+use audio::ArvFormat;
+
+#[derive(Parser)]
+#[command(name = "arrvee-arvinfo")]
+#[command(about = "Inspect an ARV prescan file's header and statistics without playing it")]
+struct Args {
+    /// ARV files to inspect
+    #[arg(required = true)]
+    arv_files: Vec<String>,
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+
+    for (index, path) in args.arv_files.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        print_arv_info(path)?;
+    }
+
+    Ok(())
+}
+
+/// Print the header-only summary of one ARV file (frames are never decoded)
+fn print_arv_info(path: &str) -> Result<()> {
+    let header = ArvFormat::load_header(path)?;
+    let file_size = std::fs::metadata(path)?.len();
+    let info = &header.file_info;
+    let stats = &header.statistics;
+
+    println!("=== {} ===", path);
+    println!("Format version: {} ({} bytes/frame)", header.version, header.frame_bytes);
+    println!("File size: {:.1} KB", file_size as f64 / 1024.0);
+    if file_size != header.expected_file_size() {
+        println!("⚠️  Expected {} bytes from the header, file has {} (truncated or trailing data)",
+                 header.expected_file_size(), file_size);
+    }
+
+    println!();
+    println!("Source: {}", info.filename);
+    println!("Duration: {:.2} seconds", info.duration_seconds);
+    println!("Sample rate: {}Hz, chunk size: {}", info.sample_rate, info.chunk_size);
+    println!("Frames: {} @ {:.2} Hz", header.frame_count, info.frame_rate);
+    if info.time_offset_seconds != 0.0 {
+        println!("First frame at {:.3}s (beat-locked grid)", info.time_offset_seconds);
+    }
+
+    println!();
+    println!("Beats detected: {}", stats.total_beats);
+    println!("Average BPM: {:.1} (range {:.1} - {:.1})", stats.average_bpm, stats.bpm_range.0, stats.bpm_range.1);
+    println!("Energy profile: {}", stats.energy_profile);
+    println!("Dominant frequency range: {}", stats.dominant_frequency_range);
+    println!("Complexity score: {:.3}", stats.complexity_score);
+
+    println!();
+    println!("Peaks: bass {:.3}, mid {:.3}, treble {:.3}, presence {:.3}",
+             stats.peak_bass, stats.peak_mid, stats.peak_treble, stats.peak_presence);
+    println!("       volume {:.3}, spectral flux {:.3}, onset {:.3}",
+             stats.peak_volume, stats.peak_spectral_flux, stats.peak_onset);
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use super::envelope::EnvelopeFollower;
+
+/// Settings for automatic gain control on real-time input
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct AgcConfig {
+    /// RMS level incoming audio is scaled towards (0.0-1.0)
+    pub target_level: f32,
+    /// Seconds for the level estimate to rise (how fast gain drops on loud input)
+    pub attack_time: f32,
+    /// Seconds for the level estimate to fall (how slowly gain recovers on quiet input)
+    pub release_time: f32,
+    /// Upper bound on applied gain so near-silence is never blown up into noise
+    pub max_gain: f32,
+    /// Chunks with RMS below this are treated as silence and leave the gain untouched
+    pub noise_floor: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            target_level: 0.15,  // Roughly the level the normalizer is calibrated for
+            attack_time: 0.1,    // Back off quickly when a loud source comes in
+            release_time: 3.0,   // Recover slowly so quiet passages stay quiet
+            max_gain: 16.0,      // +24 dB
+            noise_floor: 0.0005, // About -66 dBFS
+        }
+    }
+}
+
+/// Automatic gain control for live input.
+///
+/// Tracks a slow running RMS of the incoming chunks and scales them towards
+/// `target_level` before analysis, so quiet mics and hot line inputs end up in the
+/// same range. This is independent of `FeatureNormalizer`, which works on features
+/// after analysis rather than on the signal itself.
+#[allow(dead_code)]
+pub struct AutomaticGainControl {
+    config: AgcConfig,
+    level: EnvelopeFollower,
+    gain: f32,
+    primed: bool,
+}
+
+#[allow(dead_code)]
+impl AutomaticGainControl {
+    pub fn new(config: AgcConfig) -> Self {
+        let level = EnvelopeFollower::new(config.attack_time, config.release_time);
+        Self {
+            config,
+            level,
+            gain: 1.0,
+            primed: false,
+        }
+    }
+
+    pub fn config(&self) -> &AgcConfig {
+        &self.config
+    }
+
+    /// Gain currently being applied (1.0 = unity)
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Scale one chunk of samples, updating the running level estimate
+    pub fn process(&mut self, samples: &[f32], sample_rate: f32) -> Vec<f32> {
+        if samples.is_empty() {
+            return Vec::new();
+        }
+
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        // Silence holds the current gain instead of letting it creep up to the cap
+        if rms > self.config.noise_floor {
+            let level = if self.primed {
+                self.level.process(rms, samples.len() as f32 / sample_rate.max(1.0))
+            } else {
+                // Jump straight to the first real level so the opening seconds aren't mis-scaled
+                self.primed = true;
+                self.level.process(rms, f32::INFINITY)
+            };
+
+            self.gain = (self.config.target_level / level.max(f32::EPSILON)).min(self.config.max_gain);
+        }
+
+        samples.iter().map(|s| (s * self.gain).clamp(-1.0, 1.0)).collect()
+    }
+
+    /// Forget the level estimate (e.g. when the input source changes)
+    pub fn reset(&mut self) {
+        self.level.reset();
+        self.gain = 1.0;
+        self.primed = false;
+    }
+}
+
+impl Default for AutomaticGainControl {
+    fn default() -> Self {
+        Self::new(AgcConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// 10ms of a square wave whose RMS is `amplitude`
+    fn chunk(amplitude: f32) -> Vec<f32> {
+        (0..441).map(|i| if i % 2 == 0 { amplitude } else { -amplitude }).collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_quiet_input_is_brought_up_to_the_target() {
+        let mut agc = AutomaticGainControl::default();
+        let target = agc.config().target_level;
+        for _ in 0..100 {
+            let output = agc.process(&chunk(0.03), SAMPLE_RATE);
+            assert!((rms(&output) - target).abs() < 1e-4, "{}", rms(&output));
+        }
+        assert!((agc.gain() - target / 0.03).abs() < 1e-3);
+
+        // Silence holds the gain rather than drifting up to the cap
+        let held = agc.gain();
+        agc.process(&chunk(0.0001), SAMPLE_RATE);
+        assert_eq!(agc.gain(), held);
+
+        agc.reset();
+        assert_eq!(agc.gain(), 1.0);
+        assert!(agc.process(&[], SAMPLE_RATE).is_empty());
+    }
+
+    #[test]
+    fn test_gain_is_capped_and_loud_input_clipped() {
+        let mut agc = AutomaticGainControl::default();
+        agc.process(&chunk(0.001), SAMPLE_RATE);
+        assert_eq!(agc.gain(), agc.config().max_gain, "near-silence isn't blown up past the cap");
+
+        // A loud source comes in: the gain backs off below unity, and the output never leaves -1 to 1
+        for _ in 0..100 {
+            let output = agc.process(&chunk(0.9), SAMPLE_RATE);
+            assert!(output.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+        }
+        assert!((agc.gain() - agc.config().target_level / 0.9).abs() < 1e-3, "{}", agc.gain());
+    }
+
+    #[test]
+    fn test_level_rises_with_the_attack_and_falls_with_the_release() {
+        let config = AgcConfig::default();
+        let (attack, release, target) = (config.attack_time, config.release_time, config.target_level);
+        let (quiet, loud) = (0.05, 0.5);
+        let mut agc = AutomaticGainControl::new(config);
+        agc.process(&chunk(quiet), SAMPLE_RATE);
+
+        // One time constant covers 1 - 1/e of the way to the new level
+        let expected_gain = |from: f32, to: f32| target / (from + (to - from) * (1.0 - (-1.0f32).exp()));
+        for _ in 0..(attack * 100.0).round() as usize {
+            agc.process(&chunk(loud), SAMPLE_RATE);
+        }
+        assert!((agc.gain() - expected_gain(quiet, loud)).abs() < 1e-3, "{}", agc.gain());
+
+        for _ in 0..1000 {
+            agc.process(&chunk(loud), SAMPLE_RATE);
+        }
+        for _ in 0..(release * 100.0).round() as usize {
+            agc.process(&chunk(quiet), SAMPLE_RATE);
+        }
+        assert!((agc.gain() - expected_gain(loud, quiet)).abs() < 1e-3, "{}", agc.gain());
+    }
+}
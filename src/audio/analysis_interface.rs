@@ -15,7 +15,7 @@ use async_trait::async_trait;
 /// # Usage
 /// ```rust,no_run
 /// # use anyhow::Result;
-/// # async fn example(analyzer: &mut dyn crate::audio::AudioAnalyzer, audio: &[f32]) -> Result<()> {
+/// # async fn example(analyzer: &mut dyn arrvee::audio::AudioAnalyzer, audio: &[f32]) -> Result<()> {
 /// let raw_features = analyzer.analyze_chunk(audio).await?;
 /// println!("Bass energy: {}", raw_features.bass);
 /// # Ok(())
@@ -67,7 +67,7 @@ pub struct RawAudioFeatures {
 ///
 /// # Example Usage
 /// ```rust,no_run
-/// use crate::audio::{AudioAnalyzer, CpuAudioAnalyzer, FeatureNormalizer};
+/// use arrvee::audio::{AudioAnalyzer, CpuAudioAnalyzer, FeatureNormalizer};
 /// use anyhow::Result;
 ///
 /// async fn process_audio(audio: &[f32]) -> Result<()> {
@@ -109,6 +109,115 @@ pub trait AudioAnalyzer {
     ///
     /// Used for logging and debugging to identify which analyzer is active.
     fn analyzer_type(&self) -> &'static str;
+
+    /// Forget all state carried between chunks (previous spectrum, histories, beat timing),
+    /// so a newly loaded track isn't analyzed against the end of the previous one.
+    fn reset(&mut self);
+
+    /// Magnitude spectrum (`chunk_size / 2` bins, DC to Nyquist) from the last analyzed chunk.
+    ///
+    /// Analyzers that never read the spectrum back (e.g. GPU) return `None`.
+    fn last_spectrum(&self) -> Option<&[f32]> {
+        None
+    }
+
+    /// Mel band levels (0.0-1.0) from the last analyzed chunk, when the analyzer computes them
+    fn last_mel_bands(&self) -> Option<&[f32]> {
+        None
+    }
+
+    /// Seconds of audio from one `analyze_chunk` call to the next, which beat timing steps by.
+    /// The default is back-to-back chunks (`chunk_size / sample_rate`); callers analyzing
+    /// overlapping windows set the spacing they actually advance by.
+    ///
+    /// Analyzers that time beats by the clock (e.g. GPU) ignore it.
+    fn set_chunk_interval(&mut self, _seconds: f32) {}
+
+    /// How slowly `estimated_bpm` follows tempo changes (see `TempoDetector::set_smoothing`).
+    ///
+    /// Analyzers that don't estimate tempo on the CPU (e.g. GPU) ignore it.
+    fn set_bpm_smoothing(&mut self, _smoothing: f32) {}
+}
+
+/// Runs a primary analyzer (normally the GPU one) and switches to a fallback for good the
+/// first time it fails, re-analyzing that chunk so no frame is lost.
+///
+/// The GPU analyzer fails rather than blocking when a readback stalls, so this turns a hung
+/// driver into a one-off warning and CPU analysis instead of a frozen render loop.
+#[allow(dead_code)]
+pub struct FallbackAnalyzer {
+    primary: Box<dyn AudioAnalyzer + Send>,
+    fallback: Box<dyn AudioAnalyzer + Send>,
+    using_fallback: bool,
+}
+
+#[allow(dead_code)]
+impl FallbackAnalyzer {
+    pub fn new(primary: Box<dyn AudioAnalyzer + Send>, fallback: Box<dyn AudioAnalyzer + Send>) -> Self {
+        Self { primary, fallback, using_fallback: false }
+    }
+
+    /// True once the primary analyzer has failed
+    pub fn is_using_fallback(&self) -> bool {
+        self.using_fallback
+    }
+
+    fn active(&self) -> &(dyn AudioAnalyzer + Send) {
+        if self.using_fallback { self.fallback.as_ref() } else { self.primary.as_ref() }
+    }
+}
+
+#[async_trait]
+impl AudioAnalyzer for FallbackAnalyzer {
+    async fn analyze_chunk(&mut self, audio_data: &[f32]) -> Result<RawAudioFeatures> {
+        if !self.using_fallback {
+            match self.primary.analyze_chunk(audio_data).await {
+                Ok(features) => return Ok(features),
+                Err(e) => {
+                    log::warn!("⚠️  {} analyzer failed ({}); switching to {} analysis",
+                               self.primary.analyzer_type(), e, self.fallback.analyzer_type());
+                    self.using_fallback = true;
+                }
+            }
+        }
+        self.fallback.analyze_chunk(audio_data).await
+    }
+
+    fn sample_rate(&self) -> f32 {
+        self.active().sample_rate()
+    }
+
+    fn chunk_size(&self) -> usize {
+        self.active().chunk_size()
+    }
+
+    fn analyzer_type(&self) -> &'static str {
+        self.active().analyzer_type()
+    }
+
+    fn reset(&mut self) {
+        // A failed primary stays retired; resetting it would only retry a broken device
+        self.primary.reset();
+        self.fallback.reset();
+    }
+
+    fn last_spectrum(&self) -> Option<&[f32]> {
+        self.active().last_spectrum()
+    }
+
+    fn last_mel_bands(&self) -> Option<&[f32]> {
+        self.active().last_mel_bands()
+    }
+
+    fn set_chunk_interval(&mut self, seconds: f32) {
+        self.primary.set_chunk_interval(seconds);
+        self.fallback.set_chunk_interval(seconds);
+    }
+
+    fn set_bpm_smoothing(&mut self, smoothing: f32) {
+        self.primary.set_bpm_smoothing(smoothing);
+        self.fallback.set_bpm_smoothing(smoothing);
+    }
 }
 
 /// Normalized audio features (guaranteed 0.0-1.0 range)
@@ -141,4 +250,56 @@ pub struct NormalizedAudioFeatures {
     pub volume: f32,
     pub dynamic_range: f32,
     pub pitch_confidence: f32,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::CpuAudioAnalyzer;
+
+    /// Stands in for a GPU analyzer whose readback times out after a few good frames
+    struct StallingAnalyzer {
+        frames_before_stall: usize,
+    }
+
+    #[async_trait]
+    impl AudioAnalyzer for StallingAnalyzer {
+        async fn analyze_chunk(&mut self, _audio_data: &[f32]) -> Result<RawAudioFeatures> {
+            if self.frames_before_stall == 0 {
+                return Err(anyhow::anyhow!("GPU analysis readback timed out"));
+            }
+            self.frames_before_stall -= 1;
+            CpuAudioAnalyzer::new(44100.0, 512)?.analyze_chunk(&[0.0; 512]).await
+        }
+
+        fn sample_rate(&self) -> f32 {
+            44100.0
+        }
+
+        fn chunk_size(&self) -> usize {
+            512
+        }
+
+        fn analyzer_type(&self) -> &'static str {
+            "GPU"
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    #[tokio::test]
+    async fn test_stalled_primary_falls_back_to_cpu() {
+        let mut analyzer = FallbackAnalyzer::new(
+            Box::new(StallingAnalyzer { frames_before_stall: 2 }),
+            Box::new(CpuAudioAnalyzer::new(44100.0, 512).unwrap()),
+        );
+        let chunk: Vec<f32> = (0..512).map(|i| (i as f32 * 0.1).sin()).collect();
+
+        for frame in 0..5 {
+            assert!(analyzer.analyze_chunk(&chunk).await.is_ok(), "frame {} was lost", frame);
+            assert_eq!(analyzer.is_using_fallback(), frame >= 2);
+        }
+        assert_eq!(analyzer.analyzer_type(), "CPU");
+        assert!(analyzer.last_spectrum().is_some());
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use super::prescan::{PrescanData, PrescanFrame, FileInfo, AnalysisStatistics};
+use crate::error::ArrveeError;
 
 /// Arrvee Audio-Visual (.arv) - Proprietary binary format for ultra-efficient prescan data
 ///
@@ -10,22 +11,27 @@ use super::prescan::{PrescanData, PrescanFrame, FileInfo, AnalysisStatistics};
 /// - Version: u8 (1 byte)
 /// - Header: FileInfo + Statistics (variable)
 /// - Frame count: u32 (4 bytes)
-/// - Frames: Packed binary data (16 bytes per frame)
+/// - Frames: Packed binary data (20 bytes per frame)
 ///
-/// Per-frame data (16 bytes total):
+/// Per-frame data (20 bytes total):
 /// - 5x frequency bands: u16 (0-65535 maps to 0.0-1.0) = 10 bytes
 /// - 3x spectral features: u16 = 6 bytes
 /// - Beat data: u8 (packed bits) + u8 (beat_strength scaled) = 2 bytes
 /// - Reserved: 2 bytes for future expansion
 ///
+/// Version 1 files were written with 16-byte frames, which truncated the beat data and
+/// reserved bytes. They still load, but with beat_detected/beat_strength zeroed.
+///
 /// Total compression: ~85% smaller than JSON
 
 #[allow(dead_code)]
 const MAGIC_BYTES: &[u8; 4] = b"ARVV";
 #[allow(dead_code)]
-const FORMAT_VERSION: u8 = 1;
+const FORMAT_VERSION: u8 = 2;
+#[allow(dead_code)]
+const BYTES_PER_FRAME: usize = std::mem::size_of::<PackedFrame>();
 #[allow(dead_code)]
-const BYTES_PER_FRAME: usize = 16;
+const LEGACY_V1_BYTES_PER_FRAME: usize = 16;
 
 #[allow(dead_code)]
 #[repr(packed)]
@@ -116,6 +122,28 @@ impl PackedFrame {
     }
 }
 
+/// ARV header contents, readable without decoding the frame data
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ArvHeader {
+    pub version: u8,
+    pub file_info: FileInfo,
+    pub statistics: AnalysisStatistics,
+    pub frame_count: usize,
+    /// Size of each packed frame for this version
+    pub frame_bytes: usize,
+    /// Offset where the packed frames start
+    pub header_bytes: usize,
+}
+
+#[allow(dead_code)]
+impl ArvHeader {
+    /// File size implied by the header (header + all packed frames)
+    pub fn expected_file_size(&self) -> u64 {
+        (self.header_bytes + self.frame_count * self.frame_bytes) as u64
+    }
+}
+
 #[allow(dead_code)]
 pub struct ArvFormat;
 
@@ -161,10 +189,57 @@ impl ArvFormat {
     }
 
     /// Load prescan data from ARV binary format
-    pub fn load_arv<P: AsRef<std::path::Path>>(path: P) -> Result<PrescanData> {
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
+    pub fn load_arv<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<PrescanData> {
+        let mut reader = Self::open(path.as_ref())?;
+        Self::read_frames(&mut reader).map_err(|e| corrupt(path.as_ref(), e))
+    }
+
+    fn read_frames<R: Read>(reader: &mut R) -> Result<PrescanData> {
+        let ArvHeader { file_info, statistics, frame_count, frame_bytes, .. } = Self::read_header(reader)?;
+
+        // Read packed frames
+        let mut frames = Vec::with_capacity(frame_count);
+        let mut packed_data = [0u8; BYTES_PER_FRAME];
+
+        for i in 0..frame_count {
+            // Legacy frames are shorter; the tail stays zeroed
+            reader.read_exact(&mut packed_data[..frame_bytes])?;
 
+            let packed_frame = unsafe {
+                *(packed_data.as_ptr() as *const PackedFrame)
+            };
+
+            // Calculate timestamp from frame index
+            let timestamp = file_info.time_offset_seconds + i as f32 / file_info.frame_rate;
+
+            // Use BPM from statistics (more efficient than storing per-frame)
+            let estimated_bpm = statistics.average_bpm;
+
+            let frame = packed_frame.to_prescan_frame(timestamp, estimated_bpm);
+            frames.push(frame);
+        }
+
+        Ok(PrescanData {
+            file_info,
+            frames,
+            statistics,
+        })
+    }
+
+    /// Read only the header (file info, statistics, frame count) without decoding any frames
+    #[allow(dead_code)]
+    pub fn load_header<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<ArvHeader> {
+        let mut reader = Self::open(path.as_ref())?;
+        Self::read_header(&mut reader).map_err(|e| corrupt(path.as_ref(), e))
+    }
+
+    fn open(path: &std::path::Path) -> crate::error::Result<BufReader<File>> {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|source| ArrveeError::Io { path: path.to_path_buf(), source })
+    }
+
+    fn read_header<R: Read>(reader: &mut R) -> Result<ArvHeader> {
         // Verify magic bytes
         let mut magic = [0u8; 4];
         reader.read_exact(&mut magic)?;
@@ -175,9 +250,11 @@ impl ArvFormat {
         // Read version
         let mut version = [0u8; 1];
         reader.read_exact(&mut version)?;
-        if version[0] != FORMAT_VERSION {
-            return Err(anyhow::anyhow!("Unsupported ARV version: {}", version[0]));
-        }
+        let frame_bytes = match version[0] {
+            FORMAT_VERSION => BYTES_PER_FRAME,
+            1 => LEGACY_V1_BYTES_PER_FRAME,
+            other => return Err(anyhow::anyhow!("Unsupported ARV version: {}", other)),
+        };
 
         // Read file info
         let mut len_bytes = [0u8; 4];
@@ -198,31 +275,13 @@ impl ArvFormat {
         reader.read_exact(&mut len_bytes)?;
         let frame_count = u32::from_le_bytes(len_bytes) as usize;
 
-        // Read packed frames
-        let mut frames = Vec::with_capacity(frame_count);
-        let mut packed_data = vec![0u8; BYTES_PER_FRAME];
-
-        for i in 0..frame_count {
-            reader.read_exact(&mut packed_data)?;
-
-            let packed_frame = unsafe {
-                *(packed_data.as_ptr() as *const PackedFrame)
-            };
-
-            // Calculate timestamp from frame index
-            let timestamp = i as f32 / file_info.frame_rate;
-
-            // Use BPM from statistics (more efficient than storing per-frame)
-            let estimated_bpm = statistics.average_bpm;
-
-            let frame = packed_frame.to_prescan_frame(timestamp, estimated_bpm);
-            frames.push(frame);
-        }
-
-        Ok(PrescanData {
+        Ok(ArvHeader {
+            version: version[0],
             file_info,
-            frames,
             statistics,
+            frame_count,
+            frame_bytes,
+            header_bytes: MAGIC_BYTES.len() + 1 + 4 + file_info_len + 4 + stats_len + 4,
         })
     }
 
@@ -232,6 +291,14 @@ impl ArvFormat {
     }
 }
 
+/// A read failure past the open, as `FileCorrupt` (a short read means the file was cut off)
+fn corrupt(path: &std::path::Path, error: anyhow::Error) -> ArrveeError {
+    let truncated = error.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof);
+    let reason = if truncated { "file is truncated".to_string() } else { error.to_string() };
+    ArrveeError::FileCorrupt { path: path.to_path_buf(), reason }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
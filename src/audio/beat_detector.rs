@@ -27,6 +27,14 @@ impl BeatDetector {
         }
     }
 
+    /// Clear energy history and beat timing
+    pub fn reset(&mut self) {
+        self.bass_history.clear();
+        self.kick_history.clear();
+        self.last_beat_time = 0.0;
+        self.time_counter = 0.0;
+    }
+
     pub fn detect_beat(&mut self, bands: &FrequencyBands) -> (bool, f32) {
         self.time_counter += 1024.0 / self.sample_rate;
 
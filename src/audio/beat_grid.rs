@@ -0,0 +1,128 @@
+use std::f64::consts::TAU;
+
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+const PREFERRED_BPM: f32 = 120.0;   // Tempo prior: doubles and halves of a tempo score alike otherwise
+const PRIOR_WIDTH_OCTAVES: f32 = 1.0;
+const ENVELOPE_HOP_SECONDS: f32 = 0.005;
+const LOW_PASS_HZ: f32 = 150.0;
+
+/// Track-wide tempo and beat phase for beat-locked prescans
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct BeatGrid {
+    pub bpm: f32,
+    /// Time of the first beat (0 to one beat period)
+    pub first_beat_seconds: f32,
+}
+
+impl BeatGrid {
+    pub fn beat_seconds(&self) -> f32 {
+        60.0 / self.bpm
+    }
+
+    /// Power-of-two subdivision whose hop comes closest to `chunk_size` samples
+    #[allow(dead_code)]
+    pub fn frames_per_beat_near(&self, chunk_size: usize, sample_rate: f32) -> u32 {
+        let frames = self.beat_seconds() * sample_rate / chunk_size.max(1) as f32;
+        2u32.pow(frames.max(1.0).log2().round() as u32)
+    }
+
+    /// Frame rate that places `frames_per_beat` frames on every beat
+    pub fn frame_rate(&self, frames_per_beat: u32) -> f32 {
+        self.bpm / 60.0 * frames_per_beat as f32
+    }
+
+    /// Time of the first grid subdivision at or after zero
+    pub fn offset_seconds(&self, frames_per_beat: u32) -> f32 {
+        self.first_beat_seconds.rem_euclid(1.0 / self.frame_rate(frames_per_beat))
+    }
+
+    /// Analysis chunk start positions (in samples) on the subdivision grid
+    #[allow(dead_code)]
+    pub fn frame_positions(&self, frames_per_beat: u32, sample_rate: f32, chunk_size: usize, total_samples: usize) -> Vec<usize> {
+        let step = sample_rate as f64 / self.frame_rate(frames_per_beat) as f64;
+        let offset = self.offset_seconds(frames_per_beat) as f64 * sample_rate as f64;
+        (0..)
+            .map(|k| (offset + k as f64 * step).round() as usize)
+            .take_while(|&position| position + chunk_size <= total_samples)
+            .collect()
+    }
+}
+
+/// Estimate tempo and beat phase from a mono sample buffer.
+///
+/// Rises in log energy (full band and below ~150 Hz) are treated as onsets; each candidate
+/// tempo is scored by how strongly the onsets line up on one phase of its beat period (a
+/// weighted circular mean), so the whole track votes and the phase comes out finer than the
+/// envelope's hop. Works from the raw samples because normalized features saturate on loud
+/// material. Returns `None` for silence or very short buffers.
+#[allow(dead_code)]
+pub fn estimate_beat_grid(samples: &[f32], sample_rate: f32) -> Option<BeatGrid> {
+    let hop = ((sample_rate * ENVELOPE_HOP_SECONDS) as usize).max(1);
+    if samples.len() < hop * 4 {
+        return None;
+    }
+
+    // One-pole low-pass for the kick/bass envelope
+    let alpha = 1.0 - (-std::f32::consts::TAU * LOW_PASS_HZ / sample_rate).exp();
+    let mut low = 0.0f32;
+    let envelope: Vec<(f32, f32)> = samples.chunks_exact(hop)
+        .map(|chunk| {
+            let (mut full, mut bass) = (0.0f32, 0.0f32);
+            for &sample in chunk {
+                low += (sample - low) * alpha;
+                full += sample * sample;
+                bass += low * low;
+            }
+            let n = chunk.len() as f32;
+            ((full / n + 1e-10).ln(), (bass / n + 1e-10).ln())
+        })
+        .collect();
+
+    // An onset anywhere in a hop shows up in that hop, so place it mid-hop
+    let hop_seconds = hop as f64 / sample_rate as f64;
+    let onsets: Vec<(f64, f64)> = envelope.windows(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let rise = (pair[1].0 - pair[0].0).max(0.0) + (pair[1].1 - pair[0].1).max(0.0);
+            ((index as f64 + 1.5) * hop_seconds, rise as f64)
+        })
+        .filter(|(_, rise)| *rise > 0.0)
+        .collect();
+    if onsets.is_empty() {
+        return None;
+    }
+
+    // Coarse sweep in 0.5 BPM steps, then refine around the winner in 0.01 BPM steps
+    let coarse = best_tempo(&onsets, (0..=((MAX_BPM - MIN_BPM) * 2.0) as u32).map(|i| MIN_BPM + i as f32 * 0.5))?;
+    let (bpm, phase) = best_tempo(&onsets, (0..=100).map(|i| coarse.0 - 0.5 + i as f32 * 0.01))?;
+
+    Some(BeatGrid { bpm, first_beat_seconds: phase })
+}
+
+/// Highest-scoring (bpm, phase seconds) among `candidates`
+fn best_tempo(onsets: &[(f64, f64)], candidates: impl Iterator<Item = f32>) -> Option<(f32, f32)> {
+    candidates
+        .map(|bpm| {
+            let (score, phase) = tempo_score(onsets, bpm);
+            (score, bpm, phase)
+        })
+        .filter(|(score, _, _)| *score > 0.0)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, bpm, phase)| (bpm, phase))
+}
+
+/// How tightly the onsets cluster on one phase of the beat period, and that phase in seconds
+fn tempo_score(onsets: &[(f64, f64)], bpm: f32) -> (f64, f32) {
+    let period = 60.0 / bpm as f64;
+    let (re, im) = onsets.iter().fold((0.0, 0.0), |(re, im), &(time, weight)| {
+        let angle = TAU * time / period;
+        (re + weight * angle.cos(), im + weight * angle.sin())
+    });
+
+    let octaves = (bpm / PREFERRED_BPM).log2() / PRIOR_WIDTH_OCTAVES;
+    let prior = (-0.5 * octaves * octaves).exp() as f64;
+    let phase = (im.atan2(re) / TAU).rem_euclid(1.0) * period;
+    (re.hypot(im) * prior, phase as f32)
+}
@@ -1,7 +1,29 @@
-use super::{AudioAnalyzer, RawAudioFeatures};
+use super::{AnalysisProfile, AudioAnalyzer, FeatureMask, RawAudioFeatures};
 use super::fft::AudioAnalyzer as CpuAnalyzer;
-use anyhow::Result;
+use super::spectral_whitening::SpectralWhitener;
+use super::mel_bands::{MelFilterbank, MEL_BANDS};
+use super::tempo_detector::TempoDetector;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Bin level treated as silence by the log-magnitude band mapping
+pub const LOG_MAGNITUDE_FLOOR_DB: f32 = -80.0;
+
+const BEAT_HISTORY_SECONDS: f32 = 1.0; // Recent beat strength a beat has to stand out from
+const BEAT_ONSET_RATIO: f32 = 1.5; // How far above the recent mean a beat's strength rises
+const BEAT_STRENGTH_FLOOR: f32 = 1e-3; // Raw strength below which nothing counts (near-silence)
+
+/// Accumulated per-stage analysis time, collected when stage timing is enabled
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct StageTimings {
+    pub chunks: usize,
+    pub fft: Duration,      // Windowing + FFT
+    pub features: Duration, // Band extraction + spectral/temporal features
+    pub beat: Duration,     // Beat strength + BPM estimation
+}
 
 /// CPU-based audio analyzer that implements the common AudioAnalyzer trait
 /// This wraps the existing CPU FFT analyzer and outputs raw features
@@ -10,6 +32,20 @@ pub struct CpuAudioAnalyzer {
     inner: CpuAnalyzer,
     sample_rate: f32,
     chunk_size: usize,
+    fft_size: usize,
+    history: Vec<f32>, // Last `fft_size` samples, kept when the FFT is longer than the hop
+    whitener: Option<SpectralWhitener>,
+    timings: Option<StageTimings>,
+    last_spectrum: Vec<f32>,
+    log_magnitude: bool,
+    mel_filterbank: Option<MelFilterbank>, // Built when mel bands are enabled
+    last_mel_bands: Vec<f32>,
+    profile: AnalysisProfile,
+    features: FeatureMask,
+    tempo: TempoDetector,
+    chunk_interval: f32, // Seconds of audio between successive chunks, for beat timing
+    beat_history: VecDeque<f32>, // Raw beat strength over the last `BEAT_HISTORY_SECONDS`
+    in_beat: bool, // From a beat until the strength falls back to the recent mean
 }
 
 impl CpuAudioAnalyzer {
@@ -20,8 +56,125 @@ impl CpuAudioAnalyzer {
             inner,
             sample_rate,
             chunk_size,
+            fft_size: chunk_size,
+            history: Vec::new(),
+            whitener: None,
+            timings: None,
+            last_spectrum: Vec::new(),
+            log_magnitude: false,
+            mel_filterbank: None,
+            last_mel_bands: Vec::new(),
+            profile: AnalysisProfile::Full,
+            features: FeatureMask::all(),
+            tempo: TempoDetector::new(),
+            chunk_interval: chunk_size as f32 / sample_rate,
+            beat_history: VecDeque::new(),
+            in_beat: false,
         })
     }
+
+    /// Use an FFT of `fft_size` samples while `chunk_size` stays the hop between frames.
+    /// Larger sizes resolve low bass better; each FFT covers the most recent `fft_size`
+    /// samples (zero-padded while the stream is shorter). Sizes that aren't a power of two
+    /// (from 2 up) are rejected, leaving the size as it was.
+    #[allow(dead_code)]
+    pub fn set_fft_size(&mut self, fft_size: usize) -> Result<()> {
+        if fft_size < 2 || !fft_size.is_power_of_two() {
+            return Err(anyhow!("FFT size must be a power of two (e.g. 2048 or 4096), not {}", fft_size));
+        }
+        self.fft_size = fft_size;
+        self.history = vec![0.0; fft_size];
+        if self.mel_filterbank.is_some() {
+            self.set_mel_bands(true); // The filterbank is laid out for the old bin count
+        }
+        Ok(())
+    }
+
+    /// FFT length in samples (equal to the chunk size unless `set_fft_size` was called)
+    #[allow(dead_code)]
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Hz between FFT bins
+    #[allow(dead_code)]
+    pub fn frequency_resolution(&self) -> f32 {
+        self.sample_rate / self.fft_size as f32
+    }
+
+    /// Enable or disable spectral whitening before frequency band extraction
+    #[allow(dead_code)]
+    pub fn set_spectral_whitening(&mut self, enabled: bool) {
+        self.whitener = if enabled { Some(SpectralWhitener::new()) } else { None };
+    }
+
+    /// Whether spectral whitening is enabled
+    #[allow(dead_code)]
+    pub fn spectral_whitening(&self) -> bool {
+        self.whitener.is_some()
+    }
+
+    /// Average bin levels in dB (mapped to 0-1 above `LOG_MAGNITUDE_FLOOR_DB`) instead of linear
+    /// magnitudes when extracting bands, so quiet but audible treble registers the way it is
+    /// heard. Pair with `NormalizationParameters::log_magnitude()`.
+    #[allow(dead_code)]
+    pub fn set_log_magnitude(&mut self, enabled: bool) {
+        self.log_magnitude = enabled;
+    }
+
+    #[allow(dead_code)]
+    pub fn log_magnitude(&self) -> bool {
+        self.log_magnitude
+    }
+
+    /// Also compute a `MEL_BANDS`-band mel spectrum each chunk (see `AudioAnalyzer::last_mel_bands`),
+    /// in the same dB mapping as `set_log_magnitude`
+    #[allow(dead_code)]
+    pub fn set_mel_bands(&mut self, enabled: bool) {
+        self.mel_filterbank = enabled.then(|| MelFilterbank::new(self.sample_rate, self.fft_size / 2, MEL_BANDS));
+        self.last_mel_bands.clear();
+    }
+
+    #[allow(dead_code)]
+    pub fn mel_bands(&self) -> bool {
+        self.mel_filterbank.is_some()
+    }
+
+    /// `AnalysisProfile::Lite` skips the spectral shape and temporal features, which come out as 0
+    #[allow(dead_code)]
+    pub fn set_profile(&mut self, profile: AnalysisProfile) {
+        self.profile = profile;
+    }
+
+    #[allow(dead_code)]
+    pub fn profile(&self) -> AnalysisProfile {
+        self.profile
+    }
+
+    /// Compute only the optional features in `features` (further limited by the profile);
+    /// the rest are skipped and read as 0
+    #[allow(dead_code)]
+    pub fn set_feature_mask(&mut self, features: FeatureMask) {
+        self.features = features;
+    }
+
+    /// The optional features computed each chunk, after the profile's limits
+    #[allow(dead_code)]
+    pub fn feature_mask(&self) -> FeatureMask {
+        self.features & self.profile.features()
+    }
+
+    /// Start (or restart) accumulating per-stage timings for benchmarking
+    #[allow(dead_code)]
+    pub fn enable_stage_timing(&mut self) {
+        self.timings = Some(StageTimings::default());
+    }
+
+    /// Per-stage timings accumulated since `enable_stage_timing`
+    #[allow(dead_code)]
+    pub fn stage_timings(&self) -> Option<&StageTimings> {
+        self.timings.as_ref()
+    }
 }
 
 #[async_trait]
@@ -43,42 +196,119 @@ impl AudioAnalyzer for CpuAudioAnalyzer {
     fn analyzer_type(&self) -> &'static str {
         "CPU"
     }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.history.iter_mut().for_each(|sample| *sample = 0.0);
+        if let Some(whitener) = &mut self.whitener {
+            whitener.reset();
+        }
+        self.last_spectrum.clear();
+        self.last_mel_bands.clear();
+        let smoothing = self.tempo.smoothing();
+        self.tempo = TempoDetector::new();
+        self.tempo.set_smoothing(smoothing);
+        self.beat_history.clear();
+        self.in_beat = false;
+    }
+
+    fn last_spectrum(&self) -> Option<&[f32]> {
+        (!self.last_spectrum.is_empty()).then_some(&self.last_spectrum[..])
+    }
+
+    fn last_mel_bands(&self) -> Option<&[f32]> {
+        (!self.last_mel_bands.is_empty()).then_some(&self.last_mel_bands[..])
+    }
+
+    fn set_chunk_interval(&mut self, seconds: f32) {
+        if seconds.is_finite() && seconds > 0.0 {
+            self.chunk_interval = seconds;
+        }
+    }
+
+    fn set_bpm_smoothing(&mut self, smoothing: f32) {
+        self.tempo.set_smoothing(smoothing);
+    }
 }
 
 impl CpuAudioAnalyzer {
     /// Extract raw features before normalization by replicating CPU analyzer logic
     fn extract_raw_features(&mut self, audio_data: &[f32]) -> RawAudioFeatures {
+        let stage_start = self.timings.as_ref().map(|_| Instant::now());
+
         // Apply the same windowing and FFT as the inner analyzer
-        let windowed_data = self.apply_window(audio_data);
+        let windowed_data = if self.fft_size == self.chunk_size {
+            self.apply_window(audio_data)
+        } else {
+            let excess = (self.history.len() + audio_data.len()).saturating_sub(self.fft_size);
+            self.history.drain(..excess.min(self.history.len()));
+            self.history.extend_from_slice(&audio_data[audio_data.len().saturating_sub(self.fft_size)..]);
+            self.apply_window(&self.history)
+        };
         let spectrum = self.compute_fft(&windowed_data);
-        let raw_frequency_bands = self.extract_raw_frequency_bands(&spectrum);
+        let fft_done = stage_start.map(|_| Instant::now());
+
+        // Whitening only affects band extraction; spectral features use the real spectrum
+        let whitened = self.whitener.as_mut().map(|whitener| whitener.whiten(&spectrum));
+        let band_spectrum = whitened.as_deref().unwrap_or(&spectrum);
+        let raw_frequency_bands = self.extract_raw_frequency_bands(band_spectrum);
+        // Beat strength keeps using linear bands, so its tuning is unaffected by the dB mapping
+        let reported_bands = if self.log_magnitude {
+            let levels = self.magnitude_levels(band_spectrum);
+            self.extract_raw_frequency_bands(&levels)
+        } else {
+            raw_frequency_bands.clone()
+        };
 
         // Calculate volume (RMS) - raw value
         let volume = (audio_data.iter().map(|x| x * x).sum::<f32>() / audio_data.len() as f32).sqrt();
 
-        // Advanced analysis features - raw values
-        let spectral_centroid = self.calculate_spectral_centroid(&spectrum);
-        let spectral_rolloff = self.calculate_spectral_rolloff(&spectrum);
-        let zero_crossing_rate = self.calculate_zero_crossing_rate(audio_data);
-        let spectral_flux = self.calculate_spectral_flux(&spectrum);
-        let onset_strength = self.calculate_onset_strength(&spectrum);
-        let pitch_confidence = self.calculate_pitch_confidence(&spectrum);
+        // Advanced analysis features - raw values, skipping any left out of the mask
+        let features = self.feature_mask();
+        let spectral_centroid = if features.contains(FeatureMask::CENTROID) { self.calculate_spectral_centroid(&spectrum) } else { 0.0 };
+        let spectral_rolloff = if features.contains(FeatureMask::ROLLOFF) { self.calculate_spectral_rolloff(&spectrum) } else { 0.0 };
+        let zero_crossing_rate = if features.contains(FeatureMask::ZERO_CROSSING_RATE) { self.calculate_zero_crossing_rate(audio_data) } else { 0.0 };
+        let spectral_flux = if features.contains(FeatureMask::FLUX) { self.calculate_spectral_flux(&spectrum) } else { 0.0 };
+        let onset_strength = if features.contains(FeatureMask::ONSET) { self.calculate_onset_strength(&spectrum) } else { 0.0 };
+        let pitch_confidence = if features.contains(FeatureMask::PITCH_CONFIDENCE) { self.calculate_pitch_confidence(&spectrum) } else { 0.0 };
 
         // Update volume history for dynamic range calculation
-        let dynamic_range = self.calculate_dynamic_range(volume);
+        let dynamic_range = if features.contains(FeatureMask::DYNAMIC_RANGE) { self.calculate_dynamic_range(volume) } else { 0.0 };
+        let features_done = stage_start.map(|_| Instant::now());
 
         // Run beat detection on raw frequency bands
         let beat_strength = self.calculate_beat_strength(&raw_frequency_bands);
 
-        // Update BPM estimation
-        let estimated_bpm = self.update_bpm_estimation(beat_strength > 0.3);
+        // Update BPM estimation from the spacing of detected beats
+        let beat_detected = self.detect_beat(beat_strength);
+        let estimated_bpm = self.tempo.update(beat_detected, self.chunk_interval);
+
+        if let (Some(timings), Some(start), Some(fft_done), Some(features_done)) =
+            (&mut self.timings, stage_start, fft_done, features_done)
+        {
+            timings.chunks += 1;
+            timings.fft += fft_done - start;
+            timings.features += features_done - fft_done;
+            timings.beat += features_done.elapsed();
+        }
+
+        if let Some(filterbank) = &mut self.mel_filterbank {
+            if filterbank.num_bins() != spectrum.len() {
+                *filterbank = MelFilterbank::new(self.sample_rate, spectrum.len(), MEL_BANDS); // FFT size changed
+            }
+            let magnitudes = filterbank.apply(&spectrum);
+            self.last_mel_bands = self.magnitude_levels(&magnitudes).into_iter().map(|level| level.min(1.0)).collect();
+        }
+
+        // Kept for spectrum displays (see `AudioAnalyzer::last_spectrum`)
+        self.last_spectrum = spectrum;
 
         RawAudioFeatures {
-            sub_bass: raw_frequency_bands.sub_bass,
-            bass: raw_frequency_bands.bass,
-            mid: raw_frequency_bands.mid,
-            treble: raw_frequency_bands.treble,
-            presence: raw_frequency_bands.presence,
+            sub_bass: reported_bands.sub_bass,
+            bass: reported_bands.bass,
+            mid: reported_bands.mid,
+            treble: reported_bands.treble,
+            presence: reported_bands.presence,
             spectral_centroid,
             spectral_rolloff,
             spectral_flux,
@@ -95,7 +325,7 @@ impl CpuAudioAnalyzer {
     // Helper methods that replicate the CPU analyzer's internal logic
 
     fn apply_window(&self, audio_data: &[f32]) -> Vec<f32> {
-        let len = self.chunk_size.min(audio_data.len());
+        let len = self.fft_size.min(audio_data.len());
         // Hann window
         (0..len)
             .map(|i| {
@@ -109,7 +339,7 @@ impl CpuAudioAnalyzer {
         use rustfft::{FftPlanner, num_complex::Complex};
 
         let mut planner = FftPlanner::new();
-        let fft = planner.plan_fft_forward(self.chunk_size);
+        let fft = planner.plan_fft_forward(self.fft_size);
 
         let mut buffer: Vec<Complex<f32>> = windowed_data
             .iter()
@@ -117,20 +347,35 @@ impl CpuAudioAnalyzer {
             .collect();
 
         // Pad with zeros if needed
-        buffer.resize(self.chunk_size, Complex::new(0.0, 0.0));
+        buffer.resize(self.fft_size, Complex::new(0.0, 0.0));
 
         fft.process(&mut buffer);
 
-        // Convert to magnitudes
+        // Convert to magnitudes. A longer FFT gathers more energy into each tonal bin, so scale
+        // back to the chunk-sized level the normalizer's ranges were tuned for.
+        let scale = self.chunk_size as f32 / self.fft_size as f32;
         buffer.iter()
-            .take(self.chunk_size / 2)
-            .map(|c| (c.re * c.re + c.im * c.im).sqrt())
+            .take(self.fft_size / 2)
+            .map(|c| (c.re * c.re + c.im * c.im).sqrt() * scale)
+            .collect()
+    }
+
+    /// `20*log10` of each bin relative to a full-scale sine (a Hann-windowed sine of amplitude 1
+    /// peaks at `chunk_size / 4`), floored at `LOG_MAGNITUDE_FLOOR_DB` and mapped so the floor
+    /// is 0 and full scale is 1
+    fn magnitude_levels(&self, spectrum: &[f32]) -> Vec<f32> {
+        let full_scale = self.chunk_size as f32 / 4.0;
+        spectrum.iter()
+            .map(|&magnitude| {
+                let db = 20.0 * (magnitude / full_scale + 1e-9).log10();
+                (1.0 - db / LOG_MAGNITUDE_FLOOR_DB).max(0.0)
+            })
             .collect()
     }
 
     fn extract_raw_frequency_bands(&self, spectrum: &[f32]) -> RawFrequencyBands {
         let sample_rate = self.sample_rate;
-        let fft_size = self.chunk_size;
+        let fft_size = self.fft_size;
 
         let mut bass = 0.0;
         let mut mid = 0.0;
@@ -180,7 +425,7 @@ impl CpuAudioAnalyzer {
         let mut magnitude_sum = 0.0;
 
         for (i, &magnitude) in spectrum.iter().enumerate() {
-            let frequency = (i as f32 * self.sample_rate) / self.chunk_size as f32;
+            let frequency = (i as f32 * self.sample_rate) / self.fft_size as f32;
             weighted_sum += frequency * magnitude;
             magnitude_sum += magnitude;
         }
@@ -200,7 +445,7 @@ impl CpuAudioAnalyzer {
         for (i, &magnitude) in spectrum.iter().enumerate() {
             cumulative_energy += magnitude * magnitude;
             if cumulative_energy >= threshold {
-                return (i as f32 * self.sample_rate) / self.chunk_size as f32;
+                return (i as f32 * self.sample_rate) / self.fft_size as f32;
             }
         }
 
@@ -268,17 +513,176 @@ impl CpuAudioAnalyzer {
         bands.bass + bands.sub_bass * 0.5
     }
 
-    fn update_bpm_estimation(&self, _beat_detected: bool) -> f32 {
-        // Simplified BPM estimation - return a reasonable default
-        120.0 // In real implementation, track beat intervals
+    /// Note this chunk's beat strength and report a beat when it first rises clear of the
+    /// recent mean, so the decision adapts to the track's level
+    fn detect_beat(&mut self, beat_strength: f32) -> bool {
+        let mean = if self.beat_history.is_empty() {
+            0.0
+        } else {
+            self.beat_history.iter().sum::<f32>() / self.beat_history.len() as f32
+        };
+        let beat_detected = !self.in_beat
+            && beat_strength > BEAT_STRENGTH_FLOOR
+            && beat_strength > mean * BEAT_ONSET_RATIO;
+        if beat_detected {
+            self.in_beat = true;
+        } else if beat_strength <= mean {
+            // Re-armed only once the strength falls back to the mean, so a kick's ragged decay
+            // doesn't count twice
+            self.in_beat = false;
+        }
+
+        let history_len = ((BEAT_HISTORY_SECONDS / self.chunk_interval).ceil() as usize).max(1);
+        self.beat_history.push_back(beat_strength);
+        while self.beat_history.len() > history_len {
+            self.beat_history.pop_front();
+        }
+        beat_detected
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RawFrequencyBands {
     sub_bass: f32,
     bass: f32,
     mid: f32,
     treble: f32,
     presence: f32,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_log_magnitude_lifts_quiet_treble() {
+        // Broadband noise at about -30 dBFS, like hi-hats in a mix (each bin is far quieter)
+        let mut seed = 1u32;
+        let noise: Vec<f32> = (0..512)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                ((seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * 0.05
+            })
+            .collect();
+
+        let mut linear = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        let mut log = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        log.set_log_magnitude(true);
+        let linear_features = linear.analyze_chunk(&noise).await.unwrap();
+        let log_features = log.analyze_chunk(&noise).await.unwrap();
+
+        // Linear magnitudes put quiet treble at a tiny fraction of full scale; levels put it mid-range
+        let full_scale = 512.0 / 4.0;
+        assert!(linear_features.treble / full_scale < 0.01);
+        assert!(log_features.treble > 0.2 && log_features.treble < 0.8, "treble level {}", log_features.treble);
+        assert_eq!(log_features.beat_strength, linear_features.beat_strength);
+
+        // Silence sits on the floor
+        assert_eq!(log.analyze_chunk(&[0.0; 512]).await.unwrap().presence, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_lite_profile_keeps_bands_and_beats() {
+        // 60 Hz kick-like tone with some upper harmonics
+        let tone: Vec<f32> = (0..512)
+            .map(|i| {
+                let phase = i as f32 * 60.0 * std::f32::consts::TAU / 44100.0;
+                0.8 * phase.sin() + 0.2 * (phase * 7.0).sin()
+            })
+            .collect();
+
+        let mut full = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        let mut lite = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        lite.set_profile(AnalysisProfile::Lite);
+        let full_features = full.analyze_chunk(&tone).await.unwrap();
+        let lite_features = lite.analyze_chunk(&tone).await.unwrap();
+
+        assert_eq!(lite_features.bass, full_features.bass);
+        assert_eq!(lite_features.beat_strength, full_features.beat_strength);
+        assert_eq!(lite_features.spectral_centroid, full_features.spectral_centroid);
+        assert!(full_features.onset_strength > 0.0 && full_features.zero_crossing_rate > 0.0);
+        assert_eq!((lite_features.onset_strength, lite_features.zero_crossing_rate, lite_features.spectral_flux), (0.0, 0.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_masked_features_are_skipped_and_the_rest_unchanged() {
+        let tone: Vec<f32> = (0..512).map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 44100.0).sin() * 0.5).collect();
+
+        let mut full = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        let mut masked = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        let skipped: FeatureMask = "pitch, rolloff,centroid".parse().unwrap();
+        masked.set_feature_mask(FeatureMask::all() - skipped);
+        let full_features = full.analyze_chunk(&tone).await.unwrap();
+        let masked_features = masked.analyze_chunk(&tone).await.unwrap();
+
+        assert!(full_features.spectral_centroid > 0.0 && full_features.spectral_rolloff > 0.0);
+        assert_eq!((masked_features.spectral_centroid, masked_features.spectral_rolloff, masked_features.pitch_confidence), (0.0, 0.0, 0.0));
+        assert_eq!(masked_features.spectral_flux, full_features.spectral_flux);
+        assert_eq!(masked_features.zero_crossing_rate, full_features.zero_crossing_rate);
+        assert_eq!(masked_features.mid, full_features.mid);
+
+        // The profile still applies on top of the mask
+        masked.set_profile(AnalysisProfile::Lite);
+        assert_eq!(masked.feature_mask(), FeatureMask::DYNAMIC_RANGE);
+        assert!("pitch,tempo".parse::<FeatureMask>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bpm_follows_a_kick_pattern() {
+        // 60 Hz kicks decaying over about 100 ms at 128 BPM, over a quiet 220 Hz hum
+        let sample_rate = 44100.0;
+        let samples: Vec<f32> = (0..(sample_rate * 12.0) as usize)
+            .map(|i| {
+                let t = i as f32 / sample_rate;
+                let since_kick = t % (60.0 / 128.0);
+                0.8 * (-since_kick * 30.0).exp() * (t * 60.0 * std::f32::consts::TAU).sin()
+                    + 0.02 * (t * 220.0 * std::f32::consts::TAU).sin()
+            })
+            .collect();
+
+        let mut analyzer = CpuAudioAnalyzer::new(sample_rate, 512).unwrap();
+        let mut bpm = 0.0;
+        for chunk in samples.chunks_exact(512) {
+            bpm = analyzer.analyze_chunk(chunk).await.unwrap().estimated_bpm;
+        }
+        assert!((bpm - 128.0).abs() < 2.0, "back-to-back chunks estimated {:.1} BPM", bpm);
+
+        // Half-overlapping windows only keep time once the analyzer knows their spacing
+        analyzer.reset();
+        analyzer.set_chunk_interval(256.0 / sample_rate);
+        for start in (0..samples.len() - 512).step_by(256) {
+            bpm = analyzer.analyze_chunk(&samples[start..start + 512]).await.unwrap().estimated_bpm;
+        }
+        assert!((bpm - 128.0).abs() < 2.0, "overlapping windows estimated {:.1} BPM", bpm);
+    }
+
+    #[tokio::test]
+    async fn test_fft_size_sets_the_bins_and_rejects_bad_sizes() {
+        // A 50 Hz tone: between bins at 512 points, resolved at 4096
+        let tone: Vec<f32> = (0..4096)
+            .map(|i| (i as f32 * 50.0 * std::f32::consts::TAU / 44100.0).sin())
+            .collect();
+        let mut analyzer = CpuAudioAnalyzer::new(44100.0, 512).unwrap();
+        analyzer.set_mel_bands(true);
+        analyzer.analyze_chunk(&tone[..512]).await.unwrap();
+        assert_eq!(analyzer.last_spectrum().map(<[f32]>::len), Some(256));
+        assert!((analyzer.frequency_resolution() - 86.13).abs() < 0.01);
+
+        analyzer.set_fft_size(4096).unwrap();
+        assert_eq!(analyzer.fft_size(), 4096);
+        assert!((analyzer.frequency_resolution() - 10.77).abs() < 0.01);
+        for chunk in tone.chunks_exact(512) {
+            analyzer.analyze_chunk(chunk).await.unwrap();
+        }
+        let spectrum = analyzer.last_spectrum().unwrap();
+        assert_eq!(spectrum.len(), 2048);
+        let peak = (0..spectrum.len()).max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b])).unwrap();
+        assert!((4..=5).contains(&peak), "50 Hz peaked in bin {}", peak);
+        assert_eq!(analyzer.last_mel_bands().map(<[f32]>::len), Some(MEL_BANDS));
+
+        for bad in [0, 1, 1000, 4097] {
+            assert!(analyzer.set_fft_size(bad).is_err(), "{} was accepted", bad);
+        }
+        assert_eq!(analyzer.fft_size(), 4096);
+    }
+}
@@ -0,0 +1,260 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::AudioFrame;
+
+/// Attack/release envelope follower for a single 0.0-1.0 feature.
+///
+/// Rising input is tracked with the (fast) attack time constant and falling input with the
+/// (slow) release time constant, so transients hit immediately while quiet passages decay
+/// naturally to zero instead of snapping or being floored.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct EnvelopeFollower {
+    attack_time: f32,  // seconds
+    release_time: f32, // seconds
+    value: f32,
+}
+
+#[allow(dead_code)]
+impl EnvelopeFollower {
+    pub fn new(attack_time: f32, release_time: f32) -> Self {
+        Self {
+            attack_time: attack_time.max(0.0),
+            release_time: release_time.max(0.0),
+            value: 0.0,
+        }
+    }
+
+    /// Advance the envelope towards `target` over `delta_time` seconds
+    pub fn process(&mut self, target: f32, delta_time: f32) -> f32 {
+        let time_constant = if target > self.value { self.attack_time } else { self.release_time };
+
+        let coefficient = if time_constant <= f32::EPSILON {
+            1.0
+        } else {
+            1.0 - (-delta_time / time_constant).exp()
+        };
+
+        self.value += (target - self.value) * coefficient;
+        self.value
+    }
+
+    pub fn set_times(&mut self, attack_time: f32, release_time: f32) {
+        self.attack_time = attack_time.max(0.0);
+        self.release_time = release_time.max(0.0);
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn reset(&mut self) {
+        self.value = 0.0;
+    }
+}
+
+/// Attack/release time constants in seconds for one feature group
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AttackRelease {
+    /// How fast the feature rises
+    pub attack: f32,
+    /// How slowly the feature falls back
+    pub release: f32,
+}
+
+impl AttackRelease {
+    pub const fn new(attack: f32, release: f32) -> Self {
+        Self { attack, release }
+    }
+}
+
+/// Independent attack/release per feature group, so e.g. bass can be sluggish while
+/// treble stays snappy. Loadable from JSON:
+///
+/// ```json
+/// {
+///   "per_band": [
+///     { "attack": 0.05, "release": 0.6 },
+///     { "attack": 0.04, "release": 0.5 },
+///     { "attack": 0.02, "release": 0.25 },
+///     { "attack": 0.01, "release": 0.12 },
+///     { "attack": 0.01, "release": 0.1 }
+///   ],
+///   "beat": { "attack": 0.005, "release": 0.2 },
+///   "spectral": { "attack": 0.02, "release": 0.3 }
+/// }
+/// ```
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmoothingConfig {
+    /// Sub-bass, bass, mid, treble, presence
+    pub per_band: [AttackRelease; 5],
+    /// Beat strength and onset strength
+    pub beat: AttackRelease,
+    /// Volume, spectral flux and dynamic range
+    pub spectral: AttackRelease,
+}
+
+#[allow(dead_code)]
+impl SmoothingConfig {
+    /// Same attack/release for every feature group
+    pub fn uniform(attack: f32, release: f32) -> Self {
+        let times = AttackRelease::new(attack, release);
+        Self {
+            per_band: [times; 5],
+            beat: times,
+            spectral: times,
+        }
+    }
+
+    /// Load a smoothing configuration from a JSON file
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Save this configuration as pretty-printed JSON
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        // Near-instant attack for transients, smooth decay through quiet passages
+        Self::uniform(0.01, 0.25)
+    }
+}
+
+/// Settings for the audio→visual mapping applied after normalization
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct EnvelopeConfig {
+    /// Attack/release times per feature group
+    pub smoothing: SmoothingConfig,
+    /// Constant added to reactive features (0.0 keeps silence at zero)
+    pub baseline_boost: f32,
+    /// Multiplier applied to reactive features before clamping to 0.0-1.0
+    pub dynamic_boost: f32,
+}
+
+impl Default for EnvelopeConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: SmoothingConfig::default(),
+            baseline_boost: 0.0, // Silence should look like silence
+            dynamic_boost: 2.0,  // Extra multiplier for better dynamic range
+        }
+    }
+}
+
+/// Per-feature attack/release envelopes for the reactive parts of an `AudioFrame`.
+///
+/// Only features that drive visual intensity are enveloped (bands, beat strength, volume,
+/// flux, onset, dynamic range); descriptive features like centroid and BPM pass through.
+#[allow(dead_code)]
+pub struct AudioEnvelope {
+    config: EnvelopeConfig,
+    sub_bass: EnvelopeFollower,
+    bass: EnvelopeFollower,
+    mid: EnvelopeFollower,
+    treble: EnvelopeFollower,
+    presence: EnvelopeFollower,
+    beat_strength: EnvelopeFollower,
+    volume: EnvelopeFollower,
+    spectral_flux: EnvelopeFollower,
+    onset_strength: EnvelopeFollower,
+    dynamic_range: EnvelopeFollower,
+}
+
+#[allow(dead_code)]
+impl AudioEnvelope {
+    pub fn new(config: EnvelopeConfig) -> Self {
+        let follower = |times: AttackRelease| EnvelopeFollower::new(times.attack, times.release);
+        let smoothing = &config.smoothing;
+        Self {
+            sub_bass: follower(smoothing.per_band[0]),
+            bass: follower(smoothing.per_band[1]),
+            mid: follower(smoothing.per_band[2]),
+            treble: follower(smoothing.per_band[3]),
+            presence: follower(smoothing.per_band[4]),
+            beat_strength: follower(smoothing.beat),
+            volume: follower(smoothing.spectral),
+            spectral_flux: follower(smoothing.spectral),
+            onset_strength: follower(smoothing.beat),
+            dynamic_range: follower(smoothing.spectral),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &EnvelopeConfig {
+        &self.config
+    }
+
+    /// Replace the configuration, keeping current envelope levels
+    pub fn set_config(&mut self, config: EnvelopeConfig) {
+        let smoothing = &config.smoothing;
+        let groups = [
+            smoothing.per_band[0],
+            smoothing.per_band[1],
+            smoothing.per_band[2],
+            smoothing.per_band[3],
+            smoothing.per_band[4],
+            smoothing.beat,
+            smoothing.spectral,
+            smoothing.spectral,
+            smoothing.beat,
+            smoothing.spectral,
+        ];
+        for (follower, times) in self.followers_mut().into_iter().zip(groups) {
+            follower.set_times(times.attack, times.release);
+        }
+        self.config = config;
+    }
+
+    /// Apply the envelopes in place to one frame covering `delta_time` seconds
+    pub fn process(&mut self, frame: &mut AudioFrame, delta_time: f32) {
+        let bands = &mut frame.frequency_bands;
+        bands.sub_bass = self.sub_bass.process(bands.sub_bass, delta_time);
+        bands.bass = self.bass.process(bands.bass, delta_time);
+        bands.mid = self.mid.process(bands.mid, delta_time);
+        bands.treble = self.treble.process(bands.treble, delta_time);
+        bands.presence = self.presence.process(bands.presence, delta_time);
+
+        frame.beat_strength = self.beat_strength.process(frame.beat_strength, delta_time);
+        frame.volume = self.volume.process(frame.volume, delta_time);
+        frame.spectral_flux = self.spectral_flux.process(frame.spectral_flux, delta_time);
+        frame.onset_strength = self.onset_strength.process(frame.onset_strength, delta_time);
+        frame.dynamic_range = self.dynamic_range.process(frame.dynamic_range, delta_time);
+    }
+
+    /// Drop all envelopes back to zero
+    pub fn reset(&mut self) {
+        for follower in self.followers_mut() {
+            follower.reset();
+        }
+    }
+
+    fn followers_mut(&mut self) -> [&mut EnvelopeFollower; 10] {
+        [
+            &mut self.sub_bass,
+            &mut self.bass,
+            &mut self.mid,
+            &mut self.treble,
+            &mut self.presence,
+            &mut self.beat_strength,
+            &mut self.volume,
+            &mut self.spectral_flux,
+            &mut self.onset_strength,
+            &mut self.dynamic_range,
+        ]
+    }
+}
+
+impl Default for AudioEnvelope {
+    fn default() -> Self {
+        Self::new(EnvelopeConfig::default())
+    }
+}
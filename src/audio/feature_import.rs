@@ -0,0 +1,297 @@
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::feature_normalizer::DEFAULT_BEAT_THRESHOLD;
+use super::prescan::{AnalysisStatistics, FileInfo, PrescanData, PrescanFrame};
+use super::FrequencyBands;
+
+/// Nominal sample rate recorded for imported streams (they carry no audio)
+const NOMINAL_SAMPLE_RATE: f32 = 44100.0;
+
+/// Feature columns understood by the importer, with the aliases other tools tend to use
+const COLUMNS: &[(&str, &[&str])] = &[
+    ("sub_bass", &["subbass"]),
+    ("bass", &["low"]),
+    ("mid", &["mids", "middle"]),
+    ("treble", &["high", "highs"]),
+    ("presence", &["air"]),
+    ("beat_strength", &["beat", "beats"]),
+    ("beat_detected", &["is_beat", "onset_detected"]),
+    ("estimated_bpm", &["bpm", "tempo"]),
+    ("spectral_centroid", &["centroid", "brightness"]),
+    ("spectral_rolloff", &["rolloff"]),
+    ("pitch_confidence", &["pitch", "tonality"]),
+    ("zero_crossing_rate", &["zcr"]),
+    ("spectral_flux", &["flux"]),
+    ("onset_strength", &["onset", "onset_envelope"]),
+    ("dynamic_range", &["dynamics"]),
+    ("volume", &["rms", "loudness", "energy"]),
+];
+
+/// Columns kept in their own units instead of being rescaled to 0.0-1.0
+const UNSCALED_COLUMNS: &[&str] = &["estimated_bpm", "beat_detected"];
+
+/// Import per-frame features exported by another tool (CSV or JSON) as `PrescanData`.
+///
+/// - CSV: a header row naming the columns, then one row per frame
+/// - JSON: an array of objects, or an object with a `frames` array
+///
+/// `timestamp` (seconds) is required, along with at least one feature column; missing
+/// features are zero. Aliases such as `beat`, `bpm`, `rms` or `centroid` map onto the standard
+/// fields. Any feature column with values outside 0.0-1.0 is rescaled from its minimum (0.0) to
+/// its maximum (1.0), so Hz or dB exports still land in the range the visuals expect (a column
+/// stuck at one out-of-range value is clamped instead). Without a `beat_detected`
+/// column, beats are frames whose beat strength exceeds the default beat threshold.
+#[allow(dead_code)]
+pub fn import_feature_stream<P: AsRef<Path>>(path: P) -> Result<PrescanData> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read feature stream {}", path.display()))?;
+
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let rows = if is_json { parse_json_rows(&text)? } else { parse_csv_rows(&text)? };
+    let frames = rows_to_frames(rows)?;
+
+    let frame_interval = median_interval(&frames);
+    let chunk_size = ((frame_interval * NOMINAL_SAMPLE_RATE).round() as usize).max(1);
+    let duration_seconds = frames.last().map_or(0.0, |frame| frame.timestamp) + frame_interval;
+    let statistics = AnalysisStatistics::from_frames(&frames);
+
+    info!("📥 Imported {} frames ({:.1}s, {:.1} Hz) from {}",
+          frames.len(), duration_seconds, 1.0 / frame_interval, path.display());
+
+    Ok(PrescanData {
+        file_info: FileInfo {
+            filename: path.to_string_lossy().to_string(),
+            duration_seconds,
+            sample_rate: NOMINAL_SAMPLE_RATE,
+            total_samples: chunk_size * frames.len(),
+            frame_rate: 1.0 / frame_interval,
+            chunk_size,
+            time_offset_seconds: frames.first().map_or(0.0, |frame| frame.timestamp),
+        },
+        frames,
+        statistics,
+    })
+}
+
+/// Map a column name onto its canonical field name, if it is one we understand
+fn canonical_column(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+    if matches!(name.as_str(), "timestamp" | "time" | "t" | "seconds") {
+        return Some("timestamp");
+    }
+    COLUMNS.iter()
+        .find(|(canonical, aliases)| *canonical == name || aliases.contains(&name.as_str()))
+        .map(|(canonical, _)| *canonical)
+}
+
+fn parse_csv_rows(text: &str) -> Result<Vec<HashMap<&'static str, f32>>> {
+    let mut lines = text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+    let (_, header) = lines.next().ok_or_else(|| anyhow!("Feature stream CSV is empty"))?;
+    let columns: Vec<Option<&'static str>> = header.split(',').map(canonical_column).collect();
+
+    let ignored: Vec<&str> = header.split(',')
+        .zip(&columns)
+        .filter(|(_, canonical)| canonical.is_none())
+        .map(|(name, _)| name.trim())
+        .collect();
+    if !ignored.is_empty() {
+        warn!("⚠️  Ignoring unknown feature columns: {}", ignored.join(", "));
+    }
+
+    lines.map(|(line_index, line)| {
+        let mut row = HashMap::new();
+        for (cell, column) in line.split(',').zip(&columns) {
+            let (Some(column), cell) = (column, cell.trim()) else { continue };
+            if cell.is_empty() {
+                continue; // Treated like a missing optional field
+            }
+            let value = parse_cell(cell)
+                .ok_or_else(|| anyhow!("Line {}: '{}' is not a number for column {}", line_index + 1, cell, column))?;
+            row.insert(*column, value);
+        }
+        Ok(row)
+    }).collect()
+}
+
+fn parse_json_rows(text: &str) -> Result<Vec<HashMap<&'static str, f32>>> {
+    let root: Value = serde_json::from_str(text)?;
+    let frames = match &root {
+        Value::Array(frames) => frames,
+        Value::Object(object) => object.get("frames")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Feature stream JSON object has no 'frames' array"))?,
+        _ => return Err(anyhow!("Feature stream JSON must be an array of frames")),
+    };
+
+    frames.iter().enumerate().map(|(index, frame)| {
+        let object = frame.as_object()
+            .ok_or_else(|| anyhow!("Frame {} is not a JSON object", index))?;
+        let mut row = HashMap::new();
+        for (name, value) in object {
+            let Some(column) = canonical_column(name) else { continue };
+            let number = match value {
+                Value::Number(number) => number.as_f64().map(|n| n as f32),
+                Value::Bool(flag) => Some(if *flag { 1.0 } else { 0.0 }),
+                Value::Null => None,
+                _ => return Err(anyhow!("Frame {}: field '{}' is not a number", index, name)),
+            };
+            if let Some(number) = number {
+                row.insert(column, number);
+            }
+        }
+        Ok(row)
+    }).collect()
+}
+
+fn parse_cell(cell: &str) -> Option<f32> {
+    match cell.to_lowercase().as_str() {
+        "true" | "yes" => Some(1.0),
+        "false" | "no" => Some(0.0),
+        other => other.parse::<f32>().ok().filter(|value| value.is_finite()),
+    }
+}
+
+fn rows_to_frames(mut rows: Vec<HashMap<&'static str, f32>>) -> Result<Vec<PrescanFrame>> {
+    if rows.is_empty() {
+        return Err(anyhow!("Feature stream has no frames"));
+    }
+    if let Some(index) = rows.iter().position(|row| !row.contains_key("timestamp")) {
+        return Err(anyhow!("Frame {} has no timestamp (a 'timestamp' column is required)", index));
+    }
+    let has_features = rows.iter().any(|row| row.keys().any(|&column| column != "timestamp"));
+    if !has_features {
+        let known: Vec<&str> = COLUMNS.iter().map(|(canonical, _)| *canonical).collect();
+        return Err(anyhow!("Feature stream has no recognised feature columns (expected some of: {})", known.join(", ")));
+    }
+
+    // Playback looks frames up by binary search, so they must be in time order
+    if rows.windows(2).any(|pair| pair[1]["timestamp"] < pair[0]["timestamp"]) {
+        warn!("⚠️  Feature stream timestamps are out of order; sorting");
+        rows.sort_by(|a, b| a["timestamp"].total_cmp(&b["timestamp"]));
+    }
+
+    // Rescale columns that clearly aren't 0-1 already, as (minimum, range)
+    let mut scales: HashMap<&'static str, (f32, f32)> = HashMap::new();
+    for (column, _) in COLUMNS.iter().filter(|(column, _)| !UNSCALED_COLUMNS.contains(column)) {
+        let (min, max) = rows.iter().filter_map(|row| row.get(column))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| (min.min(value), max.max(value)));
+        if min < 0.0 || max > 1.0 {
+            info!("📏 Rescaling '{}' from {:.3}-{:.3} to 0-1", column, min, max);
+            scales.insert(column, (min, max - min));
+        }
+    }
+
+    let has_beat_flags = rows.iter().any(|row| row.contains_key("beat_detected"));
+
+    Ok(rows.iter().map(|row| {
+        // Missing features stay at zero rather than being shifted with the column
+        let get = |column: &str| match (row.get(column), scales.get(column)) {
+            (None, _) => 0.0,
+            (Some(&value), None) => value,
+            (Some(&value), Some(&(_, range))) if range <= f32::EPSILON => value.clamp(0.0, 1.0),
+            (Some(&value), Some(&(min, range))) => (value - min) / range,
+        };
+        let beat_strength = get("beat_strength");
+
+        PrescanFrame {
+            timestamp: get("timestamp"),
+            frequency_bands: FrequencyBands {
+                sub_bass: get("sub_bass"),
+                bass: get("bass"),
+                mid: get("mid"),
+                treble: get("treble"),
+                presence: get("presence"),
+            },
+            beat_detected: if has_beat_flags { get("beat_detected") > 0.5 } else { beat_strength > DEFAULT_BEAT_THRESHOLD },
+            beat_strength,
+            estimated_bpm: get("estimated_bpm"),
+            spectral_centroid: get("spectral_centroid"),
+            spectral_rolloff: get("spectral_rolloff"),
+            pitch_confidence: get("pitch_confidence"),
+            zero_crossing_rate: get("zero_crossing_rate"),
+            spectral_flux: get("spectral_flux"),
+            onset_strength: get("onset_strength"),
+            dynamic_range: get("dynamic_range"),
+            volume: get("volume"),
+        }
+    }).collect())
+}
+
+/// Typical spacing between frames (robust to the odd dropped or duplicated row)
+fn median_interval(frames: &[PrescanFrame]) -> f32 {
+    let mut intervals: Vec<f32> = frames.windows(2)
+        .map(|pair| pair[1].timestamp - pair[0].timestamp)
+        .filter(|interval| *interval > 0.0)
+        .collect();
+    if intervals.is_empty() {
+        return 512.0 / NOMINAL_SAMPLE_RATE; // Single frame: assume the usual prescan rate
+    }
+    intervals.sort_by(f32::total_cmp);
+    intervals[intervals.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    fn write_stream(name: &str, contents: &str) -> TempPath {
+        let path = TempPath::file(&format!("feature_import_{}", name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_csv_aliases_map_onto_features() {
+        let path = write_stream("aliases.csv", "\
+# exported by another tool
+Time, low, beat, BPM, rms, centroid, dynamics, notes
+0.00, 0.2, 0.9, 128, 0.5, 2000, -30, intro
+0.05, 0.4, 0.1, 128, , 4000, -12, x
+0.10, 0.6, 0.8, 128, 0.7, 1000, -6, y
+");
+        let data = import_feature_stream(&path).unwrap();
+
+        assert_eq!(data.frames.len(), 3);
+        let frame = &data.frames[1];
+        assert_eq!((frame.timestamp, frame.frequency_bands.bass, frame.estimated_bpm), (0.05, 0.4, 128.0));
+        assert_eq!(frame.volume, 0.0, "a blank cell reads as a missing feature");
+        // Hz and dB columns are rescaled from their minimum to their maximum; BPM keeps its units
+        let centroids: Vec<f32> = data.frames.iter().map(|frame| frame.spectral_centroid).collect();
+        assert_eq!(centroids, [1.0 / 3.0, 1.0, 0.0]);
+        let dynamics: Vec<f32> = data.frames.iter().map(|frame| frame.dynamic_range).collect();
+        assert_eq!(dynamics, [0.0, 0.75, 1.0], "negative dB lands in 0-1");
+        // Beats come from the strength without a beat_detected column
+        assert_eq!(data.frames.iter().map(|frame| frame.beat_detected).collect::<Vec<_>>(), [true, false, true]);
+        assert!((data.file_info.frame_rate - 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_json_frames_and_a_required_timestamp() {
+        let object = write_stream("object.json", r#"{"frames": [
+            {"t": 0.5, "highs": 0.3, "is_beat": true, "zcr": null},
+            {"t": 0.0, "highs": 0.1, "is_beat": false}
+        ]}"#);
+        let array = write_stream("array.json", r#"[{"seconds": 0.0, "flux": 0.2}, {"seconds": 0.1, "flux": 0.4}]"#);
+        let untimed = write_stream("untimed.csv", "bass,mid\n0.1,0.2\n");
+        let featureless = write_stream("featureless.json", r#"[{"time": 0.0, "comment": 1}]"#);
+
+        // Out-of-order frames are sorted, and beat_detected flags are taken as given
+        let data = import_feature_stream(&object).unwrap();
+        assert_eq!(data.frames.iter().map(|frame| frame.timestamp).collect::<Vec<_>>(), [0.0, 0.5]);
+        assert_eq!((data.frames[1].frequency_bands.treble, data.frames[1].beat_detected), (0.3, true));
+        assert_eq!(import_feature_stream(&array).unwrap().frames[1].spectral_flux, 0.4);
+
+        let error = import_feature_stream(&untimed).unwrap_err().to_string();
+        assert!(error.contains("timestamp"), "{}", error);
+        assert!(import_feature_stream(&featureless).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use super::{RawAudioFeatures, NormalizedAudioFeatures};
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 
 /// Normalization parameters defining the maximum expected ranges for each audio feature.
 ///
@@ -76,6 +77,67 @@ impl Default for NormalizationParameters {
     }
 }
 
+#[allow(dead_code)]
+impl NormalizationParameters {
+    /// Defaults for `CpuAudioAnalyzer::set_log_magnitude`, whose bands are already 0-1 levels
+    /// above the dB floor; the ceilings sit near the loud end of typical music for each band
+    pub fn log_magnitude() -> Self {
+        Self {
+            sub_bass_max: 0.6,
+            bass_max: 0.7,
+            mid_max: 0.5,
+            treble_max: 0.4,
+            presence_max: 0.3,
+            ..Self::default()
+        }
+    }
+
+    /// Maxima at each feature's raw peak times `headroom`, keyed as in `OBSERVED_FEATURE_NAMES`
+    /// (e.g. an analysis JSON's `raw_feature_peaks`). Features without a positive peak, and the
+    /// BPM range, keep their defaults.
+    pub fn from_peaks(peaks: &HashMap<String, f32>, headroom: f32) -> Self {
+        let defaults = Self::default();
+        let pick = |name: &str, default: f32| {
+            peaks.get(name)
+                .filter(|peak| peak.is_finite() && **peak > 0.0)
+                .map_or(default, |peak| peak * headroom)
+        };
+
+        Self {
+            sub_bass_max: pick("sub_bass", defaults.sub_bass_max),
+            bass_max: pick("bass", defaults.bass_max),
+            mid_max: pick("mid", defaults.mid_max),
+            treble_max: pick("treble", defaults.treble_max),
+            presence_max: pick("presence", defaults.presence_max),
+            spectral_centroid_max: pick("spectral_centroid", defaults.spectral_centroid_max),
+            spectral_rolloff_max: pick("spectral_rolloff", defaults.spectral_rolloff_max),
+            spectral_flux_max: pick("spectral_flux", defaults.spectral_flux_max),
+            zero_crossing_rate_max: pick("zero_crossing_rate", defaults.zero_crossing_rate_max),
+            onset_strength_max: pick("onset_strength", defaults.onset_strength_max),
+            beat_strength_max: pick("beat_strength", defaults.beat_strength_max),
+            volume_max: pick("volume", defaults.volume_max),
+            dynamic_range_max: pick("dynamic_range", defaults.dynamic_range_max),
+            pitch_confidence_max: pick("pitch_confidence", defaults.pitch_confidence_max),
+            ..defaults
+        }
+    }
+
+    /// Load parameters from a JSON file (as written by `save` or `audio-analyzer --apply-suggestions`)
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Save these parameters as pretty-printed JSON
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Normalized beat strength above which a frame counts as a beat
+pub const DEFAULT_BEAT_THRESHOLD: f32 = 0.3;
+
 /// The single source of truth for audio feature normalization.
 ///
 /// `FeatureNormalizer` ensures that all audio features, regardless of their source
@@ -90,7 +152,7 @@ impl Default for NormalizationParameters {
 ///
 /// # Usage Pattern
 /// ```rust,no_run
-/// use crate::audio::{FeatureNormalizer, RawAudioFeatures};
+/// use arrvee::audio::{FeatureNormalizer, RawAudioFeatures};
 ///
 /// let mut normalizer = FeatureNormalizer::new();
 /// let raw_features = RawAudioFeatures {
@@ -111,6 +173,7 @@ impl Default for NormalizationParameters {
 /// # Adaptive Learning
 /// When enabled, the normalizer can learn appropriate ranges from data:
 /// ```rust,no_run
+/// # use arrvee::audio::FeatureNormalizer;
 /// let mut adaptive_normalizer = FeatureNormalizer::new_adaptive();
 /// // Normalization parameters automatically adjust based on observed data
 /// ```
@@ -118,6 +181,7 @@ impl Default for NormalizationParameters {
 pub struct FeatureNormalizer {
     parameters: NormalizationParameters,
     adaptive: bool,
+    beat_threshold: f32,
 
     // For adaptive normalization - track observed ranges
     observed_ranges: Option<ObservedRanges>,
@@ -174,6 +238,7 @@ impl FeatureNormalizer {
         Self {
             parameters: NormalizationParameters::default(),
             adaptive: false,
+            beat_threshold: DEFAULT_BEAT_THRESHOLD,
             observed_ranges: None,
         }
     }
@@ -183,6 +248,7 @@ impl FeatureNormalizer {
         Self {
             parameters: NormalizationParameters::default(),
             adaptive: true,
+            beat_threshold: DEFAULT_BEAT_THRESHOLD,
             observed_ranges: Some(ObservedRanges::default()),
         }
     }
@@ -192,10 +258,32 @@ impl FeatureNormalizer {
         Self {
             parameters,
             adaptive: false,
+            beat_threshold: DEFAULT_BEAT_THRESHOLD,
             observed_ranges: None,
         }
     }
 
+    /// Normalized beat strength above which `beat_detected` is set
+    #[allow(dead_code)]
+    pub fn beat_threshold(&self) -> f32 {
+        self.beat_threshold
+    }
+
+    /// Set the beat threshold (clamped to 0.0-1.0, default `DEFAULT_BEAT_THRESHOLD`)
+    #[allow(dead_code)]
+    pub fn set_beat_threshold(&mut self, threshold: f32) {
+        self.beat_threshold = threshold.clamp(0.0, 1.0);
+    }
+
+    /// Forget ranges learned in adaptive mode (e.g. when switching tracks); the configured
+    /// parameters and beat threshold stay
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        if self.adaptive {
+            self.observed_ranges = Some(ObservedRanges::default());
+        }
+    }
+
     /// Normalize raw features to 0.0-1.0 range
     pub fn normalize(&mut self, raw: &RawAudioFeatures) -> NormalizedAudioFeatures {
         // Debug logging to see raw input values (log occasionally to avoid spam)
@@ -217,6 +305,7 @@ impl FeatureNormalizer {
         }
 
         let params = self.effective_parameters();
+        let beat_strength = self.normalize_value(raw.beat_strength, params.beat_strength_max);
 
         NormalizedAudioFeatures {
             // Frequency bands
@@ -236,8 +325,8 @@ impl FeatureNormalizer {
             onset_strength: self.normalize_value(raw.onset_strength, params.onset_strength_max),
 
             // Beat analysis
-            beat_detected: raw.beat_strength > (params.beat_strength_max * 0.3), // 30% threshold
-            beat_strength: self.normalize_value(raw.beat_strength, params.beat_strength_max),
+            beat_detected: beat_strength > self.beat_threshold,
+            beat_strength,
             estimated_bpm: raw.estimated_bpm.clamp(params.bpm_min, params.bpm_max), // Keep as raw BPM
 
             // Dynamic features
@@ -327,4 +416,233 @@ impl Default for FeatureNormalizer {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+const RANGE_PERCENTILE: f32 = 0.99;     // Fitted max: transients above this are allowed to clip
+const HISTOGRAM_MIN_DECADE: f32 = -10.0; // Raw features span ~1e-10 (band energies) to ~1e4 (Hz)
+const HISTOGRAM_BINS_PER_DECADE: f32 = 8.0;
+const HISTOGRAM_BINS: usize = 128;      // 16 decades
+const OBSERVED_FEATURES: usize = 14;
+
+/// Raw features `RangeObserver` tracks, in order; their `NormalizationParameters` fields add `_max`
+pub const OBSERVED_FEATURE_NAMES: [&str; OBSERVED_FEATURES] = [
+    "sub_bass", "bass", "mid", "treble", "presence",
+    "spectral_centroid", "spectral_rolloff", "spectral_flux",
+    "zero_crossing_rate", "onset_strength", "beat_strength",
+    "volume", "dynamic_range", "pitch_confidence",
+];
+
+/// Streaming estimate of each raw feature's upper range over a whole track, for fitting
+/// `NormalizationParameters` (see `suggested_parameters`).
+///
+/// Keeps a log-spaced histogram per feature rather than every value, so memory is constant
+/// however long the track is; the fitted maxima are accurate to about 1/8 decade (~33%).
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RangeObserver {
+    histograms: [[u32; HISTOGRAM_BINS]; OBSERVED_FEATURES],
+    count: usize,
+}
+
+#[allow(dead_code)]
+impl RangeObserver {
+    pub fn new() -> Self {
+        Self {
+            histograms: [[0; HISTOGRAM_BINS]; OBSERVED_FEATURES],
+            count: 0,
+        }
+    }
+
+    pub fn observe(&mut self, raw: &RawAudioFeatures) {
+        let values = [
+            raw.sub_bass, raw.bass, raw.mid, raw.treble, raw.presence,
+            raw.spectral_centroid, raw.spectral_rolloff, raw.spectral_flux,
+            raw.zero_crossing_rate, raw.onset_strength, raw.beat_strength,
+            raw.volume, raw.dynamic_range, raw.pitch_confidence,
+        ];
+        for (histogram, value) in self.histograms.iter_mut().zip(values) {
+            // Only non-zero values vote: sparse features (beats, onsets) are zero most frames
+            if value.is_finite() && value > 0.0 {
+                let bin = ((value.log10() - HISTOGRAM_MIN_DECADE) * HISTOGRAM_BINS_PER_DECADE).floor();
+                histogram[(bin.max(0.0) as usize).min(HISTOGRAM_BINS - 1)] += 1;
+            }
+        }
+        self.count += 1;
+    }
+
+    /// Frames observed so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Parameters whose maxima sit at each feature's 99th percentile (of non-zero frames), so the track's loud
+    /// passages reach the top of the 0.0-1.0 range without single transients squashing the
+    /// rest. Features that never rose above zero, and the BPM range, keep their defaults.
+    pub fn suggested_parameters(&self) -> NormalizationParameters {
+        NormalizationParameters::from_peaks(&self.peaks(), 1.0)
+    }
+
+    /// Each feature's fitted 99th percentile by name (see `OBSERVED_FEATURE_NAMES`), for the
+    /// features that rose above zero
+    pub fn peaks(&self) -> HashMap<String, f32> {
+        OBSERVED_FEATURE_NAMES.iter()
+            .enumerate()
+            .filter_map(|(feature, name)| self.percentile(feature).map(|peak| (name.to_string(), peak)))
+            .collect()
+    }
+
+    /// Upper edge of the histogram bin holding the percentile, or `None` without signal
+    fn percentile(&self, feature: usize) -> Option<f32> {
+        let histogram = &self.histograms[feature];
+        let positive: u32 = histogram.iter().sum();
+        if positive == 0 {
+            return None;
+        }
+
+        let target = (positive as f32 * RANGE_PERCENTILE).ceil() as u32;
+        let mut cumulative = 0;
+        let bin = histogram.iter()
+            .position(|&count| {
+                cumulative += count;
+                cumulative >= target
+            })
+            .unwrap_or(HISTOGRAM_BINS - 1);
+        Some(10f32.powf((bin + 1) as f32 / HISTOGRAM_BINS_PER_DECADE + HISTOGRAM_MIN_DECADE))
+    }
+}
+
+impl Default for RangeObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    /// Raw features with every field set to `value` (BPM kept in range)
+    fn raw_features(value: f32) -> RawAudioFeatures {
+        RawAudioFeatures {
+            sub_bass: value,
+            bass: value,
+            mid: value,
+            treble: value,
+            presence: value,
+            spectral_centroid: value,
+            spectral_rolloff: value,
+            spectral_flux: value,
+            zero_crossing_rate: value,
+            onset_strength: value,
+            beat_strength: value,
+            estimated_bpm: 120.0,
+            volume: value,
+            dynamic_range: value,
+            pitch_confidence: value,
+        }
+    }
+
+    #[test]
+    fn test_values_above_max_clamp_to_one() {
+        let mut normalizer = FeatureNormalizer::new();
+        let normalized = normalizer.normalize(&raw_features(1.0e6));
+        for value in [normalized.sub_bass, normalized.bass, normalized.mid, normalized.treble, normalized.presence,
+                      normalized.spectral_centroid, normalized.spectral_rolloff, normalized.spectral_flux,
+                      normalized.zero_crossing_rate, normalized.onset_strength, normalized.beat_strength,
+                      normalized.volume, normalized.dynamic_range, normalized.pitch_confidence] {
+            assert_eq!(value, 1.0);
+        }
+        assert!(normalized.beat_detected);
+    }
+
+    #[test]
+    fn test_zero_maps_to_zero() {
+        let mut normalizer = FeatureNormalizer::new();
+        let normalized = normalizer.normalize(&raw_features(0.0));
+        assert_eq!(normalized.bass, 0.0);
+        assert_eq!(normalized.volume, 0.0);
+        assert_eq!(normalized.spectral_centroid, 0.0);
+        assert_eq!(normalized.beat_strength, 0.0);
+        assert!(!normalized.beat_detected);
+        assert_eq!(normalized.estimated_bpm, 120.0);
+    }
+
+    #[test]
+    fn test_beat_detected_at_threshold() {
+        let beat_strength_max = NormalizationParameters::default().beat_strength_max;
+        let mut normalizer = FeatureNormalizer::new();
+        let mut at_level = |level: f32| {
+            let mut raw = raw_features(0.0);
+            raw.beat_strength = beat_strength_max * level;
+            normalizer.normalize(&raw).beat_detected
+        };
+
+        assert!(!at_level(DEFAULT_BEAT_THRESHOLD - 0.01));
+        assert!(!at_level(DEFAULT_BEAT_THRESHOLD)); // Strictly above the threshold
+        assert!(at_level(DEFAULT_BEAT_THRESHOLD + 0.01));
+    }
+
+    #[test]
+    fn test_adaptive_ranges_apply_after_100_samples() {
+        // Well above the default bass range, so fixed parameters saturate
+        let loud = raw_features(0.01);
+        let mut normalizer = FeatureNormalizer::new_adaptive();
+
+        for _ in 0..100 {
+            assert_eq!(normalizer.normalize(&loud).bass, 1.0);
+        }
+
+        // From the 101st sample the observed range (plus 20% headroom) takes over
+        let widened = normalizer.normalize(&loud).bass;
+        assert!((widened - 1.0 / 1.2).abs() < 1e-4, "bass normalized to {}", widened);
+    }
+
+    #[test]
+    fn test_reset_forgets_adaptive_ranges() {
+        let mut normalizer = FeatureNormalizer::new_adaptive();
+        for _ in 0..101 {
+            normalizer.normalize(&raw_features(0.01));
+        }
+        normalizer.reset();
+
+        // Back on the default ranges until the next track has been observed
+        assert_eq!(normalizer.normalize(&raw_features(0.01)).bass, 1.0);
+    }
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let parameters = NormalizationParameters {
+            bass_max: 0.123,
+            spectral_centroid_max: 4321.0,
+            bpm_max: 180.0,
+            ..NormalizationParameters::default()
+        };
+        let temp_path = TempPath::file("normalizer.json");
+        let path = temp_path.to_str().unwrap();
+
+        FeatureNormalizer::with_parameters(parameters).save_parameters(path).unwrap();
+        let mut loaded = FeatureNormalizer::new();
+        loaded.load_parameters(path).unwrap();
+
+        let loaded = loaded.get_parameters();
+        assert_eq!(loaded.bass_max, 0.123);
+        assert_eq!(loaded.spectral_centroid_max, 4321.0);
+        assert_eq!(loaded.bpm_max, 180.0);
+        assert_eq!(loaded.volume_max, NormalizationParameters::default().volume_max);
+    }
+
+    #[test]
+    fn test_from_peaks_fills_every_observed_feature() {
+        let peaks: HashMap<String, f32> = OBSERVED_FEATURE_NAMES.iter().map(|name| (name.to_string(), 2.0)).collect();
+        let json = serde_json::to_value(NormalizationParameters::from_peaks(&peaks, 1.5)).unwrap();
+        for name in OBSERVED_FEATURE_NAMES {
+            assert_eq!(json[format!("{}_max", name)], 3.0, "{}", name);
+        }
+
+        // Missing or silent features keep their defaults
+        let parameters = NormalizationParameters::from_peaks(&HashMap::from([("bass".to_string(), 0.0)]), 1.5);
+        assert_eq!(parameters.bass_max, NormalizationParameters::default().bass_max);
+        assert_eq!(parameters.bpm_max, NormalizationParameters::default().bpm_max);
+    }
+}
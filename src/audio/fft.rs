@@ -1,5 +1,7 @@
 use rustfft::{FftPlanner, num_complex::Complex};
 use super::{AudioFrame, FrequencyBands, BeatDetector};
+use super::spectral_whitening::SpectralWhitener;
+use super::tempo_detector::TempoDetector;
 
 #[allow(dead_code)]
 pub struct AudioAnalyzer {
@@ -19,6 +21,9 @@ pub struct AudioAnalyzer {
 
     // Dynamic sensitivity control (0.5 = less sensitive, 1.5 = more sensitive)
     sensitivity_factor: f32,
+
+    // Optional spectral whitening applied before band extraction
+    whitener: Option<SpectralWhitener>,
 }
 
 #[allow(dead_code)]
@@ -67,46 +72,6 @@ impl Default for NormalizationFactors {
     }
 }
 
-#[allow(dead_code)]
-struct TempoDetector {
-    beat_intervals: Vec<f32>,
-    last_beat_time: f32,
-    current_time: f32,
-    estimated_bpm: f32,
-}
-
-impl TempoDetector {
-    fn new() -> Self {
-        Self {
-            beat_intervals: Vec::new(),
-            last_beat_time: 0.0,
-            current_time: 0.0,
-            estimated_bpm: 120.0,
-        }
-    }
-
-    fn update(&mut self, beat_detected: bool, time_delta: f32) {
-        self.current_time += time_delta;
-
-        if beat_detected {
-            if self.last_beat_time > 0.0 {
-                let interval = self.current_time - self.last_beat_time;
-                if interval > 0.3 && interval < 2.0 { // Reasonable beat interval (30-200 BPM)
-                    self.beat_intervals.push(interval);
-                    if self.beat_intervals.len() > 8 {
-                        self.beat_intervals.remove(0);
-                    }
-
-                    // Calculate average interval and convert to BPM
-                    let avg_interval: f32 = self.beat_intervals.iter().sum::<f32>() / self.beat_intervals.len() as f32;
-                    self.estimated_bpm = 60.0 / avg_interval;
-                }
-            }
-            self.last_beat_time = self.current_time;
-        }
-    }
-}
-
 impl AudioAnalyzer {
     pub fn new(sample_rate: f32, fft_size: usize) -> Self {
         let mut planner = FftPlanner::new();
@@ -125,9 +90,34 @@ impl AudioAnalyzer {
             tempo_detector: TempoDetector::new(),
             normalization_factors: NormalizationFactors::default(),
             sensitivity_factor: 1.0, // Default sensitivity
+            whitener: None,
         }
     }
 
+    /// Enable or disable spectral whitening before frequency band extraction
+    pub fn set_spectral_whitening(&mut self, enabled: bool) {
+        self.whitener = if enabled { Some(SpectralWhitener::new()) } else { None };
+    }
+
+    /// Clear spectrum, volume and tempo history (e.g. when switching tracks)
+    pub fn reset(&mut self) {
+        self.beat_detector.reset();
+        self.previous_spectrum.iter_mut().for_each(|bin| *bin = 0.0);
+        self.volume_history.clear();
+        let smoothing = self.tempo_detector.smoothing();
+        self.tempo_detector = TempoDetector::new();
+        self.tempo_detector.set_smoothing(smoothing);
+        if let Some(whitener) = &mut self.whitener {
+            whitener.reset();
+        }
+    }
+
+    /// How slowly the BPM follows tempo changes (see `TempoDetector::set_smoothing`)
+    #[allow(dead_code)]
+    pub fn set_bpm_smoothing(&mut self, smoothing: f32) {
+        self.tempo_detector.set_smoothing(smoothing);
+    }
+
     /// Set sensitivity factor (0.5 = less sensitive, 1.5 = more sensitive)
     pub fn set_sensitivity(&mut self, sensitivity: f32) {
         self.sensitivity_factor = sensitivity.clamp(0.5, 1.5);
@@ -156,7 +146,13 @@ impl AudioAnalyzer {
     pub fn analyze(&mut self, audio_data: &[f32]) -> AudioFrame {
         let windowed_data = self.apply_window(audio_data);
         let spectrum = self.compute_fft(&windowed_data);
-        let frequency_bands = self.extract_frequency_bands(&spectrum);
+        let frequency_bands = match &mut self.whitener {
+            Some(whitener) => {
+                let whitened = whitener.whiten(&spectrum);
+                self.extract_frequency_bands(&whitened)
+            }
+            None => self.extract_frequency_bands(&spectrum),
+        };
 
         // Calculate volume (RMS)
         let volume = (audio_data.iter().map(|x| x * x).sum::<f32>() / audio_data.len() as f32).sqrt();
@@ -197,6 +193,7 @@ impl AudioAnalyzer {
         AudioFrame {
             sample_rate: self.sample_rate,
             spectrum: spectrum.clone(),
+            mel_bands: Vec::new(),
             time_domain: audio_data[..self.fft_size.min(audio_data.len())].to_vec(),
             frequency_bands: normalized_bands,
             beat_detected,
@@ -208,7 +205,7 @@ impl AudioAnalyzer {
             spectral_flux: ((spectral_flux / self.normalization_factors.spectral_flux_max) * self.sensitivity_factor).clamp(0.0, 1.0),
             onset_strength: ((onset_strength / self.normalization_factors.onset_strength_max) * self.sensitivity_factor).clamp(0.0, 1.0),
             pitch_confidence: ((pitch_confidence / self.normalization_factors.pitch_confidence_max) * self.sensitivity_factor).clamp(0.0, 1.0),
-            estimated_bpm: self.tempo_detector.estimated_bpm,
+            estimated_bpm: self.tempo_detector.estimated_bpm(),
             dynamic_range: ((dynamic_range / self.normalization_factors.dynamic_range_max) * self.sensitivity_factor).clamp(0.0, 1.0),
         }
     }
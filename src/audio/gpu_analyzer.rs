@@ -1,5 +1,94 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use bytemuck::{Pod, Zeroable};
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the results readback before treating the GPU as hung
+const READBACK_TIMEOUT: Duration = Duration::from_millis(500);
+
+const FFT_WORKGROUP_SIZE: u32 = 64;    // @workgroup_size of shaders/compute/fft.wgsl
+const STORAGE_BUFFERS_PER_STAGE: u32 = 3; // Most storage bindings in one compute shader (beat detection)
+
+/// The adapter or device can't run the analysis compute shaders (e.g. a GL/WebGL-limited
+/// backend). Returned through `anyhow` so callers can check `error.is::<ComputeUnavailable>()`
+/// and switch to CPU analysis.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ComputeUnavailable {
+    pub reason: String,
+}
+
+impl std::fmt::Display for ComputeUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GPU compute unavailable: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ComputeUnavailable {}
+
+/// Smallest chunk the GPU FFT handles (one full workgroup)
+pub const MIN_GPU_CHUNK_SIZE: u32 = FFT_WORKGROUP_SIZE;
+
+/// The GPU FFT is a radix-2 transform dispatched as `chunk_size / 64` workgroups, so it only
+/// handles power-of-two chunk sizes of at least `MIN_GPU_CHUNK_SIZE`; anything else would
+/// under-dispatch or read past the data. The CPU analyzer accepts any size.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct UnsupportedChunkSize {
+    pub chunk_size: u32,
+}
+
+impl std::fmt::Display for UnsupportedChunkSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GPU analysis needs a power-of-two chunk size of at least {} samples, got {} (try {}; the CPU analyzer accepts any size)",
+               MIN_GPU_CHUNK_SIZE, self.chunk_size, self.chunk_size.max(MIN_GPU_CHUNK_SIZE).next_power_of_two())
+    }
+}
+
+impl std::error::Error for UnsupportedChunkSize {}
+
+/// Check that the GPU FFT can handle `chunk_size`-sample chunks
+#[allow(dead_code)]
+pub fn check_chunk_size(chunk_size: u32) -> Result<(), UnsupportedChunkSize> {
+    if chunk_size >= MIN_GPU_CHUNK_SIZE && chunk_size.is_power_of_two() {
+        Ok(())
+    } else {
+        Err(UnsupportedChunkSize { chunk_size })
+    }
+}
+
+/// Check an adapter's downlevel capabilities before creating a device for analysis
+#[allow(dead_code)]
+pub fn check_adapter_compute(adapter: &wgpu::Adapter) -> Result<(), ComputeUnavailable> {
+    if adapter.get_downlevel_capabilities().flags.contains(wgpu::DownlevelFlags::COMPUTE_SHADERS) {
+        Ok(())
+    } else {
+        Err(ComputeUnavailable { reason: format!("{} does not support compute shaders", adapter.get_info().name) })
+    }
+}
+
+/// Check that device limits allow the analysis pipelines for `buffer_size`-sample chunks
+pub fn check_compute_limits(limits: &wgpu::Limits, buffer_size: u32) -> Result<(), ComputeUnavailable> {
+    let unavailable = |reason: String| Err(ComputeUnavailable { reason });
+    if limits.max_compute_workgroups_per_dimension == 0 || limits.max_compute_invocations_per_workgroup == 0 {
+        return unavailable("device allows no compute workgroups".to_string());
+    }
+    if limits.max_compute_workgroup_size_x < FFT_WORKGROUP_SIZE || limits.max_compute_invocations_per_workgroup < FFT_WORKGROUP_SIZE {
+        return unavailable(format!("device allows fewer than {} compute invocations per workgroup", FFT_WORKGROUP_SIZE));
+    }
+    if limits.max_storage_buffers_per_shader_stage < STORAGE_BUFFERS_PER_STAGE {
+        return unavailable(format!("device allows {} storage buffers per shader stage, analysis needs {}",
+                                   limits.max_storage_buffers_per_shader_stage, STORAGE_BUFFERS_PER_STAGE));
+    }
+    if limits.max_compute_workgroups_per_dimension < buffer_size / FFT_WORKGROUP_SIZE {
+        return unavailable(format!("{}-sample chunks need more workgroups than the device allows", buffer_size));
+    }
+    let fft_bytes = buffer_size as u64 * 2 * std::mem::size_of::<f32>() as u64;
+    if (limits.max_storage_buffer_binding_size as u64) < fft_bytes {
+        return unavailable(format!("{}-sample chunks exceed the device's storage buffer size", buffer_size));
+    }
+    Ok(())
+}
 
 /// GPU-accelerated audio analysis using compute shaders
 pub struct GpuAudioAnalyzer {
@@ -29,6 +118,10 @@ pub struct GpuAudioAnalyzer {
     start_time: std::time::Instant,
     frame_count: u32,
     last_beat_time: f32,
+
+    // Watchdog
+    readback_timeout: Duration,
+    failed: bool, // Set after a stall or GPU error; the output buffer may still be mid-map
 }
 
 #[repr(C)]
@@ -72,6 +165,8 @@ pub struct GpuAudioFeatures {
 }
 
 impl GpuAudioAnalyzer {
+    /// `buffer_size` is the chunk size in samples: a power of two, at least `MIN_GPU_CHUNK_SIZE`
+    /// (otherwise `UnsupportedChunkSize`)
     pub async fn new(
         device: &wgpu::Device,
         _queue: &wgpu::Queue,
@@ -80,6 +175,11 @@ impl GpuAudioAnalyzer {
     ) -> Result<Self> {
         let num_frequency_bands = 5;
 
+        check_chunk_size(buffer_size)?;
+        // Refuse up front on compute-less devices; creating the pipelines there is a validation error
+        check_compute_limits(&device.limits(), buffer_size)?;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
         // Create compute shaders
         let fft_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("FFT Compute Shader"),
@@ -308,6 +408,10 @@ impl GpuAudioAnalyzer {
             ],
         });
 
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(ComputeUnavailable { reason: format!("compute pipeline creation failed: {}", error) }.into());
+        }
+
         Ok(Self {
             fft_pipeline,
             feature_extraction_pipeline,
@@ -326,11 +430,37 @@ impl GpuAudioAnalyzer {
             start_time: std::time::Instant::now(),
             frame_count: 0,
             last_beat_time: 0.0,
+            readback_timeout: READBACK_TIMEOUT,
+            failed: false,
         })
     }
 
+    /// Longest wait for a frame's results before the analyzer gives up on the GPU
+    #[allow(dead_code)]
+    pub fn set_readback_timeout(&mut self, timeout: Duration) {
+        self.readback_timeout = timeout;
+    }
+
+    /// True once a readback timed out or the GPU reported an error; every later `analyze`
+    /// call fails straight away so callers can switch to the CPU analyzer
+    #[allow(dead_code)]
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Restart time and beat tracking (e.g. when switching tracks). A failed analyzer stays failed.
+    pub fn reset(&mut self) {
+        self.start_time = std::time::Instant::now();
+        self.frame_count = 0;
+        self.last_beat_time = 0.0;
+    }
+
     /// Analyze audio data using GPU compute shaders
     pub async fn analyze(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, audio_data: &[f32]) -> Result<GpuAudioFeatures> {
+        if self.failed {
+            return Err(anyhow!("GPU analyzer disabled after an earlier stall or error"));
+        }
+
         // Update time tracking
         let current_time = self.start_time.elapsed().as_secs_f32();
         let delta_time = if self.frame_count > 0 {
@@ -412,16 +542,41 @@ impl GpuAudioAnalyzer {
             std::mem::size_of::<GpuAudioFeatures>() as u64,
         );
 
-        // Submit commands
+        // Submit commands, catching validation errors instead of letting the device panic
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         queue.submit(std::iter::once(encoder.finish()));
+        if let Some(error) = device.pop_error_scope().await {
+            self.failed = true;
+            return Err(anyhow!("GPU analysis submit failed: {}", error));
+        }
 
-        // Read results
+        // Read results, polling against a deadline so a hung GPU can't stall the caller
         let buffer_slice = self.output_buffer.slice(..);
-        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
-        buffer_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
-
-        device.poll(wgpu::Maintain::wait());
-        receiver.receive().await.unwrap()?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |v| { let _ = sender.send(v); });
+
+        let deadline = Instant::now() + self.readback_timeout;
+        loop {
+            device.poll(wgpu::Maintain::Poll);
+            match receiver.try_recv() {
+                Ok(result) => {
+                    if let Err(e) = result {
+                        self.failed = true;
+                        return Err(anyhow!("GPU analysis readback failed: {}", e));
+                    }
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.failed = true;
+                    return Err(anyhow!("GPU analysis readback was dropped"));
+                }
+                Err(TryRecvError::Empty) if Instant::now() >= deadline => {
+                    self.failed = true;
+                    return Err(anyhow!("GPU analysis readback timed out after {:?}", self.readback_timeout));
+                }
+                Err(TryRecvError::Empty) => std::thread::sleep(Duration::from_micros(100)),
+            }
+        }
 
         let data = buffer_slice.get_mapped_range();
         let features: GpuAudioFeatures = *bytemuck::from_bytes(&data[..std::mem::size_of::<GpuAudioFeatures>()]);
@@ -431,4 +586,32 @@ impl GpuAudioAnalyzer {
 
         Ok(features)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_less_limits_are_rejected() {
+        // WebGL2-class devices have no compute at all
+        let error = check_compute_limits(&wgpu::Limits::downlevel_webgl2_defaults(), 512).unwrap_err();
+        assert!(error.reason.contains("no compute"), "{}", error);
+        assert!(anyhow::Error::from(error).is::<ComputeUnavailable>());
+
+        assert!(check_compute_limits(&wgpu::Limits::downlevel_defaults(), 512).is_ok());
+        assert!(check_compute_limits(&wgpu::Limits::default(), 2048).is_ok());
+    }
+
+    #[test]
+    fn test_chunk_size_must_be_a_power_of_two_of_at_least_64() {
+        for valid in [64, 512, 4096] {
+            assert!(check_chunk_size(valid).is_ok(), "{}", valid);
+        }
+        for invalid in [0, 32, 500, 735] {
+            assert!(check_chunk_size(invalid).is_err(), "{}", invalid);
+        }
+        let message = check_chunk_size(500).unwrap_err().to_string();
+        assert!(message.contains("got 500") && message.contains("try 512"), "{}", message);
+    }
+}
@@ -1,5 +1,5 @@
 use super::{AudioAnalyzer, RawAudioFeatures};
-use super::gpu_analyzer::{GpuAudioAnalyzer as InnerGpuAnalyzer, GpuAudioFeatures};
+use super::gpu_analyzer::{check_adapter_compute, check_chunk_size, GpuAudioAnalyzer as InnerGpuAnalyzer, GpuAudioFeatures};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -40,19 +40,25 @@ impl GpuAudioAnalyzer {
 
     /// Create with stored device and queue references for standalone usage
     pub async fn new_standalone(sample_rate: f32, chunk_size: usize) -> Result<Self> {
+        // Before touching the GPU, so a bad --chunk-size is what gets reported
+        check_chunk_size(u32::try_from(chunk_size).unwrap_or(u32::MAX))?;
+
         // Create headless GPU context for compute operations
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions::default())
             .await
             .ok_or_else(|| anyhow::anyhow!("Failed to find suitable GPU adapter"))?;
+        check_adapter_compute(&adapter)?;
 
+        // Ask for what the adapter has rather than the WebGPU defaults, which limited adapters
+        // refuse outright; the analyzer checks the limits it actually needs
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("Standalone GPU Device"),
                     required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
+                    required_limits: adapter.limits(),
                 },
                 None,
             )
@@ -105,6 +111,10 @@ impl AudioAnalyzer for GpuAudioAnalyzer {
     fn analyzer_type(&self) -> &'static str {
         "GPU"
     }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
 }
 
 impl GpuAudioAnalyzer {
@@ -0,0 +1,138 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+const WINDOW_SIZE: usize = 8192;      // ~5 Hz bins at 44.1 kHz, enough to separate semitones from C2 up
+const MIN_PITCH_HZ: f32 = 65.0;       // C2
+const MAX_PITCH_HZ: f32 = 2100.0;     // ~C7; above this, harmonics blur the pitch classes
+const SILENCE_THRESHOLD: f32 = 1e-4;  // Windows quieter than this (RMS) don't vote
+
+/// Pitch class names, C = 0
+#[allow(dead_code)]
+pub const PITCH_CLASSES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+// Krumhansl-Kessler key profiles (probe-tone ratings), tonic first
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Best-matching musical key for a track
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyEstimate {
+    pub tonic: usize, // Pitch class, C = 0
+    pub minor: bool,
+    /// Correlation between the track's chroma and the key profile (0-1)
+    pub confidence: f32,
+}
+
+impl KeyEstimate {
+    /// Key name such as "A minor" or "F# major"
+    #[allow(dead_code)]
+    pub fn name(&self) -> String {
+        format!("{} {}", PITCH_CLASSES[self.tonic], if self.minor { "minor" } else { "major" })
+    }
+}
+
+/// Estimate the key of a mono sample buffer using the Krumhansl-Schmuckler method: the
+/// track's average chroma vector is correlated against all 24 rotated major/minor key profiles.
+/// Returns `None` for silence or buffers too short for a single analysis window.
+#[allow(dead_code)]
+pub fn estimate_key(samples: &[f32], sample_rate: f32) -> Option<KeyEstimate> {
+    estimate_key_from_chroma(&average_chroma(samples, sample_rate)?)
+}
+
+/// Best key for a 12-bin chroma vector (C = 0); `None` if the vector is flat
+pub fn estimate_key_from_chroma(chroma: &[f32; 12]) -> Option<KeyEstimate> {
+    (0..12)
+        .flat_map(|tonic| [false, true].map(|minor| (tonic, minor)))
+        .filter_map(|(tonic, minor)| {
+            let profile = if minor { &MINOR_PROFILE } else { &MAJOR_PROFILE };
+            let rotated: [f32; 12] = std::array::from_fn(|pitch| profile[(pitch + 12 - tonic) % 12]);
+            correlation(chroma, &rotated).map(|r| KeyEstimate { tonic, minor, confidence: r.max(0.0) })
+        })
+        .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+}
+
+/// Mean pitch-class energy over the track, each window normalized so loud passages don't dominate
+fn average_chroma(samples: &[f32], sample_rate: f32) -> Option<[f32; 12]> {
+    let fft = FftPlanner::new().plan_fft_forward(WINDOW_SIZE);
+    let window: Vec<f32> = (0..WINDOW_SIZE)
+        .map(|i| 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / WINDOW_SIZE as f32).cos())
+        .collect();
+    let bin_hz = sample_rate / WINDOW_SIZE as f32;
+    let first_bin = (MIN_PITCH_HZ / bin_hz).ceil() as usize;
+    let last_bin = ((MAX_PITCH_HZ / bin_hz) as usize).min(WINDOW_SIZE / 2);
+
+    let mut total = [0.0f32; 12];
+    let mut windows = 0;
+    let mut buffer = vec![Complex::new(0.0f32, 0.0); WINDOW_SIZE];
+    for chunk in samples.chunks_exact(WINDOW_SIZE) {
+        let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / WINDOW_SIZE as f32).sqrt();
+        if rms < SILENCE_THRESHOLD {
+            continue;
+        }
+
+        for ((slot, &sample), &weight) in buffer.iter_mut().zip(chunk).zip(&window) {
+            *slot = Complex::new(sample * weight, 0.0);
+        }
+        fft.process(&mut buffer);
+
+        let mut chroma = [0.0f32; 12];
+        for (bin, value) in buffer.iter().enumerate().take(last_bin + 1).skip(first_bin) {
+            let midi = 69.0 + 12.0 * (bin as f32 * bin_hz / 440.0).log2();
+            chroma[(midi.round() as i32).rem_euclid(12) as usize] += value.norm();
+        }
+
+        let sum: f32 = chroma.iter().sum();
+        if sum > 0.0 {
+            for (total, value) in total.iter_mut().zip(&chroma) {
+                *total += value / sum;
+            }
+            windows += 1;
+        }
+    }
+
+    (windows > 0).then(|| total.map(|value| value / windows as f32))
+}
+
+/// Pearson correlation; `None` when either vector has no variance
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> Option<f32> {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    let denominator = (variance_a * variance_b).sqrt();
+    (denominator > f32::EPSILON).then(|| covariance / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two seconds of a chord, plus a little of the second harmonic as real instruments have
+    fn chord(frequencies: &[f32]) -> Vec<f32> {
+        (0..88200)
+            .map(|i| {
+                let t = i as f32 / 44100.0;
+                frequencies.iter()
+                    .map(|&frequency| (t * frequency * std::f32::consts::TAU).sin() + 0.3 * (t * frequency * 2.0 * std::f32::consts::TAU).sin())
+                    .sum::<f32>() * 0.2
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_triads_give_their_keys() {
+        let c_major = estimate_key(&chord(&[261.63, 329.63, 392.00]), 44100.0).unwrap();
+        assert_eq!(c_major.name(), "C major");
+        assert!(c_major.confidence > 0.5);
+
+        let a_minor = estimate_key(&chord(&[220.00, 261.63, 329.63]), 44100.0).unwrap();
+        assert_eq!(a_minor.name(), "A minor");
+
+        assert!(estimate_key(&vec![0.0; 88200], 44100.0).is_none());
+        assert!(estimate_key(&chord(&[440.0])[..WINDOW_SIZE - 1], 44100.0).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+/// Floor for dBFS readouts (digital silence reads as this rather than -inf)
+pub const MIN_DBFS: f32 = -96.0;
+
+const PEAK_HOLD_SECONDS: f32 = 1.5;    // Held peak stays put this long...
+const PEAK_FALL_DB_PER_SECOND: f32 = 20.0; // ...then falls back at this rate
+const CLIP_HOLD_SECONDS: f32 = 3.0;    // Clip indicator stays lit this long after the last over
+const CLIP_LEVEL: f32 = 0.999;         // ~-0.01 dBFS; decoded full-scale samples land here
+
+/// Convert a linear amplitude (1.0 = full scale) to dBFS, floored at `MIN_DBFS`
+pub fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return MIN_DBFS;
+    }
+    (20.0 * amplitude.log10()).max(MIN_DBFS)
+}
+
+/// One channel's readout
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelLevel {
+    /// RMS over the last block
+    pub rms_dbfs: f32,
+    /// Highest sample in the last block
+    pub peak_dbfs: f32,
+    /// Peak with hold and fall-back, for the meter's peak marker
+    pub held_peak_dbfs: f32,
+    /// A sample reached full scale within the last few seconds
+    pub clipped: bool,
+    hold_remaining: f32,
+    clip_remaining: f32,
+}
+
+impl Default for ChannelLevel {
+    fn default() -> Self {
+        Self {
+            rms_dbfs: MIN_DBFS,
+            peak_dbfs: MIN_DBFS,
+            held_peak_dbfs: MIN_DBFS,
+            clipped: false,
+            hold_remaining: 0.0,
+            clip_remaining: 0.0,
+        }
+    }
+}
+
+/// Per-channel peak/RMS meter with peak hold and clip indicators, fed with blocks of
+/// interleaved source samples (before any downmix or gain)
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct LevelMeter {
+    channels: Vec<ChannelLevel>,
+}
+
+#[allow(dead_code)]
+impl LevelMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Meter one block of interleaved samples lasting `block_seconds`
+    pub fn update(&mut self, interleaved: &[f32], channels: usize, block_seconds: f32) {
+        let channels = channels.max(1);
+        if self.channels.len() != channels {
+            self.channels = vec![ChannelLevel::default(); channels];
+        }
+        let frames = interleaved.len() / channels;
+
+        for (index, level) in self.channels.iter_mut().enumerate() {
+            let (mut peak, mut sum_squares) = (0.0f32, 0.0f32);
+            for sample in interleaved.iter().skip(index).step_by(channels).take(frames) {
+                peak = peak.max(sample.abs());
+                sum_squares += sample * sample;
+            }
+            let rms = if frames > 0 { (sum_squares / frames as f32).sqrt() } else { 0.0 };
+
+            level.rms_dbfs = to_dbfs(rms);
+            level.peak_dbfs = to_dbfs(peak);
+
+            if level.peak_dbfs >= level.held_peak_dbfs {
+                level.held_peak_dbfs = level.peak_dbfs;
+                level.hold_remaining = PEAK_HOLD_SECONDS;
+            } else if level.hold_remaining > 0.0 {
+                level.hold_remaining -= block_seconds;
+            } else {
+                level.held_peak_dbfs = (level.held_peak_dbfs - PEAK_FALL_DB_PER_SECOND * block_seconds).max(level.peak_dbfs);
+            }
+
+            if peak >= CLIP_LEVEL {
+                level.clip_remaining = CLIP_HOLD_SECONDS;
+            } else {
+                level.clip_remaining = (level.clip_remaining - block_seconds).max(0.0);
+            }
+            level.clipped = level.clip_remaining > 0.0;
+        }
+    }
+
+    /// Readouts per channel (empty until the first update)
+    pub fn channels(&self) -> &[ChannelLevel] {
+        &self.channels
+    }
+
+    pub fn reset(&mut self) {
+        self.channels.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_hold_and_clip_indicator() {
+        let block = 1.0 / 60.0;
+        let mut meter = LevelMeter::new();
+
+        // Left clips once, right sits at -6 dBFS
+        let burst: Vec<f32> = (0..735).flat_map(|_| [1.0, 0.5]).collect();
+        meter.update(&burst, 2, block);
+        let [left, right] = [meter.channels()[0], meter.channels()[1]];
+        assert!(left.clipped && left.peak_dbfs > -0.1);
+        assert!(!right.clipped && (right.peak_dbfs + 6.02).abs() < 0.05);
+        assert!((right.rms_dbfs + 6.02).abs() < 0.05);
+
+        // Quiet afterwards: the held peak stays for the hold time, then falls
+        let quiet: Vec<f32> = (0..735).flat_map(|_| [0.01, 0.01]).collect();
+        for _ in 0..60 {
+            meter.update(&quiet, 2, block);
+        }
+        let left = meter.channels()[0];
+        assert!(left.held_peak_dbfs > -0.1, "held peak fell during the hold: {}", left.held_peak_dbfs);
+        assert!(left.clipped);
+        assert!((left.peak_dbfs + 40.0).abs() < 0.05);
+
+        for _ in 0..180 {
+            meter.update(&quiet, 2, block);
+        }
+        let left = meter.channels()[0];
+        assert!(left.held_peak_dbfs < -20.0, "held peak never fell: {}", left.held_peak_dbfs);
+        assert!(!left.clipped);
+    }
+}
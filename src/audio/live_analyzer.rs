@@ -0,0 +1,225 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use super::agc::{AgcConfig, AutomaticGainControl};
+use super::{AudioAnalyzer, CpuAudioAnalyzer, FeatureNormalizer, NormalizedAudioFeatures};
+use super::feature_normalizer::NormalizationParameters;
+
+/// Live-input analyzer that decouples analysis from the capture callback.
+///
+/// Input devices deliver samples in whatever buffer sizes they like (often not a multiple of
+/// the FFT window, and different per platform). Samples are pushed into a ring buffer as they
+/// arrive, and `analyze_pending` runs overlapping windows at a fixed hop over everything that
+/// has accumulated, so no data is dropped and the spectrum updates at a steady rate.
+#[allow(dead_code)]
+pub struct LiveAnalyzer {
+    analyzer: CpuAudioAnalyzer,
+    normalizer: FeatureNormalizer,
+    sample_rate: f32,
+    agc: Option<AutomaticGainControl>, // Applied to samples as they are pushed
+    buffer: VecDeque<f32>,
+    window_size: usize,
+    hop_size: usize,
+    capacity: usize,
+    read_position: usize, // Start of the next window within `buffer`
+    window: Vec<f32>,
+    latest: Option<NormalizedAudioFeatures>,
+    dropped_samples: usize,
+}
+
+#[allow(dead_code)]
+impl LiveAnalyzer {
+    /// Default hop: 50% overlap with the 512-sample analysis window
+    pub const DEFAULT_HOP_SIZE: usize = 256;
+
+    /// Create an analyzer with a 512-sample window and 50% overlap
+    pub fn new(sample_rate: f32) -> Result<Self> {
+        Self::with_window(sample_rate, 512, Self::DEFAULT_HOP_SIZE)
+    }
+
+    /// Create an analyzer with a custom window and hop size (hop is clamped to 1..=window)
+    pub fn with_window(sample_rate: f32, window_size: usize, hop_size: usize) -> Result<Self> {
+        let window_size = window_size.max(1);
+        let hop_size = hop_size.clamp(1, window_size);
+        // Hold up to ~8 windows; anything older is stale for live visuals anyway
+        let capacity = window_size * 8;
+        let mut analyzer = CpuAudioAnalyzer::new(sample_rate, window_size)?;
+        analyzer.set_chunk_interval(hop_size as f32 / sample_rate); // Windows overlap by the hop
+
+        Ok(Self {
+            analyzer,
+            normalizer: FeatureNormalizer::new(),
+            sample_rate,
+            agc: None,
+            buffer: VecDeque::with_capacity(capacity),
+            window_size,
+            hop_size,
+            capacity,
+            read_position: 0,
+            window: vec![0.0; window_size],
+            latest: None,
+            dropped_samples: 0,
+        })
+    }
+
+    /// Normalize with these ranges instead of the defaults (e.g. `--normalization` files)
+    pub fn set_normalization_parameters(&mut self, parameters: NormalizationParameters) {
+        self.normalizer = FeatureNormalizer::with_parameters(parameters);
+    }
+
+    /// Scale pushed samples with automatic gain control before analysis (None disables it)
+    pub fn set_agc(&mut self, config: Option<AgcConfig>) {
+        self.agc = config.map(AutomaticGainControl::new);
+    }
+
+    /// Gain currently applied by AGC, if enabled
+    pub fn agc_gain(&self) -> Option<f32> {
+        self.agc.as_ref().map(AutomaticGainControl::gain)
+    }
+
+    /// Append captured mono samples (any length, e.g. straight from an input callback)
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        match &mut self.agc {
+            Some(agc) => self.buffer.extend(agc.process(samples, self.sample_rate)),
+            None => self.buffer.extend(samples.iter().copied()),
+        }
+
+        // If analysis falls behind, drop the oldest audio rather than growing without bound
+        if self.buffer.len() > self.capacity {
+            let overflow = self.buffer.len() - self.capacity;
+            self.buffer.drain(..overflow);
+            self.read_position = self.read_position.saturating_sub(overflow);
+            self.dropped_samples += overflow;
+        }
+    }
+
+    /// Analyze every full window that has accumulated since the last call.
+    ///
+    /// Returns the number of windows analyzed; the most recent result is available via `latest`.
+    pub async fn analyze_pending(&mut self) -> Result<usize> {
+        let mut analyzed = 0;
+
+        while self.read_position + self.window_size <= self.buffer.len() {
+            for (slot, &sample) in self.window.iter_mut()
+                .zip(self.buffer.range(self.read_position..self.read_position + self.window_size))
+            {
+                *slot = sample;
+            }
+
+            let raw_features = self.analyzer.analyze_chunk(&self.window).await?;
+            self.latest = Some(self.normalizer.normalize(&raw_features));
+            self.read_position += self.hop_size;
+            analyzed += 1;
+        }
+
+        // Discard samples no future window will touch, keeping the overlap
+        let consumed = self.read_position.min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.read_position -= consumed;
+
+        Ok(analyzed)
+    }
+
+    /// Most recent normalized features, if any window has been analyzed yet
+    pub fn latest(&self) -> Option<&NormalizedAudioFeatures> {
+        self.latest.as_ref()
+    }
+
+    /// Samples waiting in the ring buffer
+    pub fn buffered_samples(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Total samples discarded because analysis couldn't keep up
+    pub fn dropped_samples(&self) -> usize {
+        self.dropped_samples
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    pub fn hop_size(&self) -> usize {
+        self.hop_size
+    }
+
+    /// Clear buffered audio and analysis state (e.g. when switching input devices)
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.read_position = 0;
+        self.latest = None;
+        self.dropped_samples = 0;
+        self.analyzer.reset();
+        self.normalizer.reset();
+        if let Some(agc) = &mut self.agc {
+            agc.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_windows_overlap_whatever_the_buffer_sizes() {
+        let tone: Vec<f32> = (0..4096).map(|i| (i as f32 * 60.0 * std::f32::consts::TAU / 44100.0).sin() * 0.8).collect();
+
+        // Odd callback sizes, as an input device might deliver them
+        let mut live = LiveAnalyzer::new(44100.0).unwrap();
+        let mut windows = 0;
+        for block in tone.chunks(441) {
+            live.push_samples(block);
+            windows += live.analyze_pending().await.unwrap();
+            assert!(live.buffered_samples() < live.window_size());
+        }
+        assert_eq!(windows, (tone.len() - 512) / 256 + 1);
+        assert_eq!(live.dropped_samples(), 0);
+        let bass = live.latest().unwrap().bass;
+        assert!(bass > 0.0);
+
+        // The same audio in one block analyzes to the same frames
+        let mut whole = LiveAnalyzer::new(44100.0).unwrap();
+        whole.push_samples(&tone);
+        assert_eq!(whole.analyze_pending().await.unwrap(), windows);
+        assert_eq!(whole.latest().unwrap().bass, bass);
+
+        // Falling behind drops the oldest audio instead of growing the buffer
+        let mut behind = LiveAnalyzer::new(44100.0).unwrap();
+        behind.push_samples(&tone);
+        behind.push_samples(&tone[..1000]);
+        assert_eq!((behind.buffered_samples(), behind.dropped_samples()), (tone.len(), 1000));
+        behind.reset();
+        assert!(behind.latest().is_none() && behind.buffered_samples() == 0);
+    }
+
+    #[tokio::test]
+    async fn test_agc_brings_quiet_and_loud_input_together() {
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..44100).map(|i| (i as f32 * 220.0 * std::f32::consts::TAU / 44100.0).sin() * amplitude).collect()
+        };
+        async fn volume(samples: &[f32], agc: bool) -> f32 {
+            let mut live = LiveAnalyzer::new(44100.0).unwrap();
+            // A range wide enough that a full-scale tone doesn't pin the volume at 1
+            live.set_normalization_parameters(NormalizationParameters { volume_max: 0.6, ..Default::default() });
+            live.set_agc(agc.then(AgcConfig::default));
+            for block in samples.chunks(441) {
+                live.push_samples(block);
+                live.analyze_pending().await.unwrap();
+            }
+            live.latest().unwrap().volume
+        }
+
+        let (quiet, loud) = (tone(0.02), tone(0.8));
+        let (quiet_raw, loud_raw) = (volume(&quiet, false).await, volume(&loud, false).await);
+        assert!(loud_raw - quiet_raw > 0.3, "without AGC: quiet {} loud {}", quiet_raw, loud_raw);
+        let (quiet_gained, loud_gained) = (volume(&quiet, true).await, volume(&loud, true).await);
+        assert!((quiet_gained - loud_gained).abs() < 0.05, "with AGC: quiet {} loud {}", quiet_gained, loud_gained);
+
+        let mut live = LiveAnalyzer::new(44100.0).unwrap();
+        live.set_agc(Some(AgcConfig::default()));
+        live.push_samples(&quiet[..4410]);
+        assert!(live.agc_gain().unwrap() > 5.0);
+        live.reset();
+        assert_eq!(live.agc_gain(), Some(1.0));
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use crossbeam_channel::{Receiver, Sender};
+
+use super::live_analyzer::LiveAnalyzer;
+
+/// Capture from the default input device (microphone, line-in, or a loopback/monitor device
+/// for whatever the computer is playing), folded to mono.
+///
+/// The capture callback only hands each buffer over a channel; `drain_into` moves them into a
+/// `LiveAnalyzer` on the render thread, which copes with the device's buffer sizes.
+#[allow(dead_code)]
+pub struct LiveInput {
+    _stream: cpal::Stream, // Capture stops when this is dropped
+    receiver: Receiver<Vec<f32>>,
+    sample_rate: u32,
+    device_name: String,
+}
+
+#[allow(dead_code)]
+impl LiveInput {
+    /// Open and start the default input device at its default configuration
+    pub fn open_default() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No audio input device available"))?;
+        let device_name = device.name().unwrap_or_else(|_| "default input".to_string());
+        let config = device.default_input_config()
+            .map_err(|e| anyhow!("Could not query input device {}: {}", device_name, e))?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels().max(1) as usize;
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => Self::build_stream::<f32>(&device, &config.into(), channels, sender),
+            cpal::SampleFormat::I16 => Self::build_stream::<i16>(&device, &config.into(), channels, sender),
+            cpal::SampleFormat::U16 => Self::build_stream::<u16>(&device, &config.into(), channels, sender),
+            cpal::SampleFormat::I32 => Self::build_stream::<i32>(&device, &config.into(), channels, sender),
+            format => return Err(anyhow!("Unsupported input sample format {:?} on {}", format, device_name)),
+        }
+        .map_err(|e| anyhow!("Could not open input device {}: {}", device_name, e))?;
+        stream.play().map_err(|e| anyhow!("Could not start input device {}: {}", device_name, e))?;
+
+        Ok(Self { _stream: stream, receiver, sample_rate, device_name })
+    }
+
+    fn build_stream<T>(device: &cpal::Device, config: &cpal::StreamConfig, channels: usize,
+                       sender: Sender<Vec<f32>>) -> std::result::Result<cpal::Stream, cpal::BuildStreamError>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mono = data.chunks(channels)
+                    .map(|frame| frame.iter().map(|&sample| sample.to_sample::<f32>()).sum::<f32>() / frame.len() as f32)
+                    .collect();
+                // The receiver is gone only while shutting down
+                sender.send(mono).ok();
+            },
+            |e| log::warn!("⚠️  Audio input error: {}", e),
+            None,
+        )
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Push everything captured since the last call into `analyzer`; returns the sample count
+    pub fn drain_into(&self, analyzer: &mut LiveAnalyzer) -> usize {
+        self.receiver.try_iter()
+            .map(|samples| {
+                analyzer.push_samples(&samples);
+                samples.len()
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+
+/// Shortest loop accepted, so a double-tapped in/out can't make playback stutter in place
+pub const MIN_LOOP_SECONDS: f32 = 0.25;
+
+/// A/B loop points in track seconds, set live (`I`/`O`) or from `--loop-region`.
+///
+/// Without an in point the loop starts at the top of the track; without an out point there
+/// is no loop yet, only a marker.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoopRegion {
+    in_point: Option<f32>,
+    out_point: Option<f32>,
+}
+
+#[allow(dead_code)]
+impl LoopRegion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `START-END` in seconds (e.g. `32-48.5`)
+    pub fn parse(text: &str) -> Result<Self> {
+        let (start, end) = text.split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("Loop region '{}' should be START-END in seconds (e.g. 32-48.5)", text))?;
+        let parse = |value: &str| value.trim().parse::<f32>()
+            .map_err(|_| anyhow::anyhow!("Invalid loop point '{}' in '{}'", value.trim(), text));
+        let (start, end) = (parse(start)?, parse(end)?);
+
+        let mut region = Self::new();
+        region.set_in(start);
+        if !region.set_out(end) {
+            return Err(anyhow::anyhow!("Loop region '{}' must end at least {}s after it starts", text, MIN_LOOP_SECONDS));
+        }
+        Ok(region)
+    }
+
+    /// Set the in point, dropping an out point that would no longer follow it
+    pub fn set_in(&mut self, seconds: f32) {
+        let seconds = seconds.max(0.0);
+        self.in_point = Some(seconds);
+        if self.out_point.is_some_and(|out| out < seconds + MIN_LOOP_SECONDS) {
+            self.out_point = None;
+        }
+    }
+
+    /// Set the out point; returns false (leaving it unchanged) if it is too close to, or
+    /// before, the in point
+    pub fn set_out(&mut self, seconds: f32) -> bool {
+        if seconds < self.in_point.unwrap_or(0.0) + MIN_LOOP_SECONDS {
+            return false;
+        }
+        self.out_point = Some(seconds);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn in_point(&self) -> Option<f32> {
+        self.in_point
+    }
+
+    pub fn out_point(&self) -> Option<f32> {
+        self.out_point
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.in_point.is_none() && self.out_point.is_none()
+    }
+
+    /// Start and end of the loop, once there is an out point
+    pub fn bounds(&self) -> Option<(f32, f32)> {
+        self.out_point.map(|out| (self.in_point.unwrap_or(0.0), out))
+    }
+
+    /// Where to jump back to if playback at `seconds` has reached the out point
+    pub fn wrap(&self, seconds: f32) -> Option<f32> {
+        let (start, end) = self.bounds()?;
+        (seconds >= end).then_some(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_points_set_live_and_from_the_command_line() {
+        let mut region = LoopRegion::new();
+        assert_eq!((region.bounds(), region.wrap(100.0)), (None, None));
+
+        // An out point alone loops from the top; a later in point narrows it
+        assert!(region.set_out(20.0));
+        assert_eq!(region.bounds(), Some((0.0, 20.0)));
+        region.set_in(12.0);
+        assert_eq!(region.wrap(19.9), None);
+        assert_eq!(region.wrap(20.0), Some(12.0));
+
+        // Out points before (or right on) the in point are refused; an in point past the out drops it
+        assert!(!region.set_out(12.1));
+        region.set_in(25.0);
+        assert_eq!((region.in_point(), region.out_point()), (Some(25.0), None));
+
+        assert_eq!(LoopRegion::parse("32-48.5").unwrap().bounds(), Some((32.0, 48.5)));
+        assert!(LoopRegion::parse("48-32").is_err() && LoopRegion::parse("32").is_err());
+    }
+}
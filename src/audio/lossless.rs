@@ -0,0 +1,126 @@
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Extensions decoded at full bit depth by `decode_lossless`
+pub const LOSSLESS_EXTENSIONS: [&str; 2] = ["wav", "flac"];
+
+/// A WAV or FLAC file decoded without requantizing: interleaved samples (-1.0 to 1.0), as
+/// many bits of each as the file has
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct LosslessAudio {
+    pub samples: Vec<f32>,
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Bit depth of the source (e.g. 24), which the samples keep
+    pub bits_per_sample: u32,
+}
+
+/// Decode a WAV or FLAC file with symphonia straight to `f32`. rodio's decoder yields i16,
+/// which drops the low 8 bits of 24-bit audio before analysis ever sees it.
+///
+/// Returns None for other extensions, or when symphonia can't read the file, so the caller
+/// can fall back on rodio (and its error messages).
+#[allow(dead_code)]
+pub fn decode_lossless(path: &Path) -> Option<LosslessAudio> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if !LOSSLESS_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let file = std::fs::File::open(path).ok()?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(&extension);
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| log::debug!("symphonia can't read {}: {}", path.display(), e))
+        .ok()?;
+    let mut format = probed.format;
+    let track = format.default_track()?;
+    let (track_id, params) = (track.id, track.codec_params.clone());
+    let mut decoder = symphonia::default::get_codecs().make(&params, &DecoderOptions::default())
+        .map_err(|e| log::debug!("No symphonia decoder for {}: {}", path.display(), e))
+        .ok()?;
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(Error::ResetRequired) => break,
+            Err(e) => {
+                log::debug!("symphonia stopped reading {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let needed = decoded.capacity() * spec.channels.count();
+                if buffer.as_ref().is_none_or(|buffer| buffer.capacity() < needed) {
+                    buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+                }
+                let buffer = buffer.as_mut().expect("buffer was just allocated");
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            // A damaged packet: skip it, as rodio does
+            Err(Error::DecodeError(e)) => log::warn!("⚠️  Skipped a damaged packet in {}: {}", path.display(), e),
+            Err(e) => {
+                log::debug!("symphonia failed to decode {}: {}", path.display(), e);
+                return None;
+            }
+        }
+    }
+
+    Some(LosslessAudio {
+        channels: params.channels.map_or(1, |channels| channels.count() as u16).max(1),
+        sample_rate: params.sample_rate?,
+        bits_per_sample: params.bits_per_sample.unwrap_or(16),
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_24_bit_wav_keeps_its_low_byte() {
+        let directory = TempPath::dir("lossless");
+        let path = directory.join("quiet_24bit.wav");
+
+        // A ramp of steps far below 16-bit resolution (1/256 of an i16 LSB each)
+        let spec = hound::WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: 24, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        let values: Vec<i32> = (0..2000).map(|i| (i % 512) - 256).collect();
+        for &value in &values {
+            writer.write_sample(value).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let decoded = decode_lossless(&path).unwrap();
+        assert_eq!((decoded.channels, decoded.sample_rate, decoded.bits_per_sample), (2, 48000, 24));
+        let restored: Vec<i32> = decoded.samples.iter().map(|&sample| (sample * 8_388_608.0).round() as i32).collect();
+        assert_eq!(restored, values);
+
+        // The public decode path keeps the detail too; through i16 all of it would round to 0 or ±1
+        let (samples, channels, _) = crate::audio::decode_file(&path).unwrap();
+        assert_eq!((samples.len(), channels), (values.len(), 2));
+        assert_eq!(samples[1] * 8_388_608.0, -255.0);
+
+        assert!(decode_lossless(&directory.join("track.mp3")).is_none());
+    }
+}
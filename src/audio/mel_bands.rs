@@ -0,0 +1,109 @@
+/// Bands in the mel spectrum (also the size of the shader's mel array)
+pub const MEL_BANDS: usize = 24;
+
+pub(crate) const MIN_FREQUENCY_HZ: f32 = 30.0; // Lower edge of the first band
+
+pub(crate) fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular filters spaced evenly on the mel scale, from `MIN_FREQUENCY_HZ` to Nyquist.
+///
+/// Low bands are narrower than an FFT bin at small chunk sizes, so every band keeps at least
+/// the bin nearest its centre rather than going silent.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct MelFilterbank {
+    num_bins: usize,
+    filters: Vec<(usize, Vec<f32>)>, // First bin and weights of each band
+}
+
+#[allow(dead_code)]
+impl MelFilterbank {
+    /// Filterbank for a magnitude spectrum of `num_bins` bins spanning DC to Nyquist
+    pub fn new(sample_rate: f32, num_bins: usize, num_bands: usize) -> Self {
+        let num_bins = num_bins.max(1);
+        let nyquist = sample_rate.max(1.0) / 2.0;
+        let bin_hz = nyquist / num_bins as f32;
+        let low_mel = hz_to_mel(MIN_FREQUENCY_HZ.min(nyquist / 2.0));
+        let high_mel = hz_to_mel(nyquist);
+        let edge_hz = |edge: usize| mel_to_hz(low_mel + (high_mel - low_mel) * edge as f32 / (num_bands + 1) as f32);
+
+        let filters = (0..num_bands)
+            .map(|band| {
+                let (start, centre, end) = (edge_hz(band), edge_hz(band + 1), edge_hz(band + 2));
+                let nearest = ((centre / bin_hz).round() as usize).min(num_bins - 1);
+                let first = ((start / bin_hz).ceil() as usize).min(nearest);
+                let last = ((end / bin_hz).floor() as usize).clamp(nearest, num_bins - 1);
+
+                let weights = (first..=last)
+                    .map(|bin| {
+                        let hz = bin as f32 * bin_hz;
+                        let weight = if hz <= centre {
+                            (hz - start) / (centre - start)
+                        } else {
+                            (end - hz) / (end - centre)
+                        };
+                        if bin == nearest { weight.max(1.0) } else { weight.clamp(0.0, 1.0) }
+                    })
+                    .collect();
+                (first, weights)
+            })
+            .collect();
+
+        Self { num_bins, filters }
+    }
+
+    /// Spectrum length this filterbank was built for
+    pub fn num_bins(&self) -> usize {
+        self.num_bins
+    }
+
+    /// Each band's magnitude: the square root of its weighted bin power
+    pub fn apply(&self, spectrum: &[f32]) -> Vec<f32> {
+        self.filters.iter()
+            .map(|(first, weights)| {
+                let power: f32 = spectrum.iter()
+                    .skip(*first)
+                    .zip(weights)
+                    .map(|(magnitude, weight)| magnitude * magnitude * weight)
+                    .sum();
+                power.sqrt()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_lands_in_its_mel_band() {
+        let filterbank = MelFilterbank::new(44100.0, 256, MEL_BANDS);
+        let bin_hz = 22050.0 / 256.0;
+
+        // A single 1 kHz bin lights up the band(s) around 1 kHz and nothing else
+        let mut spectrum = vec![0.0; 256];
+        let tone_bin = (1000.0 / bin_hz) as usize;
+        spectrum[tone_bin] = 1.0;
+        let bands = filterbank.apply(&spectrum);
+        assert_eq!(bands.len(), MEL_BANDS);
+
+        let loudest = bands.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        let centre_hz = |band: usize| {
+            let (low, high) = (hz_to_mel(MIN_FREQUENCY_HZ), hz_to_mel(22050.0));
+            mel_to_hz(low + (high - low) * (band + 1) as f32 / (MEL_BANDS + 1) as f32)
+        };
+        assert!((centre_hz(loudest) - tone_bin as f32 * bin_hz).abs() < centre_hz(loudest) * 0.3);
+        assert!(bands.iter().enumerate().all(|(band, &value)| value == 0.0 || band.abs_diff(loudest) <= 1));
+
+        // Even the narrowest low bands see their nearest bin
+        let flat = vec![1.0; 256];
+        assert!(filterbank.apply(&flat).iter().all(|&value| value >= 1.0));
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+
+/// Title and artist of a loaded track, from its ID3, Vorbis comment, MP4 or RIFF INFO tags,
+/// with the file name to fall back on
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackMetadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub file_name: String,
+}
+
+#[allow(dead_code)]
+impl TrackMetadata {
+    /// Read the tags of `path`. Never fails: an unreadable or untagged file just has no title
+    /// or artist, leaving the file name.
+    pub fn read<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let mut metadata = Self {
+            file_name: path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned()),
+            ..Self::default()
+        };
+
+        for tag in read_tags(path) {
+            // RIFF INFO strings keep their NUL terminator
+            let value = tag.value.to_string().trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+            if value.is_empty() {
+                continue;
+            }
+            match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => metadata.title = metadata.title.or(Some(value)),
+                Some(StandardTagKey::Artist) => metadata.artist = metadata.artist.or(Some(value)),
+                // Only when there's no track artist (compilations often tag just the album artist)
+                Some(StandardTagKey::AlbumArtist) if metadata.artist.is_none() => metadata.artist = Some(value),
+                _ => {}
+            }
+        }
+        metadata
+    }
+
+    /// "Artist — Title", the title alone, or the file name for untagged files
+    pub fn display_text(&self) -> String {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => format!("{} — {}", artist, title),
+            (None, Some(title)) => title.clone(),
+            _ => self.file_name.clone(),
+        }
+    }
+}
+
+/// Tags ahead of the stream (e.g. ID3v2 in front of an MP3) and the container's own, in that order
+fn read_tags(path: &Path) -> Vec<Tag> {
+    let Ok(file) = std::fs::File::open(path) else { return Vec::new() };
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let Ok(mut probed) = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+    else {
+        log::debug!("No readable tags in {}", path.display());
+        return Vec::new();
+    };
+
+    let mut tags = Vec::new();
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|metadata| metadata.current()) {
+        tags.extend_from_slice(revision.tags());
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        tags.extend_from_slice(revision.tags());
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    /// A short silent WAV with a RIFF INFO list (INAM = title, IART = artist) ahead of the data
+    fn tagged_wav(tags: &[(&[u8; 4], &str)]) -> Vec<u8> {
+        let mut info = b"INFO".to_vec();
+        for (id, text) in tags {
+            let mut value = text.as_bytes().to_vec();
+            value.push(0);
+            info.extend_from_slice(*id);
+            info.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            if value.len() % 2 == 1 {
+                value.push(0);
+            }
+            info.extend_from_slice(&value);
+        }
+
+        let mut body = b"WAVE".to_vec();
+        body.extend_from_slice(b"fmt ");
+        body.extend_from_slice(&16u32.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        body.extend_from_slice(&1u16.to_le_bytes()); // Mono
+        body.extend_from_slice(&44100u32.to_le_bytes());
+        body.extend_from_slice(&88200u32.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&16u16.to_le_bytes());
+        body.extend_from_slice(b"LIST");
+        body.extend_from_slice(&(info.len() as u32).to_le_bytes());
+        body.extend_from_slice(&info);
+        body.extend_from_slice(b"data");
+        body.extend_from_slice(&200u32.to_le_bytes());
+        body.extend_from_slice(&[0u8; 200]);
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&body);
+        wav
+    }
+
+    #[test]
+    fn test_tags_give_artist_and_title_with_file_name_fallback() {
+        let directory = TempPath::dir("metadata");
+        let tagged = directory.join("tagged.wav");
+        std::fs::write(&tagged, tagged_wav(&[(b"INAM", "Lava Lamp"), (b"IART", "The Llamas")])).unwrap();
+        let untagged = directory.join("untagged.wav");
+        std::fs::write(&untagged, tagged_wav(&[])).unwrap();
+
+        let metadata = TrackMetadata::read(&tagged);
+        assert_eq!((metadata.artist.as_deref(), metadata.title.as_deref()), (Some("The Llamas"), Some("Lava Lamp")));
+        assert_eq!(metadata.display_text(), "The Llamas — Lava Lamp");
+
+        assert_eq!(TrackMetadata::read(&untagged).display_text(), "untagged.wav");
+        assert_eq!(TrackMetadata::read(directory.join("missing.mp3")).display_text(), "missing.mp3");
+    }
+}
@@ -8,16 +8,165 @@ pub mod analysis_interface;
 pub mod feature_normalizer;
 pub mod cpu_analyzer;
 pub mod gpu_analyzer_wrapper;
+pub mod spectral_whitening;
+pub mod envelope;
+pub mod test_signal;
+pub mod spectrogram;
+pub mod agc;
+pub mod live_analyzer;
+pub mod live_input;
+pub mod feature_import;
+pub mod wav;
+pub mod beat_grid;
+pub mod key_estimation;
+pub mod level_meter;
+pub mod playhead;
+pub mod mel_bands;
+pub mod stereo_phase;
+pub mod tempo_detector;
+pub mod loop_region;
+pub mod metadata;
+pub mod solo_band;
+pub mod sync_monitor;
+pub mod lossless;
+
+/// Open an audio file with rodio's decoder.
+///
+/// Format failures are turned into `UnsupportedFormat`/`DecodeFailed` errors that say what to do
+/// about them (usually: convert the file to WAV), since rodio's own messages don't name the file
+/// or the format.
+#[allow(dead_code)]
+pub fn open_decoder<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<rodio::Decoder<std::io::BufReader<std::fs::File>>> {
+    use crate::error::ArrveeError;
+
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .map_err(|source| ArrveeError::Io { path: path.to_path_buf(), source })?;
+    rodio::Decoder::new(std::io::BufReader::new(file)).map_err(|error| {
+        let (path, reason) = (path.to_path_buf(), format!("{}. {}", error, decode_failure_hint(path)));
+        match error {
+            rodio::decoder::DecoderError::UnrecognizedFormat
+            | rodio::decoder::DecoderError::NoStreams => ArrveeError::UnsupportedFormat { path, reason },
+            _ => ArrveeError::DecodeFailed { path, reason },
+        }
+    })
+}
+
+/// Decode an audio file to interleaved `f32`, returning the samples, channel count and
+/// source sample rate. WAV and FLAC keep their full bit depth (see `lossless::decode_lossless`);
+/// other formats go through rodio.
+///
+/// Fails with the same guidance as `open_decoder` when the file opens but yields no audio,
+/// which is how unsupported codec variants inside a known container (e.g. HE-AAC or ALAC in
+/// an `.m4a`) tend to show up.
+#[allow(dead_code)]
+pub fn decode_file<P: AsRef<std::path::Path>>(path: P) -> crate::error::Result<(Vec<f32>, u16, u32)> {
+    use rodio::Source;
+
+    let path = path.as_ref();
+    if let Some(audio) = lossless::decode_lossless(path).filter(|audio| !audio.samples.is_empty()) {
+        return Ok((audio.samples, audio.channels, audio.sample_rate));
+    }
+    let source = open_decoder(path)?;
+    let channels = source.channels().max(1);
+    let sample_rate = source.sample_rate();
+    let samples: Vec<f32> = source.convert_samples().collect();
+    if samples.is_empty() {
+        return Err(crate::error::ArrveeError::UnsupportedFormat {
+            path: path.to_path_buf(),
+            reason: format!("decoded no audio. {}", decode_failure_hint(path)),
+        });
+    }
+    Ok((samples, channels, sample_rate))
+}
+
+/// Decode an audio file to mono `f32`, returning the samples and the source sample rate
+#[allow(dead_code)]
+pub fn decode_file_to_mono<P: AsRef<std::path::Path>>(path: P, mode: DownmixMode) -> crate::error::Result<(Vec<f32>, u32)> {
+    let (samples, sample_rate, _) = decode_file_to_mono_checked(path, mode, false)?;
+    Ok((samples, sample_rate))
+}
+
+/// Decode an audio file to mono `f32` like `decode_file_to_mono`, also returning the stereo
+/// phase check (see `downmix_checked`)
+#[allow(dead_code)]
+pub fn decode_file_to_mono_checked<P: AsRef<std::path::Path>>(path: P, mode: DownmixMode, fix_phase: bool)
+    -> crate::error::Result<(Vec<f32>, u32, stereo_phase::StereoPhase)>
+{
+    let (samples, channels, sample_rate) = decode_file(path)?;
+    let (mono, phase) = downmix_checked(&samples, channels as usize, mode, fix_phase);
+    Ok((mono, sample_rate, phase))
+}
+
+/// Analyze a mono buffer offline, one `AudioFrame` per `chunk_size` samples (the analysis
+/// `PrescanProcessor::prescan_file` runs over a decoded track); a trailing partial chunk is skipped
+#[allow(dead_code)]
+pub fn analyze_buffer(samples: &[f32], sample_rate: u32, chunk_size: usize) -> Vec<AudioFrame> {
+    let mut analyzer = fft::AudioAnalyzer::new(sample_rate as f32, chunk_size);
+    samples.chunks_exact(chunk_size.max(1)).map(|chunk| analyzer.analyze(chunk)).collect()
+}
+
+/// What to try when a file won't decode, tailored to its extension
+fn decode_failure_hint(path: &std::path::Path) -> String {
+    let extension = path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let convert = format!("ffmpeg -i \"{}\" output.wav", path.display());
+    match extension.as_str() {
+        "m4a" | "mp4" | "aac" => format!(
+            "AAC support needs rodio's symphonia backend (the \"symphonia-aac\" and \"symphonia-isomp4\" \
+             features); HE-AAC and ALAC streams are not supported by it. Converting to WAV always works: {}",
+            convert
+        ),
+        "wav" | "mp3" | "ogg" | "flac" => format!("The file may be damaged or use an unusual encoding; try re-encoding it: {}", convert),
+        _ => format!("Supported formats are WAV, MP3, OGG, FLAC and M4A/AAC; convert it first: {}", convert),
+    }
+}
+
+/// Extensions (lowercase) of the audio files the decoder can open
+pub const AUDIO_EXTENSIONS: [&str; 7] = ["wav", "mp3", "ogg", "flac", "m4a", "mp4", "aac"];
+
+/// Whether `path` has one of the `AUDIO_EXTENSIONS` (case-insensitive)
+#[allow(dead_code)]
+pub fn is_audio_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| AUDIO_EXTENSIONS.iter().any(|audio| ext.eq_ignore_ascii_case(audio)))
+}
+
+/// Every audio file under `directory`, searched recursively, in path order.
+///
+/// Symlinked directories are skipped, so a link back up the tree can't loop forever;
+/// symlinked files are listed like any other.
+#[allow(dead_code)]
+pub fn find_audio_files<P: AsRef<std::path::Path>>(directory: P) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![directory.as_ref().to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let (path, file_type) = (entry.path(), entry.file_type()?);
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_symlink() && path.is_dir() {
+                log::debug!("Skipping symlinked directory {}", path.display());
+            } else if is_audio_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
 
 pub use beat_detector::BeatDetector;
 pub use playback::AudioPlayback;
-pub use analysis_interface::{AudioAnalyzer, RawAudioFeatures, NormalizedAudioFeatures};
+pub use analysis_interface::{AudioAnalyzer, FallbackAnalyzer, RawAudioFeatures, NormalizedAudioFeatures};
 pub use feature_normalizer::FeatureNormalizer;
 pub use cpu_analyzer::CpuAudioAnalyzer;
 pub use gpu_analyzer_wrapper::GpuAudioAnalyzer as NewGpuAudioAnalyzer;
 pub use prescan::{PrescanData, SynchronizedPlayback, PrescanProcessor};
 pub use arv_format::ArvFormat;
-pub use gpu_analyzer::{GpuAudioAnalyzer, GpuAudioFeatures};
+pub use gpu_analyzer::{ComputeUnavailable, GpuAudioAnalyzer, GpuAudioFeatures};
 
 #[derive(Debug, Clone)]
 pub struct AudioFrame {
@@ -25,6 +174,10 @@ pub struct AudioFrame {
     pub sample_rate: f32,
     #[allow(dead_code)]
     pub spectrum: Vec<f32>,
+    /// `mel_bands::MEL_BANDS` levels (0.0-1.0) of a mel-scaled spectrum; empty unless the CPU
+    /// analyzer's mel bands are enabled
+    #[allow(dead_code)]
+    pub mel_bands: Vec<f32>,
     #[allow(dead_code)]
     pub time_domain: Vec<f32>,
     pub frequency_bands: FrequencyBands,
@@ -52,11 +205,73 @@ pub struct FrequencyBands {
     pub presence: f32,
 }
 
+impl AudioFrame {
+    /// Scale the reactive features by a sensitivity factor, keeping them in 0.0-1.0.
+    /// Descriptive features (centroid, rolloff, ZCR, pitch, BPM) are left as-is.
+    #[allow(dead_code)]
+    pub fn apply_sensitivity(&mut self, sensitivity: f32) {
+        let scale = |value: f32| (value * sensitivity).clamp(0.0, 1.0);
+
+        self.frequency_bands.sub_bass = scale(self.frequency_bands.sub_bass);
+        self.frequency_bands.bass = scale(self.frequency_bands.bass);
+        self.frequency_bands.mid = scale(self.frequency_bands.mid);
+        self.frequency_bands.treble = scale(self.frequency_bands.treble);
+        self.frequency_bands.presence = scale(self.frequency_bands.presence);
+        self.beat_strength = scale(self.beat_strength);
+        self.volume = scale(self.volume);
+        self.spectral_flux = scale(self.spectral_flux);
+        self.onset_strength = scale(self.onset_strength);
+        self.dynamic_range = scale(self.dynamic_range);
+    }
+
+    /// Whether every feature, spectrum bin and mel band is a finite number
+    #[allow(dead_code)]
+    pub fn is_finite(&self) -> bool {
+        self.scalar_features().iter().all(|value| value.is_finite())
+            && self.spectrum.iter().chain(&self.mel_bands).all(|value| value.is_finite())
+    }
+
+    /// Replace NaN/infinite features, spectrum bins and mel bands with 0 (the BPM estimate with
+    /// the default 120), so a bad frame can't reach the shader as NaN uniforms
+    #[allow(dead_code)]
+    pub fn sanitize(&mut self) {
+        let finite = |value: &mut f32, fallback: f32| {
+            if !value.is_finite() {
+                *value = fallback;
+            }
+        };
+        let bands = &mut self.frequency_bands;
+        for value in [
+            &mut bands.sub_bass, &mut bands.bass, &mut bands.mid, &mut bands.treble, &mut bands.presence,
+            &mut self.beat_strength, &mut self.volume, &mut self.spectral_centroid, &mut self.spectral_rolloff,
+            &mut self.zero_crossing_rate, &mut self.spectral_flux, &mut self.onset_strength,
+            &mut self.pitch_confidence, &mut self.dynamic_range,
+        ] {
+            finite(value, 0.0);
+        }
+        finite(&mut self.estimated_bpm, 120.0);
+        for value in self.spectrum.iter_mut().chain(&mut self.mel_bands) {
+            finite(value, 0.0);
+        }
+    }
+
+    fn scalar_features(&self) -> [f32; 15] {
+        let bands = &self.frequency_bands;
+        [
+            bands.sub_bass, bands.bass, bands.mid, bands.treble, bands.presence,
+            self.beat_strength, self.volume, self.spectral_centroid, self.spectral_rolloff,
+            self.zero_crossing_rate, self.spectral_flux, self.onset_strength,
+            self.pitch_confidence, self.estimated_bpm, self.dynamic_range,
+        ]
+    }
+}
+
 impl Default for AudioFrame {
     fn default() -> Self {
         Self {
             sample_rate: 44100.0,
             spectrum: vec![0.0; 512],
+            mel_bands: Vec::new(),
             time_domain: vec![0.0; 1024],
             frequency_bands: FrequencyBands::default(),
             beat_detected: false,
@@ -84,4 +299,323 @@ impl Default for FrequencyBands {
             presence: 0.0,
         }
     }
-}
\ No newline at end of file
+}
+/// How stereo (or multichannel) sources are folded to the mono analysis signal
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DownmixMode {
+    /// Mean of all channels
+    #[default]
+    Average,
+    /// Sum of all channels / sqrt(channels): keeps the level of uncorrelated (wide) stereo
+    /// the same as a mono source, where the mean would lose 3 dB (centre-panned content comes
+    /// out 3 dB hotter instead)
+    Rms,
+    Left,
+    Right,
+    /// 0.5 * (L + R): centre-panned content such as vocals, bass and kick
+    Mid,
+    /// 0.5 * (L - R): wide stereo content; silent for mono sources
+    Side,
+}
+
+impl std::str::FromStr for DownmixMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "average" | "avg" => Ok(Self::Average),
+            "rms" | "power" => Ok(Self::Rms),
+            "left" | "l" => Ok(Self::Left),
+            "right" | "r" => Ok(Self::Right),
+            "mid" => Ok(Self::Mid),
+            "side" => Ok(Self::Side),
+            other => Err(anyhow::anyhow!(
+                "Unknown mono mix '{}'. Use: average, rms, left, right, mid, side", other
+            )),
+        }
+    }
+}
+
+/// How much real-time analysis runs per visual frame
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AnalysisProfile {
+    /// Every feature, averaged over several overlapping windows per frame
+    #[default]
+    Full,
+    /// One window per frame with bands, volume, centroid and beats only; rolloff, flux,
+    /// zero crossings, onset and pitch confidence read as 0. For laptops on battery.
+    Lite,
+}
+
+impl std::str::FromStr for AnalysisProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "lite" | "light" => Ok(Self::Lite),
+            other => Err(anyhow::anyhow!("Unknown analysis profile '{}'. Use: full, lite", other)),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl AnalysisProfile {
+    /// The optional features this profile computes
+    pub fn features(self) -> FeatureMask {
+        match self {
+            Self::Full => FeatureMask::all(),
+            Self::Lite => FeatureMask::CENTROID | FeatureMask::DYNAMIC_RANGE,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Optional per-chunk features of the CPU analyzer. Bands, volume and beats are always
+    /// computed; a feature left out of the mask is skipped and reads as 0.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct FeatureMask: u32 {
+        const CENTROID = 1 << 0;
+        const ROLLOFF = 1 << 1;
+        const ZERO_CROSSING_RATE = 1 << 2;
+        const FLUX = 1 << 3;
+        const ONSET = 1 << 4;
+        const PITCH_CONFIDENCE = 1 << 5;
+        const DYNAMIC_RANGE = 1 << 6;
+    }
+}
+
+/// `--skip-features` names for each optional feature
+#[allow(dead_code)]
+pub const FEATURE_NAMES: [(&str, FeatureMask); 7] = [
+    ("centroid", FeatureMask::CENTROID),
+    ("rolloff", FeatureMask::ROLLOFF),
+    ("zcr", FeatureMask::ZERO_CROSSING_RATE),
+    ("flux", FeatureMask::FLUX),
+    ("onset", FeatureMask::ONSET),
+    ("pitch", FeatureMask::PITCH_CONFIDENCE),
+    ("dynamic-range", FeatureMask::DYNAMIC_RANGE),
+];
+
+impl Default for FeatureMask {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// A comma-separated list of feature names (see `FEATURE_NAMES`), e.g. `pitch,rolloff`
+impl std::str::FromStr for FeatureMask {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        s.split(',').map(str::trim).filter(|name| !name.is_empty()).try_fold(Self::empty(), |mask, name| {
+            let name = name.to_lowercase().replace('_', "-");
+            FEATURE_NAMES.iter().find(|(feature, _)| *feature == name).map(|&(_, flag)| mask | flag)
+                .ok_or_else(|| anyhow::anyhow!(
+                    "Unknown feature '{}'. Use: {}", name,
+                    FEATURE_NAMES.map(|(feature, _)| feature).join(", ")
+                ))
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl FeatureMask {
+    /// Names of the features in the mask, comma-separated ("none" when empty)
+    pub fn names(self) -> String {
+        let names: Vec<_> = FEATURE_NAMES.iter().filter(|(_, flag)| self.contains(*flag)).map(|(name, _)| *name).collect();
+        if names.is_empty() { "none".to_string() } else { names.join(", ") }
+    }
+}
+
+impl DownmixMode {
+    /// Fold one interleaved frame (one sample per channel) to mono
+    fn mix(self, frame: &[f32]) -> f32 {
+        let left = frame[0];
+        let right = frame.get(1).copied().unwrap_or(left);
+        match self {
+            Self::Average => frame.iter().sum::<f32>() / frame.len() as f32,
+            Self::Rms => frame.iter().sum::<f32>() / (frame.len() as f32).sqrt(),
+            Self::Left => left,
+            Self::Right => right,
+            Self::Mid => 0.5 * (left + right),
+            Self::Side => 0.5 * (left - right),
+        }
+    }
+}
+
+/// Decode a rodio source straight to normalized `f32` and fold its channels to mono.
+///
+/// Converting to float before the downmix avoids clipping when integer channels are summed.
+/// It can't restore precision the source never had: rodio's file decoder yields i16, so files
+/// that need more (24-bit WAV/FLAC) should go through `decode_file_to_mono`.
+#[allow(dead_code)]
+pub fn decode_to_mono<S>(source: S, mode: DownmixMode) -> Vec<f32>
+where
+    S: rodio::Source,
+    S::Item: rodio::Sample,
+    f32: rodio::cpal::FromSample<S::Item>,
+{
+    let channels = source.channels().max(1) as usize;
+    let samples: Vec<f32> = source.convert_samples().collect();
+    downmix(&samples, channels, mode)
+}
+
+/// Replace NaN and infinite samples (from corrupt files or misbehaving decoders) with silence,
+/// warning once with the count; returns how many were replaced
+#[allow(dead_code)]
+pub fn sanitize_samples(samples: &mut [f32]) -> usize {
+    let mut replaced = 0;
+    for sample in samples.iter_mut().filter(|sample| !sample.is_finite()) {
+        *sample = 0.0;
+        replaced += 1;
+    }
+    if replaced > 0 {
+        log::warn!("⚠️  Replaced {} NaN/infinite samples with silence (corrupt file or decoder bug?)", replaced);
+    }
+    replaced
+}
+
+/// Downmix interleaved samples to mono. Non-finite samples count as silence (see `sanitize_samples`),
+/// since one NaN would otherwise poison every FFT window it falls in.
+///
+/// Mono is copied as is. A trailing partial frame (a sample count that isn't a multiple of
+/// `channels`, from a truncated file or a miscounting decoder) is mixed from the channels it has
+/// rather than dropped, with a warning.
+#[allow(dead_code)]
+pub fn downmix(interleaved: &[f32], channels: usize, mode: DownmixMode) -> Vec<f32> {
+    if interleaved.iter().any(|sample| !sample.is_finite()) {
+        let mut sanitized = interleaved.to_vec();
+        sanitize_samples(&mut sanitized);
+        return downmix(&sanitized, channels, mode);
+    }
+    let channels = channels.max(1);
+    if channels == 1 {
+        return interleaved.to_vec();
+    }
+    let leftover = interleaved.len() % channels;
+    if leftover != 0 {
+        log::warn!("⚠️  {} samples is not a whole number of {}-channel frames; mixing the last {} as a partial frame",
+                   interleaved.len(), channels, leftover);
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| mode.mix(frame))
+        .collect()
+}
+
+/// Downmix after checking the stereo phase, warning about out-of-phase sources (whose mono
+/// mix cancels bass and vocals). With `fix_phase` their right channel is inverted first.
+#[allow(dead_code)]
+pub fn downmix_checked(interleaved: &[f32], channels: usize, mode: DownmixMode, fix_phase: bool) -> (Vec<f32>, stereo_phase::StereoPhase) {
+    if interleaved.iter().any(|sample| !sample.is_finite()) {
+        let mut sanitized = interleaved.to_vec();
+        sanitize_samples(&mut sanitized);
+        return downmix_checked(&sanitized, channels, mode, fix_phase);
+    }
+
+    let mut phase = stereo_phase::StereoPhase::measure(interleaved, channels);
+    if !phase.is_out_of_phase() {
+        return (downmix(interleaved, channels, mode), phase);
+    }
+
+    if !fix_phase {
+        log::warn!("⚠️  Left and right channels are out of phase (correlation {:.2}); the mono mix will lose \
+                    centre content like bass and vocals. Use --fix-phase to invert the right channel", phase.correlation);
+        return (downmix(interleaved, channels, mode), phase);
+    }
+
+    log::info!("🔄 Out-of-phase stereo (correlation {:.2}): inverting the right channel before the downmix", phase.correlation);
+    let mut fixed = interleaved.to_vec();
+    stereo_phase::invert_right_channel(&mut fixed, channels);
+    phase.corrected = true;
+    (downmix(&fixed, channels, mode), phase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_rms_downmix_keeps_uncorrelated_stereo_level() {
+        // Two independent noise channels at the same level (a cheap LCG keeps this deterministic)
+        let mut seed = 12345u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+        };
+        let frames: Vec<[f32; 2]> = (0..48000).map(|_| [noise(), noise()]).collect();
+        let rms = |samples: &[f32]| (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        let left: Vec<f32> = frames.iter().map(|frame| frame[0]).collect();
+        let mixed = |mode: DownmixMode| -> Vec<f32> { frames.iter().map(|frame| mode.mix(frame)).collect() };
+
+        let mono_level = rms(&left);
+        let average_level = rms(&mixed(DownmixMode::Average));
+        let rms_level = rms(&mixed(DownmixMode::Rms));
+        assert!((rms_level / mono_level - 1.0).abs() < 0.05, "rms mix {} vs mono {}", rms_level, mono_level);
+        assert!((average_level / mono_level - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.05);
+
+        // A mono source passes through unchanged
+        assert_eq!(DownmixMode::Rms.mix(&[0.25]), 0.25);
+    }
+
+    #[test]
+    fn test_downmix_keeps_a_partial_last_frame_and_copies_mono() {
+        // Two stereo frames and a lone left sample from a truncated file
+        let interleaved = [0.25, 0.75, -0.5, -0.25, 0.5];
+        assert_eq!(downmix(&interleaved, 2, DownmixMode::Average), vec![0.5, -0.375, 0.5]);
+        assert_eq!(downmix(&interleaved, 2, DownmixMode::Right), vec![0.75, -0.25, 0.5]);
+
+        // Mono is copied sample for sample, in every mode (side of mono would otherwise be silence)
+        assert_eq!(downmix(&interleaved, 1, DownmixMode::Side), interleaved.to_vec());
+        assert_eq!(downmix(&interleaved, 0, DownmixMode::Average).len(), 5);
+    }
+
+    #[test]
+    fn test_nan_samples_are_silenced_before_analysis() {
+        // Stereo with a few corrupt samples, as some decoders yield for damaged files
+        let mut interleaved: Vec<f32> = (0..16384).map(|i| ((i / 2) as f32 * 0.05).sin() * 0.5).collect();
+        for index in [10, 4001, 4002, 9000] {
+            interleaved[index] = if index % 2 == 0 { f32::NAN } else { f32::INFINITY };
+        }
+
+        let (mono, _) = downmix_checked(&interleaved, 2, DownmixMode::Average, false);
+        assert!(mono.iter().all(|sample| sample.is_finite()));
+        assert!(analyze_buffer(&mono, 44100, 1024).iter().all(AudioFrame::is_finite));
+        assert_eq!(sanitize_samples(&mut interleaved), 4);
+
+        // And at the output end, a frame that is already bad is zeroed
+        let mut frame = AudioFrame { volume: f32::NAN, estimated_bpm: f32::INFINITY, ..AudioFrame::default() };
+        frame.spectrum[3] = f32::NEG_INFINITY;
+        assert!(!frame.is_finite());
+        frame.sanitize();
+        assert!(frame.is_finite() && frame.volume == 0.0 && frame.estimated_bpm == 120.0);
+    }
+
+    #[test]
+    fn test_audio_files_are_found_recursively_in_path_order() {
+        let directory = TempPath::dir("find_audio");
+        std::fs::create_dir_all(directory.join("album")).unwrap();
+        for name in ["b.mp3", "a.WAV", "notes.txt", "a.arv", "album/track.flac"] {
+            std::fs::write(directory.join(name), b"").unwrap();
+        }
+        // A link back up the tree is not followed (it would loop forever); linked files are kept
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&directory, directory.join("album/loop")).unwrap();
+            std::os::unix::fs::symlink(directory.join("b.mp3"), directory.join("album/linked.mp3")).unwrap();
+        }
+
+        let found = find_audio_files(&directory).unwrap();
+        let names: Vec<_> = found.iter().map(|path| path.strip_prefix(&directory).unwrap().to_path_buf()).collect();
+        #[cfg(unix)]
+        assert_eq!(names, ["a.WAV", "album/linked.mp3", "album/track.flac", "b.mp3"].map(std::path::PathBuf::from));
+        #[cfg(not(unix))]
+        assert_eq!(names, ["a.WAV", "album/track.flac", "b.mp3"].map(std::path::PathBuf::from));
+        assert!(find_audio_files(directory.join("missing")).is_err());
+    }
+}
@@ -1,10 +1,29 @@
 use anyhow::Result;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
-use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::path::{Path, PathBuf};
 use log::info;
-use crate::audio::{AudioFrame, AudioAnalyzer, CpuAudioAnalyzer, NewGpuAudioAnalyzer, FeatureNormalizer, NormalizedAudioFeatures};
+use crate::audio::stereo_phase::StereoPhase;
+use crate::audio::{AnalysisProfile, AudioFrame, FeatureMask, DownmixMode, AudioAnalyzer, ComputeUnavailable, CpuAudioAnalyzer, FallbackAnalyzer, NewGpuAudioAnalyzer, FeatureNormalizer, NormalizedAudioFeatures};
+use crate::audio::feature_normalizer::NormalizationParameters;
+use crate::audio::envelope::{AudioEnvelope, EnvelopeConfig, SmoothingConfig};
+use crate::audio::agc::{AgcConfig, AutomaticGainControl};
+use crate::audio::wav::{self, SourceAudio};
+use crate::audio::level_meter::LevelMeter;
+use crate::audio::playhead::Playhead;
+use crate::audio::metadata::TrackMetadata;
+use crate::audio::sync_monitor::SyncMonitor;
+use crate::audio::live_analyzer::LiveAnalyzer;
+use crate::audio::live_input::LiveInput;
+use crate::error::ArrveeError;
+
+// At 60fps, we should process ~735 samples per frame (44100/60)
+#[allow(dead_code)]
+const SAMPLES_PER_FRAME: usize = 735;
+
+/// Slowest playback rate `set_speed` accepts
+pub const MIN_PLAYBACK_SPEED: f32 = 0.25;
+/// Fastest playback rate `set_speed` accepts
+pub const MAX_PLAYBACK_SPEED: f32 = 4.0;
 
 pub struct AudioPlayback {
     #[allow(dead_code)]
@@ -13,15 +32,41 @@ pub struct AudioPlayback {
     sink: Option<Sink>,
     analyzer: Option<Box<dyn AudioAnalyzer + Send>>,
     normalizer: Option<FeatureNormalizer>,
+    normalization: NormalizationParameters, // Ranges the next analysis normalizer starts with
     sensitivity_factor: f32,
+    envelope: AudioEnvelope,
+    agc: Option<AutomaticGainControl>,
     sample_rate: u32,
     audio_buffer: Vec<f32>,
+    source_buffer: Vec<f32>, // Interleaved source channels for level metering (empty for mono sources)
+    source_channels: u16,
+    levels: LevelMeter,
     buffer_position: usize,
+    playhead: Playhead,
+    follow_playhead: bool,
+    log_magnitude: bool, // dB band mapping, CPU analyzer only
+    mel_bands: bool,     // Mel spectrum in each frame, CPU analyzer only
+    profile: AnalysisProfile,
+    feature_mask: FeatureMask, // Optional features to compute, CPU analyzer only
+    bpm_smoothing: f32,        // See `TempoDetector::set_smoothing`; CPU analyzer only
+    source_path: Option<PathBuf>, // Re-decoded at full quality for export (see `source_audio`)
+    downmix: DownmixMode,
+    fix_phase: bool,
+    stereo_phase: StereoPhase, // Phase check of the loaded file
+    speed: f32,                // Playback rate; the analysis advances this many frames' worth of samples per frame
+    metadata: Option<TrackMetadata>, // Tags of the loaded file (None for in-memory audio)
+    raw_frame: AudioFrame,           // Last analyzed frame before the envelopes, for the smoothing scope
+    sync_monitor: SyncMonitor,       // Warns when the analysis drifts off the audio playhead
+    last_analysis_start: Option<usize>, // Buffer position of the previous frame's analysis
+    live: Option<(LiveInput, LiveAnalyzer)>, // Input device analyzed instead of a loaded track
 }
 
 impl AudioPlayback {
-    pub fn new() -> Result<Self> {
-        let (stream, stream_handle) = OutputStream::try_default()?;
+    /// Open the default output device; fails with `ArrveeError::NoAudioDevice` on machines
+    /// without one (headless servers, CI)
+    pub fn new() -> crate::error::Result<Self> {
+        let (stream, stream_handle) = OutputStream::try_default()
+            .map_err(|e| ArrveeError::NoAudioDevice(e.to_string()))?;
 
         Ok(Self {
             stream,
@@ -29,42 +74,323 @@ impl AudioPlayback {
             sink: None,
             analyzer: None,
             normalizer: None,
+            normalization: NormalizationParameters::default(),
             sensitivity_factor: 1.0,
+            envelope: AudioEnvelope::default(),
+            agc: None,
             sample_rate: 44100,
             audio_buffer: Vec::new(),
+            source_buffer: Vec::new(),
+            source_channels: 1,
+            levels: LevelMeter::new(),
             buffer_position: 0,
+            playhead: Playhead::new(),
+            follow_playhead: false,
+            log_magnitude: false,
+            mel_bands: false,
+            profile: AnalysisProfile::Full,
+            feature_mask: FeatureMask::all(),
+            bpm_smoothing: crate::audio::tempo_detector::DEFAULT_BPM_SMOOTHING,
+            source_path: None,
+            downmix: DownmixMode::default(),
+            fix_phase: false,
+            stereo_phase: StereoPhase::default(),
+            speed: 1.0,
+            metadata: None,
+            raw_frame: AudioFrame::default(),
+            sync_monitor: SyncMonitor::default(),
+            last_analysis_start: None,
+            live: None,
         })
     }
 
-    pub async fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        let file = BufReader::new(File::open(&path)?);
-        let source = Decoder::new(file)?;
+    pub async fn load_file<P: AsRef<Path>>(&mut self, path: P) -> crate::error::Result<()> {
+        // Decode once; the mono mix feeds analysis, the source channels feed the level meter
+        let (mut samples, channels, sample_rate) = super::decode_file(&path)?;
+        super::sanitize_samples(&mut samples); // Also keeps them out of the level meter
+        self.sample_rate = sample_rate;
+        (self.audio_buffer, self.stereo_phase) = super::downmix_checked(&samples, channels as usize, self.downmix, self.fix_phase);
+        self.source_channels = channels;
+        self.source_buffer = if channels > 1 { samples } else { Vec::new() };
 
-        // Get sample rate and convert to f32 samples for analysis
-        self.sample_rate = source.sample_rate();
-        let channels = source.channels();
+        self.init_analysis().await?;
 
-        // Collect samples for analysis
-        let samples: Vec<i16> = source.convert_samples().collect();
+        // Load file again for playback (since we consumed the decoder above)
+        let source = super::open_decoder(&path)?;
+        let sink = Sink::try_new(&self.stream_handle).map_err(|e| ArrveeError::NoAudioDevice(e.to_string()))?;
+        sink.append(self.playhead.track(source));
+        sink.set_speed(self.speed);
+        sink.pause();
+
+        info!("Loaded audio file: {:?} ({}Hz, {} samples)", path.as_ref(), self.sample_rate, self.audio_buffer.len());
+        self.sink = Some(sink);
+        self.source_path = Some(path.as_ref().to_path_buf());
+        self.metadata = Some(TrackMetadata::read(&path));
+
+        Ok(())
+    }
+
+    /// How stereo files are folded to mono for analysis (applies to the next `load_file`;
+    /// playback itself stays stereo)
+    #[allow(dead_code)]
+    pub fn set_downmix(&mut self, mode: DownmixMode) {
+        self.downmix = mode;
+    }
+
+    /// Invert the right channel of out-of-phase stereo files before the analysis downmix
+    /// (applies to the next `load_file`; playback is untouched)
+    #[allow(dead_code)]
+    pub fn set_fix_phase(&mut self, enabled: bool) {
+        self.fix_phase = enabled;
+    }
+
+    /// Title/artist tags of the loaded file, for the now-playing overlay (`None` for in-memory audio)
+    #[allow(dead_code)]
+    pub fn metadata(&self) -> Option<&TrackMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// L/R phase check of the loaded file
+    #[allow(dead_code)]
+    pub fn stereo_phase(&self) -> StereoPhase {
+        self.stereo_phase
+    }
+
+    /// Take each frame's analysis position from the samples the output has actually played
+    /// instead of advancing one frame per call, so pauses and underruns can't make the
+    /// visuals drift from the sound
+    #[allow(dead_code)]
+    pub fn set_follow_playhead(&mut self, enabled: bool) {
+        self.follow_playhead = enabled;
+    }
+
+    /// Extract bands from dB levels rather than linear magnitudes (see
+    /// `CpuAudioAnalyzer::set_log_magnitude`; applies to the next load and uses the CPU analyzer).
+    /// Pair with `NormalizationParameters::log_magnitude()`.
+    #[allow(dead_code)]
+    pub fn set_log_magnitude(&mut self, enabled: bool) {
+        if enabled != self.log_magnitude {
+            self.log_magnitude = enabled;
+            self.analyzer = None; // Rebuilt with the new band mapping on the next load
+        }
+    }
+
+    /// Fill each frame's `mel_bands` (see `CpuAudioAnalyzer::set_mel_bands`; applies to the next
+    /// load and uses the CPU analyzer)
+    #[allow(dead_code)]
+    pub fn set_mel_bands(&mut self, enabled: bool) {
+        if enabled != self.mel_bands {
+            self.mel_bands = enabled;
+            self.analyzer = None;
+        }
+    }
+
+    /// Analysis per frame: `Lite` analyzes one window instead of several overlapping ones and
+    /// skips the features beat-driven visuals don't need (applies to the next load; uses the CPU analyzer)
+    #[allow(dead_code)]
+    pub fn set_analysis_profile(&mut self, profile: AnalysisProfile) {
+        if profile != self.profile {
+            self.profile = profile;
+            self.analyzer = None;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn analysis_profile(&self) -> AnalysisProfile {
+        self.profile
+    }
+
+    /// Compute only these optional features, skipping the rest to save CPU per chunk (applies
+    /// to the next load; anything short of all of them uses the CPU analyzer)
+    #[allow(dead_code)]
+    pub fn set_feature_mask(&mut self, features: FeatureMask) {
+        if features != self.feature_mask {
+            self.feature_mask = features;
+            self.analyzer = None;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn feature_mask(&self) -> FeatureMask {
+        self.feature_mask
+    }
 
-        // Convert to f32 and mix to mono for analysis
-        self.audio_buffer = samples
-            .chunks_exact(channels as usize)
-            .map(|chunk| {
-                let sum: f32 = chunk.iter().map(|&s| s as f32 / 32768.0).sum();
-                sum / channels as f32
-            })
-            .collect();
+    /// How slowly the frames' BPM follows tempo changes (0 = median of recent beats as-is;
+    /// see `TempoDetector::set_smoothing`). The GPU analyzer's tempo ignores it.
+    #[allow(dead_code)]
+    pub fn set_bpm_smoothing(&mut self, smoothing: f32) {
+        self.bpm_smoothing = smoothing;
+        if let Some(analyzer) = &mut self.analyzer {
+            analyzer.set_bpm_smoothing(smoothing);
+        }
+    }
+
+    /// Play faster or slower (pitch follows, as with a turntable), clamped to
+    /// `MIN_PLAYBACK_SPEED`-`MAX_PLAYBACK_SPEED`. The analysis steps through the track at the
+    /// same rate, so the visuals stay on the slowed or sped-up audio.
+    #[allow(dead_code)]
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED);
+        if let Some(sink) = &self.sink {
+            sink.set_speed(self.speed);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
 
-        // Create unified analyzer with GPU/CPU fallback
+    /// Track samples the analysis moves on per rendered frame at the current speed; none
+    /// while the audio is paused, so the visuals idle on the paused moment
+    fn frame_advance(&self) -> usize {
+        if self.sink.as_ref().is_some_and(|sink| sink.is_paused()) {
+            return 0;
+        }
+        ((SAMPLES_PER_FRAME as f32 * self.speed).round() as usize).max(1)
+    }
+
+    /// Seconds of the loaded track played so far
+    #[allow(dead_code)]
+    pub fn playhead_seconds(&self) -> f32 {
+        self.playhead.seconds(self.sample_rate)
+    }
+
+    /// Jump playback and analysis to `seconds` into the track. Fails for formats whose
+    /// decoder can't seek; playback carries on from where it was.
+    #[allow(dead_code)]
+    pub fn seek(&mut self, seconds: f32) -> Result<()> {
+        let seconds = seconds.max(0.0);
+        if let Some(sink) = &self.sink {
+            sink.try_seek(std::time::Duration::from_secs_f32(seconds))
+                .map_err(|e| anyhow::anyhow!("Could not seek to {:.2}s: {}", seconds, e))?;
+        }
+        let position = (seconds * self.sample_rate as f32) as usize;
+        self.buffer_position = position.min(self.audio_buffer.len().saturating_sub(1));
+        self.reset_sync_monitor();
+        Ok(())
+    }
+
+    /// Normalization ranges for analysis, e.g. fitted to a track by `audio-analyzer
+    /// --apply-suggestions` (applies to the next `load_file`/`load_samples`)
+    #[allow(dead_code)]
+    pub fn set_normalization_parameters(&mut self, parameters: NormalizationParameters) {
+        self.normalization = parameters;
+    }
+
+    /// The loaded audio at source quality (original channels, sample rate and, for WAV, bit
+    /// depth) rather than the mono analysis buffer, for muxing into exports
+    #[allow(dead_code)]
+    pub fn source_audio(&self) -> Result<SourceAudio> {
+        match &self.source_path {
+            Some(path) => wav::read_source_audio(path),
+            None => Ok(SourceAudio::from_samples(self.audio_buffer.clone(), 1, self.sample_rate)),
+        }
+    }
+
+    /// Write the loaded audio at source quality as a WAV file (see `source_audio`)
+    #[allow(dead_code)]
+    pub fn write_source_wav<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let audio = self.source_audio()?;
+        wav::write_wav(&path, &audio)?;
+        info!("💾 Wrote {}ch {}Hz {}-bit audio to {:?}",
+              audio.spec.channels, audio.spec.sample_rate, audio.spec.bits_per_sample, path.as_ref());
+        Ok(())
+    }
+
+    /// Load an in-memory mono buffer (e.g. a synthesized test signal) for playback and analysis
+    #[allow(dead_code)]
+    pub async fn load_samples(&mut self, mut samples: Vec<f32>, sample_rate: u32) -> crate::error::Result<()> {
+        super::sanitize_samples(&mut samples);
+        self.sample_rate = sample_rate;
+        self.audio_buffer = samples;
+        self.source_buffer = Vec::new();
+        self.source_channels = 1;
+
+        self.init_analysis().await?;
+
+        let source = rodio::buffer::SamplesBuffer::new(1, sample_rate, self.audio_buffer.clone());
+        let sink = Sink::try_new(&self.stream_handle).map_err(|e| ArrveeError::NoAudioDevice(e.to_string()))?;
+        sink.append(self.playhead.track(source));
+        sink.set_speed(self.speed);
+        sink.pause();
+
+        info!("Loaded in-memory audio ({}Hz, {} samples)", self.sample_rate, self.audio_buffer.len());
+        self.sink = Some(sink);
+        self.source_path = None;
+        self.metadata = None;
+
+        Ok(())
+    }
+
+    /// Create unified analyzer with GPU/CPU fallback for the current buffer, or reset the
+    /// existing one when the sample rate hasn't changed, so no state from the previous track
+    /// leaks into the first frames of this one
+    async fn init_analysis(&mut self) -> Result<()> {
         let chunk_size = 512;
         let sample_rate_f32 = self.sample_rate as f32;
 
+        match &mut self.analyzer {
+            Some(analyzer) if analyzer.sample_rate() == sample_rate_f32 => {
+                analyzer.reset();
+                info!("🔄 Reset {} analyzer state for the new track", analyzer.analyzer_type());
+            }
+            _ => {
+                let mut analyzer = Self::create_analyzer(sample_rate_f32, chunk_size, self.log_magnitude, self.mel_bands, self.profile, self.feature_mask).await?;
+                analyzer.set_bpm_smoothing(self.bpm_smoothing);
+                self.analyzer = Some(analyzer);
+            }
+        }
+
+        self.normalizer = Some(FeatureNormalizer::with_parameters(self.normalization.clone()));
+        self.buffer_position = 0;
+        self.reset_sync_monitor();
+        self.envelope.reset();
+        self.levels.reset();
+        if let Some(agc) = &mut self.agc {
+            agc.reset();
+        }
+
+        Ok(())
+    }
+
+    async fn create_analyzer(sample_rate_f32: f32, chunk_size: usize, log_magnitude: bool, mel_bands: bool,
+                             profile: AnalysisProfile, features: FeatureMask) -> Result<Box<dyn AudioAnalyzer + Send>> {
+        let lite = profile == AnalysisProfile::Lite;
+        let skipped = !features.is_all();
+        if log_magnitude || mel_bands || lite || skipped {
+            if lite {
+                info!("🔋 Lite analysis profile (using CPU analyzer)");
+            }
+            if skipped {
+                info!("🧮 Skipping features: {} (using CPU analyzer)", features.complement().names());
+            }
+            if log_magnitude {
+                info!("📈 Log-magnitude bands enabled (using CPU analyzer)");
+            }
+            if mel_bands {
+                info!("🎼 Mel spectrum enabled (using CPU analyzer)");
+            }
+            let mut cpu_analyzer = CpuAudioAnalyzer::new(sample_rate_f32, chunk_size)?;
+            cpu_analyzer.set_log_magnitude(log_magnitude);
+            cpu_analyzer.set_mel_bands(mel_bands);
+            cpu_analyzer.set_profile(profile);
+            cpu_analyzer.set_feature_mask(features);
+            return Ok(Box::new(cpu_analyzer));
+        }
+
         info!("Initializing audio analyzer with unified architecture...");
         let analyzer: Box<dyn AudioAnalyzer + Send> = match NewGpuAudioAnalyzer::new_standalone(sample_rate_f32, chunk_size).await {
             Ok(gpu_analyzer) => {
                 info!("✅ GPU analyzer initialized successfully");
-                Box::new(gpu_analyzer)
+                Box::new(FallbackAnalyzer::new(
+                    Box::new(gpu_analyzer),
+                    Box::new(CpuAudioAnalyzer::new(sample_rate_f32, chunk_size)?),
+                ))
+            }
+            Err(e) if e.is::<ComputeUnavailable>() => {
+                info!("⚠️  GPU compute unavailable on this adapter; using CPU analysis. ({})", e);
+                Box::new(CpuAudioAnalyzer::new(sample_rate_f32, chunk_size)?)
             }
             Err(e) => {
                 info!("⚠️  GPU initialization failed: {}. Falling back to CPU.", e);
@@ -72,20 +398,22 @@ impl AudioPlayback {
             }
         };
 
-        self.analyzer = Some(analyzer);
-        self.normalizer = Some(FeatureNormalizer::new());
-        self.buffer_position = 0;
-
-        // Load file again for playback (since we consumed the decoder above)
-        let file = BufReader::new(File::open(&path)?);
-        let source = Decoder::new(file)?;
-        let sink = Sink::try_new(&self.stream_handle)?;
-        sink.append(source);
-        sink.pause();
-
-        info!("Loaded audio file: {:?} ({}Hz, {} samples)", path.as_ref(), self.sample_rate, self.audio_buffer.len());
-        self.sink = Some(sink);
+        Ok(analyzer)
+    }
 
+    /// Start the loaded track `start_at` seconds in (from the top when None), or with `paused`
+    /// hold it there until `play` (`--paused --start-at 30` for lining up a screen recorder)
+    #[allow(dead_code)]
+    pub fn start(&mut self, start_at: Option<f32>, paused: bool) -> Result<()> {
+        if let Some(seconds) = start_at {
+            self.seek(seconds)?;
+        }
+        if paused {
+            self.pause();
+            info!("⏸️  Paused at {:.2}s; Space starts playback", self.buffer_position as f32 / self.sample_rate.max(1) as f32);
+        } else {
+            self.play();
+        }
         Ok(())
     }
 
@@ -121,14 +449,130 @@ impl AudioPlayback {
     }
 
     pub fn is_finished(&self) -> bool {
-        self.sink.as_ref().map_or(true, |sink| sink.empty())
+        self.live.is_none() && self.sink.as_ref().map_or(true, |sink| sink.empty())
+    }
+
+    /// Visualize the default input device (microphone, line-in or a loopback device) instead of
+    /// a loaded track. Capture goes through a `LiveAnalyzer`, so frames come from steady
+    /// overlapping windows whatever buffer sizes the device delivers. Returns the device name.
+    #[allow(dead_code)]
+    pub fn start_live_input(&mut self) -> Result<String> {
+        let input = LiveInput::open_default()?;
+        let mut analyzer = LiveAnalyzer::new(input.sample_rate() as f32)?;
+        analyzer.set_normalization_parameters(self.normalization.clone());
+        analyzer.set_agc(self.agc.as_ref().map(|agc| agc.config().clone()));
+        info!("🎤 Live input from {} ({}Hz, {}-sample windows every {} samples)",
+              input.device_name(), input.sample_rate(), analyzer.window_size(), analyzer.hop_size());
+
+        let device_name = input.device_name().to_string();
+        self.sample_rate = input.sample_rate();
+        self.envelope.reset();
+        self.live = Some((input, analyzer));
+        Ok(device_name)
+    }
+
+    /// Whether frames come from `start_live_input` rather than a loaded track
+    #[allow(dead_code)]
+    pub fn is_live(&self) -> bool {
+        self.live.is_some()
     }
 
     pub async fn get_current_audio_frame(&mut self) -> AudioFrame {
+        if self.live.is_some() {
+            return self.live_audio_frame().await;
+        }
+        if self.follow_playhead && self.sink.is_some() && !self.audio_buffer.is_empty() {
+            // Hold on the last frame once the track has played out rather than wrapping
+            self.buffer_position = (self.playhead.frames() as usize).min(self.audio_buffer.len() - 1);
+        }
+        let delta_time = SAMPLES_PER_FRAME as f32 / self.sample_rate as f32;
+        self.check_sync();
+        self.update_levels(delta_time);
+        let mut frame = self.analyze_current_frame().await;
+        self.raw_frame.clone_from(&frame);
+
+        // Attack/release smoothing so quiet passages decay naturally instead of snapping
+        self.envelope.process(&mut frame, delta_time);
+
+        frame
+    }
+
+    /// Analyze whatever the input device has captured since the last frame
+    async fn live_audio_frame(&mut self) -> AudioFrame {
+        let Some((input, analyzer)) = &mut self.live else {
+            return AudioFrame::default();
+        };
+        input.drain_into(analyzer);
+        if let Err(e) = analyzer.analyze_pending().await {
+            log::warn!("⚠️  Live analysis failed: {}", e);
+        }
+        let mut frame = analyzer.latest().map_or_else(AudioFrame::default, |features| {
+            Self::convert_to_audio_frame_static(features, self.sample_rate as f32, self.sensitivity_factor, self.envelope.config())
+        });
+        self.raw_frame.clone_from(&frame);
+        self.envelope.process(&mut frame, SAMPLES_PER_FRAME as f32 / self.sample_rate as f32);
+        frame
+    }
+
+    /// The last frame from `get_current_audio_frame` as analyzed, before the envelopes
+    #[allow(dead_code)]
+    pub fn raw_frame(&self) -> &AudioFrame {
+        &self.raw_frame
+    }
+
+    /// Compare how far the analysis has moved (frame by frame, through wraps and playhead
+    /// follows) with the audio actually played, warning when the two drift apart
+    fn check_sync(&mut self) {
+        let start = self.buffer_position;
+        let len = self.audio_buffer.len().max(1);
+        let step = self.last_analysis_start.replace(start).map_or(0, |last| (start + len - last) % len);
+        if self.sink.is_none() {
+            return;
+        }
+
+        let step_seconds = step as f32 / self.sample_rate.max(1) as f32;
+        let playing = self.is_playing();
+        if let Some(drift) = self.sync_monitor.update(std::time::Instant::now(), playing, self.speed, step_seconds, self.playhead_seconds()) {
+            log::warn!("⚠️  Visuals are {:.0}ms {} the audio (analysis {:.2}s, audio {:.2}s, wall clock {:.2}s; {:.0} fps rendered, \
+                        the analysis steps assume 60). Try --follow-playhead",
+                       drift.drift().abs() * 1000.0, if drift.drift() > 0.0 { "ahead of" } else { "behind" },
+                       drift.analysis_seconds, drift.audio_seconds, drift.wall_seconds, drift.frame_rate());
+        }
+    }
+
+    /// Restart drift measurement, e.g. after a seek moved both the analysis and the audio
+    fn reset_sync_monitor(&mut self) {
+        self.sync_monitor.reset();
+        self.last_analysis_start = None;
+    }
+
+    /// Meter the source channels of the frame about to be analyzed (before downmix and AGC)
+    fn update_levels(&mut self, delta_time: f32) {
+        let start = self.buffer_position;
+        let end = (start + SAMPLES_PER_FRAME).min(self.audio_buffer.len());
+        if start >= end {
+            return;
+        }
+        if self.source_buffer.is_empty() {
+            self.levels.update(&self.audio_buffer[start..end], 1, delta_time);
+        } else {
+            let channels = self.source_channels as usize;
+            let source_end = (end * channels).min(self.source_buffer.len());
+            self.levels.update(&self.source_buffer[start * channels..source_end], channels, delta_time);
+        }
+    }
+
+    /// Per-channel peak/RMS levels of the current frame
+    #[allow(dead_code)]
+    pub fn levels(&self) -> &LevelMeter {
+        &self.levels
+    }
+
+    async fn analyze_current_frame(&mut self) -> AudioFrame {
+        let frame_advance = self.frame_advance();
         if let Some(analyzer) = &mut self.analyzer {
             if !self.audio_buffer.is_empty() {
-                // At 60fps, we should process ~735 samples per frame (44100/60)
-                let samples_per_frame = 735;
+                let samples_per_frame = SAMPLES_PER_FRAME.max(frame_advance); // Sped up: cover every sample passed
                 let chunk_size = 512; // Analysis window size
 
                 let start = self.buffer_position;
@@ -136,17 +580,32 @@ impl AudioPlayback {
 
                 if start < self.audio_buffer.len() {
                     // Process all accumulated samples in this frame using overlapping windows
-                    let frame_data = &self.audio_buffer[start..end];
+                    let gained;
+                    let frame_data = match &mut self.agc {
+                        Some(agc) => {
+                            gained = agc.process(&self.audio_buffer[start..end], self.sample_rate as f32);
+                            &gained[..]
+                        }
+                        None => &self.audio_buffer[start..end],
+                    };
 
                     if frame_data.len() >= chunk_size {
                         // Average multiple overlapping analysis windows within this frame
                         let mut accumulated_frame = AudioFrame::default();
                         let mut analysis_count = 0;
 
-                        // Analyze overlapping windows within the frame data
+                        // Analyze overlapping windows within the frame data (Lite: only the most recent one)
                         let step_size = (frame_data.len().saturating_sub(chunk_size) / 4).max(1); // 4 overlapping analyses
+                        let window_starts: Vec<usize> = match self.profile {
+                            AnalysisProfile::Full => (0..frame_data.len().saturating_sub(chunk_size)).step_by(step_size).collect(),
+                            AnalysisProfile::Lite => vec![frame_data.len() - chunk_size],
+                        };
+                        if frame_advance > 0 && !window_starts.is_empty() {
+                            // The windows overlap, so beat timing moves on by their share of the frame
+                            analyzer.set_chunk_interval(frame_advance as f32 / window_starts.len() as f32 / self.sample_rate as f32);
+                        }
 
-                        for window_start in (0..frame_data.len().saturating_sub(chunk_size)).step_by(step_size) {
+                        for window_start in window_starts {
                             let window_end = (window_start + chunk_size).min(frame_data.len());
                             let window = &frame_data[window_start..window_end];
 
@@ -155,7 +614,7 @@ impl AudioPlayback {
                                 if let Ok(raw_features) = analyzer.analyze_chunk(window).await {
                                     if let Some(normalizer) = &mut self.normalizer {
                                         let normalized_features = normalizer.normalize(&raw_features);
-                                        let analysis = Self::convert_to_audio_frame_static(&normalized_features, self.sample_rate as f32, self.sensitivity_factor);
+                                        let analysis = Self::convert_to_audio_frame_static(&normalized_features, self.sample_rate as f32, self.sensitivity_factor, self.envelope.config());
 
                                         // Accumulate all analysis values
                                         accumulated_frame.volume += analysis.volume;
@@ -176,10 +635,8 @@ impl AudioPlayback {
                                         accumulated_frame.frequency_bands.presence += analysis.frequency_bands.presence;
 
                                         // Keep the most recent beat detection and BPM
-                                        if analysis.beat_detected {
-                                            accumulated_frame.beat_detected = true;
-                                            accumulated_frame.estimated_bpm = analysis.estimated_bpm;
-                                        }
+                                        accumulated_frame.beat_detected |= analysis.beat_detected;
+                                        accumulated_frame.estimated_bpm = analysis.estimated_bpm;
 
                                         analysis_count += 1;
                                     }
@@ -211,8 +668,16 @@ impl AudioPlayback {
                             accumulated_frame.sample_rate = self.sample_rate as f32;
                         }
 
-                        // Advance buffer position by the frame amount
-                        self.buffer_position = (self.buffer_position + samples_per_frame) % self.audio_buffer.len();
+                        // Spectrum of the most recent window, for spectrum displays
+                        if let Some(spectrum) = analyzer.last_spectrum() {
+                            accumulated_frame.spectrum = spectrum.to_vec();
+                        }
+                        if let Some(mel_bands) = analyzer.last_mel_bands() {
+                            accumulated_frame.mel_bands = mel_bands.to_vec();
+                        }
+
+                        // Advance buffer position by the frame amount, scaled by the playback speed
+                        self.buffer_position = (self.buffer_position + frame_advance) % self.audio_buffer.len();
 
                         return accumulated_frame;
                     } else {
@@ -225,13 +690,23 @@ impl AudioPlayback {
                             frame_data.to_vec()
                         };
 
-                        self.buffer_position = (self.buffer_position + samples_per_frame) % self.audio_buffer.len();
+                        self.buffer_position = (self.buffer_position + frame_advance) % self.audio_buffer.len();
+                        if frame_advance > 0 {
+                            analyzer.set_chunk_interval(frame_advance as f32 / self.sample_rate as f32);
+                        }
 
                         // Use new async analysis with normalization
                         if let Ok(raw_features) = analyzer.analyze_chunk(&padded_chunk).await {
                             if let Some(normalizer) = &mut self.normalizer {
                                 let normalized_features = normalizer.normalize(&raw_features);
-                                return Self::convert_to_audio_frame_static(&normalized_features, self.sample_rate as f32, self.sensitivity_factor);
+                                let mut frame = Self::convert_to_audio_frame_static(&normalized_features, self.sample_rate as f32, self.sensitivity_factor, self.envelope.config());
+                                if let Some(spectrum) = analyzer.last_spectrum() {
+                                    frame.spectrum = spectrum.to_vec();
+                                }
+                                if let Some(mel_bands) = analyzer.last_mel_bands() {
+                                    frame.mel_bands = mel_bands.to_vec();
+                                }
+                                return frame;
                             }
                         }
                     }
@@ -244,7 +719,7 @@ impl AudioPlayback {
     }
 
     /// Static version of convert_to_audio_frame to avoid borrowing issues
-    fn convert_to_audio_frame_static(normalized: &NormalizedAudioFeatures, sample_rate: f32, sensitivity: f32) -> AudioFrame {
+    fn convert_to_audio_frame_static(normalized: &NormalizedAudioFeatures, sample_rate: f32, sensitivity: f32, mapping: &EnvelopeConfig) -> AudioFrame {
         use crate::audio::FrequencyBands;
         use log::debug;
 
@@ -263,13 +738,14 @@ impl AudioPlayback {
             }
         }
 
-        // Apply baseline boost for minimum visual responsiveness
-        let baseline_boost = 0.05; // Ensure minimum 5% activity even in silence
-        let dynamic_boost = 2.0;   // Extra multiplier for better dynamic range
+        // Configurable boosts (baseline defaults to 0 so silence stays at zero)
+        let baseline_boost = mapping.baseline_boost;
+        let dynamic_boost = mapping.dynamic_boost;
 
         AudioFrame {
             sample_rate,
             spectrum: Vec::new(), // Not used in current analysis
+            mel_bands: Vec::new(),
             time_domain: Vec::new(), // Not used in current analysis
             frequency_bands: FrequencyBands {
                 sub_bass: (baseline_boost + normalized.sub_bass * sensitivity * dynamic_boost).clamp(0.0, 1.0),
@@ -302,6 +778,7 @@ impl AudioPlayback {
         AudioFrame {
             sample_rate: self.sample_rate as f32,
             spectrum: Vec::new(), // Not used in current analysis
+            mel_bands: Vec::new(),
             time_domain: Vec::new(), // Not used in current analysis
             frequency_bands: FrequencyBands {
                 sub_bass: (normalized.sub_bass * sensitivity).clamp(0.0, 1.0),
@@ -334,7 +811,7 @@ impl AudioPlayback {
             if start < self.audio_buffer.len() {
                 let chunk = self.audio_buffer[start..end].to_vec();
                 // Advance at real-time rate: 44100 samples/sec = ~735 samples per frame at 60fps
-                self.buffer_position = (self.buffer_position + 735) % self.audio_buffer.len();
+                self.buffer_position = (self.buffer_position + self.frame_advance()) % self.audio_buffer.len();
                 return chunk;
             }
         }
@@ -364,6 +841,51 @@ impl AudioPlayback {
         self.sensitivity_factor
     }
 
+    /// Get the envelope/boost settings used for the audio→visual mapping
+    #[allow(dead_code)]
+    pub fn envelope_config(&self) -> &EnvelopeConfig {
+        self.envelope.config()
+    }
+
+    /// Replace the envelope/boost settings (attack, release, baseline and dynamic boost)
+    #[allow(dead_code)]
+    pub fn set_envelope_config(&mut self, config: EnvelopeConfig) {
+        self.envelope.set_config(config);
+    }
+
+    /// Replace only the per-group attack/release times, keeping the boost settings
+    #[allow(dead_code)]
+    pub fn set_smoothing_config(&mut self, smoothing: SmoothingConfig) {
+        let mut config = self.envelope.config().clone();
+        config.smoothing = smoothing;
+        self.envelope.set_config(config);
+    }
+
+    /// Inherent delay of the real-time analysis path in seconds: each visual frame averages
+    /// the windows of one frame's worth of samples, then the beat envelope attack smooths the rise
+    #[allow(dead_code)]
+    pub fn analysis_latency(&self) -> f32 {
+        SAMPLES_PER_FRAME as f32 / self.sample_rate.max(1) as f32 + self.envelope.config().smoothing.beat.attack
+    }
+
+    /// Enable automatic gain control on the real-time analysis input (None disables it)
+    #[allow(dead_code)]
+    pub fn set_agc(&mut self, config: Option<AgcConfig>) {
+        if let Some((_, analyzer)) = &mut self.live {
+            analyzer.set_agc(config.clone());
+        }
+        self.agc = config.map(AutomaticGainControl::new);
+    }
+
+    /// Gain currently applied by AGC, if enabled
+    #[allow(dead_code)]
+    pub fn agc_gain(&self) -> Option<f32> {
+        match &self.live {
+            Some((_, analyzer)) => analyzer.agc_gain(),
+            None => self.agc.as_ref().map(AutomaticGainControl::gain),
+        }
+    }
+
     /// Legacy compatibility: return self for analyzer access
     pub fn analyzer(&self) -> Option<&Self> {
         Some(self)
@@ -0,0 +1,124 @@
+use rodio::source::SeekError;
+use rodio::Source;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared count of source frames handed to the output device, i.e. where playback really is.
+///
+/// rodio 0.18's `Sink` doesn't report its position, so the playback source is wrapped in a
+/// `TrackedSource` that counts the samples it yields. The sink stops pulling from its source
+/// while paused, so the count stands still across pauses and underruns.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct Playhead {
+    frames: Arc<AtomicU64>,
+}
+
+#[allow(dead_code)]
+impl Playhead {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames played since the source started (or since the last seek target)
+    pub fn frames(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    pub fn seconds(&self, sample_rate: u32) -> f32 {
+        self.frames() as f32 / sample_rate.max(1) as f32
+    }
+
+    /// Wrap a playback source so this playhead follows it (the count restarts at zero)
+    pub fn track<S: Source>(&self, source: S) -> TrackedSource<S>
+    where
+        S::Item: rodio::Sample,
+    {
+        self.frames.store(0, Ordering::Relaxed);
+        TrackedSource { channels: source.channels().max(1), input: source, pending: 0, frames: self.frames.clone() }
+    }
+}
+
+/// Passes samples through unchanged, advancing a `Playhead` once per full frame
+#[allow(dead_code)]
+pub struct TrackedSource<S> {
+    input: S,
+    channels: u16,
+    pending: u16, // Samples of the current frame already yielded
+    frames: Arc<AtomicU64>,
+}
+
+impl<S> Iterator for TrackedSource<S>
+where
+    S: Source,
+    S::Item: rodio::Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        let sample = self.input.next()?;
+        self.pending += 1;
+        if self.pending >= self.channels {
+            self.pending = 0;
+            self.frames.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S> Source for TrackedSource<S>
+where
+    S: Source,
+    S::Item: rodio::Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.pending = 0;
+        self.frames.store((pos.as_secs_f64() * self.input.sample_rate() as f64) as u64, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playhead_counts_frames_as_they_are_pulled() {
+        let playhead = Playhead::new();
+        let stereo = rodio::buffer::SamplesBuffer::new(2, 100, vec![0.5f32; 400]);
+        let mut source = playhead.track(stereo);
+
+        assert_eq!(playhead.frames(), 0);
+        source.by_ref().take(101).for_each(drop); // 50 full frames and half of the next
+        assert_eq!(playhead.frames(), 50);
+        assert!((playhead.seconds(100) - 0.5).abs() < 1e-6);
+
+        // Nothing pulled (e.g. while the sink is paused): the playhead stays put
+        assert_eq!(playhead.frames(), 50);
+
+        source.for_each(drop);
+        assert_eq!(playhead.frames(), 200);
+    }
+}
@@ -25,6 +25,9 @@ pub struct FileInfo {
     pub total_samples: usize,
     pub frame_rate: f32,
     pub chunk_size: usize,
+    /// Timestamp of the first frame (non-zero for beat-locked prescans, whose grid starts on a beat)
+    #[serde(default)]
+    pub time_offset_seconds: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +78,10 @@ pub struct AnalysisStatistics {
     pub dominant_frequency_range: String,
     pub energy_profile: String, // "Low", "Medium", "High", "Dynamic"
     pub complexity_score: f32,
+
+    /// L/R phase check of the source (missing in older prescans)
+    #[serde(default)]
+    pub stereo_phase: super::stereo_phase::StereoPhase,
 }
 
 impl From<&AudioFrame> for PrescanFrame {
@@ -101,6 +108,9 @@ impl From<&AudioFrame> for PrescanFrame {
 pub struct PrescanProcessor {
     chunk_size: usize,
     sample_rate: f32,
+    downmix: super::DownmixMode,
+    fix_phase: bool,
+    bpm_smoothing: f32,
 }
 
 impl PrescanProcessor {
@@ -108,16 +118,37 @@ impl PrescanProcessor {
         Self {
             chunk_size,
             sample_rate,
+            downmix: super::DownmixMode::default(),
+            fix_phase: false,
+            bpm_smoothing: super::tempo_detector::DEFAULT_BPM_SMOOTHING,
         }
     }
 
+    /// How stereo files are folded to mono before analysis
+    #[allow(dead_code)]
+    pub fn set_downmix(&mut self, mode: super::DownmixMode) {
+        self.downmix = mode;
+    }
+
+    /// Invert the right channel of out-of-phase stereo files before the downmix
+    #[allow(dead_code)]
+    pub fn set_fix_phase(&mut self, enabled: bool) {
+        self.fix_phase = enabled;
+    }
+
+    /// How slowly the frames' BPM follows tempo changes (0 = median of recent beats as-is)
+    #[allow(dead_code)]
+    pub fn set_bpm_smoothing(&mut self, smoothing: f32) {
+        self.bpm_smoothing = smoothing;
+    }
+
     /// Pre-scan an audio file and generate synchronization data
     pub fn prescan_file<P: AsRef<Path>>(&mut self, file_path: P) -> Result<PrescanData> {
         let path_str = file_path.as_ref().to_string_lossy().to_string();
         info!("Pre-scanning audio file: {}", path_str);
 
         // Load audio file directly using the same method as AudioPlayback
-        let audio_buffer = self.load_audio_file(&file_path)?;
+        let (audio_buffer, stereo_phase) = self.load_audio_file(&file_path)?;
         let total_samples = audio_buffer.len();
         let duration_seconds = total_samples as f32 / self.sample_rate;
         let frame_rate = self.sample_rate / self.chunk_size as f32;
@@ -126,10 +157,11 @@ impl PrescanProcessor {
 
         // Create analyzer with normalization
         let mut analyzer = AudioAnalyzer::new(self.sample_rate, self.chunk_size);
+        analyzer.set_bpm_smoothing(self.bpm_smoothing);
 
         // Process entire file chunk by chunk
         let mut frames = Vec::new();
-        let mut statistics = AnalysisStatistics::default();
+        let mut statistics = AnalysisStatistics { stereo_phase, ..AnalysisStatistics::default() };
         let mut sample_pos = 0;
         let mut beat_count = 0u32;
         let mut bpm_values = Vec::new();
@@ -179,6 +211,7 @@ impl PrescanProcessor {
                 total_samples,
                 frame_rate,
                 chunk_size: self.chunk_size,
+                time_offset_seconds: 0.0,
             },
             frames,
             statistics,
@@ -201,27 +234,10 @@ impl PrescanProcessor {
 
     // Private helper methods
 
-    fn load_audio_file<P: AsRef<Path>>(&self, file_path: P) -> Result<Vec<f32>> {
-        use rodio::{Decoder, Source};
-        use std::fs::File;
-        use std::io::BufReader;
-
-        let file = BufReader::new(File::open(file_path)?);
-        let source = Decoder::new(file)?;
-
-        let channels = source.channels();
-        let samples: Vec<i16> = source.convert_samples().collect();
-
-        // Convert to f32 and mix to mono
-        let audio_buffer = samples
-            .chunks_exact(channels as usize)
-            .map(|chunk| {
-                let sum: f32 = chunk.iter().map(|&s| s as f32 / 32768.0).sum();
-                sum / channels as f32
-            })
-            .collect();
-
-        Ok(audio_buffer)
+    fn load_audio_file<P: AsRef<Path>>(&self, file_path: P) -> Result<(Vec<f32>, super::stereo_phase::StereoPhase)> {
+        // Mix to mono f32
+        let (samples, _, stereo_phase) = super::decode_file_to_mono_checked(file_path, self.downmix, self.fix_phase)?;
+        Ok((samples, stereo_phase))
     }
 
     fn update_statistics(&self, stats: &mut AnalysisStatistics, frame: &AudioFrame,
@@ -245,12 +261,57 @@ impl PrescanProcessor {
     }
 
     fn classify_content(&self, stats: &mut AnalysisStatistics, frames: &[PrescanFrame]) {
+        stats.classify(frames);
+    }
+}
+
+impl AnalysisStatistics {
+    /// Compute peaks, beat/BPM stats and the content classification from finished frames
+    #[allow(dead_code)]
+    pub fn from_frames(frames: &[PrescanFrame]) -> Self {
+        let mut stats = Self::default();
+        if frames.is_empty() {
+            return stats;
+        }
+
+        let mut bpm_values = Vec::new();
+        for frame in frames {
+            stats.peak_bass = stats.peak_bass.max(frame.frequency_bands.bass);
+            stats.peak_mid = stats.peak_mid.max(frame.frequency_bands.mid);
+            stats.peak_treble = stats.peak_treble.max(frame.frequency_bands.treble);
+            stats.peak_presence = stats.peak_presence.max(frame.frequency_bands.presence);
+            stats.peak_volume = stats.peak_volume.max(frame.volume);
+            stats.peak_spectral_flux = stats.peak_spectral_flux.max(frame.spectral_flux);
+            stats.peak_onset = stats.peak_onset.max(frame.onset_strength);
+
+            if frame.beat_detected {
+                stats.total_beats += 1;
+                if frame.estimated_bpm > 60.0 && frame.estimated_bpm < 200.0 {
+                    bpm_values.push(frame.estimated_bpm);
+                }
+            }
+        }
+
+        if !bpm_values.is_empty() {
+            stats.average_bpm = bpm_values.iter().sum::<f32>() / bpm_values.len() as f32;
+            stats.bpm_range = (
+                bpm_values.iter().fold(f32::INFINITY, |a, &b| a.min(b)),
+                bpm_values.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b))
+            );
+        }
+
+        stats.classify(frames);
+        stats
+    }
+
+    /// Fill in the dominant frequency range, energy profile and complexity score
+    pub fn classify(&mut self, frames: &[PrescanFrame]) {
         // Determine dominant frequency range
         let avg_bass: f32 = frames.iter().map(|f| f.frequency_bands.bass).sum::<f32>() / frames.len() as f32;
         let avg_mid: f32 = frames.iter().map(|f| f.frequency_bands.mid).sum::<f32>() / frames.len() as f32;
         let avg_treble: f32 = frames.iter().map(|f| f.frequency_bands.treble).sum::<f32>() / frames.len() as f32;
 
-        stats.dominant_frequency_range = if avg_bass > avg_mid && avg_bass > avg_treble {
+        self.dominant_frequency_range = if avg_bass > avg_mid && avg_bass > avg_treble {
             "Bass-Heavy".to_string()
         } else if avg_treble > avg_bass && avg_treble > avg_mid {
             "Treble-Focused".to_string()
@@ -264,7 +325,7 @@ impl PrescanProcessor {
             .map(|f| (f.volume - avg_volume).powi(2))
             .sum::<f32>() / frames.len() as f32;
 
-        stats.energy_profile = if volume_variance > 0.1 {
+        self.energy_profile = if volume_variance > 0.1 {
             "Dynamic".to_string()
         } else if avg_volume > 0.3 {
             "High".to_string()
@@ -277,7 +338,7 @@ impl PrescanProcessor {
         // Calculate complexity score (0.0-1.0)
         let spectral_complexity = frames.iter().map(|f| f.spectral_flux).sum::<f32>() / frames.len() as f32;
         let harmonic_complexity = frames.iter().map(|f| f.pitch_confidence).sum::<f32>() / frames.len() as f32;
-        stats.complexity_score = (spectral_complexity + harmonic_complexity + volume_variance).min(1.0);
+        self.complexity_score = (spectral_complexity + harmonic_complexity + volume_variance).min(1.0);
     }
 }
 
@@ -297,6 +358,7 @@ impl Default for AnalysisStatistics {
             dominant_frequency_range: "Unknown".to_string(),
             energy_profile: "Unknown".to_string(),
             complexity_score: 0.5,
+            stereo_phase: Default::default(),
         }
     }
 }
@@ -307,6 +369,9 @@ pub struct SynchronizedPlayback {
     prescan_data: PrescanData,
     current_time: f32,
     frame_index: usize,
+    last_interpolated_time: Option<f32>, // Start of the beat window for the next interpolated lookup
+    speed: f32,
+    speed_anchor: (f32, f32), // (wall-clock, track) seconds at the last speed change
 }
 
 impl SynchronizedPlayback {
@@ -315,44 +380,146 @@ impl SynchronizedPlayback {
             prescan_data,
             current_time: 0.0,
             frame_index: 0,
+            last_interpolated_time: None,
+            speed: 1.0,
+            speed_anchor: (0.0, 0.0),
         }
     }
 
+    /// Playback rate from `wall_seconds` on (see `AudioPlayback::set_speed`); track time
+    /// continues from where it was, so changing speed mid-track doesn't jump
+    #[allow(dead_code)]
+    pub fn set_speed(&mut self, speed: f32, wall_seconds: f32) {
+        self.speed_anchor = (wall_seconds, self.track_time(wall_seconds));
+        self.speed = speed.clamp(super::playback::MIN_PLAYBACK_SPEED, super::playback::MAX_PLAYBACK_SPEED);
+    }
+
+    #[allow(dead_code)]
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Position in the track after `wall_seconds` of wall-clock playback, following speed changes
+    #[allow(dead_code)]
+    pub fn track_time(&self, wall_seconds: f32) -> f32 {
+        let (anchor_wall, anchor_track) = self.speed_anchor;
+        anchor_track + (wall_seconds - anchor_wall) * self.speed
+    }
+
     /// Get audio frame for current playback time with perfect synchronization
     pub fn get_synchronized_frame(&mut self, playback_time_seconds: f32) -> Option<&PrescanFrame> {
-        self.current_time = playback_time_seconds;
-
-        // Find the frame closest to current time
-        while self.frame_index < self.prescan_data.frames.len() {
-            let frame = &self.prescan_data.frames[self.frame_index];
-
-            if frame.timestamp <= playback_time_seconds {
-                if self.frame_index + 1 < self.prescan_data.frames.len() {
-                    let next_frame = &self.prescan_data.frames[self.frame_index + 1];
-                    if next_frame.timestamp > playback_time_seconds {
-                        return Some(frame);
-                    } else {
-                        self.frame_index += 1;
+        // Normal playback only advances a frame or two per render, so step forward from the
+        // cursor; anything else (seek, scrub, jump backwards) goes through the binary search
+        const FORWARD_FAST_PATH: usize = 4;
+
+        let frames = &self.prescan_data.frames;
+        if frames.get(self.frame_index).is_some_and(|frame| frame.timestamp <= playback_time_seconds) {
+            for _ in 0..FORWARD_FAST_PATH {
+                match frames.get(self.frame_index + 1) {
+                    Some(next) if next.timestamp <= playback_time_seconds => self.frame_index += 1,
+                    _ => {
+                        self.current_time = playback_time_seconds;
+                        return self.prescan_data.frames.get(self.frame_index);
                     }
-                } else {
-                    return Some(frame);
                 }
-            } else {
-                break;
             }
         }
 
-        // If we're behind, find the correct frame
-        if self.frame_index > 0 {
-            while self.frame_index > 0 &&
-                  self.prescan_data.frames[self.frame_index].timestamp > playback_time_seconds {
-                self.frame_index -= 1;
-            }
-        }
+        self.scrub_to(playback_time_seconds)
+    }
 
+    /// Jump to the frame for `time_seconds` in either direction (O(log n) over the sorted
+    /// frame timestamps). Use for seeking, scrub bars and reverse playback.
+    #[allow(dead_code)]
+    pub fn scrub_to(&mut self, time_seconds: f32) -> Option<&PrescanFrame> {
+        self.current_time = time_seconds;
+        self.frame_index = self.frame_index_at(time_seconds);
         self.prescan_data.frames.get(self.frame_index)
     }
 
+    /// Follow an audio seek to `time_seconds`: the frame index is found by binary search, so the
+    /// next lookup takes the O(1) forward path, and the beat window starts afresh so beats
+    /// skipped over don't all fire at once. `track_time` restarts there from `wall_seconds`.
+    #[allow(dead_code)]
+    pub fn seek(&mut self, time_seconds: f32, wall_seconds: f32) -> Option<&PrescanFrame> {
+        self.speed_anchor = (wall_seconds, time_seconds.max(0.0));
+        self.last_interpolated_time = None;
+        self.scrub_to(time_seconds)
+    }
+
+    /// Index of the last frame at or before `time_seconds` (0 if it precedes the first frame)
+    fn frame_index_at(&self, time_seconds: f32) -> usize {
+        self.prescan_data.frames
+            .partition_point(|frame| frame.timestamp <= time_seconds)
+            .saturating_sub(1)
+    }
+
+    /// Get a frame for the current playback time, linearly interpolated between the two
+    /// bracketing prescan frames so features glide instead of stepping when the render rate
+    /// doesn't divide the prescan frame rate.
+    ///
+    /// `beat_detected` is the OR of every frame since the previous call, so a beat that falls
+    /// between two renders is not lost. Returns `None` only if there is no prescan data.
+    #[allow(dead_code)]
+    pub fn get_interpolated_frame(&mut self, playback_time_seconds: f32) -> Option<PrescanFrame> {
+        self.current_time = playback_time_seconds;
+        let frames = &self.prescan_data.frames;
+        if frames.is_empty() {
+            return None;
+        }
+
+        // Index of the first frame strictly after the playback time
+        let next = frames.partition_point(|frame| frame.timestamp <= playback_time_seconds);
+        let previous = next.saturating_sub(1);
+
+        // Beat window: every frame since the last lookup (just the current one after a seek back)
+        let window_start = match self.last_interpolated_time {
+            Some(last) if last <= playback_time_seconds => {
+                frames.partition_point(|frame| frame.timestamp <= last).min(previous)
+            }
+            _ => previous,
+        };
+        let beat_detected = frames[window_start..=previous].iter().any(|frame| frame.beat_detected);
+        self.last_interpolated_time = Some(playback_time_seconds);
+        self.frame_index = previous;
+
+        let a = &frames[previous];
+        let mut frame = match frames.get(next) {
+            Some(b) if next > 0 => {
+                let span = (b.timestamp - a.timestamp).max(f32::EPSILON);
+                let t = ((playback_time_seconds - a.timestamp) / span).clamp(0.0, 1.0);
+                let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+                PrescanFrame {
+                    timestamp: playback_time_seconds,
+                    frequency_bands: FrequencyBands {
+                        sub_bass: lerp(a.frequency_bands.sub_bass, b.frequency_bands.sub_bass),
+                        bass: lerp(a.frequency_bands.bass, b.frequency_bands.bass),
+                        mid: lerp(a.frequency_bands.mid, b.frequency_bands.mid),
+                        treble: lerp(a.frequency_bands.treble, b.frequency_bands.treble),
+                        presence: lerp(a.frequency_bands.presence, b.frequency_bands.presence),
+                    },
+                    beat_detected,
+                    beat_strength: lerp(a.beat_strength, b.beat_strength),
+                    estimated_bpm: lerp(a.estimated_bpm, b.estimated_bpm),
+                    spectral_centroid: lerp(a.spectral_centroid, b.spectral_centroid),
+                    spectral_rolloff: lerp(a.spectral_rolloff, b.spectral_rolloff),
+                    pitch_confidence: lerp(a.pitch_confidence, b.pitch_confidence),
+                    zero_crossing_rate: lerp(a.zero_crossing_rate, b.zero_crossing_rate),
+                    spectral_flux: lerp(a.spectral_flux, b.spectral_flux),
+                    onset_strength: lerp(a.onset_strength, b.onset_strength),
+                    dynamic_range: lerp(a.dynamic_range, b.dynamic_range),
+                    volume: lerp(a.volume, b.volume),
+                }
+            }
+            // Before the first frame or after the last one: hold the nearest frame
+            _ => a.clone(),
+        };
+        frame.beat_detected = beat_detected;
+
+        Some(frame)
+    }
+
     /// Get statistics for this audio file
     pub fn get_statistics(&self) -> &AnalysisStatistics {
         &self.prescan_data.statistics
@@ -362,4 +529,90 @@ impl SynchronizedPlayback {
     pub fn get_file_info(&self) -> &FileInfo {
         &self.prescan_data.file_info
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Playback over frames every 0.1s from 0.5s to 1.4s, each frame's volume its index
+    fn playback() -> SynchronizedPlayback {
+        let frames: Vec<PrescanFrame> = (0..10)
+            .map(|index| PrescanFrame {
+                timestamp: 0.5 + index as f32 * 0.1,
+                ..PrescanFrame::from(&AudioFrame { volume: index as f32, ..AudioFrame::default() })
+            })
+            .collect();
+        let file_info = FileInfo {
+            filename: "test.wav".to_string(),
+            duration_seconds: 1.5,
+            sample_rate: 44100.0,
+            total_samples: 66150,
+            frame_rate: 10.0,
+            chunk_size: 4410,
+            time_offset_seconds: 0.5,
+        };
+        SynchronizedPlayback::new(PrescanData { file_info, frames, statistics: AnalysisStatistics::default() })
+    }
+
+    #[test]
+    fn test_scrub_finds_the_frame_at_any_time() {
+        let mut playback = playback();
+        let volume_at = |playback: &mut SynchronizedPlayback, time: f32| playback.scrub_to(time).unwrap().volume;
+
+        // Before the first frame holds on it; past the end holds on the last
+        assert_eq!(volume_at(&mut playback, 0.0), 0.0);
+        assert_eq!(volume_at(&mut playback, -3.0), 0.0);
+        assert_eq!(volume_at(&mut playback, 99.0), 9.0);
+        // Mid-frame times give the frame they fall in, in either direction
+        assert_eq!(volume_at(&mut playback, 0.95), 4.0);
+        assert_eq!(volume_at(&mut playback, 0.62), 1.0);
+        assert_eq!(volume_at(&mut playback, 1.0), 5.0, "a frame's own timestamp is its start");
+
+        // After a scrub, normal playback carries on from the new frame
+        assert_eq!(playback.get_synchronized_frame(1.12).unwrap().volume, 6.0);
+
+        let empty = PrescanData { frames: Vec::new(), ..playback.prescan_data.clone() };
+        assert!(SynchronizedPlayback::new(empty).scrub_to(1.0).is_none());
+    }
+
+    #[test]
+    fn test_speed_changes_keep_track_time_continuous() {
+        let mut playback = playback();
+        assert_eq!(playback.track_time(2.0), 2.0);
+
+        // Slowed to half speed two seconds in: no jump, then half a second of track per second
+        playback.set_speed(0.5, 2.0);
+        assert_eq!(playback.track_time(2.0), 2.0);
+        assert_eq!(playback.track_time(4.0), 3.0);
+
+        // And back up to double speed from there
+        playback.set_speed(2.0, 4.0);
+        assert_eq!(playback.track_time(4.0), 3.0);
+        assert_eq!(playback.track_time(5.5), 6.0);
+
+        playback.set_speed(100.0, 5.5);
+        assert_eq!(playback.speed(), crate::audio::playback::MAX_PLAYBACK_SPEED);
+        assert_eq!(playback.track_time(5.5), 6.0);
+    }
+
+    #[test]
+    fn test_seek_re_anchors_track_time() {
+        let mut playback = playback();
+        playback.set_speed(0.5, 0.0);
+        assert_eq!(playback.track_time(1.0), 0.5);
+
+        // Seeking at wall time 1s to 1.2s: later wall times count on from there, at the same speed
+        assert_eq!(playback.seek(1.2, 1.0).unwrap().volume, 7.0);
+        assert_eq!(playback.track_time(1.0), 1.2);
+        assert_eq!(playback.track_time(3.0), 2.2);
+        assert_eq!(playback.speed(), 0.5);
+        // 0.2s of wall time later the lookup has moved on one frame (0.1s of track)
+        let later = playback.track_time(1.2);
+        assert_eq!(playback.get_synchronized_frame(later).unwrap().volume, 8.0);
+
+        // A seek before the start begins from 0, on the first frame
+        assert_eq!(playback.seek(-2.0, 4.0).unwrap().volume, 0.0);
+        assert_eq!(playback.track_time(6.0), 1.0);
+    }
+}
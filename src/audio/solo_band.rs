@@ -0,0 +1,149 @@
+use std::str::FromStr;
+
+use super::AudioFrame;
+
+/// One of the five analysis bands, driving the visuals on its own ("solo" mode) for sound
+/// design and diagnostics
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoloBand {
+    SubBass,
+    Bass,
+    Mid,
+    Treble,
+    Presence,
+}
+
+impl FromStr for SoloBand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Self::ALL.into_iter()
+            .find(|band| band.name().eq_ignore_ascii_case(&s.replace('_', "-")))
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown band '{}'. Use: {}", s,
+                Self::ALL.map(|band| band.name()).join(", ")
+            ))
+    }
+}
+
+#[allow(dead_code)]
+impl SoloBand {
+    pub const ALL: [Self; 5] = [Self::SubBass, Self::Bass, Self::Mid, Self::Treble, Self::Presence];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SubBass => "sub-bass",
+            Self::Bass => "bass",
+            Self::Mid => "mid",
+            Self::Treble => "treble",
+            Self::Presence => "presence",
+        }
+    }
+
+    /// Frequency range in Hz, matching the band split in `fft::AudioAnalyzer`
+    pub fn frequency_range(self) -> (f32, f32) {
+        match self {
+            Self::SubBass => (0.0, 60.0),
+            Self::Bass => (60.0, 250.0),
+            Self::Mid => (250.0, 2000.0),
+            Self::Treble => (2000.0, 8000.0),
+            Self::Presence => (8000.0, f32::INFINITY),
+        }
+    }
+
+    /// Step through off → sub-bass → … → presence → off
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::ALL[0]),
+            Some(band) => Self::ALL.iter().position(|&other| other == band)
+                .and_then(|index| Self::ALL.get(index + 1).copied()),
+        }
+    }
+
+    fn level(self, frame: &AudioFrame) -> f32 {
+        let bands = &frame.frequency_bands;
+        match self {
+            Self::SubBass => bands.sub_bass,
+            Self::Bass => bands.bass,
+            Self::Mid => bands.mid,
+            Self::Treble => bands.treble,
+            Self::Presence => bands.presence,
+        }
+    }
+
+    /// Mask `frame` down to this band: the other bands and spectrum bins go to zero, volume
+    /// follows the band's level, and whole-mix transients (beat, onset, flux) are scaled by the
+    /// band's share of the energy, so a kick drum doesn't pulse a soloed treble.
+    pub fn apply(self, frame: &mut AudioFrame) {
+        let level = self.level(frame);
+        let bands = &frame.frequency_bands;
+        let total = bands.sub_bass + bands.bass + bands.mid + bands.treble + bands.presence;
+        let share = if total > f32::EPSILON { level / total } else { 0.0 };
+
+        let bands = &mut frame.frequency_bands;
+        for (band, value) in Self::ALL.into_iter().zip([
+            &mut bands.sub_bass, &mut bands.bass, &mut bands.mid, &mut bands.treble, &mut bands.presence,
+        ]) {
+            if band != self {
+                *value = 0.0;
+            }
+        }
+
+        frame.volume = level;
+        frame.beat_strength *= share;
+        frame.onset_strength *= share;
+        frame.spectral_flux *= share;
+        // A beat only counts if this band carries more than an even share of it
+        frame.beat_detected &= share > 1.0 / Self::ALL.len() as f32;
+
+        // Spectrum bins span 0 Hz to Nyquist
+        if !frame.spectrum.is_empty() {
+            let bin_width = frame.sample_rate / 2.0 / frame.spectrum.len() as f32;
+            let (low, high) = self.frequency_range();
+            for (index, bin) in frame.spectrum.iter_mut().enumerate() {
+                let frequency = index as f32 * bin_width;
+                if frequency < low || frequency >= high {
+                    *bin = 0.0;
+                }
+            }
+        }
+        // The spectralizer draws the masked spectrum instead of the (unmasked) mel bands
+        frame.mel_bands.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::FrequencyBands;
+
+    #[test]
+    fn test_solo_masks_other_bands_and_their_transients() {
+        // A kick: mostly bass, with a beat
+        let mut frame = AudioFrame {
+            frequency_bands: FrequencyBands { sub_bass: 0.4, bass: 0.9, mid: 0.3, treble: 0.1, presence: 0.1 },
+            beat_detected: true,
+            beat_strength: 0.8,
+            volume: 0.7,
+            spectrum: vec![1.0; 512],
+            ..AudioFrame::default()
+        };
+
+        let mut treble = frame.clone();
+        SoloBand::Treble.apply(&mut treble);
+        let bands = &treble.frequency_bands;
+        assert_eq!((bands.sub_bass, bands.bass, bands.mid, bands.presence), (0.0, 0.0, 0.0, 0.0));
+        assert_eq!((bands.treble, treble.volume), (0.1, 0.1));
+        assert!(!treble.beat_detected && treble.beat_strength < 0.1);
+        // Only 2-8 kHz bins survive (bins are ~43 Hz wide at 44.1 kHz)
+        assert!(treble.spectrum[10] == 0.0 && treble.spectrum[100] == 1.0 && treble.spectrum[300] == 0.0);
+
+        SoloBand::Bass.apply(&mut frame);
+        assert!(frame.beat_detected && frame.beat_strength > 0.3);
+
+        assert_eq!(SoloBand::cycle(None), Some(SoloBand::SubBass));
+        assert_eq!(SoloBand::cycle(Some(SoloBand::Presence)), None);
+        assert_eq!("Sub_Bass".parse::<SoloBand>().unwrap(), SoloBand::SubBass);
+    }
+}
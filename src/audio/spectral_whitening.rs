@@ -0,0 +1,74 @@
+/// Spectral whitening for flattening tonal imbalance before band extraction.
+///
+/// Each FFT bin is divided by a slowly-adapting running estimate of its own magnitude,
+/// so bins that are always loud (e.g. sub bass in a bass-heavy mix) stop dominating and
+/// quieter regions like treble/presence show their relative modulation instead. The result
+/// is rescaled by the mean running magnitude so overall levels stay in the same ballpark
+/// as the unwhitened spectrum.
+#[allow(dead_code)]
+pub struct SpectralWhitener {
+    running_magnitude: Vec<f32>,
+    adaptation_rate: f32,
+    max_gain: f32,
+}
+
+#[allow(dead_code)]
+impl SpectralWhitener {
+    /// Default adaptation rate (~2-3 seconds time constant at 512-sample hops)
+    pub const DEFAULT_ADAPTATION_RATE: f32 = 0.005;
+
+    /// Create a whitener with the default adaptation rate
+    pub fn new() -> Self {
+        Self::with_adaptation_rate(Self::DEFAULT_ADAPTATION_RATE)
+    }
+
+    /// Create a whitener with a custom adaptation rate (0.0-1.0, higher = faster)
+    pub fn with_adaptation_rate(adaptation_rate: f32) -> Self {
+        Self {
+            running_magnitude: Vec::new(),
+            adaptation_rate: adaptation_rate.clamp(0.0001, 1.0),
+            // Limits how far near-silent bins can be boosted so noise doesn't explode
+            max_gain: 100.0,
+        }
+    }
+
+    /// Whiten a magnitude spectrum, updating the running estimate
+    pub fn whiten(&mut self, spectrum: &[f32]) -> Vec<f32> {
+        // (Re)seed the estimate from the first frame so whitening starts out neutral
+        if self.running_magnitude.len() != spectrum.len() {
+            self.running_magnitude = spectrum.to_vec();
+        } else {
+            for (estimate, &magnitude) in self.running_magnitude.iter_mut().zip(spectrum) {
+                *estimate += (magnitude - *estimate) * self.adaptation_rate;
+            }
+        }
+
+        let mean_magnitude = if self.running_magnitude.is_empty() {
+            0.0
+        } else {
+            self.running_magnitude.iter().sum::<f32>() / self.running_magnitude.len() as f32
+        };
+
+        if mean_magnitude <= f32::EPSILON {
+            return spectrum.to_vec();
+        }
+
+        let floor = mean_magnitude / self.max_gain;
+        spectrum
+            .iter()
+            .zip(&self.running_magnitude)
+            .map(|(&magnitude, &estimate)| magnitude / estimate.max(floor) * mean_magnitude)
+            .collect()
+    }
+
+    /// Forget the running estimate (e.g. when switching tracks)
+    pub fn reset(&mut self) {
+        self.running_magnitude.clear();
+    }
+}
+
+impl Default for SpectralWhitener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use super::prescan::PrescanData;
+
+/// Spectrogram-style PNG export of prescan data.
+///
+/// X axis is time (frames, averaged into columns when there are more frames than
+/// `max_width`), Y axis is the five frequency bands with sub-bass at the bottom and
+/// presence at the top. Band magnitude (0.0-1.0) is mapped through a heat palette.
+#[allow(dead_code)]
+pub struct SpectrogramExporter {
+    pub max_width: usize,
+    pub band_height: usize,
+}
+
+impl Default for SpectrogramExporter {
+    fn default() -> Self {
+        Self {
+            max_width: 2048,
+            band_height: 48,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl SpectrogramExporter {
+    /// Render the prescan bands as a heatmap and write it as a PNG file
+    pub fn export<P: AsRef<std::path::Path>>(&self, prescan_data: &PrescanData, path: P) -> Result<()> {
+        let (width, height, pixels) = self.render(prescan_data)?;
+        write_png(path, width, height, &pixels)
+    }
+
+    /// Render to an RGB8 buffer, returning (width, height, pixels)
+    pub fn render(&self, prescan_data: &PrescanData) -> Result<(usize, usize, Vec<u8>)> {
+        let frames = &prescan_data.frames;
+        if frames.is_empty() {
+            return Err(anyhow::anyhow!("No frames to render"));
+        }
+
+        let width = frames.len().min(self.max_width.max(1));
+        let band_height = self.band_height.max(1);
+        let height = band_height * 5;
+        let mut pixels = vec![0u8; width * height * 3];
+
+        for column in 0..width {
+            // Average all frames that fall into this column
+            let start = column * frames.len() / width;
+            let end = ((column + 1) * frames.len() / width).max(start + 1);
+            let mut bands = [0.0f32; 5];
+            for frame in &frames[start..end] {
+                let fb = &frame.frequency_bands;
+                for (sum, value) in bands.iter_mut().zip([fb.sub_bass, fb.bass, fb.mid, fb.treble, fb.presence]) {
+                    *sum += value;
+                }
+            }
+            let count = (end - start) as f32;
+
+            for (band_index, sum) in bands.iter().enumerate() {
+                let color = heat_palette(sum / count);
+                // Sub-bass at the bottom of the image
+                let top = height - (band_index + 1) * band_height;
+                for row in top..top + band_height {
+                    let offset = (row * width + column) * 3;
+                    pixels[offset..offset + 3].copy_from_slice(&color);
+                }
+            }
+        }
+
+        Ok((width, height, pixels))
+    }
+}
+
+/// Black → purple → red → orange → yellow → white heat palette
+fn heat_palette(value: f32) -> [u8; 3] {
+    const STOPS: [[f32; 3]; 6] = [
+        [0.0, 0.0, 0.0],
+        [0.35, 0.05, 0.45],
+        [0.8, 0.1, 0.2],
+        [1.0, 0.5, 0.0],
+        [1.0, 0.9, 0.2],
+        [1.0, 1.0, 1.0],
+    ];
+
+    let scaled = value.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let index = (scaled as usize).min(STOPS.len() - 2);
+    let t = scaled - index as f32;
+    let (a, b) = (STOPS[index], STOPS[index + 1]);
+
+    let channel = |i: usize| ((a[i] + (b[i] - a[i]) * t) * 255.0).round() as u8;
+    [channel(0), channel(1), channel(2)]
+}
+
+/// Minimal RGB8 PNG writer (uncompressed deflate blocks), so no image dependency is needed
+fn write_png<P: AsRef<std::path::Path>>(path: P, width: usize, height: usize, pixels: &[u8]) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'])?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit, RGB, deflate, no filter, no interlace
+    write_chunk(&mut writer, b"IHDR", &header)?;
+
+    // Each scanline is prefixed with filter type 0 (none)
+    let stride = width * 3;
+    let mut raw = Vec::with_capacity((stride + 1) * height);
+    for row in pixels.chunks_exact(stride) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // zlib stream made of stored (uncompressed) deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(65535).peekable();
+    while let Some(block) = blocks.next() {
+        zlib.push(if blocks.peek().is_none() { 1 } else { 0 });
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+    write_chunk(&mut writer, b"IDAT", &zlib)?;
+
+    write_chunk(&mut writer, b"IEND", &[])?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+
+    let mut crc_input = Vec::with_capacity(4 + data.len());
+    crc_input.extend_from_slice(kind);
+    crc_input.extend_from_slice(data);
+    writer.write_all(&crc32(&crc_input).to_be_bytes())?;
+    Ok(())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    /// Read back a PNG as written by `write_png`, checking its structure on the way:
+    /// returns (width, height, RGB8 pixels)
+    fn read_png(bytes: &[u8]) -> (usize, usize, Vec<u8>) {
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+        let (mut header, mut zlib, mut offset) = (Vec::new(), Vec::new(), 8);
+        loop {
+            let len = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+            let (kind, data) = (&bytes[offset + 4..offset + 8], &bytes[offset + 8..offset + 8 + len]);
+            let crc = u32::from_be_bytes(bytes[offset + 8 + len..offset + 12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(&bytes[offset + 4..offset + 8 + len]), "{} CRC", String::from_utf8_lossy(kind));
+            offset += 12 + len;
+            match kind {
+                b"IHDR" => header = data.to_vec(),
+                b"IDAT" => zlib.extend_from_slice(data),
+                b"IEND" => break,
+                other => panic!("unexpected chunk {:?}", other),
+            }
+        }
+        assert_eq!(offset, bytes.len());
+        let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+        let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+        assert_eq!(&header[8..], [8, 2, 0, 0, 0]);
+
+        // Stored deflate blocks: a final-block flag, then LEN and its complement
+        assert_eq!(((zlib[0] as u16) << 8 | zlib[1] as u16) % 31, 0, "zlib header check");
+        let (mut raw, mut position) = (Vec::new(), 2);
+        loop {
+            let last = zlib[position] & 1 == 1;
+            assert_eq!(zlib[position] >> 1, 0, "only stored blocks are written");
+            let len = u16::from_le_bytes([zlib[position + 1], zlib[position + 2]]);
+            assert_eq!(!len, u16::from_le_bytes([zlib[position + 3], zlib[position + 4]]));
+            raw.extend_from_slice(&zlib[position + 5..position + 5 + len as usize]);
+            position += 5 + len as usize;
+            if last {
+                break;
+            }
+        }
+        assert_eq!(zlib[position..], adler32(&raw).to_be_bytes());
+
+        let mut pixels = Vec::new();
+        for row in raw.chunks_exact(width * 3 + 1) {
+            assert_eq!(row[0], 0, "filter type");
+            pixels.extend_from_slice(&row[1..]);
+        }
+        (width, height, pixels)
+    }
+
+    #[test]
+    fn test_png_round_trips_its_size_and_pixels() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        // Large enough that the image data spans two deflate blocks
+        let (width, height) = (200, 120);
+        let mut pixels: Vec<u8> = (0..width * height * 3).map(|i| (i % 251) as u8).collect();
+        let known = (3 * width + 7) * 3;
+        pixels[known..known + 3].copy_from_slice(&[10, 20, 30]);
+
+        let path = TempPath::file("spectrogram.png");
+        write_png(&path, width, height, &pixels).unwrap();
+        let (read_width, read_height, read_pixels) = read_png(&std::fs::read(&path).unwrap());
+
+        assert_eq!((read_width, read_height), (width, height));
+        assert_eq!(read_pixels[known..known + 3], [10, 20, 30]);
+        assert_eq!(read_pixels, pixels);
+    }
+}
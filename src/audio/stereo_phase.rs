@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+/// L/R correlation at or below which a file counts as out of phase (one channel inverted)
+pub const OUT_OF_PHASE_CORRELATION: f32 = -0.5;
+/// L/R correlation at or above which the channels share most of their content
+pub const IN_PHASE_CORRELATION: f32 = 0.2;
+
+const SAMPLE_BLOCKS: usize = 32;     // Blocks measured, spread evenly over the file
+const BLOCK_FRAMES: usize = 4096;    // Frames per block (~0.1 s at 44.1 kHz)
+
+/// How the left and right channels of a source relate
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseRelation {
+    /// Not measured (e.g. prescans made before the check existed)
+    #[default]
+    Unknown,
+    /// One channel, or silence: nothing to compare
+    Mono,
+    /// Normal stereo: shared centre content, so the mono mix keeps bass and vocals
+    InPhase,
+    /// Largely independent channels (very wide mixes, some field recordings)
+    Uncorrelated,
+    /// One channel polarity-inverted: a plain L+R mix cancels the centre
+    OutOfPhase,
+}
+
+/// Result of the stereo phase check on a source's first two channels
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StereoPhase {
+    pub relation: PhaseRelation,
+    pub correlation: f32, // Pearson correlation of L and R, -1 to 1
+    pub corrected: bool,  // The right channel was inverted before the downmix
+}
+
+#[allow(dead_code)]
+impl StereoPhase {
+    /// Correlate L and R over blocks sampled across the whole file
+    pub fn measure(interleaved: &[f32], channels: usize) -> Self {
+        let frames = interleaved.len() / channels.max(1);
+        if channels < 2 || frames == 0 {
+            return Self { relation: PhaseRelation::Mono, ..Self::default() };
+        }
+
+        let block_frames = BLOCK_FRAMES.min(frames);
+        let stride = ((frames - block_frames) / (SAMPLE_BLOCKS - 1)).max(block_frames);
+        let (mut lr, mut ll, mut rr) = (0.0f64, 0.0f64, 0.0f64);
+        for start in (0..=frames - block_frames).step_by(stride).take(SAMPLE_BLOCKS) {
+            for frame in interleaved[start * channels..(start + block_frames) * channels].chunks_exact(channels) {
+                let (left, right) = (frame[0] as f64, frame[1] as f64);
+                lr += left * right;
+                ll += left * left;
+                rr += right * right;
+            }
+        }
+
+        if ll <= f64::EPSILON || rr <= f64::EPSILON {
+            return Self { relation: PhaseRelation::Mono, ..Self::default() };
+        }
+        let correlation = (lr / (ll * rr).sqrt()) as f32;
+        let relation = if correlation <= OUT_OF_PHASE_CORRELATION {
+            PhaseRelation::OutOfPhase
+        } else if correlation >= IN_PHASE_CORRELATION {
+            PhaseRelation::InPhase
+        } else {
+            PhaseRelation::Uncorrelated
+        };
+        Self { relation, correlation, corrected: false }
+    }
+
+    pub fn is_out_of_phase(&self) -> bool {
+        self.relation == PhaseRelation::OutOfPhase
+    }
+}
+
+/// Flip the polarity of the right channel of interleaved samples
+#[allow(dead_code)]
+pub fn invert_right_channel(interleaved: &mut [f32], channels: usize) {
+    if channels < 2 {
+        return;
+    }
+    for frame in interleaved.chunks_exact_mut(channels) {
+        frame[1] = -frame[1];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{downmix_checked, DownmixMode};
+
+    #[test]
+    fn test_out_of_phase_file_is_detected_and_fixed() {
+        // Same 60 Hz "bass" on both sides, with the right channel inverted
+        let interleaved: Vec<f32> = (0..44100)
+            .flat_map(|i| {
+                let sample = (i as f32 * 60.0 * std::f32::consts::TAU / 44100.0).sin() * 0.5;
+                [sample, -sample]
+            })
+            .collect();
+        let phase = StereoPhase::measure(&interleaved, 2);
+        assert_eq!(phase.relation, PhaseRelation::OutOfPhase);
+        assert!(phase.correlation < -0.99);
+
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let (cancelled, phase) = downmix_checked(&interleaved, 2, DownmixMode::Average, false);
+        assert!(peak(&cancelled) < 1e-6 && !phase.corrected);
+        let (fixed, phase) = downmix_checked(&interleaved, 2, DownmixMode::Average, true);
+        assert!((peak(&fixed) - 0.5).abs() < 0.01 && phase.corrected);
+
+        // Ordinary stereo and mono sources are left alone
+        let in_phase: Vec<f32> = interleaved.chunks_exact(2).flat_map(|frame| [frame[0], frame[0] * 0.8]).collect();
+        assert_eq!(StereoPhase::measure(&in_phase, 2).relation, PhaseRelation::InPhase);
+        assert_eq!(StereoPhase::measure(&interleaved[..1000], 1).relation, PhaseRelation::Mono);
+    }
+}
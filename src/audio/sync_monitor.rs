@@ -0,0 +1,136 @@
+use std::time::Instant;
+
+/// Visual/audio drift that gets a warning: about where lip-sync errors become noticeable
+pub const DEFAULT_DRIFT_THRESHOLD: f32 = 0.1;
+
+/// Drift between the analysis and the audio it is meant to follow, when it first passes the
+/// threshold (and again each time it doubles)
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncDrift {
+    /// Track seconds the analysis has moved through
+    pub analysis_seconds: f32,
+    /// Track seconds the audio device has actually played
+    pub audio_seconds: f32,
+    /// Wall-clock seconds spent playing, scaled by the playback speed
+    pub wall_seconds: f32,
+    /// Frames analyzed (one per rendered frame) over that wall time
+    pub frames: u64,
+}
+
+#[allow(dead_code)]
+impl SyncDrift {
+    /// Seconds the visuals are ahead of (positive) or behind (negative) the audio
+    pub fn drift(&self) -> f32 {
+        self.analysis_seconds - self.audio_seconds
+    }
+
+    /// Rendered frames per wall-clock second, to compare with the 60 fps the analysis step assumes
+    pub fn frame_rate(&self) -> f32 {
+        self.frames as f32 / self.wall_seconds.max(f32::EPSILON)
+    }
+}
+
+/// Tracks elapsed wall time, the audio playhead and how far the frame-stepped analysis has
+/// advanced, flagging when the visuals drift off the audio (e.g. rendering at 144 Hz while each
+/// analysis step assumes 60 fps). Invisible drift on long tracks becomes a log line.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SyncMonitor {
+    threshold: f32,
+    next_warning: f32,
+    last_update: Option<Instant>,
+    audio_origin: Option<f32>,
+    analysis_seconds: f64,
+    wall_seconds: f64,
+    frames: u64,
+}
+
+impl Default for SyncMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRIFT_THRESHOLD)
+    }
+}
+
+#[allow(dead_code)]
+impl SyncMonitor {
+    pub fn new(threshold: f32) -> Self {
+        let threshold = threshold.max(0.001);
+        Self {
+            threshold,
+            next_warning: threshold,
+            last_update: None,
+            audio_origin: None,
+            analysis_seconds: 0.0,
+            wall_seconds: 0.0,
+            frames: 0,
+        }
+    }
+
+    /// Start measuring afresh from the next update (after a load or seek)
+    pub fn reset(&mut self) {
+        *self = Self::new(self.threshold);
+    }
+
+    /// Record one analysis frame that stepped `analysis_step` track seconds, with the playhead at
+    /// `audio_seconds`. Wall time only counts while `playing`, scaled by `speed`. Returns the
+    /// drift when it crosses the threshold, or its next doubling.
+    pub fn update(&mut self, now: Instant, playing: bool, speed: f32, analysis_step: f32, audio_seconds: f32) -> Option<SyncDrift> {
+        let audio_origin = *self.audio_origin.get_or_insert(audio_seconds);
+        if let Some(last) = self.last_update.replace(now) {
+            if playing {
+                self.wall_seconds += now.duration_since(last).as_secs_f64() * speed as f64;
+                self.frames += 1;
+            }
+        }
+        self.analysis_seconds += analysis_step as f64;
+
+        let drift = SyncDrift {
+            analysis_seconds: self.analysis_seconds as f32,
+            audio_seconds: audio_seconds - audio_origin,
+            wall_seconds: self.wall_seconds as f32,
+            frames: self.frames,
+        };
+        let size = drift.drift().abs();
+        if size < self.threshold * 0.5 {
+            self.next_warning = self.threshold; // Back in sync: warn again on the next excursion
+        } else if size >= self.next_warning {
+            self.next_warning = size * 2.0;
+            return Some(drift);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rendering_faster_than_60fps_is_flagged_once_per_doubling() {
+        // 120 fps rendering with each step assuming 60 fps: the analysis runs at twice real time
+        let start = Instant::now();
+        let mut monitor = SyncMonitor::default();
+        let mut warnings = Vec::new();
+        for frame in 0..600u32 {
+            let now = start + Duration::from_secs_f32(frame as f32 / 120.0);
+            let audio = frame as f32 / 120.0;
+            if let Some(drift) = monitor.update(now, true, 1.0, 1.0 / 60.0, audio) {
+                warnings.push(drift);
+            }
+        }
+        let first = warnings[0];
+        assert!(first.drift() >= 0.1 && first.drift() < 0.13, "first warning at {:+.3}s", first.drift());
+        assert!((first.frame_rate() - 120.0).abs() < 5.0);
+        // 5s of audio, 10s of analysis: warnings at ~0.1, 0.2, 0.4, 0.8, 1.6, 3.2s of drift
+        assert_eq!(warnings.len(), 6);
+
+        // In step, nothing is reported
+        let mut monitor = SyncMonitor::default();
+        assert!((0..600u32).all(|frame| {
+            let now = start + Duration::from_secs_f32(frame as f32 / 60.0);
+            monitor.update(now, true, 1.0, 1.0 / 60.0, 3.0 + frame as f32 / 60.0).is_none()
+        }));
+    }
+}
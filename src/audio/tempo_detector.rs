@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+
+/// Slowest tempo whose beat intervals are trusted; longer gaps are dropouts, not beats
+pub const MIN_BPM: f32 = 40.0;
+/// Fastest tempo whose beat intervals are trusted; shorter ones are double triggers
+pub const MAX_BPM: f32 = 240.0;
+/// Default share of the previous estimate kept on each beat (see `set_smoothing`)
+pub const DEFAULT_BPM_SMOOTHING: f32 = 0.8;
+
+const MAX_INTERVALS: usize = 8; // Beat intervals in the median window
+
+/// Tempo from the spacing of detected beats.
+///
+/// Each beat's estimate is the median of the last few intervals, so one missed or extra beat
+/// can't swing it, and the reported BPM then eases towards that estimate so effects that
+/// scale with `estimated_bpm / 120` don't jitter.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct TempoDetector {
+    beat_intervals: VecDeque<f32>,
+    last_beat_time: Option<f32>,
+    current_time: f32,
+    estimated_bpm: f32,
+    has_estimate: bool, // The first estimate is taken as-is rather than eased in from 120
+    smoothing: f32,
+}
+
+impl Default for TempoDetector {
+    fn default() -> Self {
+        Self {
+            beat_intervals: VecDeque::with_capacity(MAX_INTERVALS),
+            last_beat_time: None,
+            current_time: 0.0,
+            estimated_bpm: 120.0,
+            has_estimate: false,
+            smoothing: DEFAULT_BPM_SMOOTHING,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl TempoDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share of the previous BPM kept on each beat: 0 follows the median immediately, values
+    /// near 1 change slowly (clamped to 0-0.99; NaN is ignored)
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        if !smoothing.is_nan() {
+            self.smoothing = smoothing.clamp(0.0, 0.99);
+        }
+    }
+
+    pub fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    pub fn estimated_bpm(&self) -> f32 {
+        self.estimated_bpm
+    }
+
+    /// Advance by `time_delta` seconds, noting a beat if one was detected; returns the BPM
+    pub fn update(&mut self, beat_detected: bool, time_delta: f32) -> f32 {
+        self.current_time += time_delta;
+        if !beat_detected {
+            return self.estimated_bpm;
+        }
+
+        if let Some(last_beat_time) = self.last_beat_time {
+            let interval = self.current_time - last_beat_time;
+            if (60.0 / MAX_BPM..=60.0 / MIN_BPM).contains(&interval) {
+                if self.beat_intervals.len() == MAX_INTERVALS {
+                    self.beat_intervals.pop_front();
+                }
+                self.beat_intervals.push_back(interval);
+
+                let target_bpm = 60.0 / self.median_interval();
+                self.estimated_bpm = if self.has_estimate {
+                    self.estimated_bpm + (1.0 - self.smoothing) * (target_bpm - self.estimated_bpm)
+                } else {
+                    target_bpm
+                };
+                self.has_estimate = true;
+            }
+        }
+        self.last_beat_time = Some(self.current_time);
+        self.estimated_bpm
+    }
+
+    fn median_interval(&self) -> f32 {
+        let mut sorted: Vec<f32> = self.beat_intervals.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let middle = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2.0
+        } else {
+            sorted[middle]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bpm_holds_steady_on_a_constant_tempo() {
+        // 128 BPM with beats rounded to 60 fps frames (28 or 29 frames apart), a missed beat
+        // and a double trigger
+        let frame = 1.0 / 60.0;
+        let beat_frames: Vec<usize> = (0..64)
+            .filter(|&beat| beat != 30)
+            .map(|beat| (beat as f32 * 60.0 / 128.0 / frame).round() as usize)
+            .flat_map(|frame_index| if frame_index == 1125 { vec![frame_index, frame_index + 4] } else { vec![frame_index] })
+            .collect();
+        assert!(beat_frames.contains(&1129), "double trigger lands on a beat");
+
+        let mut detector = TempoDetector::new();
+        let mut readings = Vec::new();
+        for frame_index in 0..=*beat_frames.last().unwrap() {
+            let bpm = detector.update(beat_frames.contains(&frame_index), frame);
+            if frame_index > beat_frames[16] {
+                readings.push(bpm);
+            }
+        }
+
+        let (low, high) = readings.iter().fold((f32::MAX, f32::MIN), |(low, high), &bpm| (low.min(bpm), high.max(bpm)));
+        assert!(low > 124.0 && high < 132.0, "BPM wandered between {:.1} and {:.1}", low, high);
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use std::str::FromStr;
+
+/// Synthetic calibration signals for exercising the analysis chain without an audio file.
+///
+/// - `Sine`: steady tone at a fixed frequency (checks a single band lights up)
+/// - `Sweep`: logarithmic 20 Hz → 20 kHz sweep (checks band extraction in order)
+/// - `Noise`: white noise (checks all bands respond evenly)
+/// - `ClickTrack`: short clicks at a fixed BPM (checks beat and tempo detection)
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestSignal {
+    Sine,
+    Sweep,
+    Noise,
+    ClickTrack,
+}
+
+impl FromStr for TestSignal {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sine" => Ok(Self::Sine),
+            "sweep" => Ok(Self::Sweep),
+            "noise" => Ok(Self::Noise),
+            "click-track" | "click" => Ok(Self::ClickTrack),
+            other => Err(anyhow::anyhow!(
+                "Unknown test signal '{}'. Use: sine, sweep, noise, click-track", other
+            )),
+        }
+    }
+}
+
+/// Parameters for test signal synthesis
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct TestSignalConfig {
+    pub signal: TestSignal,
+    pub sample_rate: u32,
+    pub duration_seconds: f32,
+    pub frequency: f32, // Hz, used by the sine and as the click tone
+    pub bpm: f32,       // Used by the click track
+    pub amplitude: f32, // Peak amplitude (0.0-1.0)
+}
+
+impl Default for TestSignalConfig {
+    fn default() -> Self {
+        Self {
+            signal: TestSignal::Sine,
+            sample_rate: 44100,
+            duration_seconds: 30.0,
+            frequency: 440.0,
+            bpm: 120.0,
+            amplitude: 0.5,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl TestSignalConfig {
+    /// Synthesize the mono signal into memory
+    pub fn generate(&self) -> Vec<f32> {
+        let sample_rate = self.sample_rate as f32;
+        let total_samples = (self.duration_seconds.max(0.0) * sample_rate) as usize;
+        let two_pi = 2.0 * std::f32::consts::PI;
+
+        match self.signal {
+            TestSignal::Sine => (0..total_samples)
+                .map(|i| self.amplitude * (two_pi * self.frequency * i as f32 / sample_rate).sin())
+                .collect(),
+
+            TestSignal::Sweep => {
+                // Exponential sweep so each octave gets equal time
+                let start_hz: f32 = 20.0;
+                let end_hz = 20000.0f32.min(sample_rate / 2.0);
+                let ratio = (end_hz / start_hz).ln();
+                let duration = self.duration_seconds.max(f32::EPSILON);
+
+                (0..total_samples)
+                    .map(|i| {
+                        let t = i as f32 / sample_rate;
+                        let phase = two_pi * start_hz * duration / ratio * ((t / duration * ratio).exp() - 1.0);
+                        self.amplitude * phase.sin()
+                    })
+                    .collect()
+            }
+
+            TestSignal::Noise => {
+                // Small xorshift PRNG keeps this deterministic and dependency-free
+                let mut state: u32 = 0x2545_F491;
+                (0..total_samples)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 17;
+                        state ^= state << 5;
+                        let unit = state as f32 / u32::MAX as f32;
+                        self.amplitude * (unit * 2.0 - 1.0)
+                    })
+                    .collect()
+            }
+
+            TestSignal::ClickTrack => {
+                let samples_per_beat = (60.0 / self.bpm.max(1.0) * sample_rate).max(1.0) as usize;
+                let click_length = (0.01 * sample_rate) as usize; // 10ms click
+                let decay = click_length.max(1) as f32 / 5.0;
+
+                (0..total_samples)
+                    .map(|i| {
+                        let position = i % samples_per_beat;
+                        if position < click_length {
+                            let envelope = (-(position as f32) / decay).exp();
+                            self.amplitude * envelope * (two_pi * self.frequency * position as f32 / sample_rate).sin()
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Short human-readable description for logging
+    pub fn describe(&self) -> String {
+        match self.signal {
+            TestSignal::Sine => format!("sine @ {:.1} Hz", self.frequency),
+            TestSignal::Sweep => "log sweep 20 Hz → 20 kHz".to_string(),
+            TestSignal::Noise => "white noise".to_string(),
+            TestSignal::ClickTrack => format!("click track @ {:.1} BPM ({:.0} Hz clicks)", self.bpm, self.frequency),
+        }
+    }
+}
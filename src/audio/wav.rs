@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Context, Result};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use rodio::Source;
+use std::path::Path;
+
+/// Full-quality source audio for export: interleaved samples (-1.0 to 1.0) at the source's
+/// own sample rate and channel count, plus the sample format to write them back out in
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SourceAudio {
+    pub spec: WavSpec,
+    pub samples: Vec<f32>,
+}
+
+impl SourceAudio {
+    /// Wrap an in-memory buffer (e.g. a synthesized test signal) as 32-bit float audio
+    #[allow(dead_code)]
+    pub fn from_samples(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
+        Self {
+            spec: WavSpec {
+                channels,
+                sample_rate,
+                bits_per_sample: 32,
+                sample_format: SampleFormat::Float,
+            },
+            samples,
+        }
+    }
+
+    /// Length in seconds
+    #[allow(dead_code)]
+    pub fn duration_seconds(&self) -> f32 {
+        let frames = self.samples.len() / self.spec.channels.max(1) as usize;
+        frames as f32 / self.spec.sample_rate as f32
+    }
+}
+
+/// Decode an audio file without downmixing or resampling.
+///
+/// WAV files are read directly, keeping their bit depth (16/24/32-bit int or float), and FLAC
+/// keeps its own through symphonia. Other formats go through rodio's decoder, which yields
+/// 16-bit samples, so they are written as 16-bit.
+#[allow(dead_code)]
+pub fn read_source_audio<P: AsRef<Path>>(path: P) -> Result<SourceAudio> {
+    let path = path.as_ref();
+    let is_wav = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+
+    if is_wav {
+        let reader = WavReader::open(path)
+            .with_context(|| format!("Failed to open WAV {}", path.display()))?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<Vec<_>, _>>()?,
+            SampleFormat::Int => {
+                let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader.into_samples::<i32>()
+                    .map(|sample| sample.map(|s| s as f32 / full_scale))
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        return Ok(SourceAudio { spec, samples });
+    }
+
+    if let Some(audio) = super::lossless::decode_lossless(path) {
+        let spec = WavSpec {
+            channels: audio.channels,
+            sample_rate: audio.sample_rate,
+            bits_per_sample: audio.bits_per_sample.clamp(8, 32) as u16,
+            sample_format: SampleFormat::Int,
+        };
+        return Ok(SourceAudio { spec, samples: audio.samples });
+    }
+
+    let source = super::open_decoder(path)?;
+    let spec = WavSpec {
+        channels: source.channels(),
+        sample_rate: source.sample_rate(),
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let samples = source.convert_samples::<f32>().collect();
+    Ok(SourceAudio { spec, samples })
+}
+
+/// Write audio as a WAV file with its own sample rate, channel count and sample format
+#[allow(dead_code)]
+pub fn write_wav<P: AsRef<Path>>(path: P, audio: &SourceAudio) -> Result<()> {
+    let spec = audio.spec;
+    let mut writer = WavWriter::create(path.as_ref(), spec)
+        .with_context(|| format!("Failed to create WAV {}", path.as_ref().display()))?;
+
+    match (spec.sample_format, spec.bits_per_sample) {
+        (SampleFormat::Float, 32) => {
+            for &sample in &audio.samples {
+                writer.write_sample(sample)?;
+            }
+        }
+        (SampleFormat::Int, bits @ 8..=32) => {
+            let full_scale = (1i64 << (bits - 1)) as f32;
+            let (min, max) = (-full_scale, full_scale - 1.0);
+            for &sample in &audio.samples {
+                writer.write_sample((sample * full_scale).round().clamp(min, max) as i32)?;
+            }
+        }
+        (format, bits) => return Err(anyhow!("Unsupported WAV format: {:?} at {} bits", format, bits)),
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_wav_round_trips_keep_format_and_samples() {
+        let directory = TempPath::dir("wav");
+
+        for (bits, sample_format) in [(16, SampleFormat::Int), (24, SampleFormat::Int), (32, SampleFormat::Float)] {
+            let path = directory.join(format!("round_trip_{}.wav", bits));
+            let spec = WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: bits, sample_format };
+            // Four stereo frames: exact steps of the resolution for integers (both extremes
+            // included); float keeps even out-of-range values
+            let samples: Vec<f32> = match sample_format {
+                SampleFormat::Int => {
+                    let full_scale = (1i64 << (bits - 1)) as f32;
+                    [-full_scale, -12345.0, -1.0, 0.0, 1.0, 2.0, 777.0, full_scale - 1.0].iter().map(|step| step / full_scale).collect()
+                }
+                SampleFormat::Float => vec![-1.5, -0.987_654, -0.5, 0.0, 1e-7, 0.123_456, 1.0, 1.25],
+            };
+            let audio = SourceAudio { spec, samples };
+
+            write_wav(&path, &audio).unwrap();
+            let restored = read_source_audio(&path).unwrap();
+            assert_eq!(restored.spec, spec, "{}-bit spec", bits);
+            assert_eq!(restored.samples, audio.samples, "{}-bit samples", bits);
+            assert_eq!(restored.duration_seconds(), 4.0 / 48000.0);
+        }
+
+        // Out-of-range samples clip rather than wrapping when written as integers
+        let path = directory.join("clipped.wav");
+        let spec = WavSpec { channels: 1, sample_rate: 44100, bits_per_sample: 16, sample_format: SampleFormat::Int };
+        write_wav(&path, &SourceAudio { spec, samples: vec![2.0, -2.0] }).unwrap();
+        assert_eq!(read_source_audio(&path).unwrap().samples, [32767.0 / 32768.0, -1.0]);
+
+        let unsupported = SourceAudio { spec: WavSpec { bits_per_sample: 64, sample_format: SampleFormat::Float, ..spec }, samples: vec![0.0] };
+        assert!(write_wav(directory.join("unsupported.wav"), &unsupported).is_err());
+    }
+}
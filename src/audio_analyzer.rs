@@ -1,17 +1,23 @@
 use anyhow::Result;
 use clap::Parser;
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
-mod audio;
-mod effects;
+use arrvee::{audio, effects, logging};
 
 use audio::{AudioPlayback, AudioFrame, CpuAudioAnalyzer, NewGpuAudioAnalyzer, FeatureNormalizer, NormalizedAudioFeatures};
-use audio::analysis_interface::AudioAnalyzer;
+use audio::analysis_interface::{AudioAnalyzer, RawAudioFeatures};
+use audio::feature_normalizer::{NormalizationParameters, RangeObserver};
+use audio::key_estimation::{estimate_key, KeyEstimate};
+use audio::tempo_detector::{MAX_BPM, MIN_BPM};
 use effects::PsychedelicManager;
+use effects::cue_list::{CueKind, CueList};
+use effects::program::{SectionDetector, SECTION_MIN_SECONDS};
+use effects::recommendation::{self, TrackCharacter};
 
 #[derive(Parser)]
 #[command(name = "arrvee-audio-analyzer")]
@@ -29,13 +35,45 @@ struct Args {
     #[arg(long)]
     frame_by_frame: bool,
 
-    /// Analysis chunk size in samples
+    /// Analysis chunk size in samples (GPU analysis needs a power of two of at least 64; other
+    /// sizes fall back to the CPU analyzer)
     #[arg(long, default_value = "512")]
     chunk_size: usize,
 
     /// Sample rate override (0 = use file's native rate)
     #[arg(long, default_value = "0")]
     sample_rate: u32,
+
+    /// Also write normalization parameters fitted to this file's raw feature ranges, for
+    /// `audio-test --normalization` / `prescan-tool --normalization` or `FeatureNormalizer::load_parameters`
+    #[arg(long, value_name = "FILE")]
+    apply_suggestions: Option<String>,
+
+    /// Calibration wizard: fit normalization parameters to the track(s), write them to FILE
+    /// (default arrvee_norm.json) and show how the visuals' inputs change, instead of a full report
+    #[arg(long, value_name = "FILE", num_args = 0..=1, require_equals = true, default_missing_value = "arrvee_norm.json")]
+    calibrate: Option<String>,
+
+    /// Further tracks to calibrate on together with the first (`--calibrate` only)
+    #[arg(value_name = "MORE_FILES")]
+    more_files: Vec<String>,
+
+    /// Also write the sections and effect transitions as timestamped cues for VJ software
+    /// (JSON, or XML when FILE ends in .xml)
+    #[arg(long, value_name = "FILE")]
+    cue_list: Option<String>,
+
+    /// Also write the tempo stability time series as CSV (time_seconds, tempo_stability, bpm)
+    #[arg(long, value_name = "FILE")]
+    tempo_csv: Option<String>,
+
+    /// Suppress the periodic progress logs
+    #[arg(long)]
+    quiet: bool,
+
+    /// Emit logs as JSON lines (one object per line) for scripting and CI
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,6 +93,8 @@ struct FrameData {
     audio_frame: SerializableAudioFrame,
     effect_weights: HashMap<String, f32>,
     dominant_effect: Option<String>,
+    #[serde(default)]
+    tempo_stability: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +131,14 @@ struct BeatEvent {
     estimated_bpm: f32,
 }
 
+/// Tempo stability at one point of the track (see `TempoStabilityTracker`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TempoStabilityPoint {
+    timestamp: f32,
+    stability: f32, // 0-1, how regular the recent beats are
+    bpm: f32,       // Tempo of the recent beats, 0 until there are enough of them
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct EffectActivation {
     effect_name: String,
@@ -100,8 +148,56 @@ struct EffectActivation {
     average_weight: f32,
 }
 
+/// Version of the analysis JSON layout. Bump it whenever a field of `AnalysisResults` (or
+/// anything inside it) is added, removed, renamed or changes meaning.
+///
+/// - 1: first versioned layout
+/// - 2: `frame_data` is streamed as the last field, and omitted rather than `null` without
+///   `--frame-by-frame`
+/// - 3: `insights.estimated_key` and `insights.key_confidence`
+/// - 4: `sections`, and `effect_transitions` is filled in (it was always empty before)
+/// - 5: `raw_feature_peaks`
+/// - 6: `tempo_stability`, and `frame_data[].tempo_stability`
+const ANALYSIS_SCHEMA_VERSION: u32 = 6;
+
+/// Version fields at the top of every analysis JSON, so tooling can detect schema changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SchemaHeader {
+    #[serde(default)]
+    schema_version: u32, // 0 = written before the schema was versioned
+    #[serde(default)]
+    arrvee_version: String,
+}
+
+impl SchemaHeader {
+    fn current() -> Self {
+        Self {
+            schema_version: ANALYSIS_SCHEMA_VERSION,
+            arrvee_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Describe how this header differs from what this build writes, if it does
+    fn mismatch(&self) -> Option<String> {
+        match self.schema_version {
+            ANALYSIS_SCHEMA_VERSION => None,
+            0 => Some("has no schema version (written by an older release)".to_string()),
+            version if version > ANALYSIS_SCHEMA_VERSION => Some(format!(
+                "uses schema version {} (from arrvee {}), newer than this build's {}; unknown fields are ignored",
+                version, self.arrvee_version, ANALYSIS_SCHEMA_VERSION)),
+            version => Some(format!(
+                "uses schema version {} (from arrvee {}), older than this build's {}",
+                version, self.arrvee_version, ANALYSIS_SCHEMA_VERSION)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct AnalysisResults {
+    // Versioning (flattened so the fields sit at the top level of the JSON)
+    #[serde(flatten)]
+    schema: SchemaHeader,
+
     // Metadata
     file_info: FileInfo,
     analysis_config: AnalysisConfig,
@@ -111,16 +207,27 @@ struct AnalysisResults {
     spectral_feature_stats: HashMap<String, AudioFeatureStats>,
     temporal_feature_stats: HashMap<String, AudioFeatureStats>,
     beat_stats: BeatStats,
+    // Pre-normalization 99th percentile of each feature, for fitting normalization (`arrvee-normtool`)
+    #[serde(default)]
+    raw_feature_peaks: HashMap<String, f32>,
 
     // Effect analysis
     effect_activation_summary: HashMap<String, EffectActivationSummary>,
     effect_transitions: Vec<EffectTransition>,
+    #[serde(default)]
+    sections: Vec<Section>,
+
+    // Rhythm over time, one point every TEMPO_STABILITY_STEP_SECONDS
+    #[serde(default)]
+    tempo_stability: Vec<TempoStabilityPoint>,
 
     // Event logs
     beat_events: Vec<BeatEvent>,
     effect_activations: Vec<EffectActivation>,
 
-    // Frame-by-frame data (optional)
+    // Frame-by-frame data (optional). Never held in memory while analyzing: frames are
+    // spooled to disk and spliced in by `write_results`, so this is only filled on load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     frame_data: Option<Vec<FrameData>>,
 
     // Analysis insights
@@ -162,12 +269,130 @@ struct EffectActivationSummary {
     activation_count: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A stretch of the track between loudness section changes (see `SectionDetector`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Section {
+    start_time: f32,
+    end_time: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct EffectTransition {
     timestamp: f32,
     from_effect: Option<String>,
     to_effect: String,
-    transition_speed: f32,
+    transition_speed: f32, // Weight the new effect gained per second while taking the lead
+}
+
+const MIN_TRANSITION_SECONDS: f32 = 0.5; // A new leader must hold this long to count
+
+/// Follows the dominant effect (highest weight above 0.1) and reports when a new one takes
+/// over and keeps the lead, so near-equal blends trading places don't read as transitions
+#[derive(Debug, Default)]
+struct TransitionTracker {
+    current: Option<String>,
+    candidate: Option<(String, f32, f32)>, // Challenger, when it took the lead, its weight then
+}
+
+impl TransitionTracker {
+    fn update(&mut self, timestamp: f32, effect_weights: &HashMap<String, f32>) -> Option<EffectTransition> {
+        let leader = effect_weights.iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, weight)| **weight > 0.1);
+        let (leader, &weight) = match leader {
+            Some(leader) if Some(leader.0) != self.current.as_ref() => leader,
+            _ => {
+                // The current effect kept the lead, or nothing leads: keep the current one
+                self.candidate = None;
+                return None;
+            }
+        };
+
+        if self.candidate.as_ref().is_none_or(|(name, _, _)| name != leader) {
+            self.candidate = Some((leader.clone(), timestamp, weight));
+        }
+        let (_, since, start_weight) = self.candidate.as_ref()?;
+        let held = timestamp - since;
+        if held < MIN_TRANSITION_SECONDS {
+            return None;
+        }
+
+        let transition = EffectTransition {
+            timestamp: *since,
+            from_effect: self.current.replace(leader.clone()),
+            to_effect: leader.clone(),
+            transition_speed: (weight - start_weight) / held,
+        };
+        self.candidate = None;
+        Some(transition)
+    }
+}
+
+const TEMPO_STABILITY_INTERVALS: usize = 8;      // Beat intervals in the rolling window
+const TEMPO_STABILITY_STEP_SECONDS: f32 = 0.5;   // Spacing of the exported time series
+
+/// Rolling regularity of the beats: 1 for a locked groove, falling towards 0 as the recent beat
+/// intervals spread out (`1 / (1 + 10 * coefficient of variation)`). Intervals outside the
+/// tempo detector's BPM range are dropouts or double triggers, and are skipped.
+#[derive(Debug, Default)]
+struct TempoStabilityTracker {
+    intervals: VecDeque<f32>,
+    last_beat: Option<f32>,
+    stability: f32,
+    bpm: f32,
+}
+
+impl TempoStabilityTracker {
+    fn update(&mut self, timestamp: f32, beat_detected: bool) -> f32 {
+        if !beat_detected {
+            return self.stability;
+        }
+
+        if let Some(last_beat) = self.last_beat.replace(timestamp) {
+            let interval = timestamp - last_beat;
+            if (60.0 / MAX_BPM..=60.0 / MIN_BPM).contains(&interval) {
+                if self.intervals.len() == TEMPO_STABILITY_INTERVALS {
+                    self.intervals.pop_front();
+                }
+                self.intervals.push_back(interval);
+            }
+        }
+
+        if self.intervals.len() >= 3 {
+            let count = self.intervals.len() as f32;
+            let mean = self.intervals.iter().sum::<f32>() / count;
+            let variance = self.intervals.iter().map(|&interval| (interval - mean).powi(2)).sum::<f32>() / count;
+            self.stability = 1.0 / (1.0 + 10.0 * variance.sqrt() / mean);
+            self.bpm = 60.0 / mean;
+        }
+        self.stability
+    }
+}
+
+/// One-line block plot of the tempo stability, `width` characters wide
+fn stability_sparkline(series: &[TempoStabilityPoint], width: usize) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    if series.is_empty() || width == 0 {
+        return String::new();
+    }
+    let bucket = series.len().div_ceil(width);
+    series.chunks(bucket)
+        .map(|points| {
+            let mean = points.iter().map(|point| point.stability).sum::<f32>() / points.len() as f32;
+            LEVELS[((mean * LEVELS.len() as f32) as usize).min(LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Write the tempo stability series as CSV
+fn write_tempo_csv(path: &str, series: &[TempoStabilityPoint]) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "time_seconds,tempo_stability,bpm")?;
+    for point in series {
+        writeln!(out, "{:.3},{:.4},{:.2}", point.timestamp, point.stability, point.bpm)?;
+    }
+    out.flush()?;
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -179,6 +404,91 @@ struct AnalysisInsights {
     recommended_effects: Vec<String>,
     optimal_smoothing_factor: f32,
     suggested_thresholds: HashMap<String, f32>,
+    #[serde(default)]
+    estimated_key: Option<String>, // e.g. "A minor"; None for silence or very short files
+    #[serde(default)]
+    key_confidence: f32,           // 0-1 correlation with the key's profile
+}
+
+/// Frame-by-frame records written to a side file as they're computed, so memory stays bounded
+/// on long tracks; `write_results` splices them into the results' `frame_data` array
+struct FrameSpool {
+    writer: BufWriter<File>,
+    count: usize,
+    file: SpoolFile, // After `writer`, so the file is closed before it's removed
+}
+
+/// The spool's path; the file is removed on drop, once `write_results` has spliced it in or
+/// when the analysis or export fails partway, so no run leaves it behind
+struct SpoolFile(PathBuf);
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+impl FrameSpool {
+    fn create(path: PathBuf) -> Result<Self> {
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self { writer, count: 0, file: SpoolFile(path) })
+    }
+
+    fn push(&mut self, frame: &FrameData) -> Result<()> {
+        if self.count > 0 {
+            self.writer.write_all(b",\n")?;
+        }
+        self.writer.write_all(b"    ")?;
+        serde_json::to_writer(&mut self.writer, frame)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Flush, returning the spool file's path
+    fn finish(&mut self) -> Result<&Path> {
+        self.writer.flush()?;
+        Ok(&self.file.0)
+    }
+}
+
+/// Write the results JSON, appending spooled frames (if any) as a `frame_data` array
+/// without loading them back into memory
+fn write_results(path: &Path, results: &AnalysisResults, spool: Option<FrameSpool>) -> Result<()> {
+    let envelope = serde_json::to_string_pretty(results)?;
+    let mut out = BufWriter::new(File::create(path)?);
+
+    match spool {
+        None => out.write_all(envelope.as_bytes())?,
+        Some(mut spool) => {
+            let frame_count = spool.count;
+            let spool_path = spool.finish()?;
+            let body = envelope.trim_end()
+                .strip_suffix('}')
+                .ok_or_else(|| anyhow::anyhow!("Analysis results did not serialize to a JSON object"))?
+                .trim_end();
+
+            out.write_all(body.as_bytes())?;
+            out.write_all(b",\n  \"frame_data\": [\n")?;
+            std::io::copy(&mut File::open(spool_path)?, &mut out)?;
+            out.write_all(if frame_count > 0 { b"\n  ]\n}" } else { b"]\n}" })?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Load analysis JSON written by `audio-analyzer`, warning when its schema version differs
+/// from this build's
+#[allow(dead_code)]
+fn load_analysis_results(path: &str) -> Result<AnalysisResults> {
+    let json = std::fs::read_to_string(path)?;
+    let header: SchemaHeader = serde_json::from_str(&json)?;
+    if let Some(mismatch) = header.mismatch() {
+        warn!("⚠️  {} {}", path, mismatch);
+    }
+    serde_json::from_str(&json)
+        .map_err(|e| anyhow::anyhow!("Failed to read {} (schema version {}): {}", path, header.schema_version, e))
 }
 
 impl From<&AudioFrame> for SerializableAudioFrame {
@@ -209,12 +519,17 @@ struct AudioAnalysisEngine {
     analyzer: Box<dyn AudioAnalyzer + Send>,
     normalizer: FeatureNormalizer,
     psychedelic_manager: PsychedelicManager,
+    range_observer: RangeObserver, // Raw feature ranges, for fitted normalization parameters
 
     // Statistics collectors
     feature_collectors: HashMap<String, Vec<f32>>,
-    frame_data: Vec<FrameData>,
+    frame_spool: Option<FrameSpool>,
+    key_estimate: Option<KeyEstimate>,
     beat_events: Vec<BeatEvent>,
     effect_activations: Vec<EffectActivation>,
+    effect_transitions: Vec<EffectTransition>,
+    sections: Vec<Section>,
+    tempo_stability: Vec<TempoStabilityPoint>,
 
     // Configuration
     chunk_size: usize,
@@ -230,7 +545,14 @@ impl AudioAnalysisEngine {
         let analyzer: Box<dyn AudioAnalyzer + Send> = match NewGpuAudioAnalyzer::new_standalone(sample_rate, chunk_size).await {
             Ok(gpu_analyzer) => {
                 info!("Using GPU analyzer");
-                Box::new(gpu_analyzer)
+                Box::new(audio::FallbackAnalyzer::new(
+                    Box::new(gpu_analyzer),
+                    Box::new(CpuAudioAnalyzer::new(sample_rate, chunk_size)?),
+                ))
+            }
+            Err(e) if e.is::<audio::ComputeUnavailable>() => {
+                info!("GPU compute unavailable on this adapter; using CPU analysis. ({})", e);
+                Box::new(CpuAudioAnalyzer::new(sample_rate, chunk_size)?)
             }
             Err(e) => {
                 info!("GPU analyzer failed ({}), using CPU analyzer", e);
@@ -248,22 +570,36 @@ impl AudioAnalysisEngine {
             analyzer,
             normalizer,
             psychedelic_manager,
+            range_observer: RangeObserver::new(),
             feature_collectors: HashMap::new(),
-            frame_data: Vec::new(),
+            frame_spool: None,
+            key_estimate: None,
             beat_events: Vec::new(),
             effect_activations: Vec::new(),
+            effect_transitions: Vec::new(),
+            sections: Vec::new(),
+            tempo_stability: Vec::new(),
             chunk_size,
             sample_rate,
             frame_rate,
         })
     }
 
-    async fn analyze_file(&mut self, file_path: &str, include_frames: bool) -> Result<AnalysisResults> {
+    /// Analyze a whole file. With `frame_spool`, every frame's data is written there as it is
+    /// computed; pass the spool on to `write_results` afterwards.
+    async fn analyze_file(&mut self, file_path: &str, frame_spool: Option<FrameSpool>) -> Result<(AnalysisResults, Option<FrameSpool>)> {
+        let include_frames = frame_spool.is_some();
+        self.frame_spool = frame_spool;
+
         info!("Loading audio file: {}", file_path);
         self.playback.load_file(file_path).await?;
 
         let mut frame_count = 0;
         let mut active_effects: HashMap<String, f32> = HashMap::new(); // track when effects start
+        let mut transitions = TransitionTracker::default();
+        let mut section_detector = SectionDetector::new();
+        let mut section_start = 0.0;
+        let mut tempo_tracker = TempoStabilityTracker::default();
 
         info!("Starting comprehensive audio analysis...");
 
@@ -280,6 +616,7 @@ impl AudioAnalysisEngine {
 
             // Get raw features from analyzer
             let raw_features = self.analyzer.analyze_chunk(chunk).await?;
+            self.range_observer.observe(&raw_features);
             let normalized_features = self.normalizer.normalize(&raw_features);
             let audio_frame = self.convert_to_audio_frame(&normalized_features);
 
@@ -292,36 +629,94 @@ impl AudioAnalysisEngine {
             // Collect statistics
             self.collect_frame_statistics(&audio_frame, timestamp, &effect_weights);
 
-            // Track effect activations
+            // Track effect activations, transitions and sections
             self.track_effect_activations(timestamp, &effect_weights, &mut active_effects);
+            self.effect_transitions.extend(transitions.update(timestamp, &effect_weights));
+            if section_detector.update(1.0 / self.frame_rate, audio_frame.volume) && timestamp - section_start >= SECTION_MIN_SECONDS {
+                self.sections.push(Section { start_time: section_start, end_time: timestamp });
+                section_start = timestamp;
+            }
+            let tempo_stability = tempo_tracker.update(timestamp, audio_frame.beat_detected);
+            if timestamp >= self.tempo_stability.len() as f32 * TEMPO_STABILITY_STEP_SECONDS {
+                self.tempo_stability.push(TempoStabilityPoint { timestamp, stability: tempo_stability, bpm: tempo_tracker.bpm });
+            }
 
             // Collect frame data if requested
-            if include_frames {
+            if let Some(spool) = self.frame_spool.as_mut() {
                 let dominant_effect = effect_weights.iter()
-                    .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                    .max_by(|(_, a), (_, b)| a.total_cmp(b))
                     .filter(|(_, weight)| **weight > 0.1)
                     .map(|(name, _)| name.clone());
 
-                self.frame_data.push(FrameData {
+                spool.push(&FrameData {
                     timestamp,
                     audio_frame: SerializableAudioFrame::from(&audio_frame),
                     effect_weights: effect_weights.clone(),
                     dominant_effect,
-                });
+                    tempo_stability,
+                })?;
             }
 
             frame_count += 1;
             sample_pos += self.chunk_size;
 
             if frame_count % 1000 == 0 {
-                info!("Processed {} frames ({:.1}s of {:.1}s)", frame_count, timestamp, total_duration);
+                logging::progress(
+                    &format!("Processed {} frames ({:.1}s of {:.1}s)", frame_count, timestamp, total_duration),
+                    serde_json::json!({
+                        "frames": frame_count,
+                        "position_seconds": timestamp,
+                        "duration_seconds": total_duration,
+                    }),
+                );
             }
         }
 
         info!("Analysis complete. Processed {} frames ({:.2}s)", frame_count, total_duration);
+        if frame_count > 0 {
+            self.sections.push(Section { start_time: section_start, end_time: frame_count as f32 / self.frame_rate });
+        }
+
+        self.key_estimate = estimate_key(&audio_buffer, self.sample_rate);
 
         // Generate comprehensive results
-        self.generate_results(file_path, frame_count, include_frames)
+        let results = self.generate_results(file_path, frame_count, include_frames)?;
+        Ok((results, self.frame_spool.take()))
+    }
+
+    /// Normalization parameters fitted to the raw feature ranges of the analyzed file
+    fn suggested_normalization(&self) -> NormalizationParameters {
+        self.range_observer.suggested_parameters()
+    }
+
+    /// Run a file through the analyzer only, feeding the range observer, and return its raw
+    /// features (the calibration wizard's first pass)
+    async fn observe_file(&mut self, file_path: &str) -> Result<Vec<RawAudioFeatures>> {
+        info!("Loading audio file: {}", file_path);
+        self.playback.load_file(file_path).await?;
+        self.analyzer.reset();
+
+        let audio_buffer = self.playback.get_full_audio_buffer().clone();
+        let mut raw_frames = Vec::with_capacity(audio_buffer.len() / self.chunk_size);
+        for chunk in audio_buffer.chunks_exact(self.chunk_size) {
+            let raw_features = self.analyzer.analyze_chunk(chunk).await?;
+            self.range_observer.observe(&raw_features);
+            raw_frames.push(raw_features);
+        }
+        Ok(raw_frames)
+    }
+
+    /// Forget the statistics of previous files, so the next `analyze_file` reports on its own
+    fn clear_statistics(&mut self) {
+        self.analyzer.reset();
+        self.normalizer.reset();
+        self.feature_collectors.clear();
+        self.beat_events.clear();
+        self.effect_activations.clear();
+        self.effect_transitions.clear();
+        self.sections.clear();
+        self.tempo_stability.clear();
+        self.key_estimate = None;
     }
 
     fn collect_frame_statistics(&mut self, frame: &AudioFrame, timestamp: f32, _effect_weights: &HashMap<String, f32>) {
@@ -396,6 +791,7 @@ impl AudioAnalysisEngine {
         AudioFrame {
             sample_rate: self.sample_rate,
             spectrum: Vec::new(), // Not used in current analysis
+            mel_bands: Vec::new(),
             time_domain: Vec::new(), // Not used in current analysis
             frequency_bands: FrequencyBands {
                 sub_bass: normalized.sub_bass,
@@ -473,22 +869,29 @@ impl AudioAnalysisEngine {
         let insights = self.generate_insights(&frequency_band_stats, &spectral_feature_stats, &temporal_feature_stats);
 
         Ok(AnalysisResults {
+            schema: SchemaHeader::current(),
             file_info,
             analysis_config,
             frequency_band_stats,
             spectral_feature_stats,
             temporal_feature_stats,
             beat_stats,
+            raw_feature_peaks: self.range_observer.peaks(),
             effect_activation_summary,
-            effect_transitions: Vec::new(), // TODO: Implement transition analysis
+            effect_transitions: self.effect_transitions.clone(),
+            sections: self.sections.clone(),
+            tempo_stability: self.tempo_stability.clone(),
             beat_events: self.beat_events.clone(),
             effect_activations: self.effect_activations.clone(),
-            frame_data: if include_frames { Some(self.frame_data.clone()) } else { None },
+            frame_data: None, // Streamed from the frame spool by `write_results`
             insights,
         })
     }
 
     fn calculate_stats(&self, data: &[f32]) -> AudioFeatureStats {
+        // A degenerate frame (NaN/inf feature) shouldn't poison the whole run's statistics
+        let data: Vec<f32> = data.iter().copied().filter(|x| x.is_finite()).collect();
+        let data = data.as_slice();
         if data.is_empty() {
             return AudioFeatureStats {
                 min: 0.0, max: 0.0, mean: 0.0, median: 0.0, std_dev: 0.0,
@@ -497,7 +900,7 @@ impl AudioAnalysisEngine {
         }
 
         let mut sorted_data = data.to_vec();
-        sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted_data.sort_by(f32::total_cmp);
 
         let min = sorted_data[0];
         let max = sorted_data[sorted_data.len() - 1];
@@ -538,30 +941,16 @@ impl AudioAnalysisEngine {
             };
         }
 
-        let bpms: Vec<f32> = self.beat_events.iter().map(|b| b.estimated_bpm).collect();
-        let strengths: Vec<f32> = self.beat_events.iter().map(|b| b.strength).collect();
+        let bpms: Vec<f32> = self.beat_events.iter().map(|b| b.estimated_bpm).filter(|bpm| bpm.is_finite()).collect();
+        let strengths: Vec<f32> = self.beat_events.iter().map(|b| b.strength).filter(|s| s.is_finite()).collect();
 
-        let average_bpm = bpms.iter().sum::<f32>() / bpms.len() as f32;
+        let average_bpm = bpms.iter().sum::<f32>() / bpms.len().max(1) as f32;
         let bpm_variance = bpms.iter()
             .map(|&bpm| (bpm - average_bpm).powi(2))
-            .sum::<f32>() / bpms.len() as f32;
+            .sum::<f32>() / bpms.len().max(1) as f32;
 
         // Calculate beat consistency (how regular the timing is)
-        let mut intervals = Vec::new();
-        for i in 1..self.beat_events.len() {
-            intervals.push(self.beat_events[i].timestamp - self.beat_events[i-1].timestamp);
-        }
-
-        let beat_consistency = if intervals.len() > 1 {
-            let mean_interval = intervals.iter().sum::<f32>() / intervals.len() as f32;
-            let interval_variance = intervals.iter()
-                .map(|&interval| (interval - mean_interval).powi(2))
-                .sum::<f32>() / intervals.len() as f32;
-            // Convert variance to consistency score (0-1, higher is more consistent)
-            1.0 / (1.0 + interval_variance)
-        } else {
-            0.0
-        };
+        let beat_consistency = recommendation::beat_consistency(self.beat_events.iter().map(|beat| beat.timestamp));
 
         BeatStats {
             total_beats: self.beat_events.len(),
@@ -569,7 +958,7 @@ impl AudioAnalysisEngine {
             bpm_variance,
             beat_consistency,
             strongest_beat: strengths.iter().fold(0.0f32, |a, &b| a.max(b)),
-            weakest_beat: strengths.iter().fold(f32::INFINITY, |a, &b| a.min(b)),
+            weakest_beat: if strengths.is_empty() { 0.0 } else { strengths.iter().fold(f32::INFINITY, |a, &b| a.min(b)) },
         }
     }
 
@@ -629,7 +1018,7 @@ impl AudioAnalysisEngine {
         // Calculate music complexity
         let spectral_flux_var = temporal_stats.get("spectral_flux").map(|s| s.std_dev).unwrap_or(0.0);
         let pitch_confidence_mean = spectral_stats.get("pitch_confidence").map(|s| s.mean).unwrap_or(0.0);
-        let music_complexity = (spectral_flux_var * 2.0 + (1.0 - pitch_confidence_mean)).clamp(0.0, 1.0);
+        let music_complexity = recommendation::music_complexity(spectral_flux_var, pitch_confidence_mean);
 
         // Calculate rhythmic consistency from beat stats
         let beat_stats = self.calculate_beat_stats();
@@ -638,21 +1027,14 @@ impl AudioAnalysisEngine {
         // Harmonic content
         let harmonic_content = pitch_confidence_mean;
 
-        // Recommend effects based on analysis
-        let mut recommended_effects = Vec::new();
-        if bass_energy > 0.3 {
-            recommended_effects.push("llama_plasma".to_string());
-        }
-        if pitch_confidence_mean > 0.6 {
-            recommended_effects.push("geometric_kaleidoscope".to_string());
-            recommended_effects.push("parametric_waves".to_string());
-        }
-        if music_complexity > 0.5 {
-            recommended_effects.push("fractal_madness".to_string());
-        }
-        if rhythmic_consistency > 0.7 {
-            recommended_effects.push("particle_swarm".to_string());
-        }
+        // Recommend effects based on analysis (the same rules `--live-recommend` applies live)
+        let character = TrackCharacter {
+            bass_energy,
+            pitch_confidence: pitch_confidence_mean,
+            music_complexity,
+            rhythmic_consistency,
+        };
+        let recommended_effects = character.recommended_effects().into_iter().map(str::to_string).collect();
 
         // Suggest optimal smoothing factor based on dynamics
         let dynamic_range_mean = temporal_stats.get("dynamic_range").map(|s| s.mean).unwrap_or(0.5);
@@ -673,20 +1055,32 @@ impl AudioAnalysisEngine {
             recommended_effects,
             optimal_smoothing_factor,
             suggested_thresholds,
+            estimated_key: self.key_estimate.map(|key| key.name()),
+            key_confidence: self.key_estimate.map_or(0.0, |key| key.confidence),
         }
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
     let args = Args::parse();
+    logging::init(args.quiet, args.json_logs);
 
     info!("🎵 Starting Comprehensive Audio Analysis");
     info!("File: {}", args.audio_file);
     info!("Output: {}", args.output);
     info!("Frame-by-frame logging: {}", args.frame_by_frame);
     info!("Chunk size: {} samples", args.chunk_size);
+    logging::event("start", serde_json::json!({
+        "tool": "audio-analyzer",
+        "input": args.audio_file,
+        "output": args.output,
+        "frame_by_frame": args.frame_by_frame,
+        "apply_suggestions": args.apply_suggestions,
+        "calibrate": args.calibrate,
+        "cue_list": args.cue_list,
+        "chunk_size": args.chunk_size,
+    }));
 
     // Determine sample rate
     let sample_rate = if args.sample_rate > 0 {
@@ -697,15 +1091,29 @@ async fn main() -> Result<()> {
 
     let mut engine = AudioAnalysisEngine::new(args.chunk_size, sample_rate).await?;
 
+    if let Some(path) = &args.calibrate {
+        let files: Vec<String> = std::iter::once(args.audio_file.clone()).chain(args.more_files.iter().cloned()).collect();
+        return calibrate(&mut engine, &files, path).await;
+    }
+    if !args.more_files.is_empty() {
+        return Err(anyhow::anyhow!("Only --calibrate takes more than one file (got {:?})", args.more_files));
+    }
+
     info!("🔍 Analyzing audio file...");
-    let results = engine.analyze_file(&args.audio_file, args.frame_by_frame).await?;
+    let output_path = Path::new(&args.output);
+    let frame_spool = if args.frame_by_frame {
+        let mut spool_path = output_path.as_os_str().to_owned();
+        spool_path.push(".frames.tmp");
+        Some(FrameSpool::create(PathBuf::from(spool_path))?)
+    } else {
+        None
+    };
+    let (results, frame_spool) = engine.analyze_file(&args.audio_file, frame_spool).await?;
 
     info!("📊 Generating analysis report...");
 
     // Write results to JSON file
-    let json_output = serde_json::to_string_pretty(&results)?;
-    let mut file = File::create(&args.output)?;
-    file.write_all(json_output.as_bytes())?;
+    write_results(output_path, &results, frame_spool)?;
 
     // Print summary to console
     info!("✅ Analysis Complete!");
@@ -717,11 +1125,319 @@ async fn main() -> Result<()> {
     info!("  Dominant frequency: {}", results.insights.dominant_frequency_range);
     info!("  Music complexity: {:.2}", results.insights.music_complexity);
     info!("  Rhythmic consistency: {:.2}", results.insights.rhythmic_consistency);
+    if !results.tempo_stability.is_empty() {
+        info!("  Tempo stability:      {}", stability_sparkline(&results.tempo_stability, 60));
+    }
     info!("  Harmonic content: {:.2}", results.insights.harmonic_content);
+    match &results.insights.estimated_key {
+        Some(key) => info!("  Estimated key: {} (confidence {:.2})", key, results.insights.key_confidence),
+        None => info!("  Estimated key: unknown"),
+    }
     info!("  Recommended effects: {:?}", results.insights.recommended_effects);
     info!("  Optimal smoothing: {:.2}", results.insights.optimal_smoothing_factor);
 
     info!("📄 Detailed results written to: {}", args.output);
 
+    if let Some(path) = &args.cue_list {
+        let cues = build_cue_list(&results);
+        cues.save(path)?;
+        info!("🎬 {} cues ({} sections, {} effect transitions) written to: {}",
+              cues.cues().len(), results.sections.len(), results.effect_transitions.len(), path);
+    }
+
+    if let Some(path) = &args.tempo_csv {
+        write_tempo_csv(path, &results.tempo_stability)?;
+        info!("🥁 Tempo stability ({} points, every {}s) written to: {}",
+              results.tempo_stability.len(), TEMPO_STABILITY_STEP_SECONDS, path);
+    }
+
+    if let Some(path) = &args.apply_suggestions {
+        let parameters = engine.suggested_normalization();
+        parameters.save(path)?;
+        info!("📏 Fitted normalization parameters written to: {} (bass_max {:.3e}, volume_max {:.3e}, beat_strength_max {:.3e})",
+              path, parameters.bass_max, parameters.volume_max, parameters.beat_strength_max);
+        info!("   Use with: audio-test --normalization {} <file>", path);
+    }
+    logging::event("complete", serde_json::json!({
+        "tool": "audio-analyzer",
+        "output": args.output,
+        "frames": results.file_info.total_frames,
+        "duration_seconds": results.file_info.duration_seconds,
+        "total_beats": results.beat_stats.total_beats,
+        "average_bpm": results.beat_stats.average_bpm,
+        "dominant_frequency_range": results.insights.dominant_frequency_range,
+        "music_complexity": results.insights.music_complexity,
+        "estimated_key": results.insights.estimated_key,
+        "key_confidence": results.insights.key_confidence,
+    }));
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Cues for VJ software: one per section start and one per effect transition
+fn build_cue_list(results: &AnalysisResults) -> CueList {
+    let mut cues = CueList::new(&results.file_info.filename, results.file_info.duration_seconds);
+    for (index, section) in results.sections.iter().enumerate() {
+        cues.push(section.start_time, CueKind::Section, &format!("Section {}", index + 1));
+    }
+    for transition in &results.effect_transitions {
+        cues.push(transition.timestamp, CueKind::Effect, &transition.to_effect);
+    }
+    cues
+}
+
+type FeatureGetter = fn(&NormalizedAudioFeatures) -> f32;
+
+/// Features shown in the calibration wizard's before/after table
+const CALIBRATION_FEATURES: [(&str, FeatureGetter); 8] = [
+    ("sub_bass", |f| f.sub_bass),
+    ("bass", |f| f.bass),
+    ("mid", |f| f.mid),
+    ("treble", |f| f.treble),
+    ("presence", |f| f.presence),
+    ("volume", |f| f.volume),
+    ("onset_strength", |f| f.onset_strength),
+    ("beat_strength", |f| f.beat_strength),
+];
+
+/// Mean, 95th percentile and share of frames pinned at 1.0, for one feature over a run
+fn calibration_summary(values: &mut [f32]) -> (f32, f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    values.sort_by(f32::total_cmp);
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    let p95 = values[((values.len() - 1) as f32 * 0.95) as usize];
+    let pinned = values.iter().filter(|&&value| value >= 0.999).count() as f32 / values.len() as f32;
+    (mean, p95, pinned)
+}
+
+/// The calibration wizard: learn raw feature ranges from the tracks, write the fitted
+/// parameters to `output`, then re-analyze with them for before/after figures and thresholds
+async fn calibrate(engine: &mut AudioAnalysisEngine, files: &[String], output: &str) -> Result<()> {
+    info!("🎚️ Calibrating on {} track(s)", files.len());
+
+    let mut raw_frames = Vec::new();
+    for file in files {
+        raw_frames.extend(engine.observe_file(file).await?);
+    }
+    if raw_frames.is_empty() {
+        return Err(anyhow::anyhow!("Calibration tracks are shorter than one {}-sample chunk", engine.chunk_size));
+    }
+
+    let parameters = engine.suggested_normalization();
+    parameters.save(output)?;
+    info!("📏 Fitted {} frames; normalization parameters written to: {}", raw_frames.len(), output);
+
+    // Before/after: what the visuals would have received with the defaults vs the fitted ranges
+    let mut before = FeatureNormalizer::new();
+    let mut after = FeatureNormalizer::with_parameters(parameters.clone());
+    let before_frames: Vec<NormalizedAudioFeatures> = raw_frames.iter().map(|raw| before.normalize(raw)).collect();
+    let after_frames: Vec<NormalizedAudioFeatures> = raw_frames.iter().map(|raw| after.normalize(raw)).collect();
+
+    info!("📊 Before → after (mean / 95th percentile / frames pinned at 1.0):");
+    let mut comparison = serde_json::Map::new();
+    for (name, feature) in CALIBRATION_FEATURES {
+        let (mean_before, p95_before, pinned_before) = calibration_summary(&mut before_frames.iter().map(feature).collect::<Vec<_>>());
+        let (mean_after, p95_after, pinned_after) = calibration_summary(&mut after_frames.iter().map(feature).collect::<Vec<_>>());
+        info!("  {:<15} {:.2} → {:.2}   {:.2} → {:.2}   {:>3.0}% → {:>3.0}%",
+              name, mean_before, mean_after, p95_before, p95_after, pinned_before * 100.0, pinned_after * 100.0);
+        comparison.insert(name.to_string(), serde_json::json!({
+            "before": { "mean": mean_before, "p95": p95_before, "pinned": pinned_before },
+            "after": { "mean": mean_after, "p95": p95_after, "pinned": pinned_after },
+        }));
+    }
+
+    // Thresholds come from each track's insights under the fitted parameters, averaged
+    engine.normalizer = FeatureNormalizer::with_parameters(parameters);
+    let mut thresholds: HashMap<String, f32> = HashMap::new();
+    for file in files {
+        engine.clear_statistics();
+        let (results, _) = engine.analyze_file(file, None).await?;
+        info!("  {}: {}, recommended effects {:?}, smoothing {:.2}", file, results.insights.dominant_frequency_range,
+              results.insights.recommended_effects, results.insights.optimal_smoothing_factor);
+        for (name, value) in results.insights.suggested_thresholds {
+            *thresholds.entry(name).or_insert(0.0) += value / files.len() as f32;
+        }
+    }
+
+    let mut names: Vec<&String> = thresholds.keys().collect();
+    names.sort();
+    info!("🎯 Suggested thresholds:");
+    for name in names {
+        info!("  {}: {:.3}", name, thresholds[name]);
+    }
+    info!("   Use with: audio-test --normalization {} <file>", output);
+    if let Some(beat_threshold) = thresholds.get("beat_threshold") {
+        info!("             prescan-tool --normalization {} --beat-threshold {:.2} <file>", output, beat_threshold.clamp(0.0, 1.0));
+    }
+    logging::event("calibrated", serde_json::json!({
+        "tool": "audio-analyzer",
+        "files": files,
+        "output": output,
+        "frames": raw_frames.len(),
+        "comparison": comparison,
+        "suggested_thresholds": thresholds,
+    }));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_header_serialization() {
+        let json = serde_json::to_value(SchemaHeader::current()).unwrap();
+        assert_eq!(json["schema_version"], ANALYSIS_SCHEMA_VERSION);
+        assert_eq!(json["arrvee_version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_schema_version_mismatch() {
+        assert_eq!(SchemaHeader::current().mismatch(), None);
+
+        // Pre-versioning files have neither field
+        let unversioned: SchemaHeader = serde_json::from_str(r#"{"file_info": {}}"#).unwrap();
+        assert_eq!(unversioned.schema_version, 0);
+        assert!(unversioned.mismatch().is_some());
+
+        let newer = SchemaHeader { schema_version: ANALYSIS_SCHEMA_VERSION + 1, ..SchemaHeader::current() };
+        assert!(newer.mismatch().unwrap().contains("newer"));
+    }
+
+    fn sample_results() -> AnalysisResults {
+        AnalysisResults {
+            schema: SchemaHeader::current(),
+            file_info: FileInfo { filename: "test.wav".to_string(), duration_seconds: 1.0, sample_rate: 44100.0, total_samples: 1536, total_frames: 3 },
+            analysis_config: AnalysisConfig { chunk_size: 512, frame_rate: 86.1, include_frame_data: true },
+            frequency_band_stats: HashMap::new(),
+            spectral_feature_stats: HashMap::new(),
+            temporal_feature_stats: HashMap::new(),
+            beat_stats: BeatStats { total_beats: 0, average_bpm: 0.0, bpm_variance: 0.0, beat_consistency: 0.0, strongest_beat: 0.0, weakest_beat: 0.0 },
+            raw_feature_peaks: HashMap::new(),
+            effect_activation_summary: HashMap::new(),
+            effect_transitions: Vec::new(),
+            sections: Vec::new(),
+            tempo_stability: Vec::new(),
+            beat_events: Vec::new(),
+            effect_activations: Vec::new(),
+            frame_data: None,
+            insights: AnalysisInsights {
+                dominant_frequency_range: "Mid-Focused".to_string(), music_complexity: 0.0, rhythmic_consistency: 0.0,
+                harmonic_content: 0.0, recommended_effects: Vec::new(), optimal_smoothing_factor: 0.3, suggested_thresholds: HashMap::new(),
+                estimated_key: Some("A minor".to_string()), key_confidence: 0.72,
+            },
+        }
+    }
+
+    #[test]
+    fn test_spooled_frames_round_trip() {
+        let results = sample_results();
+        let dir = std::env::temp_dir();
+        let output = dir.join(format!("arrvee_spool_test_{}.json", std::process::id()));
+        let spool_path = dir.join(format!("arrvee_spool_test_{}.frames.tmp", std::process::id()));
+        let mut spool = FrameSpool::create(spool_path.clone()).unwrap();
+        for index in 0..3 {
+            let frame = AudioFrame::default();
+            spool.push(&FrameData {
+                timestamp: index as f32 * 0.5,
+                audio_frame: SerializableAudioFrame::from(&frame),
+                effect_weights: HashMap::new(),
+                dominant_effect: None,
+                tempo_stability: 0.0,
+            }).unwrap();
+        }
+        write_results(&output, &results, Some(spool)).unwrap();
+        assert!(!spool_path.exists());
+
+        let loaded = load_analysis_results(output.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&output).unwrap();
+        let frames = loaded.frame_data.expect("frames spliced into the results");
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].timestamp, 1.0);
+        assert_eq!(loaded.schema, SchemaHeader::current());
+    }
+
+    #[test]
+    fn test_failed_export_removes_the_spool() {
+        let spool_path = std::env::temp_dir().join(format!("arrvee_spool_fail_test_{}.frames.tmp", std::process::id()));
+        let mut spool = FrameSpool::create(spool_path.clone()).unwrap();
+        spool.writer.write_all(b"{}").unwrap();
+        let output = spool_path.with_extension("missing").join("results.json");
+        assert!(write_results(&output, &sample_results(), Some(spool)).is_err());
+        assert!(!spool_path.exists());
+    }
+
+    #[test]
+    fn test_transitions_ignore_brief_lead_changes() {
+        let weights = |plasma: f32, tunnel: f32| HashMap::from([
+            ("llama_plasma".to_string(), plasma),
+            ("psychedelic_tunnel".to_string(), tunnel),
+        ]);
+        let mut tracker = TransitionTracker::default();
+        let mut transitions = Vec::new();
+        let step = 0.1;
+        for frame in 0..40 {
+            let time = frame as f32 * step;
+            let frame_weights = match frame {
+                0..=9 => weights(0.8, 0.2),
+                10..=11 => weights(0.4, 0.6), // 0.2s blip: too short to count
+                12..=19 => weights(0.8, 0.2),
+                _ => weights(0.3, 0.7),       // Tunnel takes over for good at 2.0s
+            };
+            transitions.extend(tracker.update(time, &frame_weights));
+        }
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!((transitions[0].from_effect.as_deref(), transitions[0].to_effect.as_str()), (None, "llama_plasma"));
+        assert_eq!(transitions[1].from_effect.as_deref(), Some("llama_plasma"));
+        assert_eq!(transitions[1].to_effect, "psychedelic_tunnel");
+        assert!((transitions[1].timestamp - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_tempo_stability_follows_the_groove() {
+        // 8 bars of 120 BPM on 86 fps frames, then beats that drift between 100 and 140 BPM
+        let frame = 512.0 / 44100.0;
+        let mut beat_times: Vec<f32> = (0..32).map(|beat| beat as f32 * 0.5).collect();
+        beat_times.extend((0..16).map(|beat| 16.0 + beat as f32 * 0.5 + if beat % 2 == 0 { 0.08 } else { -0.08 }));
+
+        let mut tracker = TempoStabilityTracker::default();
+        let (mut steady, mut loose) = (0.0, 1.0f32);
+        let mut next_beat = 0;
+        for frame_index in 0..(24.0 / frame) as usize {
+            let timestamp = frame_index as f32 * frame;
+            let beat = next_beat < beat_times.len() && timestamp >= beat_times[next_beat];
+            next_beat += beat as usize;
+            let stability = tracker.update(timestamp, beat);
+            if (14.0..16.0).contains(&timestamp) {
+                steady = stability;
+            } else if timestamp > 20.0 {
+                loose = loose.min(stability);
+            }
+        }
+
+        assert!(steady > 0.8, "steady groove scored {:.2}", steady);
+        assert!(loose < 0.5, "loose groove scored {:.2}", loose);
+        assert!((tracker.bpm - 120.0).abs() < 10.0);
+
+        let series: Vec<TempoStabilityPoint> = [0.0, 0.5, 1.0].iter()
+            .map(|&stability| TempoStabilityPoint { timestamp: 0.0, stability, bpm: 120.0 })
+            .collect();
+        assert_eq!(stability_sparkline(&series, 3), "▁▅█");
+    }
+
+    #[test]
+    fn test_calibration_summary() {
+        // 100 frames: 90 quiet, 10 pinned at the top of the range
+        let mut values: Vec<f32> = (0..100).map(|i| if i < 90 { 0.1 } else { 1.0 }).collect();
+        let (mean, p95, pinned) = calibration_summary(&mut values);
+        assert!((mean - 0.19).abs() < 1e-5);
+        assert_eq!(p95, 1.0);
+        assert!((pinned - 0.1).abs() < 1e-6);
+
+        assert_eq!(calibration_summary(&mut []), (0.0, 0.0, 0.0));
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use log::info;
 use std::sync::Arc;
+use std::time::Instant;
 use winit::{
     event::{ElementState, Event, WindowEvent},
     event_loop::EventLoop,
@@ -11,37 +12,62 @@ use winit::{
 
 // Enhanced terminal-based debug interface (egui integration would go here for future GUI overlay)
 
-mod graphics;
-mod audio;
-mod effects;
+use arrvee::{audio, effects, graphics, ui};
+use arrvee::control::ControlChannel;
 
 use graphics::GraphicsEngine;
+use ui::now_playing::{NowPlayingOverlay, OverlayPosition};
+use ui::band_grid::BandGridOverlay;
+use ui::level_meter::{channel_label, LevelMeterOverlay};
+use ui::refresh_throttle::RefreshThrottle;
+use ui::smoothing_scope::SmoothingScopeOverlay;
+use graphics::smoothing_scope::ScopeFeature;
+use effects::program::EffectProgram;
 use audio::{AudioPlayback, AudioFrame};
+use audio::test_signal::{TestSignal, TestSignalConfig};
+use audio::agc::AgcConfig;
+use audio::envelope::SmoothingConfig;
+use audio::solo_band::SoloBand;
+use audio::feature_normalizer::NormalizationParameters;
 
 struct DebugOverlay {
     show_overlay: bool,
     volume_control: f32,
-    frame_count: u32,
+    refresh: RefreshThrottle, // Redraws at `--debug-hz`
 }
 
 impl DebugOverlay {
-    fn new() -> Self {
+    fn new(refresh_hz: f32) -> Self {
         Self {
             show_overlay: true,
             volume_control: 0.1, // 10% default volume
-            frame_count: 0,
+            refresh: RefreshThrottle::new(refresh_hz),
         }
     }
 
+    /// 20-cell meter over -60..0 dBFS: filled to the RMS level, with a marker at the held peak
+    fn level_bar(rms_dbfs: f32, peak_dbfs: f32) -> String {
+        let cells = |dbfs: f32| (((dbfs + 60.0) / 60.0).clamp(0.0, 1.0) * 20.0) as usize;
+        let (filled, peak) = (cells(rms_dbfs), cells(peak_dbfs).min(19));
+        (0..20)
+            .map(|cell| if cell < filled { '█' } else if cell == peak && peak_dbfs > -60.0 { '|' } else { '·' })
+            .collect()
+    }
+
+    /// One cell per mel band, lowest first, as block characters rising with the level
+    fn mel_row(mel_bands: &[f32]) -> String {
+        const LEVELS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        mel_bands.iter()
+            .map(|level| LEVELS[(level.clamp(0.0, 1.0) * 8.0).round() as usize])
+            .collect()
+    }
+
     fn render_debug_info(&mut self, audio_frame: &AudioFrame, graphics_engine: &graphics::GraphicsEngine, audio_playback: &AudioPlayback) {
         if !self.show_overlay {
             return;
         }
 
-        self.frame_count += 1;
-
-        // Only update display every 30 frames (roughly twice per second) to reduce spam
-        if self.frame_count % 30 != 0 {
+        if !self.refresh.ready(Instant::now()) {
             return;
         }
 
@@ -69,6 +95,12 @@ impl DebugOverlay {
                  audio_frame.frequency_bands.presence,
                  "█".repeat((audio_frame.frequency_bands.presence * 20.0) as usize));
 
+        if !audio_frame.mel_bands.is_empty() {
+            println!("║                                                               ║");
+            println!("║ 🎼 MEL SPECTRUM (low → high)                                  ║");
+            println!("║   {}                                    ║", Self::mel_row(&audio_frame.mel_bands));
+        }
+
         println!("║                                                               ║");
         println!("║ 🥁 RHYTHM ANALYSIS                                            ║");
         println!("║   Beat: {:>12} | Strength: {:>6.3} | BPM: {:>6.1}         ║",
@@ -76,6 +108,18 @@ impl DebugOverlay {
                  audio_frame.beat_strength,
                  audio_frame.estimated_bpm);
 
+        println!("║                                                               ║");
+        println!("║ 🔊 LEVELS (dBFS, source channels)                             ║");
+        let channels = audio_playback.levels().channels();
+        for (index, level) in channels.iter().enumerate() {
+            println!("║   {:<2} {} RMS {:>6.1} | Pk {:>6.1} {}        ║",
+                     channel_label(channels.len(), index),
+                     Self::level_bar(level.rms_dbfs, level.held_peak_dbfs),
+                     level.rms_dbfs,
+                     level.held_peak_dbfs,
+                     if level.clipped { "🔴CLIP" } else { "      " });
+        }
+
         println!("║                                                               ║");
         println!("║ 🎚️ VISUAL CONTROLS                                            ║");
         let palette_names = ["Rainbow", "Neon Cyber", "Warm Sunset", "Deep Ocean", "Purple Haze", "Electric Green"];
@@ -96,6 +140,9 @@ impl DebugOverlay {
                  proj_mode, audio_frame.dynamic_range);
         println!("║   Sensitivity: {:>5.2}x | Analysis Quality: Enhanced       ║",
                  sensitivity);
+        if let Some(gain) = audio_playback.agc_gain() {
+            println!("║   AGC Gain: {:>6.2}x                                        ║", gain);
+        }
 
         println!("║                                                               ║");
         println!("║ 🌈 ACTIVE EFFECTS                                             ║");
@@ -110,6 +157,7 @@ impl DebugOverlay {
                     "fractal_madness" => "Fractal Madness",
                     "spectralizer_bars" => "Spectralizer",
                     "parametric_waves" => "Parametric Waves",
+                    "flux_debug" => "Flux Debug",
                     _ => effect
                 };
                 println!("║   {:<15}: {:>6.3} ■{:<15}                    ║",
@@ -128,8 +176,11 @@ impl DebugOverlay {
         println!("║                                                               ║");
         println!("║ 🎮 CONTROLS                                                   ║");
         println!("║   P: Palette | [/]: Smoothing | Q/W/E/R/T: Projection       ║");
-        println!("║   1-7: Effects | 0: Auto | D: Toggle Debug | Space: Pause   ║");
-        println!("║   +/-: Volume | ;/': Sensitivity | Tab: Cycle Modes        ║");
+        println!("║   1-8: Effects | 0: Auto | D: Toggle Debug | Space: Pause   ║");
+        println!("║   F: Freeze Visuals | F11: Fullscreen                       ║");
+        println!("║   B: Fade to Black/In | PgUp/PgDn: Master Intensity         ║");
+        println!("║   U: Dump Uniforms to JSON (reload with --load-uniforms)    ║");
+        println!("║   +/-: Volume | ,/.: Sensitivity | Tab: Cycle Modes        ║");
         println!("╚═══════════════════════════════════════════════════════════════╝");
     }
 
@@ -143,6 +194,12 @@ impl DebugOverlay {
     }
 }
 
+/// Attack/release line for the smoothing scope's traced feature
+fn scope_detail(audio_playback: &AudioPlayback, feature: ScopeFeature) -> String {
+    let times = feature.smoothing(&audio_playback.envelope_config().smoothing);
+    format!("attack {:.3}s · release {:.3}s (G/H)", times.attack, times.release)
+}
+
 #[derive(Parser)]
 #[command(name = "arrvee-audio-test")]
 #[command(about = "Arrvee Music Visualizer - Audio File Test")]
@@ -154,35 +211,441 @@ struct Args {
     /// Show developer overlay with analysis stats
     #[arg(long, short)]
     debug: bool,
+
+    /// Developer overlay refreshes per second (independent of the render frame rate)
+    #[arg(long, value_name = "HZ", default_value_t = 2.0, value_parser = ui::refresh_throttle::parse_refresh_hz)]
+    debug_hz: f32,
+
+    /// Synthesize a calibration signal instead of loading a file: sine, sweep, noise, click-track
+    #[arg(long)]
+    test_signal: Option<String>,
+
+    /// Visualize the default input device (microphone, line-in or a loopback device) instead
+    /// of a file
+    #[arg(long, conflicts_with = "test_signal")]
+    live_input: bool,
+
+    /// Test signal frequency in Hz (sine tone / click tone)
+    #[arg(long, default_value = "440.0")]
+    frequency: f32,
+
+    /// Test signal tempo in BPM (click track)
+    #[arg(long, default_value = "120.0")]
+    bpm: f32,
+
+    /// Test signal length in seconds
+    #[arg(long, default_value = "30.0")]
+    duration: f32,
+
+    /// Graphics backend: vulkan, metal, dx12, gl, or auto
+    #[arg(long, default_value = "auto")]
+    backend: String,
+
+    /// Playback speed (0.25-4.0; e.g. 0.5 for half-speed ambient; pitch follows)
+    #[arg(long, default_value = "1.0")]
+    speed: f32,
+
+    /// Open with the audio paused (visuals idle) until Space, e.g. while a screen recorder gets ready
+    #[arg(long, overrides_with = "autoplay")]
+    paused: bool,
+
+    /// Start playing as soon as the window opens (the default; overrides an earlier `--paused`)
+    #[arg(long, overrides_with = "paused")]
+    autoplay: bool,
+
+    /// Seek this many seconds into the track before starting
+    #[arg(long, value_name = "SECONDS")]
+    start_at: Option<f32>,
+
+    /// Start in borderless fullscreen, optionally on a specific monitor (`--fullscreen=1`)
+    #[arg(long, value_name = "MONITOR", num_args = 0..=1, require_equals = true)]
+    fullscreen: Option<Option<usize>>,
+
+    /// Show a "now playing" caption ("Artist — Title" from the file's tags, else the file name) in a
+    /// corner: --now-playing=top-left, top-right, bottom-left (default) or bottom-right; N toggles it
+    #[arg(long, value_name = "POSITION", num_args = 0..=1, default_missing_value = "bottom-left", require_equals = true)]
+    now_playing: Option<String>,
+
+    /// Window width in pixels (at least 320; default is the tool's usual size)
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Window height in pixels (at least 240)
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Window title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Seconds for a full master fade in/out (B to fade to black and back)
+    #[arg(long, default_value = "2.0")]
+    fade_time: f32,
+
+    /// Render a fixed uniforms dump (written with U) instead of audio-driven values
+    #[arg(long, value_name = "FILE")]
+    load_uniforms: Option<String>,
+
+    /// Automatic gain control: scale the analysis input towards a target level (for mic/line sources)
+    #[arg(long)]
+    agc: bool,
+
+    /// Analyze at the position the audio output has actually reached (stays locked through pauses and underruns)
+    #[arg(long)]
+    follow_playhead: bool,
+
+    /// Average bands in dB instead of linear magnitude, so quiet treble registers (CPU analyzer)
+    #[arg(long)]
+    log_magnitude: bool,
+
+    /// Compute a 24-band mel spectrum for the spectralizer and the debug overlay (CPU analyzer)
+    #[arg(long)]
+    mel_bands: bool,
+
+    /// JSON file with per-band/beat/spectral attack and release times (seconds)
+    #[arg(long, value_name = "FILE")]
+    smoothing_config: Option<String>,
+
+    /// Show the smoothing scope (raw vs. smoothed traces) for a feature: --scope=bass (default),
+    /// sub-bass, mid, treble, presence, beat, volume, flux or onset; K toggles it, J cycles the feature
+    #[arg(long, value_name = "FEATURE", num_args = 0..=1, default_missing_value = "bass", require_equals = true)]
+    scope: Option<String>,
+
+    /// Number of log-spaced spectrum bars shown by the spectralizer (1-64)
+    #[arg(long, default_value = "64")]
+    spectrum_bars: usize,
+
+    /// Spectrum points interpolated per spectralizer bar for smooth contours (1 = per-bin bars, up to 16)
+    #[arg(long, default_value = "4")]
+    spectrum_supersampling: usize,
+
+    /// Start in a manual effect instead of auto-blend (e.g. `fractal_madness`)
+    #[arg(long)]
+    effect: Option<String>,
+
+    /// Starting color palette: index 0-5 or name (e.g. `deep-ocean`)
+    #[arg(long)]
+    palette: Option<String>,
+
+    /// Starting projection: auto, sphere, cylinder, torus, flat
+    #[arg(long)]
+    projection: Option<String>,
+
+    /// Leave effects out of auto-blend (comma-separated or repeated, e.g. `particle_swarm`)
+    #[arg(long, value_delimiter = ',')]
+    disable_effect: Vec<String>,
+
+    /// Invert the auto-blend: visuals calm down when the music is intense and come alive in quiet passages
+    #[arg(long)]
+    invert_response: bool,
+
+    /// Multiplier on how fast auto-blend crossfades between effects (0.1 = slow, cinematic blends
+    /// over seconds; 4 = snappy switching)
+    #[arg(long, default_value = "1.0")]
+    transition_speed: f32,
+
+    /// Drive the visuals from one band alone: sub-bass, bass, mid, treble or presence (Y cycles it)
+    #[arg(long, value_name = "BAND")]
+    solo_band: Option<String>,
+
+    /// Accept line commands on stdin (`effect fractal_madness`, `palette 3`, `smoothing 0.5`, `volume 0.2`, ...)
+    #[arg(long)]
+    control_stdin: bool,
+
+    /// Accept the same commands from TCP clients on 127.0.0.1:<PORT>
+    #[arg(long, value_name = "PORT")]
+    control_tcp: Option<u16>,
+
+    /// Pulse the whole screen on each beat at this strength (0-1), over any effect; rate-limited to 3 flashes/s
+    #[arg(long, value_name = "INTENSITY", num_args = 0..=1, default_missing_value = "0.5")]
+    beat_flash: Option<f32>,
+
+    /// Motion trails: share of each frame kept into the next (0 = off, up to 0.98)
+    #[arg(long, value_name = "AMOUNT", default_value_t = 0.0)]
+    feedback: f32,
+
+    /// Fixed number of mirrored kaleidoscope segments (2-32) instead of following pitch and onsets
+    #[arg(long, value_name = "COUNT")]
+    kaleidoscope_segments: Option<u32>,
+
+    /// Blend width of the kaleidoscope seams, as a share of a segment (0 = hard mirror lines)
+    #[arg(long, value_name = "AMOUNT", default_value_t = graphics::engine::DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS)]
+    kaleidoscope_softness: f32,
+
+    /// Scale the kaleidoscope segment count with the tempo (half at 60 BPM, double at 240)
+    #[arg(long)]
+    kaleidoscope_bpm_sync: bool,
+
+    /// Size of the particle swarm's bursts on onsets and beats (0 = no bursts, up to 3)
+    #[arg(long, value_name = "RATE", default_value_t = 1.0)]
+    particle_bursts: f32,
+
+    /// Calmer visuals for motion-sensitive viewers: slower animation and crossfades, a gentler
+    /// camera, no particle bursts, and auto-blend favouring plasma and waves
+    #[arg(long)]
+    reduced_motion: bool,
+
+    /// Keep the camera still in the 3D projections instead of rolling and zooming with the music
+    #[arg(long)]
+    still_camera: bool,
+
+    /// Background behind unlit areas, as hex (e.g. `#102030`)
+    #[arg(long, value_name = "HEX")]
+    bg_color: Option<String>,
+
+    /// See-through background for compositing in OBS or over the desktop (needs a compositor)
+    #[arg(long, conflicts_with = "bg_color")]
+    transparent: bool,
+
+    /// Highlight tonemapping for bright blends: off (clip), reinhard or aces
+    #[arg(long, default_value = "off")]
+    tonemap: String,
+
+    /// Window output color space: srgb or linear
+    #[arg(long, default_value = "srgb")]
+    output_color_space: String,
+
+    /// Stereo downmix for analysis: average, rms (level-preserving sum), left, right, mid (L+R) or side (L-R)
+    #[arg(long, default_value = "average")]
+    mono_mix: String,
+
+    /// If the file's channels are out of phase, invert the right one before the analysis downmix
+    #[arg(long)]
+    fix_phase: bool,
+
+    /// Analysis profile: full, or lite (one window per frame, beat-driven features only; less CPU)
+    #[arg(long, default_value = "full")]
+    profile: String,
+
+    /// Comma-separated analysis features to skip, to save CPU when the visuals don't use them:
+    /// centroid, rolloff, zcr, flux, onset, pitch, dynamic-range (uses the CPU analyzer)
+    #[arg(long, value_name = "LIST", default_value = "")]
+    skip_features: String,
+
+    /// How slowly the BPM follows tempo changes: 0 takes the median of recent beats as-is,
+    /// values near 1 change slowly (CPU analysis)
+    #[arg(long, default_value_t = audio::tempo_detector::DEFAULT_BPM_SMOOTHING)]
+    bpm_smoothing: f32,
+
+    /// JSON effect program that steps through effects on a schedule (overrides auto-blend)
+    #[arg(long, value_name = "FILE")]
+    effect_program: Option<String>,
+
+    /// After a few seconds, lean auto-blend toward the effects audio-analyzer would recommend
+    /// for what has played so far (no prescan needed)
+    #[arg(long)]
+    live_recommend: bool,
+
+    /// Log every frame's effect weights to a CSV that `--replay-weights` can play back
+    #[arg(long, value_name = "FILE")]
+    record_weights: Option<String>,
+
+    /// Drive the effect weights from a `--record-weights` log instead of auto-blend
+    #[arg(long, value_name = "FILE")]
+    replay_weights: Option<String>,
+
+    /// Normalization parameters JSON (e.g. from `audio-analyzer --apply-suggestions`)
+    #[arg(long, value_name = "FILE")]
+    normalization: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     let args = Args::parse();
+    graphics::crash_report::install("arrvee-audio-test");
+    let backends = graphics::engine::parse_backends(&args.backend)?;
+    let window_size = graphics::engine::window_size(args.width, args.height, (1200, 800))?;
+
+    // Parse up front so a typo fails before a window is opened
+    let test_signal = args.test_signal.as_deref().map(str::parse::<TestSignal>).transpose()?;
+    let start_effect = args.effect.as_deref().map(effects::psychedelic_manager::parse_effect_name).transpose()?;
+    let start_palette = args.palette.as_deref().map(graphics::engine::parse_palette).transpose()?;
+    let start_projection = args.projection.as_deref().map(graphics::engine::parse_projection).transpose()?;
+    let disabled_effects = args.disable_effect.iter()
+        .map(|name| effects::psychedelic_manager::parse_effect_name(name))
+        .collect::<Result<Vec<_>>>()?;
+    let tonemap = graphics::engine::parse_tonemap(&args.tonemap)?;
+    let output_color_space: graphics::engine::OutputColorSpace = args.output_color_space.parse()?;
+    let bg_color = args.bg_color.as_deref().map(graphics::engine::parse_hex_color).transpose()?;
+    let effect_program = args.effect_program.as_deref().map(EffectProgram::load).transpose()?;
+    let mono_mix: audio::DownmixMode = args.mono_mix.parse()?;
+    let profile: audio::AnalysisProfile = args.profile.parse()?;
+    let skipped_features: audio::FeatureMask = args.skip_features.parse()?;
+    graphics::crash_report::set_audio_file(if args.live_input { "live input" } else { args.test_signal.as_deref().unwrap_or(&args.audio_file) });
+    graphics::crash_report::set_analysis_config(format!(
+        "real-time, {:?} profile, skipping {}, {:?} mono mix, fix phase {}, log magnitude {}, mel bands {}, AGC {}, speed {:.2}x",
+        profile, skipped_features.names(), mono_mix, args.fix_phase, args.log_magnitude, args.mel_bands, args.agc, args.speed));
+    let normalization = args.normalization.as_deref().map(NormalizationParameters::load).transpose()?;
 
     info!("Starting Audio File Test with Real-time Visualization");
-    info!("Audio file: {}", args.audio_file);
+    if test_signal.is_none() && !args.live_input {
+        info!("Audio file: {}", args.audio_file);
+    }
     info!("Debug overlay: {}", args.debug);
 
     let event_loop = EventLoop::new()?;
     let window = Arc::new(WindowBuilder::new()
-        .with_title("Arrvee Audio File Test")
-        .with_inner_size(winit::dpi::LogicalSize::new(1200, 800))
+        .with_title(args.title.as_deref().unwrap_or("Arrvee Audio File Test"))
+        .with_inner_size(window_size)
+        .with_transparent(args.transparent)
+        .with_fullscreen(args.fullscreen.map(|monitor| graphics::engine::borderless_fullscreen(&event_loop, monitor)))
         .build(&event_loop)?);
 
-    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window))?;
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, backends))?;
+    graphics_engine.set_master_fade_time(args.fade_time);
+    if let Some(color) = bg_color {
+        graphics_engine.set_clear_color(color);
+    }
+    if args.transparent {
+        graphics_engine.set_transparent(true);
+    }
+    graphics_engine.set_spectrum_bars(args.spectrum_bars);
+    graphics_engine.set_spectrum_supersampling(args.spectrum_supersampling);
+    if let Some(effect) = start_effect {
+        graphics_engine.psychedelic_manager_mut().set_manual_effect(Some(effect.to_string()));
+        info!("🎆 Starting in manual effect: {}", effect);
+    }
+    if let Some(palette) = start_palette {
+        graphics_engine.palette_index = palette;
+    }
+    for effect in &disabled_effects {
+        graphics_engine.psychedelic_manager_mut().set_effect_enabled(effect, false);
+    }
+    if !disabled_effects.is_empty() {
+        info!("🚫 Auto-blend skips: {}", disabled_effects.join(", "));
+    }
+    if args.invert_response {
+        graphics_engine.psychedelic_manager_mut().set_invert_response(true);
+        info!("🙃 Inverted response: quiet passages drive the effects");
+    }
+    graphics_engine.psychedelic_manager_mut().set_transition_speed_multiplier(args.transition_speed)?;
+    if args.transition_speed != 1.0 {
+        info!("🌊 Effect transitions at {:.2}x speed", args.transition_speed);
+    }
+    if let Some(band) = args.solo_band.as_deref().map(str::parse::<SoloBand>).transpose()? {
+        info!("🎯 Solo band: {}", band.name());
+        graphics_engine.set_solo_band(Some(band));
+    }
+    if let Some(projection) = start_projection {
+        graphics_engine.projection_mode = projection;
+    }
+    if let Some(intensity) = args.beat_flash {
+        graphics_engine.set_beat_flash_enabled(true);
+        graphics_engine.set_beat_flash_intensity(intensity);
+        info!("💥 Beat flash at {:.0}% intensity", graphics_engine.beat_flash().intensity() * 100.0);
+    }
+    if args.feedback > 0.0 {
+        graphics_engine.set_feedback(args.feedback);
+        info!("🌀 Frame feedback at {:.2}", graphics_engine.feedback());
+    }
+    graphics_engine.set_kaleidoscope_segments(args.kaleidoscope_segments);
+    graphics_engine.set_kaleidoscope_seam_softness(args.kaleidoscope_softness);
+    graphics_engine.set_kaleidoscope_bpm_sync(args.kaleidoscope_bpm_sync);
+    graphics_engine.set_particle_emission_rate(args.particle_bursts);
+    if args.reduced_motion {
+        graphics_engine.set_reduced_motion(true);
+        info!("🐢 Reduced motion: animation at {:.0}% speed, no particle bursts, gentle effects favoured",
+              graphics::engine::REDUCED_MOTION_SCALE * 100.0);
+    }
+    if let Some(segments) = graphics_engine.kaleidoscope_segments() {
+        info!("🔷 Kaleidoscope: {} segments{}", segments, if args.kaleidoscope_bpm_sync { ", scaled with the tempo" } else { "" });
+    }
+    graphics_engine.set_camera_motion(!args.still_camera);
+    graphics_engine.tonemap_mode = tonemap;
+    graphics_engine.set_output_color_space(output_color_space)?;
+    if let Some(program) = effect_program {
+        info!("🎬 Running effect program from {}", args.effect_program.as_deref().unwrap_or_default());
+        graphics_engine.psychedelic_manager_mut().set_program(Some(program));
+    }
+    if args.live_recommend {
+        info!("🧭 Live effect recommendation on (after {:.0}s of listening)", effects::recommendation::DEFAULT_WARMUP_SECONDS);
+        graphics_engine.psychedelic_manager_mut().set_live_recommendation(true);
+    }
+    if let Some(path) = &args.replay_weights {
+        graphics_engine.replay_weights(path)?;
+    }
+    if let Some(path) = &args.record_weights {
+        graphics_engine.record_weights(path)?;
+    }
+    if let Some(path) = &args.load_uniforms {
+        graphics_engine.load_uniforms(path)?;
+    }
     let mut shutdown_requested = false;
     let mut audio_playback = AudioPlayback::new()?;
+    audio_playback.set_downmix(mono_mix);
+    audio_playback.set_fix_phase(args.fix_phase);
+    audio_playback.set_analysis_profile(profile);
+    audio_playback.set_feature_mask(!skipped_features);
+    audio_playback.set_bpm_smoothing(args.bpm_smoothing);
+    if args.speed != 1.0 {
+        audio_playback.set_speed(args.speed);
+        info!("⏩ Playback speed {:.2}x", audio_playback.speed());
+    }
+    if let Some(parameters) = normalization {
+        info!("📏 Loaded normalization parameters from {}", args.normalization.as_deref().unwrap_or_default());
+        audio_playback.set_normalization_parameters(parameters);
+    } else if args.log_magnitude {
+        audio_playback.set_normalization_parameters(NormalizationParameters::log_magnitude());
+    }
+    audio_playback.set_log_magnitude(args.log_magnitude);
+    audio_playback.set_mel_bands(args.mel_bands);
+    if args.agc {
+        info!("🎚️  Automatic gain control enabled");
+        audio_playback.set_agc(Some(AgcConfig::default()));
+    }
+    if args.follow_playhead {
+        info!("⏱️  Analysis follows the audio playhead");
+        audio_playback.set_follow_playhead(true);
+    }
+    if let Some(path) = &args.smoothing_config {
+        let smoothing = SmoothingConfig::load(path)?;
+        info!("〰️  Loaded smoothing config from {} (bass release {:.2}s, treble release {:.2}s)",
+              path, smoothing.per_band[1].release, smoothing.per_band[3].release);
+        audio_playback.set_smoothing_config(smoothing);
+    }
     let mut debug_overlay = if args.debug {
-        Some(DebugOverlay::new())
+        Some(DebugOverlay::new(args.debug_hz))
     } else {
         None
     };
 
-    // Load and start playing the specified audio file
-    info!("Loading {}...", args.audio_file);
-    audio_playback.load_file(&args.audio_file).await?;
+    // Load and start playing the specified audio file, or synthesize a test signal
+    let now_playing_text = if args.live_input {
+        format!("Live input: {}", audio_playback.start_live_input()?)
+    } else if let Some(signal) = test_signal {
+        let config = TestSignalConfig {
+            signal,
+            frequency: args.frequency,
+            bpm: args.bpm,
+            duration_seconds: args.duration,
+            ..Default::default()
+        };
+        info!("🧪 Generating test signal: {} ({:.0}s)", config.describe(), config.duration_seconds);
+        audio_playback.load_samples(config.generate(), config.sample_rate).await?;
+        format!("Test signal: {}", config.describe())
+    } else {
+        info!("Loading {}...", args.audio_file);
+        audio_playback.load_file(&args.audio_file).await?;
+        audio_playback.metadata().map_or_else(|| args.audio_file.clone(), |metadata| metadata.display_text())
+    };
+    let now_playing_position = args.now_playing.as_deref().map(str::parse::<OverlayPosition>).transpose()?;
+    let mut now_playing = NowPlayingOverlay::new(&graphics_engine.device, graphics_engine.config.format, window.scale_factor() as f32,
+                                                 now_playing_text.clone(), now_playing_position.unwrap_or_default());
+    now_playing.set_visible(now_playing_position.is_some());
+    info!("🎵 Now playing: {}", now_playing_text);
+
+    let scope_feature = args.scope.as_deref().map(str::parse::<ScopeFeature>).transpose()?;
+    let mut smoothing_scope = SmoothingScopeOverlay::new(&graphics_engine.device, graphics_engine.config.format,
+                                                         window.scale_factor() as f32, OverlayPosition::TopRight);
+    graphics_engine.smoothing_scope_mut().set_feature(scope_feature.unwrap_or_default());
+    smoothing_scope.set_visible(scope_feature.is_some());
+    smoothing_scope.set_detail(scope_detail(&audio_playback, scope_feature.unwrap_or_default()));
+    let mut band_grid = BandGridOverlay::new(&graphics_engine.device, graphics_engine.config.format,
+                                             window.scale_factor() as f32, OverlayPosition::BottomRight);
+    let mut level_meter = LevelMeterOverlay::new(&graphics_engine.device, graphics_engine.config.format,
+                                                 window.scale_factor() as f32, OverlayPosition::TopLeft);
+    level_meter.set_visible(args.debug);
 
     // Set initial volume to 10%
     let initial_volume = if let Some(debug) = &debug_overlay {
@@ -192,11 +655,23 @@ async fn main() -> Result<()> {
     };
     audio_playback.set_volume(initial_volume);
 
-    audio_playback.play();
-    info!("Audio playback started at {:.0}% volume", initial_volume * 100.0);
+    if !audio_playback.is_live() {
+        audio_playback.start(args.start_at, args.paused && !args.autoplay)?;
+        info!("Audio playback started at {:.0}% volume", initial_volume * 100.0);
+        info!("Real-time analysis latency: ~{:.0}ms (window accumulation + envelope attack)",
+              audio_playback.analysis_latency() * 1000.0);
+    }
 
     info!("Audio file test initialized successfully");
 
+    let control = ControlChannel::new();
+    if args.control_stdin {
+        control.listen_stdin();
+    }
+    if let Some(port) = args.control_tcp {
+        control.listen_tcp(port)?;
+    }
+
     let window_clone = Arc::clone(&window);
     event_loop.run(move |event, elwt| {
         match event {
@@ -223,6 +698,9 @@ async fn main() -> Result<()> {
                                 info!("Cleanup complete");
                                 elwt.exit();
                             }
+                            PhysicalKey::Code(KeyCode::F11) => {
+                                graphics::engine::toggle_fullscreen(&window_clone);
+                            }
                             PhysicalKey::Code(KeyCode::Space) => {
                                 if audio_playback.is_playing() {
                                     audio_playback.pause();
@@ -232,6 +710,67 @@ async fn main() -> Result<()> {
                                     info!("Audio resumed");
                                 }
                             }
+                            // Freeze visuals independently of audio
+                            PhysicalKey::Code(KeyCode::KeyF) => {
+                                let frozen = !graphics_engine.is_time_paused();
+                                graphics_engine.set_time_paused(frozen);
+                                info!("{}", if frozen { "❄️ Visuals frozen" } else { "▶️ Visuals resumed" });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyU) => {
+                                let timestamp = std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .map_or(0, |elapsed| elapsed.as_secs());
+                                if let Err(e) = graphics_engine.dump_uniforms(format!("uniforms_{}.json", timestamp)) {
+                                    log::error!("Failed to dump uniforms: {}", e);
+                                }
+                            }
+                            PhysicalKey::Code(KeyCode::KeyN) => {
+                                now_playing.set_visible(!now_playing.is_visible());
+                            }
+                            PhysicalKey::Code(KeyCode::KeyL) => {
+                                level_meter.set_visible(!level_meter.is_visible());
+                            }
+                            PhysicalKey::Code(KeyCode::KeyY) => {
+                                let band = SoloBand::cycle(graphics_engine.solo_band());
+                                graphics_engine.set_solo_band(band);
+                                info!("🎯 Solo band: {}", band.map_or("off (full mix)", SoloBand::name));
+                            }
+                            // Smoothing scope: K shows it, J picks the next feature, G/H shorten/lengthen its release
+                            PhysicalKey::Code(KeyCode::KeyK) => {
+                                smoothing_scope.set_visible(!smoothing_scope.is_visible());
+                            }
+                            PhysicalKey::Code(KeyCode::KeyJ) => {
+                                let feature = graphics_engine.smoothing_scope().feature().next();
+                                graphics_engine.smoothing_scope_mut().set_feature(feature);
+                                smoothing_scope.set_detail(scope_detail(&audio_playback, feature));
+                                info!("〰️  Smoothing scope: {}", feature.name());
+                            }
+                            PhysicalKey::Code(KeyCode::KeyG) | PhysicalKey::Code(KeyCode::KeyH) => {
+                                let scale = if event.physical_key == PhysicalKey::Code(KeyCode::KeyH) { 1.25 } else { 0.8 };
+                                let feature = graphics_engine.smoothing_scope().feature();
+                                let mut smoothing = audio_playback.envelope_config().smoothing.clone();
+                                let times = feature.smoothing_mut(&mut smoothing);
+                                times.release = (times.release * scale).clamp(0.005, 5.0);
+                                info!("〰️  {} release: {:.3}s", feature.name(), times.release);
+                                audio_playback.set_smoothing_config(smoothing);
+                                smoothing_scope.set_detail(scope_detail(&audio_playback, feature));
+                            }
+                            // Band grid: FFT bins and band edges over the spectralizer
+                            PhysicalKey::Code(KeyCode::KeyA) => {
+                                let enabled = !graphics_engine.band_grid();
+                                graphics_engine.set_band_grid(enabled);
+                                band_grid.set_visible(enabled);
+                                info!("📏 Band grid {}", if enabled { "on" } else { "off" });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyB) => {
+                                let target = graphics_engine.toggle_master_fade();
+                                info!("{}", if target > 0.0 { "🌅 Fading in" } else { "🌑 Fading to black" });
+                            }
+                            PhysicalKey::Code(KeyCode::PageUp) | PhysicalKey::Code(KeyCode::PageDown) => {
+                                let step = if event.physical_key == PhysicalKey::Code(KeyCode::PageUp) { 0.25 } else { -0.25 };
+                                let target = graphics_engine.step_master_fade(step);
+                                info!("🎚️ Master intensity → {:.0}%", target * 100.0);
+                            }
                             PhysicalKey::Code(KeyCode::KeyD) => {
                                 if let Some(debug) = &mut debug_overlay {
                                     debug.toggle_overlay();
@@ -281,6 +820,10 @@ async fn main() -> Result<()> {
                                 graphics_engine.psychedelic_manager_mut().set_manual_effect(Some("parametric_waves".to_string()));
                                 info!("🌈 Effect switched to: Parametric Waves (Audio-Reactive Mathematical Patterns)");
                             }
+                            PhysicalKey::Code(KeyCode::Digit8) => {
+                                graphics_engine.psychedelic_manager_mut().set_manual_effect(Some("flux_debug".to_string()));
+                                info!("🌈 Effect switched to: Flux Debug (per-bin spectral flux, scrolling)");
+                            }
                             PhysicalKey::Code(KeyCode::Digit0) => {
                                 graphics_engine.psychedelic_manager_mut().set_manual_effect(None);
                                 info!("🌈 Effect switched to: Auto-Blend Mode (intelligent music analysis)");
@@ -322,14 +865,14 @@ async fn main() -> Result<()> {
                                 graphics_engine.smoothing_factor = (graphics_engine.smoothing_factor + 0.1).min(2.0);
                                 info!("🎛️ Smoothing: {:.1}", graphics_engine.smoothing_factor);
                             }
-                            // Sensitivity controls using Semicolon/Quote for easier access
-                            PhysicalKey::Code(KeyCode::Semicolon) => {
+                            // Sensitivity controls: ./; increase, ,/' decrease
+                            PhysicalKey::Code(KeyCode::Period) | PhysicalKey::Code(KeyCode::Semicolon) => {
                                 if let Some(analyzer) = audio_playback.analyzer_mut() {
                                     let new_sensitivity = analyzer.adjust_sensitivity(0.1);
                                     info!("🎚️ Sensitivity increased to {:.2}x", new_sensitivity);
                                 }
                             }
-                            PhysicalKey::Code(KeyCode::Quote) => {
+                            PhysicalKey::Code(KeyCode::Comma) | PhysicalKey::Code(KeyCode::Quote) => {
                                 if let Some(analyzer) = audio_playback.analyzer_mut() {
                                     let new_sensitivity = analyzer.adjust_sensitivity(-0.1);
                                     info!("🎚️ Sensitivity decreased to {:.2}x", new_sensitivity);
@@ -339,6 +882,9 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
+                WindowEvent::CursorMoved { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::MouseInput { .. } => {
+                    graphics_engine.handle_mouse_event(&event);
+                }
                 WindowEvent::Resized(physical_size) => {
                     graphics_engine.resize(physical_size);
                 }
@@ -349,25 +895,36 @@ async fn main() -> Result<()> {
 
                     // Get real-time audio analysis from the loaded file
                     let audio_data = pollster::block_on(audio_playback.get_current_audio_frame());
+                    graphics_engine.record_smoothing_scope(audio_playback.raw_frame(), &audio_data);
 
-                    // Render debug overlay if enabled (limit to ~2Hz to avoid spam)
-                    static mut FRAME_COUNT: u32 = 0;
-                    unsafe {
-                        FRAME_COUNT += 1;
-                        if FRAME_COUNT % 30 == 0 { // Show debug every 30 frames (~2Hz at 60fps)
-                            if let Some(debug) = &mut debug_overlay {
-                                debug.render_debug_info(&audio_data, &graphics_engine, &audio_playback);
-                            }
-                        }
+                    // Render debug overlay if enabled (it throttles itself to --debug-hz)
+                    if let Some(debug) = &mut debug_overlay {
+                        debug.render_debug_info(&audio_data, &graphics_engine, &audio_playback);
                     }
 
-                    if let Err(e) = graphics_engine.render(&audio_data, &window_clone) {
+                    // The scope is drawn from a copy, as the engine is busy rendering
+                    let scope = smoothing_scope.is_visible().then(|| graphics_engine.smoothing_scope().clone());
+                    let band_edges = graphics_engine.band_edges();
+                    let levels = audio_playback.levels().channels();
+                    let result = graphics_engine.render_with_overlay(&audio_data, &window_clone, |encoder, view, device, queue, size| {
+                        now_playing.render(encoder, view, device, queue, size)?;
+                        band_grid.render(encoder, view, device, queue, size, &band_edges)?;
+                        level_meter.render(encoder, view, device, queue, size, levels)?;
+                        match &scope {
+                            Some(scope) => smoothing_scope.render(encoder, view, device, queue, size, scope),
+                            None => Ok(()),
+                        }
+                    });
+                    if let Err(e) = result {
                         log::error!("Render error: {}", e);
                     }
                 }
                 _ => {}
             },
             Event::AboutToWait => {
+                for command in control.poll() {
+                    command.apply(&mut graphics_engine, &mut audio_playback);
+                }
                 // Check if audio finished
                 if audio_playback.is_finished() {
                     info!("Audio finished playing");
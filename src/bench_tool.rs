@@ -0,0 +1,192 @@
+use anyhow::Result;
+use clap::Parser;
+use log::info;
+use std::time::{Duration, Instant};
+
+use arrvee::audio;
+
+use audio::{AudioAnalyzer, CpuAudioAnalyzer, FeatureNormalizer, NewGpuAudioAnalyzer};
+use audio::cpu_analyzer::StageTimings;
+use audio::test_signal::{TestSignal, TestSignalConfig};
+
+#[derive(Parser)]
+#[command(name = "arrvee-bench")]
+#[command(about = "Headless analysis throughput benchmark (CPU and optional GPU analyzer)")]
+struct Args {
+    /// Audio file to benchmark with (omit to use a generated signal)
+    #[arg()]
+    input_file: Option<String>,
+
+    /// Generated signal when no file is given: sine, sweep, noise, click-track
+    #[arg(long, default_value = "noise")]
+    signal: String,
+
+    /// Generated signal length in seconds
+    #[arg(long, default_value = "30.0")]
+    duration: f32,
+
+    /// Sample rate for generated signals (files use their native rate)
+    #[arg(long, default_value = "44100")]
+    sample_rate: u32,
+
+    /// Analysis chunk size (`--gpu` needs a power of two of at least 64)
+    #[arg(long, default_value = "512")]
+    chunk_size: usize,
+
+    /// Maximum number of chunks to analyze (default: the whole buffer)
+    #[arg(long)]
+    chunks: Option<usize>,
+
+    /// Chunks analyzed before timing starts
+    #[arg(long, default_value = "32")]
+    warmup: usize,
+
+    /// Also benchmark the GPU analyzer
+    #[arg(long)]
+    gpu: bool,
+
+    /// CPU analysis profile: full or lite
+    #[arg(long, default_value = "full")]
+    profile: String,
+
+    /// Comma-separated CPU features to skip: centroid, rolloff, zcr, flux, onset, pitch, dynamic-range
+    #[arg(long, value_name = "LIST", default_value = "")]
+    skip_features: String,
+}
+
+/// Timing results for one analyzer run
+struct BenchResult {
+    analyzer: &'static str,
+    chunks: usize,
+    total: Duration,
+    normalize: Duration,
+    stages: Option<StageTimings>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+    let profile: audio::AnalysisProfile = args.profile.parse()?;
+    let skipped: audio::FeatureMask = args.skip_features.parse()?;
+
+    let (samples, sample_rate, source) = load_benchmark_audio(&args)?;
+    let available_chunks = samples.len() / args.chunk_size.max(1);
+    let chunks = args.chunks.map_or(available_chunks, |limit| limit.min(available_chunks));
+    if chunks == 0 {
+        return Err(anyhow::anyhow!("Not enough audio for a single {}-sample chunk", args.chunk_size));
+    }
+    info!("Benchmarking {} chunks of {} samples from {}", chunks, args.chunk_size, source);
+
+    let mut results = Vec::new();
+
+    let mut cpu_analyzer = CpuAudioAnalyzer::new(sample_rate as f32, args.chunk_size)?;
+    cpu_analyzer.set_profile(profile);
+    cpu_analyzer.set_feature_mask(!skipped);
+    warm_up(&mut cpu_analyzer, &samples, args.chunk_size, args.warmup).await?;
+    cpu_analyzer.enable_stage_timing();
+    let mut cpu_result = run_benchmark(&mut cpu_analyzer, &samples, args.chunk_size, chunks).await?;
+    cpu_result.stages = cpu_analyzer.stage_timings().cloned();
+    results.push(cpu_result);
+
+    if args.gpu {
+        match NewGpuAudioAnalyzer::new_standalone(sample_rate as f32, args.chunk_size).await {
+            Ok(mut gpu_analyzer) => {
+                warm_up(&mut gpu_analyzer, &samples, args.chunk_size, args.warmup).await?;
+                results.push(run_benchmark(&mut gpu_analyzer, &samples, args.chunk_size, chunks).await?);
+            }
+            Err(e) => log::warn!("⚠️  GPU analyzer unavailable, skipping: {}", e),
+        }
+    }
+
+    print_report(&results, &source, sample_rate, args.chunk_size, profile, cpu_analyzer.feature_mask());
+    Ok(())
+}
+
+/// Decode the input file to mono f32, or synthesize a signal when no file is given
+fn load_benchmark_audio(args: &Args) -> Result<(Vec<f32>, u32, String)> {
+    match &args.input_file {
+        Some(path) => {
+            let (samples, sample_rate) = audio::decode_file_to_mono(path, audio::DownmixMode::Average)?;
+            Ok((samples, sample_rate, path.clone()))
+        }
+        None => {
+            let config = TestSignalConfig {
+                signal: args.signal.parse::<TestSignal>()?,
+                sample_rate: args.sample_rate,
+                duration_seconds: args.duration,
+                ..Default::default()
+            };
+            let description = format!("generated {} ({:.0}s)", config.describe(), config.duration_seconds);
+            Ok((config.generate(), config.sample_rate, description))
+        }
+    }
+}
+
+async fn warm_up(analyzer: &mut dyn AudioAnalyzer, samples: &[f32], chunk_size: usize, chunks: usize) -> Result<()> {
+    for chunk in samples.chunks_exact(chunk_size).take(chunks) {
+        analyzer.analyze_chunk(chunk).await?;
+    }
+    Ok(())
+}
+
+async fn run_benchmark(analyzer: &mut dyn AudioAnalyzer, samples: &[f32], chunk_size: usize, chunks: usize) -> Result<BenchResult> {
+    let mut normalizer = FeatureNormalizer::new();
+    let mut normalize = Duration::ZERO;
+
+    let start = Instant::now();
+    for chunk in samples.chunks_exact(chunk_size).take(chunks) {
+        let raw_features = analyzer.analyze_chunk(chunk).await?;
+
+        let normalize_start = Instant::now();
+        std::hint::black_box(normalizer.normalize(&raw_features));
+        normalize += normalize_start.elapsed();
+    }
+
+    Ok(BenchResult {
+        analyzer: analyzer.analyzer_type(),
+        chunks,
+        total: start.elapsed(),
+        normalize,
+        stages: None,
+    })
+}
+
+fn print_report(results: &[BenchResult], source: &str, sample_rate: u32, chunk_size: usize, profile: audio::AnalysisProfile,
+                features: audio::FeatureMask) {
+    let micros_per_chunk = |duration: Duration, chunks: usize| duration.as_secs_f64() * 1e6 / chunks.max(1) as f64;
+
+    println!();
+    println!("=== ANALYSIS THROUGHPUT ===");
+    println!("Source: {}", source);
+    println!("Sample rate: {}Hz, chunk size: {} ({:.2}ms of audio per chunk)",
+             sample_rate, chunk_size, chunk_size as f64 * 1000.0 / sample_rate as f64);
+    println!("CPU profile: {:?}, features: {}", profile, features.names());
+    println!();
+    println!("{:<10} {:>8} {:>10} {:>12} {:>10} {:>10}", "Analyzer", "Chunks", "Time (s)", "Chunks/sec", "µs/chunk", "Realtime");
+
+    for result in results {
+        let seconds = result.total.as_secs_f64().max(f64::EPSILON);
+        let audio_seconds = (result.chunks * chunk_size) as f64 / sample_rate as f64;
+        println!("{:<10} {:>8} {:>10.3} {:>12.0} {:>10.1} {:>9.1}x",
+                 result.analyzer,
+                 result.chunks,
+                 seconds,
+                 result.chunks as f64 / seconds,
+                 micros_per_chunk(result.total, result.chunks),
+                 audio_seconds / seconds);
+    }
+
+    for result in results {
+        println!();
+        println!("{} per-stage timings (µs/chunk):", result.analyzer);
+        if let Some(stages) = &result.stages {
+            println!("  FFT (window + transform): {:>8.2}", micros_per_chunk(stages.fft, stages.chunks));
+            println!("  Feature extraction:       {:>8.2}", micros_per_chunk(stages.features, stages.chunks));
+            println!("  Beat / BPM:               {:>8.2}", micros_per_chunk(stages.beat, stages.chunks));
+        } else {
+            println!("  (stage breakdown not available for this analyzer)");
+        }
+        println!("  Normalization:            {:>8.2}", micros_per_chunk(result.normalize, result.chunks));
+    }
+}
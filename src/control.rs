@@ -0,0 +1,214 @@
+use anyhow::Result;
+use log::{info, warn};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::audio::AudioPlayback;
+use crate::effects::psychedelic_manager::{parse_effect_name, DEBUG_EFFECT_NAMES, EFFECT_NAMES};
+use crate::graphics::engine::{parse_palette, parse_projection, PALETTE_NAMES};
+use crate::graphics::GraphicsEngine;
+
+/// One line of the control protocol, e.g. `effect fractal_madness` or `volume 0.2`
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    /// Manual effect, or None for auto-blend (`effect auto`)
+    Effect(Option<&'static str>),
+    Palette(f32),
+    Projection(f32),
+    Smoothing(f32),
+    Volume(f32),
+    Sensitivity(f32),
+}
+
+impl FromStr for ControlCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let (command, argument) = line.trim().split_once(char::is_whitespace)
+            .map_or((line.trim(), ""), |(command, argument)| (command, argument.trim()));
+        let number = || argument.parse::<f32>().ok().filter(|value| value.is_finite())
+            .ok_or_else(|| anyhow::anyhow!("'{}' needs a number, got '{}'", command, argument));
+
+        match command.to_lowercase().as_str() {
+            "effect" if argument.eq_ignore_ascii_case("auto") => Ok(Self::Effect(None)),
+            "effect" => Ok(Self::Effect(Some(find_effect(argument)?))),
+            "palette" => Ok(Self::Palette(parse_palette(argument)?)),
+            "projection" => Ok(Self::Projection(parse_projection(argument)?)),
+            "smoothing" => Ok(Self::Smoothing(number()?.clamp(0.1, 2.0))),
+            "volume" => Ok(Self::Volume(number()?.clamp(0.0, 1.0))),
+            "sensitivity" => Ok(Self::Sensitivity(number()?.clamp(0.1, 5.0))),
+            _ => Err(anyhow::anyhow!(
+                "Unknown command '{}'. Use: effect <name|auto>, palette <0-{}|name>, projection <name>, \
+                 smoothing <0.1-2.0>, volume <0-1>, sensitivity <0.1-5.0>",
+                line.trim(), PALETTE_NAMES.len() - 1
+            )),
+        }
+    }
+}
+
+/// An effect by full name, or by a part of it that names only one (`kaleidoscope`)
+fn find_effect(name: &str) -> Result<&'static str> {
+    if let Ok(effect) = parse_effect_name(name) {
+        return Ok(effect);
+    }
+    let needle = name.trim().to_lowercase().replace('-', "_");
+    let mut matches = EFFECT_NAMES.iter().chain(&DEBUG_EFFECT_NAMES).filter(|effect| !needle.is_empty() && effect.contains(&needle));
+    match (matches.next(), matches.next()) {
+        (Some(effect), None) => Ok(effect),
+        _ => parse_effect_name(name),
+    }
+}
+
+#[allow(dead_code)]
+impl ControlCommand {
+    /// Apply the command live, as the matching key would
+    pub fn apply(&self, graphics_engine: &mut GraphicsEngine, audio_playback: &mut AudioPlayback) {
+        match *self {
+            Self::Effect(effect) => {
+                graphics_engine.psychedelic_manager_mut().set_manual_effect(effect.map(str::to_string));
+                info!("🌈 Effect switched to: {}", effect.unwrap_or("auto-blend"));
+            }
+            Self::Palette(palette) => {
+                graphics_engine.palette_index = palette;
+                info!("🎨 Palette: {}", PALETTE_NAMES[palette as usize]);
+            }
+            Self::Projection(projection) => {
+                graphics_engine.projection_mode = projection;
+                info!("🌐 Projection mode: {}", projection);
+            }
+            Self::Smoothing(smoothing) => {
+                graphics_engine.smoothing_factor = smoothing;
+                info!("🎛️ Smoothing: {:.1}", smoothing);
+            }
+            Self::Volume(volume) => {
+                audio_playback.set_volume(volume);
+                info!("🔊 Volume: {:.0}%", volume * 100.0);
+            }
+            Self::Sensitivity(sensitivity) => {
+                audio_playback.set_sensitivity(sensitivity);
+                info!("🎚️ Sensitivity: {:.2}x", sensitivity);
+            }
+        }
+    }
+}
+
+/// Line commands from stdin and/or TCP clients (`--control-stdin`, `--control-tcp <port>`),
+/// read on background threads and picked up by the render loop with `poll`.
+///
+/// TCP clients get `ok` or `error: <reason>` back for each line; unparsable stdin lines are
+/// logged. Commands arrive at most a frame late.
+#[allow(dead_code)]
+pub struct ControlChannel {
+    receiver: Receiver<ControlCommand>,
+    sender: Sender<ControlCommand>,
+}
+
+#[allow(dead_code)]
+impl ControlChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { receiver, sender }
+    }
+
+    /// Read commands from stdin until it closes (so `echo "effect auto" | audio-test …` works)
+    pub fn listen_stdin(&self) {
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines().map_while(std::io::Result::ok) {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match line.parse() {
+                    Ok(command) => {
+                        if sender.send(command).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("⚠️  Control: {}", e),
+                }
+            }
+        });
+        info!("🎮 Accepting control commands on stdin");
+    }
+
+    /// Accept TCP clients on `127.0.0.1:<port>` (local only: the protocol has no authentication)
+    pub fn listen_tcp(&self, port: u16) -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| anyhow::anyhow!("Could not listen for control commands on port {}: {}", port, e))?;
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().map_while(std::io::Result::ok) {
+                let sender = sender.clone();
+                std::thread::spawn(move || serve_client(stream, sender));
+            }
+        });
+        info!("🎮 Accepting control commands on 127.0.0.1:{}", port);
+        Ok(())
+    }
+
+    /// Commands received since the last call, oldest first
+    pub fn poll(&self) -> Vec<ControlCommand> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Default for ControlChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn serve_client(stream: TcpStream, sender: Sender<ControlCommand>) {
+    let Ok(mut reply) = stream.try_clone() else { return };
+    for line in BufReader::new(stream).lines().map_while(std::io::Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match line.parse() {
+            Ok(command) => {
+                if sender.send(command).is_err() {
+                    return; // The visualizer has shut down
+                }
+                "ok".to_string()
+            }
+            Err(e) => format!("error: {}", e),
+        };
+        if writeln!(reply, "{}", response).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_parse_and_arrive_over_tcp() {
+        assert_eq!("effect fractal_madness".parse::<ControlCommand>().unwrap(), ControlCommand::Effect(Some("fractal_madness")));
+        assert_eq!("effect kaleidoscope".parse::<ControlCommand>().unwrap(), ControlCommand::Effect(Some("geometric_kaleidoscope")));
+        assert_eq!("EFFECT auto".parse::<ControlCommand>().unwrap(), ControlCommand::Effect(None));
+        assert_eq!("palette 3".parse::<ControlCommand>().unwrap(), ControlCommand::Palette(3.0));
+        assert_eq!("  volume   1.5 ".parse::<ControlCommand>().unwrap(), ControlCommand::Volume(1.0));
+        assert!("smoothing lots".parse::<ControlCommand>().is_err());
+        assert!("effect".parse::<ControlCommand>().is_err());
+        assert!("dance".parse::<ControlCommand>().is_err());
+
+        // Port 0 picks a free one; bind our own listener to learn it, then serve it the same way
+        let channel = ControlChannel::new();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let sender = channel.sender.clone();
+        std::thread::spawn(move || serve_client(listener.accept().unwrap().0, sender));
+
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(b"smoothing 0.5\nnonsense\n").unwrap();
+        let mut replies = BufReader::new(client.try_clone().unwrap()).lines();
+        assert_eq!(replies.next().unwrap().unwrap(), "ok");
+        assert!(replies.next().unwrap().unwrap().starts_with("error: Unknown command"));
+        assert_eq!(channel.poll(), vec![ControlCommand::Smoothing(0.5)]);
+    }
+}
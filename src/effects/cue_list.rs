@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// What a cue marks
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CueKind {
+    /// Start of a loudness section
+    Section,
+    /// A new dominant effect
+    Effect,
+}
+
+impl CueKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            CueKind::Section => "section",
+            CueKind::Effect => "effect",
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize)]
+pub struct Cue {
+    pub time: f32, // Seconds from the start of the track
+    pub kind: CueKind,
+    pub name: String,
+}
+
+/// Timestamped markers from an analysis, for importing into VJ software (Resolume, VDMX, ...).
+///
+/// Saved as JSON, or as XML when the path ends in `.xml`:
+/// ```xml
+/// <cuelist source="track.wav" duration="184.200">
+///   <cue time="0.000" kind="section" name="Section 1"/>
+///   <cue time="12.480" kind="effect" name="fractal_madness"/>
+/// </cuelist>
+/// ```
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize)]
+pub struct CueList {
+    source: String,
+    duration_seconds: f32,
+    cues: Vec<Cue>,
+}
+
+#[allow(dead_code)]
+impl CueList {
+    pub fn new(source: &str, duration_seconds: f32) -> Self {
+        Self { source: source.to_string(), duration_seconds, cues: Vec::new() }
+    }
+
+    /// Add a cue, keeping the list in time order (cues at the same time keep insertion order)
+    pub fn push(&mut self, time: f32, kind: CueKind, name: &str) {
+        let index = self.cues.partition_point(|cue| cue.time <= time);
+        self.cues.insert(index, Cue { time, kind, name: name.to_string() });
+    }
+
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let is_xml = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+        let contents = if is_xml { self.to_xml() } else { serde_json::to_string_pretty(self)? };
+        std::fs::write(path, contents).with_context(|| format!("Failed to write cue list {}", path.display()))
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<cuelist source=\"{}\" duration=\"{:.3}\">\n", escape_xml(&self.source), self.duration_seconds));
+        for cue in &self.cues {
+            xml.push_str(&format!("  <cue time=\"{:.3}\" kind=\"{}\" name=\"{}\"/>\n",
+                                  cue.time, cue.kind.as_str(), escape_xml(&cue.name)));
+        }
+        xml.push_str("</cuelist>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_cues_are_time_ordered_in_both_formats() {
+        let mut cues = CueList::new("a & b.wav", 30.0);
+        cues.push(12.5, CueKind::Effect, "fractal_madness");
+        cues.push(0.0, CueKind::Section, "Section 1");
+        cues.push(20.0, CueKind::Section, "Section 2");
+        cues.push(12.5, CueKind::Effect, "particle_swarm");
+        let times: Vec<f32> = cues.cues().iter().map(|cue| cue.time).collect();
+        assert_eq!(times, [0.0, 12.5, 12.5, 20.0]);
+        assert_eq!(cues.cues()[2].name, "particle_swarm");
+
+        let path = TempPath::file("cues.json");
+        cues.save(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["cues"][1]["kind"], "effect");
+        assert_eq!(json["cues"][3]["time"], 20.0);
+
+        let xml = cues.to_xml();
+        assert!(xml.contains("<cuelist source=\"a &amp; b.wav\" duration=\"30.000\">"));
+        assert!(xml.contains("<cue time=\"12.500\" kind=\"effect\" name=\"fractal_madness\"/>"));
+        assert_eq!(xml.matches("<cue ").count(), 4);
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::psychedelic_manager::{parse_effect_name, PsychedelicManager, EFFECT_NAMES};
+
+/// Where the windowed tools keep the auto-blend pool (in the working directory)
+pub const DEFAULT_EFFECT_POOL_PATH: &str = "arrvee_effects.json";
+
+/// The curated auto-blend pool as saved between runs: the effects auto-blend skips, so
+/// effects added in later versions start out enabled
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EffectPool {
+    pub disabled_effects: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl EffectPool {
+    /// The manager's current pool, in `EFFECT_NAMES` order
+    pub fn from_manager(manager: &PsychedelicManager) -> Self {
+        Self {
+            disabled_effects: EFFECT_NAMES.iter()
+                .filter(|effect| !manager.is_effect_enabled(effect))
+                .map(|effect| effect.to_string())
+                .collect(),
+        }
+    }
+
+    /// Enable every effect except the disabled ones. Unknown names (e.g. from a newer
+    /// version) are skipped with a warning.
+    pub fn apply(&self, manager: &mut PsychedelicManager) {
+        let disabled: Vec<&str> = self.disabled_effects.iter()
+            .filter_map(|name| parse_effect_name(name)
+                .map_err(|e| log::warn!("⚠️  Effect pool: {}", e))
+                .ok())
+            .collect();
+        for effect in EFFECT_NAMES {
+            manager.set_effect_enabled(effect, !disabled.contains(&effect));
+        }
+    }
+
+    /// Load a saved pool; a missing file is the default (every effect enabled)
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| anyhow::anyhow!("Invalid effect pool {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow::anyhow!("Could not read effect pool {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Save the pool as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_pool_round_trips_through_its_file() {
+        let path = TempPath::file("effect_pool.json");
+        assert_eq!(EffectPool::load(&path).unwrap(), EffectPool::default());
+
+        let mut manager = PsychedelicManager::new();
+        manager.set_effect_enabled("particle_swarm", false);
+        manager.set_effect_enabled("llama_plasma", false);
+        let pool = EffectPool::from_manager(&manager);
+        assert_eq!(pool.disabled_effects, ["llama_plasma", "particle_swarm"]);
+        pool.save(&path).unwrap();
+
+        let mut restored = PsychedelicManager::new();
+        restored.set_effect_enabled("psychedelic_tunnel", false);
+        EffectPool::load(&path).unwrap().apply(&mut restored);
+        assert_eq!(EffectPool::from_manager(&restored), pool);
+        assert!(restored.is_effect_enabled("psychedelic_tunnel"));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(EffectPool::load(&path).is_err());
+    }
+}
@@ -1,4 +1,9 @@
+pub mod cue_list;
+pub mod effect_pool;
 pub mod preset;
+pub mod program;
 pub mod psychedelic_manager;
+pub mod recommendation;
+pub mod weight_log;
 
 pub use psychedelic_manager::PsychedelicManager;
\ No newline at end of file
@@ -34,6 +34,12 @@ pub struct PresetManager {
     current_preset: usize,
 }
 
+impl Default for PresetManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PresetManager {
     pub fn new() -> Self {
         let presets = vec![
@@ -0,0 +1,252 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Deserialize;
+
+use crate::audio::AudioFrame;
+use super::psychedelic_manager::parse_effect_name;
+
+const BEATS_PER_BAR: u32 = 4;
+const SECTION_SHORT_SECONDS: f32 = 2.0;  // "What's playing now" energy window
+const SECTION_LONG_SECONDS: f32 = 12.0;  // "What the section has been like" energy window
+const SECTION_CHANGE_RATIO: f32 = 1.6;   // Short/long energy ratio (either way) that marks a new section
+/// Ignore section changes this soon after the last one (or the last program advance)
+pub const SECTION_MIN_SECONDS: f32 = 10.0;
+
+/// One step of an effect program. Exactly one of `seconds`, `bars` or `until_section_change`
+/// says when to move on; `effect` is an effect name or `auto` for the usual auto-blend.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProgramEntry {
+    pub effect: String,
+    #[serde(default)]
+    pub seconds: Option<f32>,
+    #[serde(default)]
+    pub bars: Option<u32>,
+    #[serde(default)]
+    pub until_section_change: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProgramFile {
+    entries: Vec<ProgramEntry>,
+    #[serde(default = "default_repeat")]
+    repeat: bool,
+}
+
+fn default_repeat() -> bool {
+    true
+}
+
+/// Scripted effect sequence for unattended shows (`--effect-program`), stepped by `PsychedelicManager`.
+///
+/// ```json
+/// { "repeat": true, "entries": [
+///     { "effect": "llama_plasma", "seconds": 60 },
+///     { "effect": "psychedelic_tunnel", "bars": 32 },
+///     { "effect": "fractal_madness", "until_section_change": true },
+///     { "effect": "auto", "seconds": 120 } ] }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EffectProgram {
+    entries: Vec<ProgramEntry>,
+    repeat: bool,
+    index: usize,
+    finished: bool,
+    entry_elapsed: f32,
+    entry_beats: u32,
+    last_beat: bool,
+    sections: SectionDetector,
+}
+
+impl EffectProgram {
+    /// Load and validate a program from a JSON file
+    #[allow(dead_code)]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let file: ProgramFile = serde_json::from_str(&json)?;
+        Self::new(file.entries, file.repeat)
+    }
+
+    pub fn new(mut entries: Vec<ProgramEntry>, repeat: bool) -> Result<Self> {
+        if entries.is_empty() {
+            return Err(anyhow!("Effect program has no entries"));
+        }
+        for (index, entry) in entries.iter_mut().enumerate() {
+            if !entry.effect.eq_ignore_ascii_case("auto") {
+                entry.effect = parse_effect_name(&entry.effect)
+                    .map_err(|e| anyhow!("Effect program entry {}: {}", index + 1, e))?
+                    .to_string();
+            }
+            let conditions = [entry.seconds.is_some(), entry.bars.is_some(), entry.until_section_change];
+            if conditions.iter().filter(|set| **set).count() != 1 {
+                return Err(anyhow!("Effect program entry {} ({}) needs exactly one of seconds, bars or until_section_change",
+                                   index + 1, entry.effect));
+            }
+            if let Some(seconds) = entry.seconds.filter(|seconds| !(seconds.is_finite() && *seconds > 0.0)) {
+                return Err(anyhow!("Effect program entry {} ({}): seconds must be a positive number, not {}",
+                                   index + 1, entry.effect, seconds));
+            }
+            if entry.bars == Some(0) {
+                return Err(anyhow!("Effect program entry {} ({}): bars must be at least 1", index + 1, entry.effect));
+            }
+        }
+
+        Ok(Self {
+            entries,
+            repeat,
+            index: 0,
+            finished: false,
+            entry_elapsed: 0.0,
+            entry_beats: 0,
+            last_beat: false,
+            sections: SectionDetector::new(),
+        })
+    }
+
+    /// Effect the program currently wants: `Some(name)`, or `None` for auto-blend
+    pub fn current_effect(&self) -> Option<&str> {
+        let effect = self.entries[self.index].effect.as_str();
+        (!effect.eq_ignore_ascii_case("auto")).then_some(effect)
+    }
+
+    /// Advance the program clock; returns true when it moved on to the next entry
+    pub fn update(&mut self, delta_time: f32, audio_frame: &AudioFrame) -> bool {
+        if self.finished {
+            return false;
+        }
+        let section_change = self.sections.update(delta_time, audio_frame.volume);
+
+        // Count beat onsets, not frames that happen to still be flagged
+        self.entry_elapsed += delta_time;
+        if audio_frame.beat_detected && !self.last_beat {
+            self.entry_beats += 1;
+        }
+        self.last_beat = audio_frame.beat_detected;
+
+        let entry = &self.entries[self.index];
+        let done = if let Some(seconds) = entry.seconds {
+            self.entry_elapsed >= seconds
+        } else if let Some(bars) = entry.bars {
+            self.entry_beats >= bars * BEATS_PER_BAR
+        } else {
+            section_change && self.entry_elapsed >= SECTION_MIN_SECONDS
+        };
+        if !done {
+            return false;
+        }
+
+        if self.index + 1 < self.entries.len() {
+            self.index += 1;
+        } else if self.repeat {
+            self.index = 0;
+        } else {
+            self.finished = true;
+            info!("🎬 Effect program finished; holding {}", self.entries[self.index].effect);
+            return false;
+        }
+        self.entry_elapsed = 0.0;
+        self.entry_beats = 0;
+        info!("🎬 Effect program step {}/{}: {}", self.index + 1, self.entries.len(), self.entries[self.index].effect);
+        true
+    }
+}
+
+/// Loudness-based section detection: compares short- and long-term energy, so a big jump
+/// either way reads as a new section. Reports a change on every frame the two stay apart;
+/// callers debounce with `SECTION_MIN_SECONDS`.
+#[derive(Debug, Clone, Default)]
+pub struct SectionDetector {
+    short_energy: f32,
+    long_energy: f32,
+}
+
+impl SectionDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one frame's volume (0-1); true while it sounds like a new section
+    pub fn update(&mut self, delta_time: f32, volume: f32) -> bool {
+        let short_alpha = (delta_time / SECTION_SHORT_SECONDS).min(1.0);
+        let long_alpha = (delta_time / SECTION_LONG_SECONDS).min(1.0);
+        self.short_energy += (volume - self.short_energy) * short_alpha;
+        self.long_energy += (volume - self.long_energy) * long_alpha;
+
+        let floor = 0.01; // Don't read noise-floor wobble as a section change
+        let ratio = (self.short_energy + floor) / (self.long_energy + floor);
+        !(1.0 / SECTION_CHANGE_RATIO..=SECTION_CHANGE_RATIO).contains(&ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(effect: &str, seconds: Option<f32>, bars: Option<u32>, until_section_change: bool) -> ProgramEntry {
+        ProgramEntry { effect: effect.to_string(), seconds, bars, until_section_change }
+    }
+
+    /// Run the program for `seconds` of 60 fps frames, returning the effect after each advance
+    fn run(program: &mut EffectProgram, seconds: f32, frame: impl Fn(usize) -> AudioFrame) -> Vec<Option<String>> {
+        let mut advances = Vec::new();
+        for index in 0..(seconds * 60.0).round() as usize {
+            if program.update(1.0 / 60.0, &frame(index)) {
+                advances.push(program.current_effect().map(str::to_string));
+            }
+        }
+        advances
+    }
+
+    #[test]
+    fn test_invalid_durations_are_rejected() {
+        for seconds in [0.0, -5.0, f32::NAN, f32::INFINITY] {
+            let error = EffectProgram::new(vec![entry("auto", Some(seconds), None, false)], true).unwrap_err();
+            assert!(error.to_string().contains("seconds must be a positive number"), "{}", error);
+        }
+        assert!(EffectProgram::new(vec![entry("auto", None, Some(0), false)], true).is_err());
+        assert!(EffectProgram::new(vec![entry("auto", Some(1.0), Some(4), false)], true).is_err());
+        assert!(EffectProgram::new(vec![entry("no_such_effect", Some(1.0), None, false)], true).is_err());
+        assert!(EffectProgram::new(Vec::new(), true).is_err());
+    }
+
+    #[test]
+    fn test_entries_step_by_time_and_beats_then_repeat() {
+        let entries = vec![
+            entry("llama_plasma", Some(1.0), None, false),
+            entry("psychedelic_tunnel", None, Some(1), false),
+            entry("auto", Some(0.5), None, false),
+        ];
+        let mut program = EffectProgram::new(entries.clone(), true).unwrap();
+        assert_eq!(program.current_effect(), Some("llama_plasma"));
+
+        let silent = |_| AudioFrame::default();
+        // A beat onset every 10 frames, each held for 3 frames: a bar is 4 onsets, not 12 frames
+        let beats = |index: usize| AudioFrame { beat_detected: index % 10 < 3, ..AudioFrame::default() };
+        assert_eq!(run(&mut program, 1.05, silent), [Some("psychedelic_tunnel".to_string())]);
+        assert!(run(&mut program, 0.5, beats).is_empty());
+        assert_eq!(run(&mut program, 0.2, beats), [None]);
+        // Repeating wraps back to the first entry
+        assert_eq!(run(&mut program, 0.55, silent), [Some("llama_plasma".to_string())]);
+
+        // Without repeat the last entry holds
+        let mut once = EffectProgram::new(entries, false).unwrap();
+        assert_eq!(run(&mut once, 10.0, beats).len(), 2);
+        assert_eq!(once.current_effect(), None);
+    }
+
+    #[test]
+    fn test_section_changes_advance_after_the_minimum_time() {
+        let entries = vec![
+            entry("fractal_madness", Some(30.0), None, false),
+            entry("psychedelic_tunnel", None, None, true),
+        ];
+        let mut program = EffectProgram::new(entries, true).unwrap();
+        let quiet = |_| AudioFrame { volume: 0.1, ..AudioFrame::default() };
+        let loud = |_| AudioFrame { volume: 0.9, ..AudioFrame::default() };
+
+        // Settle on a quiet passage, which doesn't read as a new section
+        assert_eq!(run(&mut program, 30.1, quiet).len(), 1);
+        assert!(run(&mut program, 12.0, quiet).is_empty());
+        // The drop does, within a couple of seconds
+        assert_eq!(run(&mut program, 2.0, loud), [Some("fractal_madness".to_string())]);
+    }
+}
@@ -1,10 +1,60 @@
 use crate::audio::AudioFrame;
-use std::collections::HashMap;
+use crate::audio::prescan::AnalysisStatistics;
+use std::collections::{HashMap, HashSet};
+use super::program::EffectProgram;
+use super::recommendation::LiveRecommender;
+
+/// Extra target weight the track's profile effect keeps in auto mode
+const PROFILE_BIAS: f32 = 0.25;
+
+/// Extra target weight each live-recommended effect gets in auto mode
+const RECOMMENDATION_BIAS: f32 = 0.2;
+
+/// Slow, smooth effects that reduced-motion auto-blend favours...
+pub const GENTLE_EFFECTS: [&str; 2] = ["llama_plasma", "parametric_waves"];
+/// ...and the fast-moving ones it holds back
+pub const FAST_EFFECTS: [&str; 3] = ["psychedelic_tunnel", "particle_swarm", "fractal_madness"];
+
+const GENTLE_BIAS: f32 = 0.2;                // Extra target weight for each gentle effect
+const REDUCED_MOTION_DAMPING: f32 = 0.3;     // Share of their target weight fast effects keep
+const REDUCED_MOTION_TRANSITIONS: f32 = 0.3; // Transition speed multiplier on top of `transition_speed`
+const REDUCED_MOTION_BEATS: f32 = 0.25;      // Share of the beat-driven boosts and pulses kept
+
+/// Per-effect transition speed (exponential rate per second) before `EffectConfig::transition_speed`
+pub const DEFAULT_TRANSITION_SPEED: f32 = 4.0;
+
+/// Effect names in number-key order (1-7)
+pub const EFFECT_NAMES: [&str; 7] = [
+    "llama_plasma",
+    "geometric_kaleidoscope",
+    "psychedelic_tunnel",
+    "particle_swarm",
+    "fractal_madness",
+    "spectralizer_bars",
+    "parametric_waves",
+];
+
+/// Developer views (key 8): selectable like any effect, but never chosen by auto-blend
+pub const DEBUG_EFFECT_NAMES: [&str; 1] = [
+    "flux_debug",
+];
+
+/// Parse an effect name for `--effect` (case-insensitive, `-` or `_`)
+#[allow(dead_code)]
+pub fn parse_effect_name(name: &str) -> anyhow::Result<&'static str> {
+    let normalized = name.trim().to_lowercase().replace('-', "_");
+    EFFECT_NAMES.iter()
+        .chain(&DEBUG_EFFECT_NAMES)
+        .find(|effect| **effect == normalized)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Unknown effect '{}'. Use: {}, {}", name,
+                                       EFFECT_NAMES.join(", "), DEBUG_EFFECT_NAMES.join(", ")))
+}
 
 /// Psychedelic Effect Manager - Handles dynamic effect selection and blending
 /// Based on musical characteristics and user preferences
 pub struct PsychedelicManager {
-    /// Current effect weights (0.0 to 1.0)
+    /// Current effect weights (0.0 to `config.weight_ceiling`)
     effect_weights: HashMap<String, f32>,
 
     /// Transition speeds for each effect
@@ -21,6 +71,23 @@ pub struct PsychedelicManager {
 
     /// Configuration
     config: EffectConfig,
+
+    /// Effect favoured by the loaded track's prescan profile (see `apply_profile`)
+    profile_effect: Option<String>,
+
+    /// Scripted effect sequence that overrides auto-blend (see `set_program`)
+    program: Option<EffectProgram>,
+
+    /// Effects recommended from the live features so far (see `set_live_recommendation`)
+    recommender: Option<LiveRecommender>,
+}
+
+/// Starting visuals picked from a track's prescan statistics
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct ProfileDefaults {
+    pub effect: &'static str,
+    pub palette_index: f32,
 }
 
 #[derive(Clone)]
@@ -37,11 +104,32 @@ pub struct EffectConfig {
     /// Smoothing factor for transitions (0.0 to 1.0, higher = smoother)
     pub transition_smoothing: f32,
 
+    /// Multiplier on every effect's transition speed (default 1.0; 0.1 crossfades over several
+    /// seconds, 4.0 switches almost instantly). Must be positive; see `set_transition_speed_multiplier`.
+    pub transition_speed: f32,
+
     /// Enable automatic effect switching based on music
     pub auto_switch: bool,
 
     /// Manual effect override (None for auto, Some(effect_name) for manual)
     pub manual_override: Option<String>,
+
+    /// Highest weight any effect can reach, and the most the blended weights may add up to
+    /// (default 1.0, the 0-1 range the shader's `*_weight` uniforms mix with). Values above
+    /// 1.0 deliberately overdrive effects.
+    pub weight_ceiling: f32,
+
+    /// Effects auto-blend never selects (see `set_effect_enabled`); they can still be
+    /// picked manually
+    pub disabled_effects: HashSet<String>,
+
+    /// "Quiet = busy": auto-blend and intensity read each reactive feature as `1 - value`,
+    /// so silence lights every effect up and loud passages calm down to the plasma base
+    pub invert_response: bool,
+
+    /// Accessibility mode for motion-sensitive viewers: fast effects are held back in favour
+    /// of `GENTLE_EFFECTS`, crossfades slow down and beats push the blend and intensity less
+    pub reduced_motion: bool,
 }
 
 impl Default for EffectConfig {
@@ -51,12 +139,23 @@ impl Default for EffectConfig {
             base_intensity: 1.0,
             beat_sensitivity: 0.9, // Higher beat sensitivity
             transition_smoothing: 0.5, // Less smoothing = more responsive
+            transition_speed: 1.0,
             auto_switch: true,
             manual_override: None,
+            weight_ceiling: 1.0,
+            disabled_effects: HashSet::new(),
+            invert_response: false,
+            reduced_motion: false,
         }
     }
 }
 
+impl Default for PsychedelicManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PsychedelicManager {
     pub fn new() -> Self {
         let mut effect_weights = HashMap::new();
@@ -64,20 +163,11 @@ impl PsychedelicManager {
         let mut target_weights = HashMap::new();
         let mut intensity_scalers = HashMap::new();
 
-        // Initialize all effects
-        let effects = vec![
-            "llama_plasma",
-            "geometric_kaleidoscope",
-            "psychedelic_tunnel",
-            "particle_swarm",
-            "fractal_madness",
-            "spectralizer_bars",
-            "parametric_waves"
-        ];
-
-        for effect in effects {
+        // Initialize all effects (auto-blend only ever raises the targets it knows about,
+        // so the debug views stay at zero unless picked manually)
+        for effect in EFFECT_NAMES.iter().chain(&DEBUG_EFFECT_NAMES) {
             effect_weights.insert(effect.to_string(), 0.0);
-            transition_speeds.insert(effect.to_string(), 4.0); // Faster transitions for real-time response
+            transition_speeds.insert(effect.to_string(), DEFAULT_TRANSITION_SPEED);
             target_weights.insert(effect.to_string(), 0.0);
             intensity_scalers.insert(effect.to_string(), 1.0);
         }
@@ -93,12 +183,36 @@ impl PsychedelicManager {
             intensity_scalers,
             time: 0.0,
             config: EffectConfig::default(),
+            profile_effect: None,
+            program: None,
+            recommender: None,
         }
     }
 
     pub fn update(&mut self, delta_time: f32, audio_frame: &AudioFrame) {
         self.time += delta_time;
 
+        if let Some(program) = &mut self.program {
+            if program.update(delta_time, audio_frame) {
+                let effect = program.current_effect().map(str::to_string);
+                self.set_manual_effect(effect);
+            }
+        }
+
+        if let Some(recommender) = &mut self.recommender {
+            if recommender.update(delta_time, audio_frame) {
+                log::info!("🧭 Live recommendation: {}", recommender.recommended().join(", "));
+            }
+        }
+
+        let inverted;
+        let audio_frame = if self.config.invert_response {
+            inverted = inverted_response(audio_frame);
+            &inverted
+        } else {
+            audio_frame
+        };
+
         if self.config.auto_switch && self.config.manual_override.is_none() {
             self.analyze_and_set_targets(audio_frame);
         }
@@ -168,13 +282,50 @@ impl PsychedelicManager {
             *self.target_weights.get_mut("parametric_waves").unwrap() = parametric_weight;
         }
 
+        // Keep the track's profile effect in the mix so its character carries through
+        if let Some(weight) = self.profile_effect.as_ref().and_then(|effect| self.target_weights.get_mut(effect)) {
+            *weight += PROFILE_BIAS;
+        }
+
+        // Lean toward what an offline analysis of the track heard so far would recommend
+        if let Some(recommender) = &self.recommender {
+            for effect in recommender.recommended() {
+                if let Some(weight) = self.target_weights.get_mut(*effect) {
+                    *weight += RECOMMENDATION_BIAS;
+                }
+            }
+        }
+
+        // Curated out by the user
+        for effect in &self.config.disabled_effects {
+            if let Some(weight) = self.target_weights.get_mut(effect) {
+                *weight = 0.0;
+            }
+        }
+
+        if self.config.reduced_motion {
+            for effect in FAST_EFFECTS {
+                if let Some(weight) = self.target_weights.get_mut(effect) {
+                    *weight *= REDUCED_MOTION_DAMPING;
+                }
+            }
+            for effect in GENTLE_EFFECTS.iter().filter(|effect| !self.config.disabled_effects.contains(**effect)) {
+                if let Some(weight) = self.target_weights.get_mut(*effect) {
+                    *weight += GENTLE_BIAS;
+                }
+            }
+        }
+
         // Beat-driven effect boosting
         if audio_frame.beat_strength > 0.5 {
-            let beat_boost = (audio_frame.beat_strength - 0.5) * 2.0 * self.config.beat_sensitivity;
+            let beat_response = if self.config.reduced_motion { REDUCED_MOTION_BEATS } else { 1.0 };
+            let beat_boost = (audio_frame.beat_strength - 0.5) * 2.0 * self.config.beat_sensitivity * beat_response;
 
             // Find the currently dominant effect and boost it
+            let disabled = &self.config.disabled_effects;
             let dominant_effect_name = self.target_weights.iter()
-                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .filter(|(name, _)| !disabled.contains(*name))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
                 .map(|(name, _)| name.clone());
 
             if let Some(effect_name) = dominant_effect_name {
@@ -185,23 +336,30 @@ impl PsychedelicManager {
         }
 
         // Clamp all weights to reasonable ranges
+        let ceiling = self.config.weight_ceiling;
         for (_, weight) in self.target_weights.iter_mut() {
-            *weight = weight.clamp(0.0, 1.5);
+            *weight = weight.clamp(0.0, ceiling);
         }
     }
 
     fn update_transitions(&mut self, delta_time: f32) {
+        let ceiling = self.config.weight_ceiling;
+        let multiplier = self.config.transition_speed
+            * if self.config.reduced_motion { REDUCED_MOTION_TRANSITIONS } else { 1.0 };
         for (effect_name, current_weight) in self.effect_weights.iter_mut() {
             if let Some(target_weight) = self.target_weights.get(effect_name) {
                 if let Some(transition_speed) = self.transition_speeds.get(effect_name) {
                     let diff = target_weight - *current_weight;
 
                     // Enhanced smoothing with exponential decay
-                    let smoothing_factor = 1.0 - (-transition_speed * delta_time).exp();
+                    let smoothing_factor = 1.0 - (-transition_speed * multiplier * delta_time).exp();
                     let change = diff * smoothing_factor * self.config.transition_smoothing;
 
                     *current_weight += change;
-                    *current_weight = current_weight.clamp(0.0, 1.5); // Lower ceiling for smoother visuals
+                    *current_weight = current_weight.clamp(0.0, ceiling);
+                    if !current_weight.is_finite() {
+                        *current_weight = 0.0; // A NaN frame would otherwise stick in the blend forever
+                    }
                 }
             }
         }
@@ -209,9 +367,10 @@ impl PsychedelicManager {
 
     fn update_intensity_scalers(&mut self, audio_frame: &AudioFrame) {
         // Global intensity based on volume and beat strength
+        let beat_response = if self.config.reduced_motion { REDUCED_MOTION_BEATS } else { 1.0 };
         let global_intensity = self.config.base_intensity *
             (0.7 + audio_frame.volume * 0.3) *
-            (1.0 + audio_frame.beat_strength * 0.4);
+            (1.0 + audio_frame.beat_strength * 0.4 * beat_response);
 
         // Per-effect intensity adjustments
         for (effect_name, scaler) in self.intensity_scalers.iter_mut() {
@@ -240,11 +399,79 @@ impl PsychedelicManager {
         }
     }
 
-    /// Get current effect weights for the shader
+    /// See `EffectConfig::invert_response`
+    #[allow(dead_code)]
+    pub fn set_invert_response(&mut self, inverted: bool) {
+        self.config.invert_response = inverted;
+    }
+
+    #[allow(dead_code)]
+    pub fn is_response_inverted(&self) -> bool {
+        self.config.invert_response
+    }
+
+    /// How fast one effect's weight follows its target, as an exponential rate per second
+    /// (default `DEFAULT_TRANSITION_SPEED`; scaled by `EffectConfig::transition_speed`)
+    #[allow(dead_code)]
+    pub fn set_transition_speed(&mut self, effect_name: &str, speed: f32) -> anyhow::Result<()> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(anyhow::anyhow!("Transition speed for {} must be positive, got {}", effect_name, speed));
+        }
+        let current = self.transition_speeds.get_mut(effect_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown effect '{}'", effect_name))?;
+        *current = speed;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn transition_speed(&self, effect_name: &str) -> Option<f32> {
+        self.transition_speeds.get(effect_name).copied()
+    }
+
+    /// See `EffectConfig::reduced_motion`
+    #[allow(dead_code)]
+    pub fn set_reduced_motion(&mut self, enabled: bool) {
+        self.config.reduced_motion = enabled;
+    }
+
+    #[allow(dead_code)]
+    pub fn is_reduced_motion(&self) -> bool {
+        self.config.reduced_motion
+    }
+
+    /// See `EffectConfig::transition_speed`
+    #[allow(dead_code)]
+    pub fn set_transition_speed_multiplier(&mut self, multiplier: f32) -> anyhow::Result<()> {
+        if !(multiplier.is_finite() && multiplier > 0.0) {
+            return Err(anyhow::anyhow!("Transition speed multiplier must be positive, got {}", multiplier));
+        }
+        self.config.transition_speed = multiplier;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn transition_speed_multiplier(&self) -> f32 {
+        self.config.transition_speed
+    }
+
+    /// Get current (unnormalized) effect weights
+    #[allow(dead_code)]
     pub fn get_effect_weights(&self) -> &HashMap<String, f32> {
         &self.effect_weights
     }
 
+    /// Effect weights as the shader blends them: the current weights, scaled down together
+    /// when their sum exceeds `weight_ceiling` so stacked effects can't over-saturate
+    #[allow(dead_code)]
+    pub fn get_blend_weights(&self) -> HashMap<String, f32> {
+        let total: f32 = self.effect_weights.values().sum();
+        let ceiling = self.config.weight_ceiling;
+        let scale = if total > ceiling { ceiling / total } else { 1.0 };
+        self.effect_weights.iter()
+            .map(|(effect, weight)| (effect.clone(), weight * scale))
+            .collect()
+    }
+
     /// Get current intensity scalers for the shader
     pub fn get_intensity_scalers(&self) -> &HashMap<String, f32> {
         &self.intensity_scalers
@@ -262,6 +489,91 @@ impl PsychedelicManager {
         }
     }
 
+    /// Include or exclude an effect from auto-blend. Disabling drops it out of the blend
+    /// immediately (unless it is the manual pick, which stays as chosen).
+    #[allow(dead_code)]
+    pub fn set_effect_enabled(&mut self, effect_name: &str, enabled: bool) {
+        if enabled {
+            self.config.disabled_effects.remove(effect_name);
+            return;
+        }
+        if self.config.disabled_effects.insert(effect_name.to_string())
+            && self.config.manual_override.as_deref() != Some(effect_name)
+        {
+            for weights in [&mut self.effect_weights, &mut self.target_weights] {
+                if let Some(weight) = weights.get_mut(effect_name) {
+                    *weight = 0.0;
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_effect_enabled(&self, effect_name: &str) -> bool {
+        !self.config.disabled_effects.contains(effect_name)
+    }
+
+    /// Run a scripted effect program (or `None` to stop); its first entry starts immediately
+    /// and later entries crossfade in with the usual transitions
+    #[allow(dead_code)]
+    pub fn set_program(&mut self, program: Option<EffectProgram>) {
+        let effect = program.as_ref().and_then(|p| p.current_effect()).map(str::to_string);
+        self.program = program;
+        if self.program.is_some() {
+            self.set_manual_effect(effect);
+        }
+    }
+
+    /// Bias auto-blend toward the effects `audio-analyzer` would recommend, judged from the live
+    /// features once a few seconds have been heard (no prescan needed). Turning it on starts
+    /// the measurement afresh.
+    #[allow(dead_code)]
+    pub fn set_live_recommendation(&mut self, enabled: bool) {
+        self.recommender = enabled.then(LiveRecommender::default);
+    }
+
+    /// Effects the live recommendation currently favours (empty when off or still listening)
+    #[allow(dead_code)]
+    pub fn live_recommendation(&self) -> &[&'static str] {
+        self.recommender.as_ref().map_or(&[], |recommender| recommender.recommended())
+    }
+
+    /// Forget the live features heard so far, e.g. when a new track starts
+    #[allow(dead_code)]
+    pub fn reset_live_recommendation(&mut self) {
+        if let Some(recommender) = &mut self.recommender {
+            recommender.reset();
+        }
+    }
+
+    /// Pick a starting effect and palette from a prescanned track's statistics.
+    ///
+    /// The chosen effect starts at full weight and keeps a small bias in auto mode; the
+    /// returned palette is for the caller to apply to the graphics engine.
+    #[allow(dead_code)]
+    pub fn apply_profile(&mut self, statistics: &AnalysisStatistics) -> ProfileDefaults {
+        let defaults = match (statistics.energy_profile.as_str(), statistics.dominant_frequency_range.as_str()) {
+            ("Dynamic", _) => ProfileDefaults { effect: "fractal_madness", palette_index: 4.0 }, // Purple Haze
+            (_, "Bass-Heavy") => ProfileDefaults { effect: "llama_plasma", palette_index: 2.0 }, // Warm Sunset
+            (_, "Treble-Focused") => ProfileDefaults { effect: "geometric_kaleidoscope", palette_index: 1.0 }, // Neon Cyber
+            ("High", _) => ProfileDefaults { effect: "spectralizer_bars", palette_index: 5.0 }, // Electric Green
+            ("Low", _) => ProfileDefaults { effect: "psychedelic_tunnel", palette_index: 3.0 }, // Deep Ocean
+            _ => ProfileDefaults { effect: "llama_plasma", palette_index: 0.0 }, // Rainbow
+        };
+
+        // A disabled profile effect still sets the palette, but auto-blend fills in the visuals
+        let start = if self.is_effect_enabled(defaults.effect) { 1.0 } else { 0.0 };
+        for (name, weight) in self.effect_weights.iter_mut() {
+            *weight = if name == defaults.effect { start } else { 0.0 };
+        }
+        for (name, target) in self.target_weights.iter_mut() {
+            *target = if name == defaults.effect { start } else { 0.0 };
+        }
+        self.profile_effect = Some(defaults.effect.to_string());
+
+        defaults
+    }
+
     /// Get configuration for external modification
     pub fn config_mut(&mut self) -> &mut EffectConfig {
         &mut self.config
@@ -284,10 +596,179 @@ impl PsychedelicManager {
 
         if let Some(manual) = &self.config.manual_override {
             info.push_str(&format!("Manual Override: {}\n", manual));
+        } else if self.config.invert_response {
+            info.push_str("Auto Mode (inverted response)\n");
         } else {
             info.push_str("Auto Mode\n");
         }
 
         info
     }
-}
\ No newline at end of file
+}
+
+/// A copy of `frame` with every feature the auto-blend gates on flipped to `1 - value`
+/// (tempo, spectra and the beat flag are left alone)
+fn inverted_response(frame: &AudioFrame) -> AudioFrame {
+    let invert = |value: f32| 1.0 - value.clamp(0.0, 1.0);
+    let mut inverted = frame.clone();
+    inverted.frequency_bands.sub_bass = invert(frame.frequency_bands.sub_bass);
+    inverted.frequency_bands.bass = invert(frame.frequency_bands.bass);
+    inverted.frequency_bands.mid = invert(frame.frequency_bands.mid);
+    inverted.frequency_bands.treble = invert(frame.frequency_bands.treble);
+    inverted.frequency_bands.presence = invert(frame.frequency_bands.presence);
+    inverted.volume = invert(frame.volume);
+    inverted.beat_strength = invert(frame.beat_strength);
+    inverted.pitch_confidence = invert(frame.pitch_confidence);
+    inverted.spectral_rolloff = invert(frame.spectral_rolloff);
+    inverted.onset_strength = invert(frame.onset_strength);
+    inverted.zero_crossing_rate = invert(frame.zero_crossing_rate);
+    inverted.dynamic_range = invert(frame.dynamic_range);
+    inverted.spectral_flux = invert(frame.spectral_flux);
+    inverted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frame that trips every auto-blend rule at once
+    fn loud_frame() -> AudioFrame {
+        let mut frame = AudioFrame::default();
+        frame.frequency_bands.sub_bass = 1.0;
+        frame.frequency_bands.bass = 1.0;
+        frame.frequency_bands.mid = 1.0;
+        frame.frequency_bands.treble = 1.0;
+        frame.frequency_bands.presence = 1.0;
+        frame.volume = 1.0;
+        frame.pitch_confidence = 1.0;
+        frame.spectral_rolloff = 1.0;
+        frame.onset_strength = 1.0;
+        frame.zero_crossing_rate = 1.0;
+        frame.dynamic_range = 1.0;
+        frame.spectral_flux = 1.0;
+        frame.beat_strength = 1.0;
+        frame
+    }
+
+    #[test]
+    fn test_blended_weights_stay_bounded() {
+        for ceiling in [1.0, 1.5] {
+            let mut manager = PsychedelicManager::new();
+            manager.config_mut().weight_ceiling = ceiling;
+            for _ in 0..600 {
+                manager.update(1.0 / 60.0, &loud_frame());
+            }
+
+            assert!(manager.get_effect_weights().values().all(|w| (0.0..=ceiling).contains(w)));
+            let total: f32 = manager.get_blend_weights().values().sum();
+            assert!(total <= ceiling + 1e-4, "blended weights sum to {} with ceiling {}", total, ceiling);
+        }
+    }
+
+    #[test]
+    fn test_disabled_effects_stay_out_of_auto_blend() {
+        let mut manager = PsychedelicManager::new();
+        manager.set_effect_enabled("llama_plasma", false);
+        manager.set_effect_enabled("particle_swarm", false);
+
+        for _ in 0..600 {
+            manager.update(1.0 / 60.0, &loud_frame());
+            let weights = manager.get_effect_weights();
+            assert_eq!(weights["llama_plasma"], 0.0);
+            assert_eq!(weights["particle_swarm"], 0.0);
+        }
+        assert!(manager.get_effect_weights()["geometric_kaleidoscope"] > 0.0);
+
+        // Manual selection still works for a disabled effect
+        manager.set_manual_effect(Some("particle_swarm".to_string()));
+        for _ in 0..60 {
+            manager.update(1.0 / 60.0, &loud_frame());
+        }
+        assert!(manager.get_effect_weights()["particle_swarm"] > 0.0);
+    }
+
+    #[test]
+    fn test_inverted_response_livens_up_silence() {
+        let busy = |manager: &PsychedelicManager| {
+            manager.get_effect_weights().iter().filter(|(name, &weight)| *name != "llama_plasma" && weight > 0.05).count()
+        };
+        let run = |inverted: bool, frame: &AudioFrame| {
+            let mut manager = PsychedelicManager::new();
+            manager.set_invert_response(inverted);
+            for _ in 0..300 {
+                manager.update(1.0 / 60.0, frame);
+            }
+            manager
+        };
+
+        let silence = AudioFrame::default();
+        assert_eq!(busy(&run(false, &silence)), 0);
+        assert_eq!(busy(&run(true, &loud_frame())), 0);
+        assert!(busy(&run(true, &silence)) >= 4);
+        assert!(busy(&run(false, &loud_frame())) >= 4);
+
+        // Dimmer when loud, too
+        let loud_scaler = run(true, &loud_frame()).get_intensity_scalers()["psychedelic_tunnel"];
+        let quiet_scaler = run(true, &silence).get_intensity_scalers()["psychedelic_tunnel"];
+        assert!(loud_scaler < quiet_scaler);
+    }
+
+    #[test]
+    fn test_slower_transitions_crossfade_over_seconds() {
+        // Seconds for a manually picked effect to reach most of its weight
+        let seconds_to_blend_in = |multiplier: f32| {
+            let mut manager = PsychedelicManager::new();
+            manager.set_transition_speed_multiplier(multiplier).unwrap();
+            manager.set_manual_effect(Some("psychedelic_tunnel".to_string()));
+            let mut frames = 0;
+            while manager.get_effect_weights()["psychedelic_tunnel"] < 0.9 {
+                manager.update(1.0 / 60.0, &AudioFrame::default());
+                frames += 1;
+            }
+            frames as f32 / 60.0
+        };
+
+        assert!(seconds_to_blend_in(1.0) < 1.5);
+        assert!(seconds_to_blend_in(0.1) > 5.0);
+
+        let mut manager = PsychedelicManager::new();
+        assert!(manager.set_transition_speed_multiplier(0.0).is_err());
+        assert!(manager.set_transition_speed("llama_plasma", -1.0).is_err());
+        assert!(manager.set_transition_speed("no_such_effect", 1.0).is_err());
+        manager.set_transition_speed("llama_plasma", 0.5).unwrap();
+        assert_eq!(manager.transition_speed("llama_plasma"), Some(0.5));
+    }
+
+    #[test]
+    fn test_reduced_motion_favours_gentle_effects_but_still_responds() {
+        let run = |reduced: bool, frame: &AudioFrame| {
+            let mut manager = PsychedelicManager::new();
+            manager.set_reduced_motion(reduced);
+            for _ in 0..600 {
+                manager.update(1.0 / 60.0, frame);
+            }
+            manager
+        };
+        let share = |manager: &PsychedelicManager, effects: &[&str]| {
+            let weights = manager.get_blend_weights();
+            effects.iter().map(|effect| weights[*effect]).sum::<f32>()
+        };
+
+        let normal = run(false, &loud_frame());
+        let reduced = run(true, &loud_frame());
+        assert!(share(&reduced, &FAST_EFFECTS) < share(&normal, &FAST_EFFECTS) * 0.5);
+        assert!(share(&reduced, &GENTLE_EFFECTS) > share(&normal, &GENTLE_EFFECTS));
+        // Still following the music: silence is calmer than a loud passage
+        let quiet = run(true, &AudioFrame::default());
+        assert!(quiet.get_effect_weights()["llama_plasma"] < reduced.get_effect_weights()["llama_plasma"]);
+
+        // And crossfades take longer
+        let mut manager = PsychedelicManager::new();
+        manager.set_reduced_motion(true);
+        manager.set_manual_effect(Some("parametric_waves".to_string()));
+        for _ in 0..60 {
+            manager.update(1.0 / 60.0, &AudioFrame::default());
+        }
+        assert!(manager.get_effect_weights()["parametric_waves"] < 0.5);
+    }
+}
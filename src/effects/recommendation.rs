@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+use crate::audio::AudioFrame;
+
+/// Bass + sub-bass mean above which plasma is recommended
+pub const BASS_ENERGY_THRESHOLD: f32 = 0.3;
+/// Mean pitch confidence above which the kaleidoscope and parametric waves are recommended
+pub const PITCH_CONFIDENCE_THRESHOLD: f32 = 0.6;
+/// Music complexity above which fractal madness is recommended
+pub const COMPLEXITY_THRESHOLD: f32 = 0.5;
+/// Beat consistency above which the particle swarm is recommended
+pub const RHYTHMIC_CONSISTENCY_THRESHOLD: f32 = 0.7;
+
+/// Seconds of live features gathered before the first recommendation
+pub const DEFAULT_WARMUP_SECONDS: f32 = 5.0;
+
+const REFRESH_SECONDS: f32 = 1.0; // How often the live recommendation is re-evaluated
+const MAX_BEATS: usize = 64;      // Recent beats kept for the consistency measure
+
+/// The summary statistics effect recommendations are made from: the same ones whether they
+/// come from a whole-track analysis (`audio-analyzer`) or from live playback
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackCharacter {
+    /// Mean bass + sub-bass level
+    pub bass_energy: f32,
+    /// Mean pitch confidence
+    pub pitch_confidence: f32,
+    /// See `music_complexity`
+    pub music_complexity: f32,
+    /// See `beat_consistency`
+    pub rhythmic_consistency: f32,
+}
+
+#[allow(dead_code)]
+impl TrackCharacter {
+    /// Effects that suit the track, in `EFFECT_NAMES` order
+    pub fn recommended_effects(&self) -> Vec<&'static str> {
+        let mut effects = Vec::new();
+        if self.bass_energy > BASS_ENERGY_THRESHOLD {
+            effects.push("llama_plasma");
+        }
+        if self.pitch_confidence > PITCH_CONFIDENCE_THRESHOLD {
+            effects.push("geometric_kaleidoscope");
+            effects.push("parametric_waves");
+        }
+        if self.music_complexity > COMPLEXITY_THRESHOLD {
+            effects.push("fractal_madness");
+        }
+        if self.rhythmic_consistency > RHYTHMIC_CONSISTENCY_THRESHOLD {
+            effects.push("particle_swarm");
+        }
+        effects
+    }
+}
+
+/// Complexity (0-1) from how much the spectral flux varies and how unpitched the track is
+#[allow(dead_code)]
+pub fn music_complexity(spectral_flux_std_dev: f32, pitch_confidence_mean: f32) -> f32 {
+    (spectral_flux_std_dev * 2.0 + (1.0 - pitch_confidence_mean)).clamp(0.0, 1.0)
+}
+
+/// How regular the beats' timing is (0-1, higher is steadier), from their timestamps in seconds
+#[allow(dead_code)]
+pub fn beat_consistency(beat_times: impl IntoIterator<Item = f32>) -> f32 {
+    let times: Vec<f32> = beat_times.into_iter().collect();
+    let intervals: Vec<f32> = times.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    if intervals.len() < 2 {
+        return 0.0;
+    }
+    let mean_interval = intervals.iter().sum::<f32>() / intervals.len() as f32;
+    let interval_variance = intervals.iter()
+        .map(|&interval| (interval - mean_interval).powi(2))
+        .sum::<f32>() / intervals.len() as f32;
+    1.0 / (1.0 + interval_variance)
+}
+
+/// Builds a `TrackCharacter` from live frames, so auto-blend can lean toward the effects a
+/// whole-track analysis would recommend without a prescan. Nothing is recommended until
+/// `warmup` seconds have been heard; after that the recommendation is refreshed each second
+/// from everything heard so far.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct LiveRecommender {
+    warmup: f32,
+    elapsed: f32,
+    since_refresh: f32,
+    frames: u32,
+    bass_sum: f64,
+    pitch_sum: f64,
+    flux_mean: f64, // Running mean and sum of squared deviations (Welford)
+    flux_m2: f64,
+    beat_times: VecDeque<f32>,
+    recommended: Vec<&'static str>,
+}
+
+impl Default for LiveRecommender {
+    fn default() -> Self {
+        Self::new(DEFAULT_WARMUP_SECONDS)
+    }
+}
+
+#[allow(dead_code)]
+impl LiveRecommender {
+    pub fn new(warmup: f32) -> Self {
+        Self {
+            warmup: warmup.max(0.0),
+            elapsed: 0.0,
+            since_refresh: 0.0,
+            frames: 0,
+            bass_sum: 0.0,
+            pitch_sum: 0.0,
+            flux_mean: 0.0,
+            flux_m2: 0.0,
+            beat_times: VecDeque::with_capacity(MAX_BEATS),
+            recommended: Vec::new(),
+        }
+    }
+
+    /// Forget the track so far (on a new track)
+    pub fn reset(&mut self) {
+        *self = Self::new(self.warmup);
+    }
+
+    /// Add one frame; returns true when the recommendation changed
+    pub fn update(&mut self, delta_time: f32, frame: &AudioFrame) -> bool {
+        self.elapsed += delta_time;
+        self.since_refresh += delta_time;
+        self.frames += 1;
+        self.bass_sum += (frame.frequency_bands.bass + frame.frequency_bands.sub_bass) as f64;
+        self.pitch_sum += frame.pitch_confidence as f64;
+        let flux = frame.spectral_flux as f64;
+        let delta = flux - self.flux_mean;
+        self.flux_mean += delta / self.frames as f64;
+        self.flux_m2 += delta * (flux - self.flux_mean);
+        if frame.beat_detected {
+            if self.beat_times.len() == MAX_BEATS {
+                self.beat_times.pop_front();
+            }
+            self.beat_times.push_back(self.elapsed);
+        }
+
+        if self.elapsed < self.warmup || self.since_refresh < REFRESH_SECONDS {
+            return false;
+        }
+        self.since_refresh = 0.0;
+        let recommended = self.character().recommended_effects();
+        if recommended == self.recommended {
+            return false;
+        }
+        self.recommended = recommended;
+        true
+    }
+
+    /// The statistics heard so far
+    pub fn character(&self) -> TrackCharacter {
+        let frames = self.frames.max(1) as f64;
+        let pitch_confidence = (self.pitch_sum / frames) as f32;
+        let flux_std_dev = (self.flux_m2 / frames).sqrt() as f32;
+        TrackCharacter {
+            bass_energy: (self.bass_sum / frames) as f32,
+            pitch_confidence,
+            music_complexity: music_complexity(flux_std_dev, pitch_confidence),
+            rhythmic_consistency: beat_consistency(self.beat_times.iter().copied()),
+        }
+    }
+
+    /// Effects currently recommended (empty during the warm-up)
+    pub fn recommended(&self) -> &[&'static str] {
+        &self.recommended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::FrequencyBands;
+    use crate::effects::PsychedelicManager;
+
+    #[test]
+    fn test_bass_heavy_track_leans_live_blend_toward_plasma() {
+        // A steady, mostly unpitched bass track with no beats detected
+        let frame = AudioFrame {
+            frequency_bands: FrequencyBands { sub_bass: 0.15, bass: 0.2, mid: 0.1, treble: 0.05, presence: 0.0 },
+            pitch_confidence: 0.1,
+            volume: 0.5,
+            ..AudioFrame::default()
+        };
+        let frame_time = 1.0 / 60.0;
+
+        let mut recommender = LiveRecommender::default();
+        let changes: Vec<_> = (0..360).filter(|_| recommender.update(frame_time, &frame)).collect();
+        // Recommended once, after the five-second warm-up
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0] >= 299);
+        // Unpitched reads as complex, as in the offline analysis
+        assert_eq!(recommender.recommended(), ["llama_plasma", "fractal_madness"]);
+
+        let mut plain = PsychedelicManager::new();
+        let mut live = PsychedelicManager::new();
+        live.set_live_recommendation(true);
+        for _ in 0..360 {
+            plain.update(frame_time, &frame);
+            live.update(frame_time, &frame);
+        }
+        assert_eq!(live.live_recommendation(), ["llama_plasma", "fractal_madness"]);
+        let weight = |manager: &PsychedelicManager| manager.get_blend_weights()["llama_plasma"];
+        assert!(weight(&live) > weight(&plain), "plasma {} live vs {} without", weight(&live), weight(&plain));
+
+        // Regular beats half a second apart are perfectly consistent
+        assert_eq!(beat_consistency((0..8).map(|beat| beat as f32 * 0.5)), 1.0);
+        assert_eq!(beat_consistency([1.0, 2.0]), 0.0);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::psychedelic_manager::{parse_effect_name, DEBUG_EFFECT_NAMES, EFFECT_NAMES};
+
+/// Records the blend weights the shader received each frame, as CSV:
+/// `time,llama_plasma,...,flux_debug`, with `time` the engine's visual clock in seconds.
+///
+/// The engine advances that clock by a fixed step per frame, so replaying the log with
+/// `WeightLog` reproduces the same weights on the same frames.
+#[allow(dead_code)]
+pub struct WeightRecorder {
+    writer: BufWriter<File>,
+    frames: usize,
+}
+
+impl WeightRecorder {
+    #[allow(dead_code)]
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut writer = BufWriter::new(File::create(path)
+            .with_context(|| format!("Failed to create weight log {}", path.display()))?);
+        let columns: Vec<&str> = EFFECT_NAMES.iter().chain(&DEBUG_EFFECT_NAMES).copied().collect();
+        writeln!(writer, "time,{}", columns.join(","))?;
+        Ok(Self { writer, frames: 0 })
+    }
+
+    /// Append one frame's weights (missing effects are written as 0)
+    #[allow(dead_code)]
+    pub fn record(&mut self, time: f32, weights: &HashMap<String, f32>) -> Result<()> {
+        write!(self.writer, "{}", time)?;
+        for effect in EFFECT_NAMES.iter().chain(&DEBUG_EFFECT_NAMES) {
+            write!(self.writer, ",{}", weights.get(*effect).copied().unwrap_or(0.0))?;
+        }
+        writeln!(self.writer)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flush the log, returning the number of frames written
+    #[allow(dead_code)]
+    pub fn finish(mut self) -> Result<usize> {
+        self.writer.flush()?;
+        Ok(self.frames)
+    }
+}
+
+/// A recorded weight log, looked up by the engine's visual clock
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct WeightLog {
+    effects: Vec<&'static str>,
+    frames: Vec<(f32, Vec<f32>)>,
+}
+
+impl WeightLog {
+    /// Load a log written by `WeightRecorder`. Columns may be in any order; effects the log
+    /// doesn't mention replay at zero.
+    #[allow(dead_code)]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read weight log {}", path.display()))?;
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+
+        let header = lines.next().ok_or_else(|| anyhow!("Weight log {} is empty", path.display()))?;
+        let mut columns = header.split(',').map(str::trim);
+        if columns.next() != Some("time") {
+            return Err(anyhow!("Weight log {} must start with a 'time' column", path.display()));
+        }
+        let effects = columns.map(parse_effect_name).collect::<Result<Vec<_>>>()?;
+
+        let mut frames: Vec<(f32, Vec<f32>)> = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let values = line.split(',')
+                .map(|cell| cell.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("Weight log line {}: {}", index + 2, e))?;
+            if values.len() != effects.len() + 1 {
+                return Err(anyhow!("Weight log line {}: expected {} values, found {}", index + 2, effects.len() + 1, values.len()));
+            }
+            if frames.last().is_some_and(|(time, _)| values[0] < *time) {
+                return Err(anyhow!("Weight log line {}: time goes backwards", index + 2));
+            }
+            frames.push((values[0], values[1..].to_vec()));
+        }
+        if frames.is_empty() {
+            return Err(anyhow!("Weight log {} has no frames", path.display()));
+        }
+
+        Ok(Self { effects, frames })
+    }
+
+    /// Weights of the last frame recorded at or before `time` (the first frame before the
+    /// log starts, the last one after it ends)
+    #[allow(dead_code)]
+    pub fn weights_at(&self, time: f32) -> HashMap<String, f32> {
+        // Tolerance for clock values that went through text and back
+        let index = self.frames.partition_point(|(frame_time, _)| *frame_time <= time + 1e-4).saturating_sub(1);
+        let mut weights: HashMap<String, f32> = EFFECT_NAMES.iter()
+            .chain(&DEBUG_EFFECT_NAMES)
+            .map(|effect| (effect.to_string(), 0.0))
+            .collect();
+        for (effect, weight) in self.effects.iter().zip(&self.frames[index].1) {
+            weights.insert(effect.to_string(), *weight);
+        }
+        weights
+    }
+
+    /// Recorded length in seconds
+    #[allow(dead_code)]
+    pub fn duration_seconds(&self) -> f32 {
+        self.frames.last().map_or(0.0, |(time, _)| *time)
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_recorded_weights_replay_frame_for_frame() {
+        let path = TempPath::file("weights.csv");
+        let frames: Vec<(f32, HashMap<String, f32>)> = (1..=120)
+            .map(|frame| {
+                let time = frame as f32 / 60.0; // Same clock steps as the engine
+                let weights = EFFECT_NAMES.iter()
+                    .enumerate()
+                    .map(|(i, effect)| (effect.to_string(), ((time * 3.0 + i as f32).sin() * 0.5 + 0.5) / 7.0))
+                    .collect();
+                (time, weights)
+            })
+            .collect();
+
+        let mut recorder = WeightRecorder::create(&path).unwrap();
+        for (time, weights) in &frames {
+            recorder.record(*time, weights).unwrap();
+        }
+        assert_eq!(recorder.finish().unwrap(), frames.len());
+
+        let log = WeightLog::load(&path).unwrap();
+        assert_eq!(log.len(), frames.len());
+        for (time, weights) in &frames {
+            let replayed = log.weights_at(*time);
+            for (effect, weight) in weights {
+                assert_eq!(replayed[effect], *weight, "{} at {}s", effect, time);
+            }
+            assert_eq!(replayed["flux_debug"], 0.0);
+        }
+    }
+}
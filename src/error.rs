@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failures of the core audio and graphics APIs that a caller may want to handle on their own
+/// (e.g. carry on without sound on `NoAudioDevice`). The binaries flatten them into `anyhow`
+/// with `?`; anything without a variant of its own arrives as `Other`.
+#[allow(dead_code)]
+#[derive(Debug, Error)]
+pub enum ArrveeError {
+    #[error("No audio output device available: {0}")]
+    NoAudioDevice(String),
+
+    /// Not an audio format (or codec variant) the decoder can read
+    #[error("Unsupported audio format in {}: {reason}", path.display())]
+    UnsupportedFormat { path: PathBuf, reason: String },
+
+    /// A supported format whose data is damaged or couldn't be decoded
+    #[error("Could not decode {}: {reason}", path.display())]
+    DecodeFailed { path: PathBuf, reason: String },
+
+    /// No usable adapter, surface or device
+    #[error("GPU initialization failed: {0}")]
+    GpuInit(String),
+
+    /// An Arrvee data file (e.g. `.arv`) that is truncated, has bad magic bytes or an unknown version
+    #[error("{} is corrupt: {reason}", path.display())]
+    FileCorrupt { path: PathBuf, reason: String },
+
+    #[error("Failed to open {}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ArrveeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::arv_format::ArvFormat;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_failures_can_be_matched_by_kind() {
+        let directory = TempPath::dir("error");
+        let garbage = directory.join("garbage.wav");
+        std::fs::write(&garbage, b"definitely not audio").unwrap();
+        let truncated = directory.join("truncated.arv");
+        std::fs::write(&truncated, b"ARVV").unwrap();
+
+        assert!(matches!(crate::audio::decode_file(&garbage), Err(ArrveeError::UnsupportedFormat { .. })));
+        assert!(matches!(crate::audio::decode_file(directory.join("missing.wav")), Err(ArrveeError::Io { .. })));
+        assert!(matches!(ArvFormat::load_arv(&truncated), Err(ArrveeError::FileCorrupt { .. })));
+
+        // Still flattens into anyhow for the binaries, keeping the kind for downcasting
+        let flattened: anyhow::Error = ArvFormat::load_arv(&truncated).unwrap_err().into();
+        assert!(flattened.downcast_ref::<ArrveeError>().is_some());
+    }
+}
@@ -9,9 +9,7 @@ use winit::{
     window::WindowBuilder,
 };
 
-mod graphics;
-mod audio;
-mod effects;
+use arrvee::{audio, graphics};
 
 use graphics::GraphicsEngine;
 use audio::AudioPlayback;
@@ -31,12 +29,62 @@ struct Args {
     /// Show developer overlay with analysis stats
     #[arg(long, short)]
     debug: bool,
+
+    /// Graphics backend: vulkan, metal, dx12, gl, or auto
+    #[arg(long, default_value = "auto")]
+    backend: String,
+
+    /// Start in borderless fullscreen, optionally on a specific monitor (`--fullscreen=1`)
+    #[arg(long, value_name = "MONITOR", num_args = 0..=1, require_equals = true)]
+    fullscreen: Option<Option<usize>>,
+
+    /// Window width in pixels (at least 320; default is the tool's usual size)
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Window height in pixels (at least 240)
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Window title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Seconds for a full master fade in/out (B to fade to black and back)
+    #[arg(long, default_value = "2.0")]
+    fade_time: f32,
+
+    /// Render a fixed uniforms dump (written with U) instead of audio-driven values
+    #[arg(long, value_name = "FILE")]
+    load_uniforms: Option<String>,
+
+    /// Open with the audio paused (visuals idle) until Space, e.g. while a screen recorder gets ready
+    #[arg(long, overrides_with = "autoplay")]
+    paused: bool,
+
+    /// Start playing as soon as the window opens (the default; overrides an earlier `--paused`)
+    #[arg(long, overrides_with = "paused")]
+    autoplay: bool,
+
+    /// Seek this many seconds into the track before starting
+    #[arg(long, value_name = "SECONDS")]
+    start_at: Option<f32>,
+
+    /// How slowly the BPM follows tempo changes: 0 takes the median of recent beats as-is,
+    /// values near 1 change slowly (CPU analysis)
+    #[arg(long, default_value_t = audio::tempo_detector::DEFAULT_BPM_SMOOTHING)]
+    bpm_smoothing: f32,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     let args = Args::parse();
+    graphics::crash_report::install("arrvee-gpu-audio-test");
+    graphics::crash_report::set_audio_file(&args.audio_file);
+    graphics::crash_report::set_analysis_config(format!("real-time, GPU analysis {}", args.gpu));
+    let backends = graphics::engine::parse_backends(&args.backend)?;
+    let window_size = graphics::engine::window_size(args.width, args.height, (1200, 800))?;
 
     info!("Starting GPU Audio Analysis Test");
     info!("Audio file: {}", args.audio_file);
@@ -45,18 +93,24 @@ async fn main() -> Result<()> {
 
     let event_loop = EventLoop::new()?;
     let window = Arc::new(WindowBuilder::new()
-        .with_title("Arrvee GPU Audio Analysis Test")
-        .with_inner_size(winit::dpi::LogicalSize::new(1200, 800))
+        .with_title(args.title.as_deref().unwrap_or("Arrvee GPU Audio Analysis Test"))
+        .with_inner_size(window_size)
+        .with_fullscreen(args.fullscreen.map(|monitor| graphics::engine::borderless_fullscreen(&event_loop, monitor)))
         .build(&event_loop)?);
 
-    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window))?;
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, backends))?;
+    graphics_engine.set_master_fade_time(args.fade_time);
+    if let Some(path) = &args.load_uniforms {
+        graphics_engine.load_uniforms(path)?;
+    }
     let mut shutdown_requested = false;
     let mut audio_playback = AudioPlayback::new()?;
+    audio_playback.set_bpm_smoothing(args.bpm_smoothing);
 
     // Load and start playing the specified audio file
     info!("Loading {}...", args.audio_file);
     audio_playback.load_file(&args.audio_file).await?;
-    audio_playback.play();
+    audio_playback.start(args.start_at, args.paused && !args.autoplay)?;
     info!("Audio playback started");
 
     // Initialize and test GPU analyzer availability
@@ -107,6 +161,9 @@ async fn main() -> Result<()> {
                                 info!("Cleanup complete");
                                 elwt.exit();
                             }
+                            PhysicalKey::Code(KeyCode::F11) => {
+                                graphics::engine::toggle_fullscreen(&window_clone);
+                            }
                             PhysicalKey::Code(KeyCode::Space) => {
                                 if audio_playback.is_playing() {
                                     audio_playback.pause();
@@ -116,6 +173,29 @@ async fn main() -> Result<()> {
                                     info!("Audio resumed");
                                 }
                             }
+                            // Freeze visuals independently of audio
+                            PhysicalKey::Code(KeyCode::KeyF) => {
+                                let frozen = !graphics_engine.is_time_paused();
+                                graphics_engine.set_time_paused(frozen);
+                                info!("{}", if frozen { "❄️ Visuals frozen" } else { "▶️ Visuals resumed" });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyU) => {
+                                let timestamp = std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .map_or(0, |elapsed| elapsed.as_secs());
+                                if let Err(e) = graphics_engine.dump_uniforms(format!("uniforms_{}.json", timestamp)) {
+                                    log::error!("Failed to dump uniforms: {}", e);
+                                }
+                            }
+                            PhysicalKey::Code(KeyCode::KeyB) => {
+                                let target = graphics_engine.toggle_master_fade();
+                                info!("{}", if target > 0.0 { "🌅 Fading in" } else { "🌑 Fading to black" });
+                            }
+                            PhysicalKey::Code(KeyCode::PageUp) | PhysicalKey::Code(KeyCode::PageDown) => {
+                                let step = if event.physical_key == PhysicalKey::Code(KeyCode::PageUp) { 0.25 } else { -0.25 };
+                                let target = graphics_engine.step_master_fade(step);
+                                info!("🎚️ Master intensity → {:.0}%", target * 100.0);
+                            }
                             PhysicalKey::Code(KeyCode::KeyG) => {
                                 // Test GPU analysis on demand
                                 let audio_chunk = audio_playback.get_current_audio_chunk();
@@ -174,6 +254,9 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
+                WindowEvent::CursorMoved { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::MouseInput { .. } => {
+                    graphics_engine.handle_mouse_event(&event);
+                }
                 WindowEvent::Resized(physical_size) => {
                     graphics_engine.resize(physical_size);
                 }
@@ -0,0 +1,100 @@
+/// Photosensitivity limit: no more than three flashes in any one second (WCAG 2.3.1)
+pub const MAX_FLASHES_PER_SECOND: f32 = 3.0;
+/// Brightest lift a flash may add, so even a full-intensity pulse never hits pure white
+pub const MAX_FLASH_INTENSITY: f32 = 0.6;
+
+const DECAY_SECONDS: f32 = 0.12; // Time constant of the pulse's fall-off
+
+/// Full-screen pulse on each detected beat, layered over whatever effects are active.
+///
+/// Beats arriving faster than `MAX_FLASHES_PER_SECOND` are skipped rather than queued, so
+/// dense hi-hat "beats" or a misfiring detector can't turn the pulse into a strobe.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct BeatFlash {
+    enabled: bool,
+    intensity: f32, // 0-1, scaled to MAX_FLASH_INTENSITY
+    level: f32,     // Current pulse (1 at a flash, decaying to 0)
+    since_flash: f32,
+}
+
+impl Default for BeatFlash {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.5,
+            level: 0.0,
+            since_flash: f32::INFINITY,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl BeatFlash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Flash strength, 0-1 (1 = `MAX_FLASH_INTENSITY`)
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0.0, 1.0);
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Advance one frame, returning the flash brightness for the `beat_flash_intensity` uniform
+    pub fn update(&mut self, beat_detected: bool, delta_time: f32) -> f32 {
+        self.since_flash += delta_time;
+        self.level *= (-delta_time / DECAY_SECONDS).exp();
+
+        if beat_detected && self.since_flash >= 1.0 / MAX_FLASHES_PER_SECOND {
+            self.level = 1.0;
+            self.since_flash = 0.0;
+        }
+
+        if self.enabled {
+            self.level * self.intensity * MAX_FLASH_INTENSITY
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flash_rate_is_limited() {
+        let mut flash = BeatFlash::new();
+        flash.set_enabled(true);
+        flash.set_intensity(1.0);
+
+        // A beat on every frame for one second still flashes at most three times
+        let mut flashes = 0;
+        let mut previous = 0.0;
+        for _ in 0..60 {
+            let value = flash.update(true, 1.0 / 60.0);
+            assert!(value <= MAX_FLASH_INTENSITY);
+            if value > previous {
+                flashes += 1;
+            }
+            previous = value;
+        }
+        assert_eq!(flashes, MAX_FLASHES_PER_SECOND as usize);
+
+        // Disabled: nothing reaches the shader
+        flash.set_enabled(false);
+        assert_eq!(flash.update(true, 1.0), 0.0);
+    }
+}
@@ -0,0 +1,141 @@
+use glam::{Mat4, Vec3};
+
+use crate::audio::AudioFrame;
+
+/// Scale of the effect quad in the vertex shader (`CAMERA_OVERSCAN` in
+/// psychedelic_effects.wgsl), so a rolled or tilted plane still covers the whole window
+#[allow(dead_code)]
+pub const CAMERA_OVERSCAN: f32 = 4.0;
+/// Motion-sickness limit: fastest roll, in radians per second (~20° a second)
+pub const MAX_ROLL_SPEED: f32 = 0.35;
+/// Steepest tilt of the effect plane away from the screen, in radians (15°)
+pub const MAX_TILT: f32 = 0.26;
+/// Closest zoom the bass can push the camera to (1 = resting distance)
+pub const MAX_ZOOM: f32 = 1.25;
+
+const FOV_Y: f32 = std::f32::consts::FRAC_PI_3;
+const ROLL_SPEED_AT_120_BPM: f32 = 0.12; // Radians per second
+const TILT_RATE: f32 = 0.25;             // Radians per second of the tilt's slow orbit
+const ZOOM_ATTACK: f32 = 8.0;            // Per second, towards a louder bass
+const ZOOM_RELEASE: f32 = 2.0;           // Per second, back out as the bass fades
+const MOTION_EASE: f32 = 2.0;            // Per second, when motion is switched on or off
+
+/// Perspective camera looking at the effect plane, rolling with the tempo, tilting with the
+/// volume and pushing in on the bass, for the sphere, cylinder and torus projections.
+///
+/// At rest (or with motion off) the plane exactly fills the window, matching the flat view.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct AudioCamera {
+    roll: f32,
+    tilt_phase: f32,
+    tilt: f32,   // Tilt amplitude, 0 to MAX_TILT
+    zoom: f32,   // 1 to MAX_ZOOM
+    motion: f32, // 0 = resting camera, 1 = full motion
+}
+
+impl Default for AudioCamera {
+    fn default() -> Self {
+        Self { roll: 0.0, tilt_phase: 0.0, tilt: 0.0, zoom: 1.0, motion: 0.0 }
+    }
+}
+
+#[allow(dead_code)]
+impl AudioCamera {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance by `delta_time` seconds, easing towards `target_motion` (0 = rest, 1 = full)
+    pub fn update(&mut self, audio_frame: &AudioFrame, delta_time: f32, target_motion: f32) {
+        let ease = |rate: f32| 1.0 - (-rate * delta_time).exp();
+        let target_motion = target_motion.clamp(0.0, 1.0);
+        self.motion += (target_motion - self.motion) * ease(MOTION_EASE);
+
+        let bpm = if audio_frame.estimated_bpm.is_finite() { audio_frame.estimated_bpm.max(0.0) } else { 120.0 };
+        let roll_speed = (ROLL_SPEED_AT_120_BPM * bpm / 120.0).min(MAX_ROLL_SPEED);
+        self.roll = wrap_angle(self.roll + roll_speed * self.motion * delta_time);
+        if target_motion == 0.0 {
+            self.roll -= self.roll * ease(MOTION_EASE); // Unwind rather than snap back
+        }
+        self.tilt_phase = wrap_angle(self.tilt_phase + TILT_RATE * delta_time);
+
+        let volume = if audio_frame.volume.is_finite() { audio_frame.volume.clamp(0.0, 1.0) } else { 0.0 };
+        self.tilt += (MAX_TILT * (0.4 + 0.6 * volume) - self.tilt) * ease(ZOOM_RELEASE);
+
+        let bass = if audio_frame.frequency_bands.bass.is_finite() { audio_frame.frequency_bands.bass.clamp(0.0, 1.0) } else { 0.0 };
+        let target_zoom = 1.0 + (MAX_ZOOM - 1.0) * bass;
+        let rate = if target_zoom > self.zoom { ZOOM_ATTACK } else { ZOOM_RELEASE };
+        self.zoom += (target_zoom - self.zoom) * ease(rate);
+    }
+
+    /// View-projection matrix for a viewport of the given aspect ratio (width / height),
+    /// taking effect-plane coordinates (the quad scaled by `CAMERA_OVERSCAN`) to clip space
+    pub fn view_proj(&self, aspect: f32) -> Mat4 {
+        let aspect = if aspect.is_finite() && aspect > 0.0 { aspect } else { 1.0 };
+        let zoom = 1.0 + (self.zoom - 1.0) * self.motion;
+        let tilt = self.tilt * self.motion;
+        let distance = 1.0 / (FOV_Y / 2.0).tan() / zoom;
+
+        let projection = Mat4::perspective_rh(FOV_Y, aspect, 0.1, 10.0);
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, distance), Vec3::ZERO, Vec3::Y);
+        // Rotate in window proportions, so a non-square window doesn't shear as it rolls
+        let model = Mat4::from_rotation_x(tilt * self.tilt_phase.sin())
+            * Mat4::from_rotation_y(tilt * (self.tilt_phase * 0.7).cos())
+            * Mat4::from_rotation_z(self.roll * self.motion)
+            * Mat4::from_scale(Vec3::new(aspect, 1.0, 1.0));
+        projection * view * model
+    }
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Vec2, Vec4};
+
+    #[test]
+    fn test_plane_fills_the_window_at_rest_and_at_the_motion_limits() {
+        let to_screen = |view_proj: Mat4, x: f32, y: f32| {
+            let clip = view_proj * Vec4::new(x, y, 0.0, 1.0);
+            Vec2::new(clip.x, clip.y) / clip.w
+        };
+
+        // At rest the window edges are the plane's ±1, as in the flat view
+        let resting = AudioCamera::new().view_proj(16.0 / 9.0);
+        assert!((to_screen(resting, 1.0, -1.0) - Vec2::new(1.0, -1.0)).length() < 1e-4);
+
+        // Loud bass and a fast tempo through the tilt orbit, in wide and tall windows: the ray
+        // through every window corner must hit the quad between the near and far planes
+        let frame = AudioFrame { estimated_bpm: 999.0, volume: 1.0, ..AudioFrame::default() };
+        let mut camera = AudioCamera::new();
+        let mut steps = 0;
+        for step in 0..60 * 60 {
+            let mut frame = frame.clone();
+            frame.frequency_bands.bass = if step % 40 < 20 { 1.0 } else { 0.0 };
+            let before = camera.roll;
+            camera.update(&frame, 1.0 / 60.0, 1.0);
+            assert!(wrap_angle(camera.roll - before).abs() <= MAX_ROLL_SPEED / 60.0 + 1e-6);
+            if step % 7 != 0 {
+                continue;
+            }
+
+            for aspect in [21.0 / 9.0, 16.0 / 9.0, 1.0, 9.0 / 16.0] {
+                let to_plane = camera.view_proj(aspect).inverse();
+                for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                    let near = to_plane.project_point3(Vec3::new(x, y, 0.0));
+                    let far = to_plane.project_point3(Vec3::new(x, y, 1.0));
+                    let along = near.z / (near.z - far.z);
+                    let hit = near.lerp(far, along);
+                    assert!((0.0..=1.0).contains(&along) && hit.x.abs() < CAMERA_OVERSCAN && hit.y.abs() < CAMERA_OVERSCAN,
+                            "corner ({}, {}) uncovered at aspect {:.2} (step {}): {:?}", x, y, aspect, step, hit);
+                }
+            }
+            steps += 1;
+        }
+        assert!(steps > 0 && camera.zoom <= MAX_ZOOM && camera.tilt <= MAX_TILT + 1e-6);
+    }
+}
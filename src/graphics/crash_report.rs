@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Mutex, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::audio::AudioFrame;
+
+/// Rendered audio frames kept for the report
+const RECENT_FRAMES: usize = 8;
+
+/// The features of one rendered frame that a crash report shows
+#[derive(Debug, Clone, Copy)]
+struct FrameSummary {
+    index: u64,
+    bands: [f32; 5], // Sub-bass, bass, mid, treble, presence
+    volume: f32,
+    beat_detected: bool,
+    beat_strength: f32,
+    spectral_flux: f32,
+    onset_strength: f32,
+    estimated_bpm: f32,
+    spectral_centroid: f32,
+}
+
+/// What was running when the process panicked
+#[derive(Debug, Default)]
+struct CrashContext {
+    tool: String,
+    audio_file: Option<String>,
+    analysis: Option<String>,
+    gpu: Option<String>,
+    frames: VecDeque<FrameSummary>,
+    frame_count: u64,
+}
+
+impl CrashContext {
+    fn record(&mut self, frame: &AudioFrame) {
+        if self.frames.len() == RECENT_FRAMES {
+            self.frames.pop_front();
+        }
+        let bands = &frame.frequency_bands;
+        self.frames.push_back(FrameSummary {
+            index: self.frame_count,
+            bands: [bands.sub_bass, bands.bass, bands.mid, bands.treble, bands.presence],
+            volume: frame.volume,
+            beat_detected: frame.beat_detected,
+            beat_strength: frame.beat_strength,
+            spectral_flux: frame.spectral_flux,
+            onset_strength: frame.onset_strength,
+            estimated_bpm: frame.estimated_bpm,
+            spectral_centroid: frame.spectral_centroid,
+        });
+        self.frame_count += 1;
+    }
+}
+
+// `None` until `install`, so recording costs nothing more than the lock in tools without the hook
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+
+/// Install a panic hook that, after the usual panic message, writes `arrvee_crash_<time>.log`
+/// in the working directory with the tool, audio file, analysis settings, GPU adapter, the
+/// last few rendered audio frames and a backtrace
+#[allow(dead_code)]
+pub fn install(tool: &str) {
+    *CONTEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(CrashContext {
+        tool: tool.to_string(),
+        ..CrashContext::default()
+    });
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let panic = info.to_string();
+        let report = match CONTEXT.try_lock() {
+            Ok(guard) => report(guard.as_ref(), &panic),
+            Err(TryLockError::Poisoned(poisoned)) => report(poisoned.get_ref().as_ref(), &panic),
+            Err(TryLockError::WouldBlock) => report(None, &panic), // Panicked while recording
+        };
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        let path = format!("arrvee_crash_{}.log", seconds);
+        match std::fs::write(&path, report) {
+            Ok(()) => eprintln!("💥 Crash report written to {} (please attach it to bug reports)", path),
+            Err(e) => eprintln!("💥 Could not write crash report {}: {}", path, e),
+        }
+    }));
+}
+
+/// Note the file being visualized
+#[allow(dead_code)]
+pub fn set_audio_file(path: &str) {
+    update(|context| context.audio_file = Some(path.to_string()));
+}
+
+/// Note the analysis settings in use (a one-line description)
+#[allow(dead_code)]
+pub fn set_analysis_config(description: String) {
+    update(|context| context.analysis = Some(description));
+}
+
+/// Note the GPU adapter the engine is running on
+#[allow(dead_code)]
+pub fn set_gpu(info: &wgpu::AdapterInfo) {
+    let gpu = format!("{} ({:?}, {:?} backend, driver: {} {})",
+                      info.name, info.device_type, info.backend, info.driver, info.driver_info);
+    update(|context| context.gpu = Some(gpu));
+}
+
+/// Remember a rendered frame's features (only the last `RECENT_FRAMES` are kept)
+#[allow(dead_code)]
+pub fn record_frame(frame: &AudioFrame) {
+    update(|context| context.record(frame));
+}
+
+fn update(change: impl FnOnce(&mut CrashContext)) {
+    if let Ok(mut guard) = CONTEXT.lock() {
+        if let Some(context) = guard.as_mut() {
+            change(context);
+        }
+    }
+}
+
+fn report(context: Option<&CrashContext>, panic: &str) -> String {
+    let unknown = || "unknown".to_string();
+    let mut text = String::new();
+    let _ = writeln!(text, "Arrvee crash report (version {})", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(text, "{}", panic);
+    let _ = writeln!(text);
+
+    match context {
+        Some(context) => {
+            let _ = writeln!(text, "Tool:       {}", context.tool);
+            let _ = writeln!(text, "Audio file: {}", context.audio_file.clone().unwrap_or_else(unknown));
+            let _ = writeln!(text, "Analysis:   {}", context.analysis.clone().unwrap_or_else(unknown));
+            let _ = writeln!(text, "GPU:        {}", context.gpu.clone().unwrap_or_else(|| "not initialized".to_string()));
+            let _ = writeln!(text);
+            let _ = writeln!(text, "Last {} of {} rendered audio frames:", context.frames.len(), context.frame_count);
+            for frame in &context.frames {
+                let [sub_bass, bass, mid, treble, presence] = frame.bands;
+                let _ = writeln!(text,
+                    "  #{:<7} sub {:.3} bass {:.3} mid {:.3} treble {:.3} presence {:.3} | volume {:.3} beat {:.3}{} \
+                     flux {:.3} onset {:.3} | {:.1} BPM, centroid {:.0} Hz",
+                    frame.index, sub_bass, bass, mid, treble, presence, frame.volume, frame.beat_strength,
+                    if frame.beat_detected { " (beat)" } else { "" },
+                    frame.spectral_flux, frame.onset_strength, frame.estimated_bpm, frame.spectral_centroid);
+            }
+        }
+        None => {
+            let _ = writeln!(text, "(Analyzer and GPU state unavailable: the panic happened while it was being updated)");
+        }
+    }
+
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Backtrace:");
+    let _ = writeln!(text, "{}", std::backtrace::Backtrace::force_capture());
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_names_the_gpu_and_recent_frames() {
+        let mut context = CrashContext {
+            tool: "arrvee-audio-test".to_string(),
+            audio_file: Some("track.flac".to_string()),
+            gpu: Some("Test GPU (DiscreteGpu, Vulkan backend, driver: test 1.0)".to_string()),
+            ..CrashContext::default()
+        };
+        let mut frame = AudioFrame { estimated_bpm: 128.0, beat_detected: true, ..AudioFrame::default() };
+        frame.frequency_bands.bass = 0.8;
+        frame.frequency_bands.presence = f32::NAN; // The kind of value worth seeing in a report
+        for _ in 0..RECENT_FRAMES + 3 {
+            context.record(&frame);
+        }
+
+        let text = report(Some(&context), "panicked at src/audio_test.rs:1:1:\nboom");
+        assert!(text.contains("boom"));
+        assert!(text.contains("GPU:        Test GPU"));
+        assert!(text.contains("Audio file: track.flac") && text.contains("Analysis:   unknown"));
+        assert!(text.contains(&format!("Last {} of {} rendered audio frames", RECENT_FRAMES, RECENT_FRAMES + 3)));
+        assert!(text.contains(&format!("#{:<7} sub", RECENT_FRAMES + 2)) && !text.contains("#2 ")); // Oldest dropped
+        assert!(text.contains("bass 0.800") && text.contains("presence NaN"));
+        assert!(text.contains("beat 0.000 (beat)") && text.contains("128.0 BPM"));
+    }
+}
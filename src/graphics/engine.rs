@@ -1,12 +1,27 @@
 use anyhow::Result;
+use std::borrow::Cow;
 use wgpu::util::DeviceExt;
-use winit::window::Window;
-use glam::Mat4;
+use winit::event::{MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::window::{Fullscreen, Window};
 
 use crate::audio::{AudioFrame, GpuAudioAnalyzer, GpuAudioFeatures};
+use crate::audio::solo_band::SoloBand;
 use crate::effects::PsychedelicManager;
+use crate::error::ArrveeError;
+use crate::effects::weight_log::{WeightLog, WeightRecorder};
 use super::{ShaderManager, TextureManager, Vertex, VertexBuffer};
+use super::snapshot::UniformsSnapshot;
+use super::spectrum::{BandEdge, FluxDebug, SpectrumBars, SpectrumUniforms, DEFAULT_SPECTRUM_SUPERSAMPLING, MAX_SPECTRUM_BARS, MAX_SPECTRUM_SUPERSAMPLING};
+use super::beat_flash::BeatFlash;
+use super::particle_burst::ParticleBurst;
+use super::smoothing_scope::SmoothingScope;
+use super::camera::AudioCamera;
+use super::frame_history::{FrameHistory, MAX_FEEDBACK};
 
+// Field order matters for teardown: fields drop in declaration order, so the surface is
+// released before the device/queue, and pipelines/buffers (which hold their own device
+// references) after that. `Drop` waits for in-flight submissions first.
 pub struct GraphicsEngine<'a> {
     pub surface: wgpu::Surface<'a>,
     pub device: wgpu::Device,
@@ -20,6 +35,10 @@ pub struct GraphicsEngine<'a> {
     pub uniform_buffer: wgpu::Buffer,
     pub uniform_bind_group: wgpu::BindGroup,
     uniform_bind_group_layout: wgpu::BindGroupLayout,
+    spectrum_buffer: wgpu::Buffer,
+    flux_debug_buffer: wgpu::Buffer,
+    frame_history_bind_group_layout: wgpu::BindGroupLayout,
+    frame_history: FrameHistory, // Last two frames, for feedback trails
 
     pub vertex_buffer: VertexBuffer,
 
@@ -28,8 +47,66 @@ pub struct GraphicsEngine<'a> {
     pub projection_mode: f32, // -1=auto, 0=sphere, 1=cylinder, 2=torus, 3=flat
     pub palette_index: f32,   // Current color palette
     pub smoothing_factor: f32, // Global smoothing factor
+    pub tonemap_mode: f32,    // 0=clip, 1=Reinhard, 2=ACES
+    surface_formats: Vec<wgpu::TextureFormat>, // Formats the surface supports, for switching color space
+    surface_alpha_modes: Vec<wgpu::CompositeAlphaMode>, // Alpha modes the surface supports, for transparency
+    clear_color: wgpu::Color, // Background behind unlit areas, sRGB-encoded and opaque (`--bg-color`)
+    transparent: bool,        // Clear to `clear_color` at zero alpha, for compositing
     cleaned_up: bool,         // Flag to prevent rendering after cleanup
+    time_paused: bool,        // Freeze visuals (time + effect evolution) independent of audio
+    last_uniforms: Option<Uniforms>, // Last uploaded uniforms, re-presented while frozen
+    mouse_x: f32,             // Cursor position, normalized 0-1 (left to right)
+    mouse_y: f32,             // Cursor position, normalized 0-1 (top to bottom)
+    mouse_zoom: f32,          // Scroll wheel zoom (0.25-4.0, 1.0 = none)
+    master_intensity: f32,    // Master fade level applied to the final color (0-1)
+    master_target: f32,       // Level the master fade is ramping towards
+    master_fade_time: f32,    // Seconds for a full 0→1 ramp
+    forced_uniforms: Option<Uniforms>, // Loaded fixture rendered verbatim (static render)
+    weight_recorder: Option<WeightRecorder>, // Logs each frame's blend weights (`--record-weights`)
+    weight_replay: Option<WeightLog>,  // Recorded weights used instead of the manager's
+    spectrum_bars: SpectrumBars,  // Log-spaced spectrum for the spectralizer
+    num_spectrum_bars: usize,     // Bars shown by the spectralizer (1-64)
+    spectrum_supersampling: usize, // Interpolated spectrum samples per bar (1 = per-bin bars)
+    flux_debug: FluxDebug,        // Per-bin spectral flux for the flux_debug view
+    beat_flash: BeatFlash,        // Optional full-screen pulse on each beat
+    particle_burst: ParticleBurst, // Onset-driven particle emission for the particle swarm
+    particle_emission_rate: f32,  // Burst size as configured; reduced motion mutes it without forgetting it
+    feedback: f32,                // Frame feedback for motion trails (0-MAX_FEEDBACK)
+    camera: AudioCamera,          // Audio-driven roll/tilt/zoom for the 3D projections
+    camera_motion: bool,          // Off: the camera stays at rest (flat framing)
+    motion_scale: f32,            // Animation speed and camera movement (1 = normal, see `set_reduced_motion`)
+    animation_time: f32,          // Shader clock: `time` advanced at `motion_scale`
+    loop_markers: (f32, f32, f32), // Loop in, out (-1 = unset) and playhead, as track fractions
+    kaleidoscope_segments: f32,   // Fixed kaleidoscope segment count (0 = audio-driven)
+    kaleidoscope_seam_softness: f32, // Kaleidoscope seam blend width (0-1 of a segment)
+    kaleidoscope_bpm_sync: bool,  // Scale the kaleidoscope segment count with the tempo
     gpu_analyzer: Option<GpuAudioAnalyzer>, // GPU-accelerated audio analysis
+    split_pane: Option<SplitPane>, // Right-hand visual state for split-screen A/B renders
+    smoothing_scope: SmoothingScope, // Recent raw vs. smoothed values of one feature
+    solo_band: Option<SoloBand>,     // Drive the visuals from this band alone
+    band_grid: bool,                 // Bin grid and band-edge lines over the spectralizer
+    non_finite_warned: bool,         // Warned (once) about a frame with NaN/infinite features
+    missing_pipeline_warned: bool,   // Warned (once) that a pipeline is missing
+}
+
+/// Independent visual state for the right half of a split-screen render.
+///
+/// Each half needs its own uniform buffer: two writes to one buffer before a single
+/// submit would leave both draws seeing the last write.
+struct SplitPane {
+    uniform_buffer: wgpu::Buffer,
+    spectrum_buffer: wgpu::Buffer,
+    flux_debug_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    time: f32,
+    animation_time: f32,
+    psychedelic_manager: PsychedelicManager,
+    last_uniforms: Option<Uniforms>,
+    spectrum_bars: SpectrumBars,
+    flux_debug: FluxDebug,
+    beat_flash: BeatFlash,
+    particle_burst: ParticleBurst,
+    camera: AudioCamera,
 }
 
 #[repr(C)]
@@ -70,6 +147,15 @@ pub struct Uniforms {
     pub spectralizer_weight: f32,
     pub parametric_weight: f32,
 
+    // Per-effect intensity scalers from the psychedelic manager
+    pub plasma_intensity: f32,
+    pub kaleidoscope_intensity: f32,
+    pub tunnel_intensity: f32,
+    pub particle_intensity: f32,
+    pub fractal_intensity: f32,
+    pub spectralizer_intensity: f32,
+    pub parametric_intensity: f32,
+
     // 3D projection controls
     pub projection_mode: f32,  // 0=sphere, 1=cylinder, 2=torus, 3=flat, -1=auto
 
@@ -77,13 +163,50 @@ pub struct Uniforms {
     pub palette_index: f32,    // Current color palette (0-5)
     pub smoothing_factor: f32, // Global smoothing sensitivity (0.1-2.0)
 
-    pub _padding: [f32; 3],   // Padding to align to 16-byte boundary (176 bytes total)
+    // Mouse interaction
+    pub mouse_x: f32,          // Cursor X, normalized 0-1
+    pub mouse_y: f32,          // Cursor Y, normalized 0-1 (top = 0)
+    pub mouse_zoom: f32,       // Scroll zoom factor (1.0 = none)
+
+    // Show control
+    pub master_intensity: f32, // Master fade multiplier (0 = black, 1 = full)
+
+    // Developer views
+    pub flux_debug_weight: f32, // Spectral flux debug overlay (0 = off, 1 = full screen)
+
+    // Output
+    pub tonemap_mode: f32,     // Highlight compression: 0=clip, 1=Reinhard, 2=ACES
+
+    // Beat pulse
+    pub beat_flash_intensity: f32, // Additive flash brightness this frame (0 = none)
+
+    // Frame feedback
+    pub feedback: f32,         // Share of the previous frame kept each frame (0 = no trails)
+
+    // Loop region timeline (fractions of the track; loop points < 0 are unset)
+    pub loop_in: f32,
+    pub loop_out: f32,
+    pub loop_position: f32,
+
+    // Kaleidoscope symmetry
+    pub kaleidoscope_segments: f32,      // Mirrored segments (0 = follow pitch and onsets)
+    pub kaleidoscope_seam_softness: f32, // Blend width at segment edges, as a share of the segment (0 = hard)
+    pub kaleidoscope_bpm_sync: f32,      // 1 = scale the segment count with the tempo (120 BPM = as set)
+
+    // Particle swarm
+    pub particle_spawn_rate: f32, // Onset burst emission (1 at a hit, decaying to 0); 256 bytes total
+}
+
+impl Default for Uniforms {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Uniforms {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
-            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            view_proj: AudioCamera::new().view_proj(1.0).to_cols_array_2d(),
             time: 0.0,
             sub_bass: 0.0,
             bass: 0.0,
@@ -107,38 +230,286 @@ impl Uniforms {
             fractal_weight: 0.0,
             spectralizer_weight: 0.0,
             parametric_weight: 0.0,
+            plasma_intensity: 1.0,
+            kaleidoscope_intensity: 1.0,
+            tunnel_intensity: 1.0,
+            particle_intensity: 1.0,
+            fractal_intensity: 1.0,
+            spectralizer_intensity: 1.0,
+            parametric_intensity: 1.0,
             projection_mode: -1.0, // Auto mode by default
             palette_index: 0.0,     // Start with first palette
             smoothing_factor: 0.3,  // More responsive default smoothing
-            _padding: [0.0; 3],     // Proper padding
+            mouse_x: 0.5,           // Centered cursor = no warp
+            mouse_y: 0.5,
+            mouse_zoom: 1.0,
+            master_intensity: 1.0,  // Fully visible
+            flux_debug_weight: 0.0,
+            tonemap_mode: 0.0,      // Clip, as before tonemapping existed
+            beat_flash_intensity: 0.0,
+            feedback: 0.0,
+            loop_in: -1.0,
+            loop_out: -1.0,
+            loop_position: 0.0,
+            kaleidoscope_segments: 0.0,
+            kaleidoscope_seam_softness: DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS,
+            kaleidoscope_bpm_sync: 0.0,
+            particle_spawn_rate: 0.0,
+        }
+    }
+}
+
+impl Uniforms {
+    /// The projection the shader draws with (`projection_type` in `fs_main`, 0-3): the manual
+    /// `projection_mode`, or in auto mode its pick from the dominant effect and the bass and
+    /// presence. Mirrors the shader's selection, so keep the two in step.
+    pub fn resolved_projection(&self) -> i32 {
+        if self.projection_mode >= 0.0 {
+            return self.projection_mode as i32;
+        }
+        let max_weight = [self.plasma_weight, self.kaleidoscope_weight, self.tunnel_weight,
+                          self.particle_weight, self.fractal_weight, self.spectralizer_weight]
+            .into_iter()
+            .fold(f32::MIN, f32::max);
+        if self.tunnel_weight == max_weight && self.tunnel_weight > 0.3 {
+            1
+        } else if self.kaleidoscope_weight == max_weight && self.kaleidoscope_weight > 0.3 {
+            2
+        } else if ((self.plasma_weight == max_weight || self.particle_weight == max_weight) && max_weight > 0.4)
+            || self.bass > 0.6
+        {
+            0
+        } else if self.presence > 0.5 {
+            1
+        } else {
+            FLAT_PROJECTION
+        }
+    }
+}
+
+/// `projection_type` of the flat projection in the shader
+pub const FLAT_PROJECTION: i32 = 3;
+
+/// Camera motion (0-1) for a frame: none when it is switched off or when the shader draws the
+/// frame flat (the flat projection, chosen or auto-resolved), fading out with the flux debug
+/// view, since both are read as flat images
+fn camera_motion_target(enabled: bool, uniforms: &Uniforms, motion_scale: f32) -> f32 {
+    if !enabled || uniforms.resolved_projection() == FLAT_PROJECTION {
+        return 0.0;
+    }
+    (1.0 - uniforms.flux_debug_weight.clamp(0.0, 1.0)) * motion_scale
+}
+
+/// `frame` with any NaN/infinite features zeroed (see `AudioFrame::sanitize`), so a bad frame
+/// can't turn into NaN uniforms (a black or garbage screen). Warns the first time, setting `warned`.
+fn sanitized_frame<'f>(frame: &'f AudioFrame, warned: &mut bool) -> Cow<'f, AudioFrame> {
+    if frame.is_finite() {
+        return Cow::Borrowed(frame);
+    }
+    if !*warned {
+        log::warn!("⚠️  Audio frame with NaN/infinite features; rendering it with those zeroed");
+        *warned = true;
+    }
+    let mut sanitized = frame.clone();
+    sanitized.sanitize();
+    Cow::Owned(sanitized)
+}
+
+/// Map a `--backend` name to the wgpu backends to try
+#[allow(dead_code)]
+pub fn parse_backends(name: &str) -> Result<wgpu::Backends> {
+    match name.to_lowercase().as_str() {
+        "auto" | "all" => Ok(wgpu::Backends::all()),
+        "vulkan" => Ok(wgpu::Backends::VULKAN),
+        "metal" => Ok(wgpu::Backends::METAL),
+        "dx12" => Ok(wgpu::Backends::DX12),
+        "gl" => Ok(wgpu::Backends::GL),
+        other => Err(anyhow::anyhow!("Unknown backend '{}'. Use: vulkan, metal, dx12, gl, auto", other)),
+    }
+}
+
+/// Palette names, indexed by `palette_index`
+pub const PALETTE_NAMES: [&str; 6] = ["Rainbow", "Neon Cyber", "Warm Sunset", "Deep Ocean", "Purple Haze", "Electric Green"];
+
+/// Parse `--palette`: a palette index (0-5) or name such as `deep-ocean`
+#[allow(dead_code)]
+pub fn parse_palette(name: &str) -> Result<f32> {
+    if let Ok(index) = name.trim().parse::<usize>() {
+        if index < PALETTE_NAMES.len() {
+            return Ok(index as f32);
+        }
+    }
+    let normalized = name.trim().to_lowercase().replace(['-', '_'], " ");
+    PALETTE_NAMES.iter()
+        .position(|palette| palette.to_lowercase() == normalized)
+        .map(|index| index as f32)
+        .ok_or_else(|| anyhow::anyhow!("Unknown palette '{}'. Use 0-{} or: {}",
+                                       name, PALETTE_NAMES.len() - 1, PALETTE_NAMES.join(", ")))
+}
+
+/// Parse `--projection` into a `projection_mode` value (-1 = auto)
+#[allow(dead_code)]
+pub fn parse_projection(name: &str) -> Result<f32> {
+    match name.to_lowercase().as_str() {
+        "auto" => Ok(-1.0),
+        "sphere" | "spheres" => Ok(0.0),
+        "cylinder" => Ok(1.0),
+        "torus" => Ok(2.0),
+        "flat" => Ok(3.0),
+        other => Err(anyhow::anyhow!("Unknown projection '{}'. Use: auto, sphere, cylinder, torus, flat", other)),
+    }
+}
+
+/// Tonemap names, indexed by `tonemap_mode`
+pub const TONEMAP_NAMES: [&str; 3] = ["off", "reinhard", "aces"];
+
+/// Parse `--tonemap` into a `tonemap_mode` value: off (clip), reinhard or aces
+#[allow(dead_code)]
+pub fn parse_tonemap(name: &str) -> Result<f32> {
+    TONEMAP_NAMES.iter()
+        .position(|tonemap| tonemap.eq_ignore_ascii_case(name.trim()))
+        .map(|index| index as f32)
+        .ok_or_else(|| anyhow::anyhow!("Unknown tonemap '{}'. Use: {}", name, TONEMAP_NAMES.join(", ")))
+}
+
+/// How shader output is written to the window
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputColorSpace {
+    /// sRGB surface: shader colours are treated as linear light and gamma-encoded on output
+    #[default]
+    Srgb,
+    /// Non-sRGB surface: shader colours are written to the display unencoded (darker midtones)
+    Linear,
+}
+
+impl std::str::FromStr for OutputColorSpace {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "srgb" => Ok(Self::Srgb),
+            "linear" => Ok(Self::Linear),
+            other => Err(anyhow::anyhow!("Unknown output color space '{}'. Use: srgb, linear", other)),
         }
     }
+}
+
+/// Parse a `--bg-color` value: `#RRGGBB` or `RRGGBB` hex (sRGB, opaque)
+#[allow(dead_code)]
+pub fn parse_hex_color(text: &str) -> Result<wgpu::Color> {
+    let hex = text.trim().trim_start_matches('#');
+    let value = (hex.len() == 6).then(|| u32::from_str_radix(hex, 16).ok()).flatten()
+        .ok_or_else(|| anyhow::anyhow!("Invalid color '{}'. Use hex like #102030", text))?;
+    let channel = |shift: u32| ((value >> shift) & 0xff) as f64 / 255.0;
+    Ok(wgpu::Color { r: channel(16), g: channel(8), b: channel(0), a: 1.0 })
+}
+
+/// Segment counts `--kaleidoscope-segments` accepts
+pub const KALEIDOSCOPE_SEGMENT_RANGE: (u32, u32) = (2, 32);
+/// Kaleidoscope seam blend width, as a share of each segment
+pub const DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS: f32 = 0.15;
+
+/// Animation speed and camera movement in reduced-motion mode (1 = normal)
+pub const REDUCED_MOTION_SCALE: f32 = 0.4;
+
+/// Surface colour while a pipeline is missing, so a broken shader doesn't pass for a quiet track
+const MISSING_PIPELINE_COLOR: wgpu::Color = wgpu::Color { r: 0.4, g: 0.0, b: 0.4, a: 1.0 };
+
+/// Pipelines every frame needs
+const FRAME_PIPELINES: [&str; 2] = ["visualizer", "frame_blit"];
+
+/// Smallest window `--width`/`--height` accept; below this the egui panels stop fitting
+pub const MIN_WINDOW_SIZE: (u32, u32) = (320, 240);
+/// Largest window dimension, wgpu's default 2D texture limit (surface and frame history)
+pub const MAX_WINDOW_DIMENSION: u32 = 8192;
+
+/// Initial window size from `--width`/`--height`. Given sizes are physical pixels, so a
+/// recording comes out at exactly that resolution; otherwise the tool's default logical size.
+#[allow(dead_code)]
+pub fn window_size(width: Option<u32>, height: Option<u32>, default: (u32, u32)) -> Result<winit::dpi::Size> {
+    if width.is_none() && height.is_none() {
+        return Ok(winit::dpi::LogicalSize::new(default.0, default.1).into());
+    }
+    let (width, height) = (width.unwrap_or(default.0), height.unwrap_or(default.1));
+    if width < MIN_WINDOW_SIZE.0 || height < MIN_WINDOW_SIZE.1 {
+        anyhow::bail!("Window size {}x{} is too small (minimum {}x{})", width, height, MIN_WINDOW_SIZE.0, MIN_WINDOW_SIZE.1);
+    }
+    if width > MAX_WINDOW_DIMENSION || height > MAX_WINDOW_DIMENSION {
+        anyhow::bail!("Window size {}x{} is too large (maximum {} per side)", width, height, MAX_WINDOW_DIMENSION);
+    }
+    Ok(winit::dpi::PhysicalSize::new(width, height).into())
+}
+
+/// Borderless fullscreen for `--fullscreen [monitor]` (monitor index, or the primary monitor)
+#[allow(dead_code)]
+pub fn borderless_fullscreen<T>(target: &EventLoopWindowTarget<T>, monitor: Option<usize>) -> Fullscreen {
+    let handle = match monitor {
+        Some(index) => {
+            let handle = target.available_monitors().nth(index);
+            if handle.is_none() {
+                log::warn!("⚠️  Monitor {} not found, using the current monitor", index);
+            }
+            handle
+        }
+        None => target.primary_monitor(),
+    };
+    Fullscreen::Borderless(handle)
+}
 
-    fn update_view_proj(&mut self, width: f32, height: f32) {
-        let proj = Mat4::orthographic_rh(-width/2.0, width/2.0, -height/2.0, height/2.0, -1.0, 1.0);
-        self.view_proj = proj.to_cols_array_2d();
+/// Switch between windowed and borderless fullscreen on the window's current monitor (F11)
+#[allow(dead_code)]
+pub fn toggle_fullscreen(window: &Window) {
+    if window.fullscreen().is_some() {
+        window.set_fullscreen(None);
+        log::info!("🪟 Windowed mode");
+    } else {
+        window.set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor())));
+        log::info!("🖥️  Fullscreen mode");
     }
 }
 
 impl<'a> GraphicsEngine<'a> {
-    pub async fn new(window: &'a Window) -> Result<Self> {
+    pub async fn new(window: &'a Window, backends: wgpu::Backends) -> crate::error::Result<Self> {
         let size = window.inner_size();
 
+        log::info!("🔧 Requested graphics backends: {:?}", backends);
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
+            backends,
             ..Default::default()
         });
 
-        let surface = instance.create_surface(window)?;
+        let surface = instance.create_surface(window)
+            .map_err(|e| ArrveeError::GpuInit(format!("could not create a window surface: {}", e)))?;
 
-        let adapter = instance
+        let adapter = match instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
                 compatible_surface: Some(&surface),
                 force_fallback_adapter: false,
             })
             .await
-            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
+        {
+            Some(adapter) => adapter,
+            None => {
+                // Headless/VM setups often only expose a software adapter
+                log::warn!("⚠️  No hardware adapter found, retrying with fallback (software) adapter");
+                instance
+                    .request_adapter(&wgpu::RequestAdapterOptions {
+                        power_preference: wgpu::PowerPreference::HighPerformance,
+                        compatible_surface: Some(&surface),
+                        force_fallback_adapter: true,
+                    })
+                    .await
+                    .ok_or_else(|| ArrveeError::GpuInit(format!("no adapter for {:?} (including fallback)", backends)))?
+            }
+        };
+
+        let adapter_info = adapter.get_info();
+        log::info!("🖥️  GPU adapter: {} ({:?}, {:?} backend, driver: {} {})",
+                   adapter_info.name, adapter_info.device_type, adapter_info.backend,
+                   adapter_info.driver, adapter_info.driver_info);
+        super::crash_report::set_gpu(&adapter_info);
 
         let (device, queue) = adapter
             .request_device(
@@ -149,7 +520,8 @@ impl<'a> GraphicsEngine<'a> {
                 },
                 None,
             )
-            .await?;
+            .await
+            .map_err(|e| ArrveeError::GpuInit(format!("could not open {}: {}", adapter_info.name, e)))?;
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -173,21 +545,44 @@ impl<'a> GraphicsEngine<'a> {
         surface.configure(&device, &config);
 
         let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+                // Spectrum bars (a uniform rather than storage buffer so the GL backend works too)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Spectral flux debug view (uniform for the same reason)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
             label: Some("uniform_bind_group_layout"),
         });
 
-        let mut uniforms = Uniforms::new();
-        uniforms.update_view_proj(size.width as f32, size.height as f32);
+        let uniforms = Uniforms::new(); // A resting camera frames every aspect ratio alike
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
@@ -195,14 +590,19 @@ impl<'a> GraphicsEngine<'a> {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &uniform_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-            label: Some("uniform_bind_group"),
-        });
+        let spectrum_buffer = Self::create_spectrum_buffer(&device);
+        let flux_debug_buffer = Self::create_flux_debug_buffer(&device);
+        let uniform_bind_group = Self::create_uniform_bind_group(
+            &device,
+            &uniform_bind_group_layout,
+            &uniform_buffer,
+            &spectrum_buffer,
+            &flux_debug_buffer,
+            "uniform_bind_group",
+        );
+
+        let frame_history_bind_group_layout = FrameHistory::create_bind_group_layout(&device);
+        let frame_history = FrameHistory::new(&device, &frame_history_bind_group_layout, &config);
 
         let mut shader_manager = ShaderManager::new();
         let texture_manager = TextureManager::new();
@@ -214,13 +614,23 @@ impl<'a> GraphicsEngine<'a> {
         let psychedelic_shader = include_str!("../../shaders/psychedelic_effects.wgsl");
         shader_manager.load_shader(&device, "psychedelic", psychedelic_shader)?;
 
-        // Create pipeline with psychedelic shader
+        let frame_blit_shader = include_str!("../../shaders/frame_blit.wgsl");
+        shader_manager.load_shader(&device, "frame_blit", frame_blit_shader)?;
+
+        // Create pipeline with psychedelic shader, rendering into the frame history
         shader_manager.create_pipeline(
             &device,
             "visualizer",
             "psychedelic",
             surface_format,
-            &uniform_bind_group_layout,
+            &[&uniform_bind_group_layout, &frame_history_bind_group_layout],
+        )?;
+        shader_manager.create_pipeline(
+            &device,
+            "frame_blit",
+            "frame_blit",
+            surface_format,
+            &[&frame_history_bind_group_layout],
         )?;
 
         let vertices = Self::create_fullscreen_quad();
@@ -239,14 +649,100 @@ impl<'a> GraphicsEngine<'a> {
             uniform_buffer,
             uniform_bind_group,
             uniform_bind_group_layout,
+            spectrum_buffer,
+            flux_debug_buffer,
+            frame_history_bind_group_layout,
+            frame_history,
             vertex_buffer,
             time: 0.0,
             psychedelic_manager,
             projection_mode: -1.0, // Start in auto mode
             palette_index: 0.0,    // Start with first palette
             smoothing_factor: 0.3, // More responsive default smoothing
+            tonemap_mode: 0.0,     // Clip highlights
+            surface_formats: surface_caps.formats,
+            surface_alpha_modes: surface_caps.alpha_modes,
+            clear_color: wgpu::Color::BLACK,
+            transparent: false,
             cleaned_up: false,     // Not cleaned up yet
+            time_paused: false,
+            last_uniforms: None,
+            mouse_x: 0.5,
+            mouse_y: 0.5,
+            mouse_zoom: 1.0,
+            master_intensity: 1.0,
+            master_target: 1.0,
+            master_fade_time: 2.0,
+            forced_uniforms: None,
+            weight_recorder: None,
+            weight_replay: None,
+            spectrum_bars: SpectrumBars::new(),
+            num_spectrum_bars: MAX_SPECTRUM_BARS,
+            spectrum_supersampling: DEFAULT_SPECTRUM_SUPERSAMPLING,
+            flux_debug: FluxDebug::new(),
+            beat_flash: BeatFlash::new(),
+            particle_burst: ParticleBurst::new(),
+            particle_emission_rate: 1.0,
+            feedback: 0.0,
+            camera: AudioCamera::new(),
+            camera_motion: true,
+            motion_scale: 1.0,
+            animation_time: 0.0,
+            loop_markers: (-1.0, -1.0, 0.0),
+            kaleidoscope_segments: 0.0,
+            kaleidoscope_seam_softness: DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS,
+            kaleidoscope_bpm_sync: false,
             gpu_analyzer: None,    // GPU analyzer will be created externally
+            split_pane: None,
+            smoothing_scope: SmoothingScope::default(),
+            solo_band: None,
+            band_grid: false,
+            non_finite_warned: false,
+            missing_pipeline_warned: false,
+        })
+    }
+
+    fn create_spectrum_buffer(device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Spectrum Buffer"),
+            contents: bytemuck::cast_slice(&[SpectrumBars::new().to_uniforms(0)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        })
+    }
+
+    fn create_flux_debug_buffer(device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Flux Debug Buffer"),
+            contents: bytemuck::cast_slice(&[FluxDebug::new().to_uniforms()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        })
+    }
+
+    fn create_uniform_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        spectrum_buffer: &wgpu::Buffer,
+        flux_debug_buffer: &wgpu::Buffer,
+        label: &str,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: spectrum_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: flux_debug_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some(label),
         })
     }
 
@@ -285,65 +781,632 @@ impl<'a> GraphicsEngine<'a> {
         ]
     }
 
+    /// Freeze or unfreeze the visuals. While frozen, time and effect evolution stop and the
+    /// last uniforms are re-presented, regardless of what the audio is doing.
+    pub fn set_time_paused(&mut self, paused: bool) {
+        self.time_paused = paused;
+    }
+
+    pub fn is_time_paused(&self) -> bool {
+        self.time_paused
+    }
+
+    /// Set the normalized cursor position (0-1 on each axis, origin top-left)
+    pub fn set_mouse_position(&mut self, x: f32, y: f32) {
+        self.mouse_x = x.clamp(0.0, 1.0);
+        self.mouse_y = y.clamp(0.0, 1.0);
+    }
+
+    /// Multiply the zoom by `1 + delta` (scroll up zooms in), returning the new zoom
+    pub fn adjust_mouse_zoom(&mut self, delta: f32) -> f32 {
+        self.mouse_zoom = (self.mouse_zoom * (1.0 + delta)).clamp(0.25, 4.0);
+        self.mouse_zoom
+    }
+
+    /// Center the cursor warp and reset the zoom
+    pub fn reset_mouse(&mut self) {
+        self.mouse_x = 0.5;
+        self.mouse_y = 0.5;
+        self.mouse_zoom = 1.0;
+    }
+
+    #[allow(dead_code)]
+    pub fn mouse_state(&self) -> (f32, f32, f32) {
+        (self.mouse_x, self.mouse_y, self.mouse_zoom)
+    }
+
+    /// Set the master intensity immediately (0 = black, 1 = full), cancelling any fade
+    #[allow(dead_code)]
+    pub fn set_master_intensity(&mut self, intensity: f32) {
+        self.master_intensity = intensity.clamp(0.0, 1.0);
+        self.master_target = self.master_intensity;
+    }
+
+    pub fn master_intensity(&self) -> f32 {
+        self.master_intensity
+    }
+
+    /// Ramp the master intensity towards `target` at the configured fade speed
+    pub fn fade_master_to(&mut self, target: f32) {
+        self.master_target = target.clamp(0.0, 1.0);
+    }
+
+    /// Move the fade target by `step`, snapped to quarters; returns the new target. Steps from
+    /// where the fade is heading, so repeated presses add up even mid-fade.
+    pub fn step_master_fade(&mut self, step: f32) -> f32 {
+        self.fade_master_to(((self.master_target + step) * 4.0).round() / 4.0);
+        self.master_target
+    }
+
+    /// Fade to black, or back to full if already at/heading to black; returns the new target
+    pub fn toggle_master_fade(&mut self) -> f32 {
+        let target = if self.master_target > 0.0 { 0.0 } else { 1.0 };
+        self.fade_master_to(target);
+        target
+    }
+
+    /// Seconds a full 0→1 fade takes (0 = instant)
+    pub fn set_master_fade_time(&mut self, seconds: f32) {
+        self.master_fade_time = seconds.max(0.0);
+    }
+
+    /// Turn the beat flash on or off (for both halves of a split screen)
+    #[allow(dead_code)]
+    pub fn set_beat_flash_enabled(&mut self, enabled: bool) {
+        self.beat_flash.set_enabled(enabled);
+        if let Some(pane) = self.split_pane.as_mut() {
+            pane.beat_flash.set_enabled(enabled);
+        }
+    }
+
+    /// Beat flash strength, 0-1 (capped by the flash's photosensitivity limits)
+    #[allow(dead_code)]
+    pub fn set_beat_flash_intensity(&mut self, intensity: f32) {
+        self.beat_flash.set_intensity(intensity);
+        if let Some(pane) = self.split_pane.as_mut() {
+            pane.beat_flash.set_intensity(intensity);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn beat_flash(&self) -> &BeatFlash {
+        &self.beat_flash
+    }
+
+    /// How big the particle swarm's onset bursts are, 0-`MAX_EMISSION_RATE` (0 = no bursts;
+    /// for both halves of a split screen). Reduced motion holds it at 0 and restores it after.
+    #[allow(dead_code)]
+    pub fn set_particle_emission_rate(&mut self, rate: f32) {
+        self.particle_emission_rate = rate;
+        self.apply_particle_emission_rate();
+    }
+
+    fn apply_particle_emission_rate(&mut self) {
+        let rate = if self.is_reduced_motion() { 0.0 } else { self.particle_emission_rate };
+        self.particle_burst.set_emission_rate(rate);
+        if let Some(pane) = self.split_pane.as_mut() {
+            pane.particle_burst.set_emission_rate(rate);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn particle_burst(&self) -> &ParticleBurst {
+        &self.particle_burst
+    }
+
+    /// Add a frame to the smoothing scope: `raw` as analyzed, `smoothed` after the envelopes
+    #[allow(dead_code)]
+    pub fn record_smoothing_scope(&mut self, raw: &AudioFrame, smoothed: &AudioFrame) {
+        self.smoothing_scope.push(raw, smoothed);
+    }
+
+    #[allow(dead_code)]
+    pub fn smoothing_scope(&self) -> &SmoothingScope {
+        &self.smoothing_scope
+    }
+
+    #[allow(dead_code)]
+    pub fn smoothing_scope_mut(&mut self) -> &mut SmoothingScope {
+        &mut self.smoothing_scope
+    }
+
+    /// Drive the effects and shader from one band only (None: the full mix)
+    #[allow(dead_code)]
+    pub fn set_solo_band(&mut self, band: Option<SoloBand>) {
+        self.solo_band = band;
+    }
+
+    #[allow(dead_code)]
+    pub fn solo_band(&self) -> Option<SoloBand> {
+        self.solo_band
+    }
+
+    /// Developer view over the spectralizer: shade alternate FFT bins and draw a colored line
+    /// at each band edge (see `band_edges`), to check where 60/250/2000/8000 Hz land
+    #[allow(dead_code)]
+    pub fn set_band_grid(&mut self, enabled: bool) {
+        self.band_grid = enabled;
+    }
+
+    #[allow(dead_code)]
+    pub fn band_grid(&self) -> bool {
+        self.band_grid
+    }
+
+    /// The band edges as the spectralizer is currently drawing them, for labelling the grid
+    #[allow(dead_code)]
+    pub fn band_edges(&self) -> [BandEdge; 4] {
+        self.spectrum_bars.band_edges()
+    }
+
+    fn spectrum_uniforms(&self) -> SpectrumUniforms {
+        let mut spectrum = self.spectrum_bars.to_uniforms(self.num_spectrum_bars);
+        spectrum.band_grid = if self.band_grid { 1.0 } else { 0.0 };
+        spectrum
+    }
+
+    /// Switch the window between sRGB and linear output, rebuilding the render pipeline for the
+    /// new surface format. Call before creating a UI overlay, which is tied to the format too.
+    #[allow(dead_code)]
+    pub fn set_output_color_space(&mut self, color_space: OutputColorSpace) -> Result<()> {
+        let want_srgb = color_space == OutputColorSpace::Srgb;
+        let Some(format) = self.surface_formats.iter().copied().find(|format| format.is_srgb() == want_srgb) else {
+            log::warn!("⚠️  Surface has no {:?} format, keeping {:?}", color_space, self.config.format);
+            return Ok(());
+        };
+        if format == self.config.format {
+            return Ok(());
+        }
+
+        self.config.format = format;
+        self.surface.configure(&self.device, &self.config);
+        self.frame_history = FrameHistory::new(&self.device, &self.frame_history_bind_group_layout, &self.config);
+        self.shader_manager.create_pipeline(
+            &self.device,
+            "visualizer",
+            "psychedelic",
+            format,
+            &[&self.uniform_bind_group_layout, &self.frame_history_bind_group_layout],
+        )?;
+        self.shader_manager.create_pipeline(
+            &self.device,
+            "frame_blit",
+            "frame_blit",
+            format,
+            &[&self.frame_history_bind_group_layout],
+        )?;
+        log::info!("🎨 Output color space: {:?} ({:?})", color_space, format);
+        Ok(())
+    }
+
+    /// Opaque background shown wherever the effects are dark (default black)
+    #[allow(dead_code)]
+    pub fn set_clear_color(&mut self, color: wgpu::Color) {
+        self.clear_color = wgpu::Color { a: 1.0, ..color };
+    }
+
+    /// The background as cleared: the configured colour, at zero alpha while transparent
+    #[allow(dead_code)]
+    pub fn clear_color(&self) -> wgpu::Color {
+        wgpu::Color { a: if self.transparent { 0.0 } else { 1.0 }, ..self.clear_color }
+    }
+
+    /// See-through background for compositing (OBS, desktop overlays): unlit areas clear to
+    /// zero alpha and the surface switches to a premultiplied alpha mode; turning it off brings
+    /// back the opaque `set_clear_color` background. The window must be
+    /// created with `with_transparent(true)`. Returns false if the surface can't composite.
+    #[allow(dead_code)]
+    pub fn set_transparent(&mut self, enabled: bool) -> bool {
+        let alpha_mode = if enabled {
+            let preferred = [
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+                wgpu::CompositeAlphaMode::Inherit,
+            ];
+            let Some(mode) = preferred.into_iter().find(|mode| self.surface_alpha_modes.contains(mode)) else {
+                log::warn!("⚠️  Surface only supports {:?}, keeping an opaque background", self.surface_alpha_modes);
+                return false;
+            };
+            mode
+        } else {
+            self.surface_alpha_modes[0]
+        };
+
+        self.transparent = enabled;
+        if alpha_mode != self.config.alpha_mode {
+            self.config.alpha_mode = alpha_mode;
+            self.surface.configure(&self.device, &self.config);
+        }
+        log::info!("🪟 Transparent background: {} ({:?})", if enabled { "on" } else { "off" }, alpha_mode);
+        true
+    }
+
+    /// `clear_color` as the render pass expects it: sRGB surfaces take linear values
+    fn surface_clear_color(&self) -> wgpu::Color {
+        let clear_color = self.clear_color();
+        if !self.config.format.is_srgb() {
+            return clear_color;
+        }
+        let to_linear = |channel: f64| {
+            if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+        };
+        wgpu::Color {
+            r: to_linear(clear_color.r),
+            g: to_linear(clear_color.g),
+            b: to_linear(clear_color.b),
+            a: clear_color.a,
+        }
+    }
+
+    /// Frame feedback for motion trails: how much of the previous frame survives into the next
+    /// (0 = off, clamped to `MAX_FEEDBACK`)
+    #[allow(dead_code)]
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(0.0, MAX_FEEDBACK);
+    }
+
+    #[allow(dead_code)]
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Fixed number of mirrored kaleidoscope segments (clamped to `KALEIDOSCOPE_SEGMENT_RANGE`),
+    /// or None to let pitch and onsets vary it
+    #[allow(dead_code)]
+    pub fn set_kaleidoscope_segments(&mut self, segments: Option<u32>) {
+        let (min, max) = KALEIDOSCOPE_SEGMENT_RANGE;
+        self.kaleidoscope_segments = segments.map_or(0.0, |segments| segments.clamp(min, max) as f32);
+    }
+
+    #[allow(dead_code)]
+    pub fn kaleidoscope_segments(&self) -> Option<u32> {
+        (self.kaleidoscope_segments > 0.0).then_some(self.kaleidoscope_segments as u32)
+    }
+
+    /// How far each kaleidoscope seam is blended, as a share of a segment (0 = hard mirror lines)
+    #[allow(dead_code)]
+    pub fn set_kaleidoscope_seam_softness(&mut self, softness: f32) {
+        self.kaleidoscope_seam_softness = softness.clamp(0.0, 1.0);
+    }
+
+    #[allow(dead_code)]
+    pub fn kaleidoscope_seam_softness(&self) -> f32 {
+        self.kaleidoscope_seam_softness
+    }
+
+    /// Scale the kaleidoscope segment count with the estimated tempo (half at 60 BPM, up to
+    /// double at 240), crossfading between counts as it changes
+    #[allow(dead_code)]
+    pub fn set_kaleidoscope_bpm_sync(&mut self, enabled: bool) {
+        self.kaleidoscope_bpm_sync = enabled;
+    }
+
+    #[allow(dead_code)]
+    pub fn kaleidoscope_bpm_sync(&self) -> bool {
+        self.kaleidoscope_bpm_sync
+    }
+
+    /// Show loop points on a timeline strip along the bottom edge, as fractions (0-1) of the
+    /// track, with the playhead at `position`; with neither point set the strip is hidden
+    #[allow(dead_code)]
+    pub fn set_loop_markers(&mut self, loop_in: Option<f32>, loop_out: Option<f32>, position: f32) {
+        let fraction = |point: Option<f32>| point.map_or(-1.0, |point| point.clamp(0.0, 1.0));
+        self.loop_markers = (fraction(loop_in), fraction(loop_out), position.clamp(0.0, 1.0));
+    }
+
+    /// Audio-driven camera motion for the 3D projections (roll with the tempo, zoom on the
+    /// bass, both capped by `camera::MAX_ROLL_SPEED` / `MAX_ZOOM`); off keeps the view still
+    #[allow(dead_code)]
+    pub fn set_camera_motion(&mut self, enabled: bool) {
+        self.camera_motion = enabled;
+    }
+
+    #[allow(dead_code)]
+    pub fn camera_motion(&self) -> bool {
+        self.camera_motion
+    }
+
+    /// Reduced-motion accessibility mode: effects animate at `REDUCED_MOTION_SCALE` speed, the
+    /// camera moves that much as far, the particle swarm stops bursting, and auto-blend favours
+    /// gentle effects with slower crossfades (see `EffectConfig::reduced_motion`). Applies to
+    /// both halves of a split screen.
+    #[allow(dead_code)]
+    pub fn set_reduced_motion(&mut self, enabled: bool) {
+        self.motion_scale = if enabled { REDUCED_MOTION_SCALE } else { 1.0 };
+        self.psychedelic_manager.set_reduced_motion(enabled);
+        if let Some(pane) = self.split_pane.as_mut() {
+            pane.psychedelic_manager.set_reduced_motion(enabled);
+        }
+        self.apply_particle_emission_rate();
+    }
+
+    #[allow(dead_code)]
+    pub fn is_reduced_motion(&self) -> bool {
+        self.psychedelic_manager.is_reduced_motion()
+    }
+
+    /// Draw the frame just rendered into the history onto the surface, over the clear colour.
+    /// With a pipeline missing the surface is filled with `MISSING_PIPELINE_COLOR` instead.
+    fn present_frame(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let missing = self.shader_manager.missing_pipeline(&FRAME_PIPELINES);
+        if let Some(name) = missing.filter(|_| !self.missing_pipeline_warned) {
+            log::error!("❌ No '{}' pipeline; filling the window with magenta instead of the visuals", name);
+            self.missing_pipeline_warned = true;
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Present Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(if missing.is_some() { MISSING_PIPELINE_COLOR } else { self.surface_clear_color() }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        if let Some(pipeline) = self.shader_manager.get_pipeline("frame_blit").filter(|_| missing.is_none()) {
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, self.frame_history.current_bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.buffer.slice(..));
+            render_pass.draw(0..self.vertex_buffer.vertex_count, 0..1);
+        }
+    }
+
+    /// Number of spectralizer bars (clamped to 1-64)
+    #[allow(dead_code)]
+    pub fn set_spectrum_bars(&mut self, bars: usize) {
+        self.num_spectrum_bars = bars.clamp(1, MAX_SPECTRUM_BARS);
+    }
+
+    /// Spectrum points sampled per spectralizer bar, interpolated between FFT bins so bars
+    /// narrower than a bin form a smooth contour (1 = per-bin bars; clamped to 1-16)
+    #[allow(dead_code)]
+    pub fn set_spectrum_supersampling(&mut self, factor: usize) {
+        self.spectrum_supersampling = factor.clamp(1, MAX_SPECTRUM_SUPERSAMPLING);
+    }
+
+    /// Write the uniforms that drove the last rendered frame as JSON (for bug reports)
+    pub fn dump_uniforms<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        let uniforms = self.last_uniforms.unwrap_or_default();
+        UniformsSnapshot::from(&uniforms).save(&path)?;
+        log::info!("📸 Uniforms written to {}", path.as_ref().display());
+        Ok(())
+    }
+
+    /// Load a uniforms dump and render exactly those values every frame, ignoring audio
+    pub fn load_uniforms<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let snapshot = UniformsSnapshot::load(&path)?;
+        self.forced_uniforms = Some(Uniforms::from(&snapshot));
+        log::info!("📂 Rendering fixed uniforms from {}", path.as_ref().display());
+        Ok(())
+    }
+
+    /// Return to live, audio-driven uniforms after `load_uniforms`
+    #[allow(dead_code)]
+    pub fn clear_forced_uniforms(&mut self) {
+        self.forced_uniforms = None;
+    }
+
+    /// Log the blend weights of every rendered frame to a CSV for `replay_weights`.
+    /// Single-view rendering only; split-screen frames are not recorded.
+    #[allow(dead_code)]
+    pub fn record_weights<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        self.weight_recorder = Some(WeightRecorder::create(&path)?);
+        log::info!("⏺️  Recording effect weights to {}", path.as_ref().display());
+        Ok(())
+    }
+
+    /// Drive the effect weights from a recorded log instead of the psychedelic manager, so
+    /// a misbehaving blend can be reproduced without the analysis that produced it
+    #[allow(dead_code)]
+    pub fn replay_weights<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let log = WeightLog::load(&path)?;
+        log::info!("⏯️  Replaying {} frames ({:.1}s) of effect weights from {}",
+                   log.len(), log.duration_seconds(), path.as_ref().display());
+        self.weight_replay = Some(log);
+        Ok(())
+    }
+
+    /// Feed cursor/scroll events into the mouse uniforms (middle click resets).
+    /// Returns true if the event was a mouse event.
+    pub fn handle_mouse_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                let width = self.size.width.max(1) as f32;
+                let height = self.size.height.max(1) as f32;
+                self.set_mouse_position(position.x as f32 / width, position.y as f32 / height);
+                true
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let steps = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / 50.0,
+                };
+                self.adjust_mouse_zoom(steps * 0.1);
+                true
+            }
+            WindowEvent::MouseInput { button: MouseButton::Middle, state, .. } => {
+                if state.is_pressed() {
+                    self.reset_mouse();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
+            self.frame_history = FrameHistory::new(&self.device, &self.frame_history_bind_group_layout, &self.config);
         }
     }
 
-    pub fn render(&mut self, audio_frame: &AudioFrame, _window: &Window) -> Result<()> {
+    /// Ramp the master intensity one frame towards its target.
+    /// The master fade keeps ramping even while visuals are frozen.
+    fn advance_master_fade(&mut self) {
+        let fade_step = if self.master_fade_time <= f32::EPSILON { 1.0 } else { (1.0 / 60.0) / self.master_fade_time };
+        let fade_delta = (self.master_target - self.master_intensity).clamp(-fade_step, fade_step);
+        self.master_intensity += fade_delta;
+    }
+
+    /// Compute this frame's uniforms for a viewport of `width` x `height` pixels,
+    /// advancing time and effect state unless frozen
+    fn build_uniforms(&mut self, audio_frame: &AudioFrame, width: f32, height: f32) -> Uniforms {
+        let uniforms = match (self.forced_uniforms, self.time_paused, self.last_uniforms) {
+            // A loaded fixture wins over everything so the frame is reproduced exactly
+            (Some(forced), _, _) => forced,
+            // Frozen: re-present the last state, keeping the window size and manual controls live
+            (None, true, Some(mut frozen)) => {
+                frozen.view_proj = self.camera.view_proj(width / height).to_cols_array_2d();
+                frozen.projection_mode = self.projection_mode;
+                frozen.palette_index = self.palette_index;
+                frozen.smoothing_factor = self.smoothing_factor;
+                frozen.mouse_x = self.mouse_x;
+                frozen.mouse_y = self.mouse_y;
+                frozen.mouse_zoom = self.mouse_zoom;
+                frozen.master_intensity = self.master_intensity;
+                frozen.feedback = self.feedback;
+                (frozen.loop_in, frozen.loop_out, frozen.loop_position) = self.loop_markers;
+                frozen.kaleidoscope_segments = self.kaleidoscope_segments;
+                frozen.kaleidoscope_seam_softness = self.kaleidoscope_seam_softness;
+                frozen.kaleidoscope_bpm_sync = if self.kaleidoscope_bpm_sync { 1.0 } else { 0.0 };
+                frozen.beat_flash_intensity = 0.0; // A held flash would read as a brightness jump
+                frozen
+            }
+            _ => {
+                let delta_time = 1.0 / 60.0;
+                self.time += delta_time;
+                self.animation_time += delta_time * self.motion_scale;
+
+                // The callers have already sanitized the frame (see `sanitized_frame`)
+                let mut adjusted;
+                let audio_frame = match self.solo_band {
+                    Some(band) => {
+                        adjusted = audio_frame.clone();
+                        band.apply(&mut adjusted);
+                        &adjusted
+                    }
+                    None => audio_frame,
+                };
+
+                // Update psychedelic effect manager
+                self.psychedelic_manager.update(delta_time, audio_frame);
+                self.spectrum_bars.update(audio_frame, self.num_spectrum_bars, self.spectrum_supersampling);
+                self.flux_debug.update(audio_frame);
+                let beat_flash_intensity = self.beat_flash.update(audio_frame.beat_detected, delta_time);
+                let particle_spawn_rate = self.particle_burst.update(
+                    audio_frame.onset_strength, audio_frame.beat_strength, audio_frame.beat_detected, delta_time);
+                let single_view = self.split_pane.is_none();
+                let effect_weights = match &self.weight_replay {
+                    Some(log) if single_view => log.weights_at(self.time),
+                    _ => self.psychedelic_manager.get_blend_weights(),
+                };
+                if let Some(recorder) = self.weight_recorder.as_mut().filter(|_| single_view) {
+                    if let Err(e) = recorder.record(self.time, &effect_weights) {
+                        log::warn!("⚠️  Stopped recording effect weights: {}", e);
+                        self.weight_recorder = None;
+                    }
+                }
+                let intensity_scalers = self.psychedelic_manager.get_intensity_scalers();
+                let flux_debug_weight = *effect_weights.get("flux_debug").unwrap_or(&0.0);
+
+                let mut uniforms = Uniforms {
+                    view_proj: [[0.0; 4]; 4], // Set below, once the projection the shader resolves is known
+                    time: self.animation_time,
+                    sub_bass: audio_frame.frequency_bands.sub_bass,
+                    bass: audio_frame.frequency_bands.bass,
+                    mid: audio_frame.frequency_bands.mid,
+                    treble: audio_frame.frequency_bands.treble,
+                    presence: audio_frame.frequency_bands.presence,
+                    beat_strength: audio_frame.beat_strength,
+                    estimated_bpm: audio_frame.estimated_bpm,
+                    volume: audio_frame.volume,
+                    spectral_centroid: audio_frame.spectral_centroid,
+                    spectral_rolloff: audio_frame.spectral_rolloff,
+                    pitch_confidence: audio_frame.pitch_confidence,
+                    zero_crossing_rate: audio_frame.zero_crossing_rate,
+                    spectral_flux: audio_frame.spectral_flux,
+                    onset_strength: audio_frame.onset_strength,
+                    dynamic_range: audio_frame.dynamic_range,
+                    plasma_weight: *effect_weights.get("llama_plasma").unwrap_or(&0.0),
+                    kaleidoscope_weight: *effect_weights.get("geometric_kaleidoscope").unwrap_or(&0.0),
+                    tunnel_weight: *effect_weights.get("psychedelic_tunnel").unwrap_or(&0.0),
+                    particle_weight: *effect_weights.get("particle_swarm").unwrap_or(&0.0),
+                    fractal_weight: *effect_weights.get("fractal_madness").unwrap_or(&0.0),
+                    spectralizer_weight: *effect_weights.get("spectralizer_bars").unwrap_or(&0.0),
+                    parametric_weight: *effect_weights.get("parametric_waves").unwrap_or(&0.0),
+                    plasma_intensity: *intensity_scalers.get("llama_plasma").unwrap_or(&1.0),
+                    kaleidoscope_intensity: *intensity_scalers.get("geometric_kaleidoscope").unwrap_or(&1.0),
+                    tunnel_intensity: *intensity_scalers.get("psychedelic_tunnel").unwrap_or(&1.0),
+                    particle_intensity: *intensity_scalers.get("particle_swarm").unwrap_or(&1.0),
+                    fractal_intensity: *intensity_scalers.get("fractal_madness").unwrap_or(&1.0),
+                    spectralizer_intensity: *intensity_scalers.get("spectralizer_bars").unwrap_or(&1.0),
+                    parametric_intensity: *intensity_scalers.get("parametric_waves").unwrap_or(&1.0),
+                    projection_mode: self.projection_mode,
+                    palette_index: self.palette_index,
+                    smoothing_factor: self.smoothing_factor,
+                    mouse_x: self.mouse_x,
+                    mouse_y: self.mouse_y,
+                    mouse_zoom: self.mouse_zoom,
+                    master_intensity: self.master_intensity,
+                    flux_debug_weight,
+                    tonemap_mode: self.tonemap_mode,
+                    beat_flash_intensity,
+                    feedback: self.feedback,
+                    loop_in: self.loop_markers.0,
+                    loop_out: self.loop_markers.1,
+                    loop_position: self.loop_markers.2,
+                    kaleidoscope_segments: self.kaleidoscope_segments,
+                    kaleidoscope_seam_softness: self.kaleidoscope_seam_softness,
+                    kaleidoscope_bpm_sync: if self.kaleidoscope_bpm_sync { 1.0 } else { 0.0 },
+                    particle_spawn_rate,
+                };
+
+                let camera_motion = camera_motion_target(self.camera_motion, &uniforms, self.motion_scale);
+                self.camera.update(audio_frame, delta_time, camera_motion);
+                uniforms.view_proj = self.camera.view_proj(width / height).to_cols_array_2d();
+                uniforms
+            }
+        };
+        self.last_uniforms = Some(uniforms);
+
+        uniforms
+    }
+
+    pub fn render(&mut self, audio_frame: &AudioFrame, window: &Window) -> Result<()> {
+        self.render_with_overlay(audio_frame, window, |_, _, _, _, _| Ok(()))
+    }
+
+    /// Render a frame, then let `overlay` (e.g. the egui UI) draw on top of it in the same encoder.
+    /// The overlay gets the size of the texture being drawn, which its screen descriptor must match.
+    #[allow(dead_code)]
+    pub fn render_with_overlay<F>(&mut self, audio_frame: &AudioFrame, _window: &Window, overlay: F) -> Result<()>
+    where
+        F: FnOnce(&mut wgpu::CommandEncoder, &wgpu::TextureView, &wgpu::Device, &wgpu::Queue, winit::dpi::PhysicalSize<u32>) -> Result<()>,
+    {
         if self.cleaned_up {
             return Ok(()); // Don't render after cleanup
         }
-        let delta_time = 1.0 / 60.0;
-        self.time += delta_time;
-
-        // Update psychedelic effect manager
-        self.psychedelic_manager.update(delta_time, audio_frame);
-        let effect_weights = self.psychedelic_manager.get_effect_weights();
-
-        let uniforms = Uniforms {
-            view_proj: Mat4::orthographic_rh(
-                -(self.size.width as f32) / 2.0,
-                (self.size.width as f32) / 2.0,
-                -(self.size.height as f32) / 2.0,
-                (self.size.height as f32) / 2.0,
-                -1.0,
-                1.0,
-            ).to_cols_array_2d(),
-            time: self.time,
-            sub_bass: audio_frame.frequency_bands.sub_bass,
-            bass: audio_frame.frequency_bands.bass,
-            mid: audio_frame.frequency_bands.mid,
-            treble: audio_frame.frequency_bands.treble,
-            presence: audio_frame.frequency_bands.presence,
-            beat_strength: audio_frame.beat_strength,
-            estimated_bpm: audio_frame.estimated_bpm,
-            volume: audio_frame.volume,
-            spectral_centroid: audio_frame.spectral_centroid,
-            spectral_rolloff: audio_frame.spectral_rolloff,
-            pitch_confidence: audio_frame.pitch_confidence,
-            zero_crossing_rate: audio_frame.zero_crossing_rate,
-            spectral_flux: audio_frame.spectral_flux,
-            onset_strength: audio_frame.onset_strength,
-            dynamic_range: audio_frame.dynamic_range,
-            plasma_weight: *effect_weights.get("llama_plasma").unwrap_or(&0.0),
-            kaleidoscope_weight: *effect_weights.get("geometric_kaleidoscope").unwrap_or(&0.0),
-            tunnel_weight: *effect_weights.get("psychedelic_tunnel").unwrap_or(&0.0),
-            particle_weight: *effect_weights.get("particle_swarm").unwrap_or(&0.0),
-            fractal_weight: *effect_weights.get("fractal_madness").unwrap_or(&0.0),
-            spectralizer_weight: *effect_weights.get("spectralizer_bars").unwrap_or(&0.0),
-            parametric_weight: *effect_weights.get("parametric_waves").unwrap_or(&0.0),
-            projection_mode: self.projection_mode,
-            palette_index: self.palette_index,
-            smoothing_factor: self.smoothing_factor,
-            _padding: [0.0; 3],  // Proper padding
-        };
 
+        super::crash_report::record_frame(audio_frame);
+        let audio_frame = sanitized_frame(audio_frame, &mut self.non_finite_warned);
+        self.advance_master_fade();
+        let uniforms = self.build_uniforms(&audio_frame, self.size.width as f32, self.size.height as f32);
+
+        let spectrum = self.spectrum_uniforms();
+        let flux_debug = self.flux_debug.to_uniforms();
         self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        self.queue.write_buffer(&self.spectrum_buffer, 0, bytemuck::cast_slice(&[spectrum]));
+        self.queue.write_buffer(&self.flux_debug_buffer, 0, bytemuck::cast_slice(&[flux_debug]));
 
         let output = self.surface.get_current_texture()?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -356,15 +1419,10 @@ impl<'a> GraphicsEngine<'a> {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: self.frame_history.current_view(),
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.0,
-                            g: 0.0,
-                            b: 0.0,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -376,15 +1434,165 @@ impl<'a> GraphicsEngine<'a> {
             if let Some(pipeline) = self.shader_manager.get_pipeline("visualizer") {
                 render_pass.set_pipeline(pipeline);
                 render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_bind_group(1, self.frame_history.previous_bind_group(), &[]);
                 render_pass.set_vertex_buffer(0, self.vertex_buffer.buffer.slice(..));
                 render_pass.draw(0..self.vertex_buffer.vertex_count, 0..1);
             }
         }
+        self.present_frame(&mut encoder, &view);
+
+        let target_size = winit::dpi::PhysicalSize::new(output.texture.width(), output.texture.height());
+        overlay(&mut encoder, &view, &self.device, &self.queue, target_size)?;
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+        self.frame_history.advance();
+
+        Ok(())
+    }
+
+    /// Enable split-screen rendering, giving the right half its own effect state
+    #[allow(dead_code)]
+    pub fn enable_split_screen(&mut self) {
+        if self.split_pane.is_some() {
+            return;
+        }
+
+        let uniform_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Split Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[Uniforms::new()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let spectrum_buffer = Self::create_spectrum_buffer(&self.device);
+        let flux_debug_buffer = Self::create_flux_debug_buffer(&self.device);
+        let uniform_bind_group = Self::create_uniform_bind_group(
+            &self.device,
+            &self.uniform_bind_group_layout,
+            &uniform_buffer,
+            &spectrum_buffer,
+            &flux_debug_buffer,
+            "split_uniform_bind_group",
+        );
+
+        self.split_pane = Some(SplitPane {
+            uniform_buffer,
+            spectrum_buffer,
+            flux_debug_buffer,
+            uniform_bind_group,
+            time: 0.0,
+            animation_time: 0.0,
+            psychedelic_manager: PsychedelicManager::new(),
+            last_uniforms: None,
+            spectrum_bars: SpectrumBars::new(),
+            flux_debug: FluxDebug::new(),
+            beat_flash: self.beat_flash.clone(),
+            particle_burst: self.particle_burst.clone(),
+            camera: self.camera.clone(),
+        });
+    }
+
+    /// Effect manager driving the right half of a split-screen render, if enabled
+    #[allow(dead_code)]
+    pub fn split_psychedelic_manager_mut(&mut self) -> Option<&mut PsychedelicManager> {
+        self.split_pane.as_mut().map(|pane| &mut pane.psychedelic_manager)
+    }
+
+    /// Swap the right-hand pane's time and effect state with the engine's own
+    fn swap_split_state(&mut self) {
+        if let Some(pane) = self.split_pane.as_mut() {
+            std::mem::swap(&mut self.time, &mut pane.time);
+            std::mem::swap(&mut self.animation_time, &mut pane.animation_time);
+            std::mem::swap(&mut self.psychedelic_manager, &mut pane.psychedelic_manager);
+            std::mem::swap(&mut self.last_uniforms, &mut pane.last_uniforms);
+            std::mem::swap(&mut self.spectrum_bars, &mut pane.spectrum_bars);
+            std::mem::swap(&mut self.flux_debug, &mut pane.flux_debug);
+            std::mem::swap(&mut self.beat_flash, &mut pane.beat_flash);
+            std::mem::swap(&mut self.particle_burst, &mut pane.particle_burst);
+            std::mem::swap(&mut self.camera, &mut pane.camera);
+        }
+    }
+
+    /// Render two audio frames side by side: `left` with the engine's own effect state,
+    /// `right` with the split pane's. Manual controls (palette, projection, mouse, master
+    /// fade, freeze) apply to both halves. Falls back to `render` if split screen is off.
+    #[allow(dead_code)]
+    pub fn render_split(&mut self, left: &AudioFrame, right: &AudioFrame, window: &Window) -> Result<()> {
+        if self.split_pane.is_none() {
+            return self.render(left, window);
+        }
+        if self.cleaned_up {
+            return Ok(());
+        }
+
+        super::crash_report::record_frame(left);
+        let left = sanitized_frame(left, &mut self.non_finite_warned);
+        let right = sanitized_frame(right, &mut self.non_finite_warned);
+        const GAP: f32 = 4.0; // Pixels of black between the halves
+        let half_width = ((self.size.width as f32 - GAP) / 2.0).max(1.0);
+        let height = self.size.height as f32;
+
+        self.advance_master_fade();
+        let left_uniforms = self.build_uniforms(&left, half_width, height);
+        let left_spectrum = self.spectrum_uniforms();
+        let left_flux = self.flux_debug.to_uniforms();
+        self.swap_split_state();
+        let right_uniforms = self.build_uniforms(&right, half_width, height);
+        let right_spectrum = self.spectrum_uniforms();
+        let right_flux = self.flux_debug.to_uniforms();
+        self.swap_split_state();
+
+        let pane = self.split_pane.as_ref().expect("split pane checked above");
+        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[left_uniforms]));
+        self.queue.write_buffer(&self.spectrum_buffer, 0, bytemuck::cast_slice(&[left_spectrum]));
+        self.queue.write_buffer(&pane.uniform_buffer, 0, bytemuck::cast_slice(&[right_uniforms]));
+        self.queue.write_buffer(&pane.spectrum_buffer, 0, bytemuck::cast_slice(&[right_spectrum]));
+        self.queue.write_buffer(&self.flux_debug_buffer, 0, bytemuck::cast_slice(&[left_flux]));
+        self.queue.write_buffer(&pane.flux_debug_buffer, 0, bytemuck::cast_slice(&[right_flux]));
+
+        let output = self.surface.get_current_texture()?;
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Split Render Encoder"),
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Split Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: self.frame_history.current_view(),
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            if let Some(pipeline) = self.shader_manager.get_pipeline("visualizer") {
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.buffer.slice(..));
+                render_pass.set_bind_group(1, self.frame_history.previous_bind_group(), &[]);
 
-        // UI rendering would go here
+                let halves = [
+                    (0.0, &self.uniform_bind_group),
+                    (half_width + GAP, &pane.uniform_bind_group),
+                ];
+                for (x, bind_group) in halves {
+                    render_pass.set_viewport(x, 0.0, half_width, height, 0.0, 1.0);
+                    render_pass.set_bind_group(0, bind_group, &[]);
+                    render_pass.draw(0..self.vertex_buffer.vertex_count, 0..1);
+                }
+            }
+        }
+        self.present_frame(&mut encoder, &view);
 
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
+        self.frame_history.advance();
 
         Ok(())
     }
@@ -399,17 +1607,24 @@ impl<'a> GraphicsEngine<'a> {
         &self.psychedelic_manager
     }
 
-    /// Explicit cleanup method to avoid destructor panics
+    /// Stop rendering and wait for all submitted GPU work to finish.
+    ///
+    /// Called automatically on drop; binaries may call it early on shutdown so that any
+    /// late redraw events become no-ops.
     pub fn cleanup(&mut self) {
         if self.cleaned_up {
             return; // Already cleaned up
         }
 
-        // Ensure all GPU operations are complete before cleanup
+        // Block until the queue is idle so no submission references resources being freed
         self.device.poll(wgpu::Maintain::Wait);
 
-        // Wait a bit longer to ensure complete cleanup
-        std::thread::sleep(std::time::Duration::from_millis(50));
+        if let Some(recorder) = self.weight_recorder.take() {
+            match recorder.finish() {
+                Ok(frames) => log::info!("⏹️  Recorded {} frames of effect weights", frames),
+                Err(e) => log::warn!("⚠️  Failed to finish the effect weight log: {}", e),
+            }
+        }
 
         self.cleaned_up = true;
     }
@@ -428,10 +1643,16 @@ impl<'a> GraphicsEngine<'a> {
     /// Analyze audio data using GPU compute shaders (if available)
     /// Falls back to CPU analysis if GPU analyzer is not initialized
     pub async fn analyze_audio_gpu(&mut self, audio_data: &[f32]) -> Option<GpuAudioFeatures> {
-        if let Some(ref mut gpu_analyzer) = self.gpu_analyzer {
-            gpu_analyzer.analyze(&self.device, &self.queue, audio_data).await.ok()
-        } else {
-            None
+        let gpu_analyzer = self.gpu_analyzer.as_mut()?;
+        match gpu_analyzer.analyze(&self.device, &self.queue, audio_data).await {
+            Ok(features) => Some(features),
+            Err(e) => {
+                if gpu_analyzer.has_failed() {
+                    log::warn!("⚠️  Disabling GPU audio analysis: {}", e);
+                    self.gpu_analyzer = None;
+                }
+                None
+            }
         }
     }
 
@@ -440,6 +1661,7 @@ impl<'a> GraphicsEngine<'a> {
         AudioFrame {
             sample_rate: 44100.0,
             spectrum: vec![0.0; 512], // Placeholder
+            mel_bands: Vec::new(),
             time_domain: vec![0.0; 1024], // Placeholder
             frequency_bands: crate::audio::FrequencyBands {
                 sub_bass: gpu_features.sub_bass,
@@ -463,5 +1685,70 @@ impl<'a> GraphicsEngine<'a> {
     }
 }
 
-// Note: Drop implementation removed to prevent destructor panics
-// Cleanup is handled manually via the cleanup() method before program exit
\ No newline at end of file
+impl Drop for GraphicsEngine<'_> {
+    fn drop(&mut self) {
+        // Resources are only released once the GPU is idle; the surface then drops first
+        // (see field order), before the device it was configured with.
+        self.cleanup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camera_rests_whenever_the_shader_resolves_flat() {
+        // Auto mode with nothing dominant and quiet bands: the shader draws flat
+        let quiet = Uniforms { plasma_weight: 0.3, bass: 0.2, presence: 0.1, ..Uniforms::new() };
+        assert_eq!(quiet.projection_mode, -1.0);
+        assert_eq!(quiet.resolved_projection(), FLAT_PROJECTION);
+        assert_eq!(camera_motion_target(true, &quiet, 1.0), 0.0);
+
+        // Manual flat, even with a dominant tunnel
+        let flat = Uniforms { projection_mode: 3.0, tunnel_weight: 0.9, ..Uniforms::new() };
+        assert_eq!(camera_motion_target(true, &flat, 1.0), 0.0);
+
+        // Auto resolving to a 3D surface moves, scaled by reduced motion and off when disabled
+        let tunnel = Uniforms { tunnel_weight: 0.8, ..Uniforms::new() };
+        assert_eq!(tunnel.resolved_projection(), 1);
+        assert_eq!(camera_motion_target(true, &tunnel, 1.0), 1.0);
+        assert_eq!(camera_motion_target(true, &tunnel, 0.4), 0.4);
+        assert_eq!(camera_motion_target(false, &tunnel, 1.0), 0.0);
+        assert_eq!(Uniforms { bass: 0.7, ..Uniforms::new() }.resolved_projection(), 0);
+    }
+
+    #[test]
+    fn test_bad_frames_are_sanitized_with_one_warning() {
+        let mut warned = false;
+        let good = AudioFrame { volume: 0.5, ..AudioFrame::default() };
+        assert!(matches!(sanitized_frame(&good, &mut warned), Cow::Borrowed(_)));
+        assert!(!warned);
+
+        let mut bad = AudioFrame { volume: f32::NAN, estimated_bpm: f32::INFINITY, ..AudioFrame::default() };
+        bad.frequency_bands.bass = f32::NAN;
+        let sanitized = sanitized_frame(&bad, &mut warned);
+        assert!(sanitized.is_finite() && warned);
+        assert_eq!((sanitized.volume, sanitized.estimated_bpm, sanitized.frequency_bands.bass), (0.0, 120.0, 0.0));
+        // Both halves of a split render go through the same flag, so it stays a single warning
+        assert!(sanitized_frame(&bad, &mut warned).is_finite());
+    }
+
+    #[test]
+    fn test_window_size_defaults_and_bounds() {
+        use winit::dpi::{LogicalSize, PhysicalSize, Size};
+        let default = (1200, 800);
+        assert!(matches!(window_size(None, None, default).unwrap(), Size::Logical(size) if size == LogicalSize::new(1200.0, 800.0)));
+        // A size given on one side only takes the default for the other, in physical pixels
+        assert!(matches!(window_size(Some(1920), None, default).unwrap(), Size::Physical(size) if size == PhysicalSize::new(1920, 800)));
+        assert!(matches!(window_size(None, Some(1080), default).unwrap(), Size::Physical(size) if size == PhysicalSize::new(1200, 1080)));
+
+        let min = MIN_WINDOW_SIZE;
+        assert!(window_size(Some(min.0), Some(min.1), default).is_ok());
+        assert!(window_size(Some(min.0 - 1), Some(min.1), default).is_err());
+        assert!(window_size(None, Some(min.1 - 1), default).is_err());
+        assert!(window_size(Some(MAX_WINDOW_DIMENSION), Some(MAX_WINDOW_DIMENSION), default).is_ok());
+        assert!(window_size(Some(MAX_WINDOW_DIMENSION + 1), None, default).is_err());
+        assert!(window_size(None, Some(MAX_WINDOW_DIMENSION + 1), default).is_err());
+    }
+}
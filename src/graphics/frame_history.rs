@@ -0,0 +1,95 @@
+/// Strongest frame feedback, so trails always fade out instead of freezing on screen
+pub const MAX_FEEDBACK: f32 = 0.98;
+
+/// Offscreen copies of the last two frames, ping-ponged for feedback trails.
+///
+/// Each frame renders into the current texture while the shader reads the previous frame
+/// from the other one; the finished frame is then drawn onto the surface and the two swap.
+/// Frames are stored premultiplied over a transparent background, so the window's clear
+/// colour (or transparency) is applied only when presenting.
+#[allow(dead_code)]
+pub struct FrameHistory {
+    _textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+    bind_groups: [wgpu::BindGroup; 2], // Each binds the texture with the same index
+    current: usize,
+}
+
+#[allow(dead_code)]
+impl FrameHistory {
+    /// Layout of the history bind group: one frame texture, read with `textureLoad`
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+            label: Some("frame_history_bind_group_layout"),
+        })
+    }
+
+    /// Two blank frames matching the surface's size and format
+    pub fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, config: &wgpu::SurfaceConfiguration) -> Self {
+        let create = |index: usize| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(&format!("Frame History {}", index)),
+                size: wgpu::Extent3d {
+                    width: config.width.max(1),
+                    height: config.height.max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                }],
+                label: Some(&format!("frame_history_bind_group_{}", index)),
+            });
+            (texture, view, bind_group)
+        };
+
+        let (first_texture, first_view, first_bind_group) = create(0);
+        let (second_texture, second_view, second_bind_group) = create(1);
+        Self {
+            _textures: [first_texture, second_texture],
+            views: [first_view, second_view],
+            bind_groups: [first_bind_group, second_bind_group],
+            current: 0,
+        }
+    }
+
+    /// Render target for this frame
+    pub fn current_view(&self) -> &wgpu::TextureView {
+        &self.views[self.current]
+    }
+
+    /// Binds this frame's texture, for presenting it once rendered
+    pub fn current_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_groups[self.current]
+    }
+
+    /// Binds the last frame's texture, for the shader's feedback
+    pub fn previous_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_groups[1 - self.current]
+    }
+
+    /// Swap after presenting, so this frame becomes the previous one
+    pub fn advance(&mut self) {
+        self.current = 1 - self.current;
+    }
+}
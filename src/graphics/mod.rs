@@ -2,6 +2,14 @@ pub mod engine;
 pub mod shader;
 pub mod vertex;
 pub mod texture;
+pub mod snapshot;
+pub mod spectrum;
+pub mod beat_flash;
+pub mod particle_burst;
+pub mod camera;
+pub mod frame_history;
+pub mod crash_report;
+pub mod smoothing_scope;
 
 pub use engine::GraphicsEngine;
 pub use shader::ShaderManager;
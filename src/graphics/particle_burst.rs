@@ -0,0 +1,81 @@
+/// Highest emission rate multiplier (`--particle-bursts`)
+pub const MAX_EMISSION_RATE: f32 = 3.0;
+
+const DECAY_SECONDS: f32 = 0.35; // Time constant of a burst's fade as its particles fly out
+const ONSET_GAIN: f32 = 2.5;     // Burst size per unit of onset rise
+
+/// Onset-driven particle emission for the particle swarm: each transient (a jump in onset
+/// strength, or a detected beat) throws out a burst, which then fades as its particles fly
+/// outward. Only rises count, so a steadily busy mix swirls instead of sitting mid-burst.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ParticleBurst {
+    emission_rate: f32, // Burst size multiplier (0 = no bursts)
+    level: f32,         // Current spawn rate (jumps on a hit, decaying to 0)
+    previous_onset: f32,
+}
+
+impl Default for ParticleBurst {
+    fn default() -> Self {
+        Self {
+            emission_rate: 1.0,
+            level: 0.0,
+            previous_onset: 0.0,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl ParticleBurst {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Burst size multiplier, 0-`MAX_EMISSION_RATE` (0 = the swarm never bursts)
+    pub fn set_emission_rate(&mut self, rate: f32) {
+        self.emission_rate = rate.clamp(0.0, MAX_EMISSION_RATE);
+    }
+
+    pub fn emission_rate(&self) -> f32 {
+        self.emission_rate
+    }
+
+    /// Advance one frame, returning the `particle_spawn_rate` uniform (0-1)
+    pub fn update(&mut self, onset_strength: f32, beat_strength: f32, beat_detected: bool, delta_time: f32) -> f32 {
+        self.level *= (-delta_time / DECAY_SECONDS).exp();
+
+        let onset_rise = (onset_strength - self.previous_onset).max(0.0);
+        self.previous_onset = onset_strength;
+        let beat = if beat_detected { beat_strength } else { 0.0 };
+        let hit = ((onset_rise * ONSET_GAIN + beat) * self.emission_rate).min(1.0);
+        self.level = self.level.max(hit);
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transients_burst_and_steady_onsets_do_not() {
+        let mut burst = ParticleBurst::new();
+        let frame = 1.0 / 60.0;
+
+        // A steady onset level settles to nothing after the first frame's rise
+        burst.update(0.4, 0.0, false, frame);
+        let steady = (0..60).map(|_| burst.update(0.4, 0.0, false, frame)).last().unwrap();
+        assert!(steady < 0.1, "steady onset left spawn rate {}", steady);
+
+        // A sharp hit bursts, then fades over a few tenths of a second
+        let peak = burst.update(0.9, 0.8, true, frame);
+        assert!(peak > 0.9);
+        let later = (0..20).map(|_| burst.update(0.9, 0.0, false, frame)).last().unwrap();
+        assert!(later < peak * 0.45 && later > 0.0);
+
+        // Emission rate 0: the swarm never bursts
+        let mut silent = ParticleBurst::new();
+        silent.set_emission_rate(0.0);
+        assert_eq!(silent.update(1.0, 1.0, true, frame), 0.0);
+    }
+}
@@ -7,6 +7,12 @@ pub struct ShaderManager {
     pipelines: HashMap<String, RenderPipeline>,
 }
 
+impl Default for ShaderManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ShaderManager {
     pub fn new() -> Self {
         Self {
@@ -15,11 +21,16 @@ impl ShaderManager {
         }
     }
 
+    /// Compile a WGSL shader, returning the compiler's message if it doesn't validate
     pub fn load_shader(&mut self, device: &Device, name: &str, source: &str) -> Result<()> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some(name),
             source: wgpu::ShaderSource::Wgsl(source.into()),
         });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(anyhow::anyhow!("Shader '{}' failed to compile: {}", name, error));
+        }
 
         self.shaders.insert(name.to_string(), shader);
         Ok(())
@@ -31,14 +42,15 @@ impl ShaderManager {
         name: &str,
         shader_name: &str,
         format: wgpu::TextureFormat,
-        bind_group_layout: &wgpu::BindGroupLayout,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
     ) -> Result<()> {
         let shader = self.shaders.get(shader_name)
             .ok_or_else(|| anyhow::anyhow!("Shader '{}' not found", shader_name))?;
 
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some(&format!("{} Pipeline Layout", name)),
-            bind_group_layouts: &[bind_group_layout],
+            bind_group_layouts,
             push_constant_ranges: &[],
         });
 
@@ -56,7 +68,7 @@ impl ShaderManager {
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
                     format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
@@ -78,6 +90,11 @@ impl ShaderManager {
             },
             multiview: None,
         });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            // Don't leave a pipeline built for another format or layout in its place
+            self.pipelines.remove(name);
+            return Err(anyhow::anyhow!("Pipeline '{}' could not be created: {}", name, error));
+        }
 
         self.pipelines.insert(name.to_string(), pipeline);
         Ok(())
@@ -86,4 +103,22 @@ impl ShaderManager {
     pub fn get_pipeline(&self, name: &str) -> Option<&RenderPipeline> {
         self.pipelines.get(name)
     }
+
+    /// The first of `names` with no pipeline, if any
+    pub fn missing_pipeline<'n>(&self, names: &[&'n str]) -> Option<&'n str> {
+        names.iter().copied().find(|name| !self.pipelines.contains_key(*name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_pipelines_are_reported_in_order() {
+        let manager = ShaderManager::new();
+        assert_eq!(manager.missing_pipeline(&["visualizer", "frame_blit"]), Some("visualizer"));
+        assert_eq!(manager.missing_pipeline(&[]), None);
+        assert!(manager.get_pipeline("visualizer").is_none());
+    }
 }
\ No newline at end of file
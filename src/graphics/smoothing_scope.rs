@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use crate::audio::envelope::{AttackRelease, SmoothingConfig};
+use crate::audio::AudioFrame;
+
+/// Frames of history the scope keeps (~4 seconds at 60 fps)
+pub const SCOPE_FRAMES: usize = 240;
+
+/// Enveloped feature traced by the smoothing scope
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScopeFeature {
+    SubBass,
+    #[default]
+    Bass,
+    Mid,
+    Treble,
+    Presence,
+    BeatStrength,
+    Volume,
+    SpectralFlux,
+    OnsetStrength,
+}
+
+impl FromStr for ScopeFeature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Self::ALL.into_iter()
+            .find(|feature| feature.name().eq_ignore_ascii_case(&s.replace('_', "-")))
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown scope feature '{}'. Use: {}", s,
+                Self::ALL.map(|feature| feature.name()).join(", ")
+            ))
+    }
+}
+
+#[allow(dead_code)]
+impl ScopeFeature {
+    pub const ALL: [Self; 9] = [
+        Self::SubBass, Self::Bass, Self::Mid, Self::Treble, Self::Presence,
+        Self::BeatStrength, Self::Volume, Self::SpectralFlux, Self::OnsetStrength,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SubBass => "sub-bass",
+            Self::Bass => "bass",
+            Self::Mid => "mid",
+            Self::Treble => "treble",
+            Self::Presence => "presence",
+            Self::BeatStrength => "beat",
+            Self::Volume => "volume",
+            Self::SpectralFlux => "flux",
+            Self::OnsetStrength => "onset",
+        }
+    }
+
+    /// The feature after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&feature| feature == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn value(self, frame: &AudioFrame) -> f32 {
+        match self {
+            Self::SubBass => frame.frequency_bands.sub_bass,
+            Self::Bass => frame.frequency_bands.bass,
+            Self::Mid => frame.frequency_bands.mid,
+            Self::Treble => frame.frequency_bands.treble,
+            Self::Presence => frame.frequency_bands.presence,
+            Self::BeatStrength => frame.beat_strength,
+            Self::Volume => frame.volume,
+            Self::SpectralFlux => frame.spectral_flux,
+            Self::OnsetStrength => frame.onset_strength,
+        }
+    }
+
+    /// Attack/release times in `smoothing` that envelope this feature
+    pub fn smoothing(self, smoothing: &SmoothingConfig) -> AttackRelease {
+        let mut smoothing = smoothing.clone();
+        *self.smoothing_mut(&mut smoothing)
+    }
+
+    /// The attack/release group in `smoothing` that envelopes this feature
+    pub fn smoothing_mut(self, smoothing: &mut SmoothingConfig) -> &mut AttackRelease {
+        match self {
+            Self::SubBass => &mut smoothing.per_band[0],
+            Self::Bass => &mut smoothing.per_band[1],
+            Self::Mid => &mut smoothing.per_band[2],
+            Self::Treble => &mut smoothing.per_band[3],
+            Self::Presence => &mut smoothing.per_band[4],
+            Self::BeatStrength | Self::OnsetStrength => &mut smoothing.beat,
+            Self::Volume | Self::SpectralFlux => &mut smoothing.spectral,
+        }
+    }
+}
+
+/// Recent raw (before the envelope) and smoothed values of one feature, oldest first,
+/// for tuning smoothing against what the visuals actually receive
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SmoothingScope {
+    feature: ScopeFeature,
+    samples: VecDeque<(f32, f32)>,
+    capacity: usize,
+}
+
+impl Default for SmoothingScope {
+    fn default() -> Self {
+        Self::new(ScopeFeature::default(), SCOPE_FRAMES)
+    }
+}
+
+#[allow(dead_code)]
+impl SmoothingScope {
+    pub fn new(feature: ScopeFeature, capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            feature,
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn feature(&self) -> ScopeFeature {
+        self.feature
+    }
+
+    /// Trace a different feature, starting a fresh history
+    pub fn set_feature(&mut self, feature: ScopeFeature) {
+        if feature != self.feature {
+            self.feature = feature;
+            self.samples.clear();
+        }
+    }
+
+    /// Record one frame, dropping the oldest once the scope is full
+    pub fn push(&mut self, raw: &AudioFrame, smoothed: &AudioFrame) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((self.feature.value(raw), self.feature.value(smoothed)));
+    }
+
+    /// (raw, smoothed) pairs, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::envelope::EnvelopeFollower;
+    use crate::audio::FrequencyBands;
+
+    fn frame_with_bass(bass: f32) -> AudioFrame {
+        AudioFrame {
+            frequency_bands: FrequencyBands { bass, ..FrequencyBands::default() },
+            ..AudioFrame::default()
+        }
+    }
+
+    /// Frames for the smoothed trace to fall to half of a raw step down from 1.0 to 0.0
+    fn frames_to_half(release: f32) -> usize {
+        let mut scope = SmoothingScope::new(ScopeFeature::Bass, 8);
+        let mut follower = EnvelopeFollower::new(0.0, release);
+        follower.process(1.0, 1.0 / 60.0);
+        for _ in 0..8 {
+            let smoothed = follower.process(0.0, 1.0 / 60.0);
+            scope.push(&frame_with_bass(0.0), &frame_with_bass(smoothed));
+        }
+        assert_eq!(scope.len(), 8);
+        assert!(scope.samples().all(|(raw, _)| raw == 0.0));
+        let frames = scope.samples().position(|(_, smoothed)| smoothed < 0.5);
+        frames.unwrap_or(usize::MAX)
+    }
+
+    #[test]
+    fn test_scope_keeps_recent_frames_and_shows_smoothing_lag() {
+        // Longer release: the smoothed trace trails the raw one for longer
+        assert!(frames_to_half(0.02) < frames_to_half(0.1));
+
+        let mut scope = SmoothingScope::new(ScopeFeature::Bass, 2);
+        for bass in [0.1, 0.2, 0.3] {
+            scope.push(&frame_with_bass(bass), &frame_with_bass(bass));
+        }
+        assert_eq!(scope.samples().map(|(raw, _)| raw).collect::<Vec<_>>(), vec![0.2, 0.3]);
+        scope.set_feature(ScopeFeature::Bass.next());
+        assert!(scope.is_empty() && scope.feature() == ScopeFeature::Mid);
+        assert_eq!("Sub_Bass".parse::<ScopeFeature>().unwrap(), ScopeFeature::SubBass);
+        assert!("loudness".parse::<ScopeFeature>().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::camera::AudioCamera;
+use super::engine::Uniforms;
+
+/// Serde-friendly mirror of `Uniforms` for dumping and reloading the exact state that
+/// drove a frame, so a visual bug can be turned into a reproducible fixture.
+///
+/// `Uniforms` itself stays a plain `#[repr(C)]` Pod type matching the WGSL layout; in this
+/// mirror missing fields fall back to the defaults, so older dumps still load after new
+/// uniforms are added.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UniformsSnapshot {
+    pub view_proj: [[f32; 4]; 4],
+    pub time: f32,
+
+    // Frequency bands
+    pub sub_bass: f32,
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+    pub presence: f32,
+
+    // Beat, rhythm and spectral features
+    pub beat_strength: f32,
+    pub estimated_bpm: f32,
+    pub volume: f32,
+    pub spectral_centroid: f32,
+    pub spectral_rolloff: f32,
+    pub pitch_confidence: f32,
+    pub zero_crossing_rate: f32,
+    pub spectral_flux: f32,
+    pub onset_strength: f32,
+    pub dynamic_range: f32,
+
+    // Effect weights
+    pub plasma_weight: f32,
+    pub kaleidoscope_weight: f32,
+    pub tunnel_weight: f32,
+    pub particle_weight: f32,
+    pub fractal_weight: f32,
+    pub spectralizer_weight: f32,
+    pub parametric_weight: f32,
+
+    // Effect intensities
+    pub plasma_intensity: f32,
+    pub kaleidoscope_intensity: f32,
+    pub tunnel_intensity: f32,
+    pub particle_intensity: f32,
+    pub fractal_intensity: f32,
+    pub spectralizer_intensity: f32,
+    pub parametric_intensity: f32,
+
+    // Visual and interaction controls
+    pub projection_mode: f32,
+    pub palette_index: f32,
+    pub smoothing_factor: f32,
+    pub mouse_x: f32,
+    pub mouse_y: f32,
+    pub mouse_zoom: f32,
+    pub master_intensity: f32,
+    pub flux_debug_weight: f32,
+    pub tonemap_mode: f32,
+    pub beat_flash_intensity: f32,
+    pub feedback: f32,
+    pub loop_in: f32,
+    pub loop_out: f32,
+    pub loop_position: f32,
+    pub kaleidoscope_segments: f32,
+    pub kaleidoscope_seam_softness: f32,
+    pub kaleidoscope_bpm_sync: f32,
+    pub particle_spawn_rate: f32,
+}
+
+#[allow(dead_code)]
+impl UniformsSnapshot {
+    /// Write the snapshot as pretty-printed JSON
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load a snapshot previously written by `save`
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let mut snapshot: Self = serde_json::from_str(&json)?;
+        // Dumps from before the audio camera hold an orthographic matrix the shader never
+        // read; it would now shrink the frame to a dot, so frame them like a resting camera
+        if snapshot.view_proj[2][3] == 0.0 && snapshot.view_proj[3][3] == 1.0 {
+            snapshot.view_proj = AudioCamera::new().view_proj(1.0).to_cols_array_2d();
+        }
+        Ok(snapshot)
+    }
+}
+
+impl Default for UniformsSnapshot {
+    fn default() -> Self {
+        Self::from(&Uniforms::new())
+    }
+}
+
+impl From<&Uniforms> for UniformsSnapshot {
+    fn from(uniforms: &Uniforms) -> Self {
+        Self {
+            view_proj: uniforms.view_proj,
+            time: uniforms.time,
+            sub_bass: uniforms.sub_bass,
+            bass: uniforms.bass,
+            mid: uniforms.mid,
+            treble: uniforms.treble,
+            presence: uniforms.presence,
+            beat_strength: uniforms.beat_strength,
+            estimated_bpm: uniforms.estimated_bpm,
+            volume: uniforms.volume,
+            spectral_centroid: uniforms.spectral_centroid,
+            spectral_rolloff: uniforms.spectral_rolloff,
+            pitch_confidence: uniforms.pitch_confidence,
+            zero_crossing_rate: uniforms.zero_crossing_rate,
+            spectral_flux: uniforms.spectral_flux,
+            onset_strength: uniforms.onset_strength,
+            dynamic_range: uniforms.dynamic_range,
+            plasma_weight: uniforms.plasma_weight,
+            kaleidoscope_weight: uniforms.kaleidoscope_weight,
+            tunnel_weight: uniforms.tunnel_weight,
+            particle_weight: uniforms.particle_weight,
+            fractal_weight: uniforms.fractal_weight,
+            spectralizer_weight: uniforms.spectralizer_weight,
+            parametric_weight: uniforms.parametric_weight,
+            plasma_intensity: uniforms.plasma_intensity,
+            kaleidoscope_intensity: uniforms.kaleidoscope_intensity,
+            tunnel_intensity: uniforms.tunnel_intensity,
+            particle_intensity: uniforms.particle_intensity,
+            fractal_intensity: uniforms.fractal_intensity,
+            spectralizer_intensity: uniforms.spectralizer_intensity,
+            parametric_intensity: uniforms.parametric_intensity,
+            projection_mode: uniforms.projection_mode,
+            palette_index: uniforms.palette_index,
+            smoothing_factor: uniforms.smoothing_factor,
+            mouse_x: uniforms.mouse_x,
+            mouse_y: uniforms.mouse_y,
+            mouse_zoom: uniforms.mouse_zoom,
+            master_intensity: uniforms.master_intensity,
+            flux_debug_weight: uniforms.flux_debug_weight,
+            tonemap_mode: uniforms.tonemap_mode,
+            beat_flash_intensity: uniforms.beat_flash_intensity,
+            feedback: uniforms.feedback,
+            loop_in: uniforms.loop_in,
+            loop_out: uniforms.loop_out,
+            loop_position: uniforms.loop_position,
+            kaleidoscope_segments: uniforms.kaleidoscope_segments,
+            kaleidoscope_seam_softness: uniforms.kaleidoscope_seam_softness,
+            kaleidoscope_bpm_sync: uniforms.kaleidoscope_bpm_sync,
+            particle_spawn_rate: uniforms.particle_spawn_rate,
+        }
+    }
+}
+
+impl From<&UniformsSnapshot> for Uniforms {
+    fn from(snapshot: &UniformsSnapshot) -> Self {
+        Self {
+            view_proj: snapshot.view_proj,
+            time: snapshot.time,
+            sub_bass: snapshot.sub_bass,
+            bass: snapshot.bass,
+            mid: snapshot.mid,
+            treble: snapshot.treble,
+            presence: snapshot.presence,
+            beat_strength: snapshot.beat_strength,
+            estimated_bpm: snapshot.estimated_bpm,
+            volume: snapshot.volume,
+            spectral_centroid: snapshot.spectral_centroid,
+            spectral_rolloff: snapshot.spectral_rolloff,
+            pitch_confidence: snapshot.pitch_confidence,
+            zero_crossing_rate: snapshot.zero_crossing_rate,
+            spectral_flux: snapshot.spectral_flux,
+            onset_strength: snapshot.onset_strength,
+            dynamic_range: snapshot.dynamic_range,
+            plasma_weight: snapshot.plasma_weight,
+            kaleidoscope_weight: snapshot.kaleidoscope_weight,
+            tunnel_weight: snapshot.tunnel_weight,
+            particle_weight: snapshot.particle_weight,
+            fractal_weight: snapshot.fractal_weight,
+            spectralizer_weight: snapshot.spectralizer_weight,
+            parametric_weight: snapshot.parametric_weight,
+            plasma_intensity: snapshot.plasma_intensity,
+            kaleidoscope_intensity: snapshot.kaleidoscope_intensity,
+            tunnel_intensity: snapshot.tunnel_intensity,
+            particle_intensity: snapshot.particle_intensity,
+            fractal_intensity: snapshot.fractal_intensity,
+            spectralizer_intensity: snapshot.spectralizer_intensity,
+            parametric_intensity: snapshot.parametric_intensity,
+            projection_mode: snapshot.projection_mode,
+            palette_index: snapshot.palette_index,
+            smoothing_factor: snapshot.smoothing_factor,
+            mouse_x: snapshot.mouse_x,
+            mouse_y: snapshot.mouse_y,
+            mouse_zoom: snapshot.mouse_zoom,
+            master_intensity: snapshot.master_intensity,
+            flux_debug_weight: snapshot.flux_debug_weight,
+            tonemap_mode: snapshot.tonemap_mode,
+            beat_flash_intensity: snapshot.beat_flash_intensity,
+            feedback: snapshot.feedback,
+            loop_in: snapshot.loop_in,
+            loop_out: snapshot.loop_out,
+            loop_position: snapshot.loop_position,
+            kaleidoscope_segments: snapshot.kaleidoscope_segments,
+            kaleidoscope_seam_softness: snapshot.kaleidoscope_seam_softness,
+            kaleidoscope_bpm_sync: snapshot.kaleidoscope_bpm_sync,
+            particle_spawn_rate: snapshot.particle_spawn_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_from_before_kaleidoscope_controls_gets_their_defaults() {
+        // Mirrors the WGSL struct, whose size is a multiple of 16
+        assert_eq!(std::mem::size_of::<Uniforms>(), 256);
+
+        let snapshot: UniformsSnapshot = serde_json::from_str(r#"{"time": 4.0, "loop_position": 0.5}"#).unwrap();
+        let uniforms = Uniforms::from(&snapshot);
+        assert_eq!((uniforms.time, uniforms.loop_position), (4.0, 0.5));
+        assert_eq!(uniforms.kaleidoscope_segments, 0.0);
+        assert_eq!(uniforms.kaleidoscope_seam_softness, crate::graphics::engine::DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS);
+        assert_eq!((uniforms.kaleidoscope_bpm_sync, uniforms.particle_spawn_rate), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,403 @@
+use crate::audio::AudioFrame;
+use crate::audio::mel_bands::{self, MEL_BANDS};
+use crate::audio::solo_band::SoloBand;
+
+/// Maximum number of spectralizer bars (size of the spectrum uniform array)
+pub const MAX_SPECTRUM_BARS: usize = 64;
+/// Default points sampled per bar between FFT bins (see `SpectrumBars::update`)
+#[allow(dead_code)]
+pub const DEFAULT_SPECTRUM_SUPERSAMPLING: usize = 4;
+/// Most points sampled per bar
+pub const MAX_SPECTRUM_SUPERSAMPLING: usize = 16;
+
+const MIN_FREQUENCY_HZ: f32 = 30.0;
+const DYNAMIC_RANGE_DB: f32 = 60.0; // Bars span 60 dB below the running peak
+const PEAK_DECAY: f32 = 0.995;      // Per frame: the reference level halves in ~2s
+const PEAK_FLOOR: f32 = 1.0;        // Keeps near-silence from being scaled up to full bars
+const BAR_FALL: f32 = 0.85;         // Per frame fall-off, like a hardware analyzer
+
+/// Spectrum bars as laid out for the shader (`@group(0) @binding(1)`), followed by the mel bands.
+///
+/// Uniform arrays need a 16-byte stride, so the bars are packed four to a `vec4`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpectrumUniforms {
+    pub num_bars: f32, // 0 = no spectrum this frame; the spectralizer falls back to the five bands
+    pub num_mel_bands: f32, // 0 = no mel spectrum this frame
+    pub band_grid: f32, // 1 = draw the bin grid and band-edge lines over the spectralizer
+    pub _padding: f32,
+    pub axis: [f32; 4], // `SpectrumAxis` mode (0 = bands, 1 = log bars, 2 = mel), low and high Hz, Hz per FFT bin
+    pub band_edges: [f32; 4], // Where the band edges fall across the spectralizer (0-1)
+    pub bars: [[f32; 4]; MAX_SPECTRUM_BARS / 4],
+    pub mel_bands: [[f32; 4]; MEL_BANDS / 4],
+}
+
+/// Log-spaced spectrum bars built from `AudioFrame::spectrum`, with peak tracking and fall-off,
+/// plus the frame's mel bands (`AudioFrame::mel_bands`) with the same fall-off
+#[derive(Debug, Clone)]
+pub struct SpectrumBars {
+    values: [f32; MAX_SPECTRUM_BARS],
+    peak: f32,
+    active: bool,
+    mel_values: [f32; MEL_BANDS],
+    mel_active: bool,
+    nyquist: f32,
+    bin_hz: f32,
+}
+
+/// How the spectralizer spreads frequency across the screen, given what the frames carry
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpectrumAxis {
+    /// No spectrum: the five bands side by side, in equal widths
+    Bands,
+    /// `num_bars` log-spaced bars from `low_hz` to Nyquist
+    Log { low_hz: f32, high_hz: f32 },
+    /// The mel bands, evenly spaced on the mel scale from `low_hz` to Nyquist
+    Mel { low_hz: f32, high_hz: f32 },
+}
+
+#[allow(dead_code)]
+impl SpectrumAxis {
+    /// Horizontal position (0 = left edge, 1 = right) the spectralizer draws `hz` at
+    pub fn x_for_hz(self, hz: f32) -> f32 {
+        match self {
+            Self::Bands => {
+                // Edges sit between the bands; anything else falls in its band's middle
+                let band = SoloBand::ALL.iter().position(|band| hz < band.frequency_range().1).unwrap_or(4);
+                let (low, _) = SoloBand::ALL[band].frequency_range();
+                (band as f32 + if hz == low { 0.0 } else { 0.5 }) / SoloBand::ALL.len() as f32
+            }
+            Self::Log { low_hz, high_hz } => (hz.max(low_hz) / low_hz).ln() / (high_hz / low_hz).ln(),
+            Self::Mel { low_hz, high_hz } => {
+                // Band k centres on the (k+1)th of MEL_BANDS + 2 evenly spaced mel edges
+                let (low, high) = (mel_bands::hz_to_mel(low_hz), mel_bands::hz_to_mel(high_hz));
+                let bands = MEL_BANDS as f32;
+                ((mel_bands::hz_to_mel(hz) - low) / (high - low) * (bands + 1.0) - 0.5) / bands
+            }
+        }
+    }
+
+    fn mode(self) -> f32 {
+        match self {
+            Self::Bands => 0.0,
+            Self::Log { .. } => 1.0,
+            Self::Mel { .. } => 2.0,
+        }
+    }
+}
+
+/// A boundary between two of the five analysis bands, as the band grid draws it
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandEdge {
+    pub hz: f32,
+    /// Where the spectralizer draws it (0-1 across), or None when off the axis
+    pub x: Option<f32>,
+    /// The FFT bin it falls in, fractionally (None without a spectrum)
+    pub bin: Option<f32>,
+    /// The bands either side
+    pub below: SoloBand,
+    pub above: SoloBand,
+}
+
+impl SpectrumBars {
+    pub fn new() -> Self {
+        Self {
+            values: [0.0; MAX_SPECTRUM_BARS],
+            peak: PEAK_FLOOR,
+            active: false,
+            mel_values: [0.0; MEL_BANDS],
+            mel_active: false,
+            nyquist: 22050.0,
+            bin_hz: 0.0,
+        }
+    }
+
+    /// Bin the frame's magnitude spectrum into `num_bars` log-spaced bars (0.0-1.0).
+    /// With `supersampling` above 1, each bar also samples that many points of the spectrum
+    /// interpolated between bins, so the narrow low bars follow a smooth curve instead of
+    /// repeating the nearest bin. Frames without a spectrum (GPU analyzer, prescan data)
+    /// leave the bars inactive.
+    pub fn update(&mut self, audio_frame: &AudioFrame, num_bars: usize, supersampling: usize) {
+        self.mel_active = audio_frame.mel_bands.len() == MEL_BANDS;
+        if self.mel_active {
+            for (value, &level) in self.mel_values.iter_mut().zip(&audio_frame.mel_bands) {
+                *value = level.max(*value * BAR_FALL);
+            }
+        }
+
+        let spectrum = &audio_frame.spectrum;
+        let num_bars = num_bars.min(MAX_SPECTRUM_BARS);
+        self.nyquist = audio_frame.sample_rate.max(1.0) / 2.0;
+        self.bin_hz = if spectrum.is_empty() { 0.0 } else { self.nyquist / spectrum.len() as f32 };
+        self.active = spectrum.len() >= 2 && num_bars > 0;
+        if !self.active {
+            return;
+        }
+
+        let levels = log_levels(audio_frame, num_bars, supersampling, &mut self.peak);
+        for (value, &level) in self.values.iter_mut().zip(&levels).take(num_bars) {
+            *value = level.max(*value * BAR_FALL);
+        }
+    }
+
+    pub fn to_uniforms(&self, num_bars: usize) -> SpectrumUniforms {
+        let mut uniforms = SpectrumUniforms {
+            num_bars: if self.active { num_bars.min(MAX_SPECTRUM_BARS) as f32 } else { 0.0 },
+            num_mel_bands: if self.mel_active { MEL_BANDS as f32 } else { 0.0 },
+            band_grid: 0.0,
+            _padding: 0.0,
+            axis: [0.0; 4],
+            band_edges: [-1.0; 4],
+            bars: [[0.0; 4]; MAX_SPECTRUM_BARS / 4],
+            mel_bands: [[0.0; 4]; MEL_BANDS / 4],
+        };
+        for (index, &value) in self.values.iter().enumerate() {
+            uniforms.bars[index / 4][index % 4] = value;
+        }
+        for (index, &value) in self.mel_values.iter().enumerate() {
+            uniforms.mel_bands[index / 4][index % 4] = value;
+        }
+        let axis = self.axis();
+        uniforms.axis = match axis {
+            SpectrumAxis::Bands => [axis.mode(), 0.0, 0.0, 0.0],
+            SpectrumAxis::Log { low_hz, high_hz } | SpectrumAxis::Mel { low_hz, high_hz } => [axis.mode(), low_hz, high_hz, self.bin_hz],
+        };
+        for (slot, edge) in uniforms.band_edges.iter_mut().zip(self.band_edges()) {
+            *slot = edge.x.unwrap_or(-1.0);
+        }
+        uniforms
+    }
+
+    /// How the spectralizer currently lays frequency out: mel bands when the frames carry
+    /// them, else log bars when they carry a spectrum, else the five bands
+    pub fn axis(&self) -> SpectrumAxis {
+        let low_hz = MIN_FREQUENCY_HZ.min(self.nyquist / 2.0);
+        if self.mel_active {
+            SpectrumAxis::Mel { low_hz: mel_bands::MIN_FREQUENCY_HZ.min(self.nyquist / 2.0), high_hz: self.nyquist }
+        } else if self.active {
+            SpectrumAxis::Log { low_hz, high_hz: self.nyquist }
+        } else {
+            SpectrumAxis::Bands
+        }
+    }
+
+    /// The four band edges (60, 250, 2000 and 8000 Hz) placed on the current `axis`
+    pub fn band_edges(&self) -> [BandEdge; 4] {
+        let axis = self.axis();
+        std::array::from_fn(|index| {
+            let (below, above) = (SoloBand::ALL[index], SoloBand::ALL[index + 1]);
+            let hz = above.frequency_range().0;
+            let x = (hz < self.nyquist).then(|| axis.x_for_hz(hz)).filter(|x| (0.0..=1.0).contains(x));
+            let bin = (self.bin_hz > 0.0).then(|| hz / self.bin_hz);
+            BandEdge { hz, x, bin, below, above }
+        })
+    }
+
+    /// Mel band levels with fall-off, for overlays (`None` while the frames carry no mel spectrum)
+    #[allow(dead_code)]
+    pub fn mel_bands(&self) -> Option<&[f32; MEL_BANDS]> {
+        self.mel_active.then_some(&self.mel_values)
+    }
+}
+
+impl Default for SpectrumBars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bin a frame's magnitude spectrum into `num_bars` log-spaced levels (0.0-1.0, spanning
+/// `DYNAMIC_RANGE_DB` below `peak`, which tracks the loudest bar). With `supersampling` of 1
+/// each bar is the loudest bin it touches; above 1 it is the loudest of the bins inside it and
+/// `supersampling` Catmull-Rom samples across it.
+fn log_levels(audio_frame: &AudioFrame, num_bars: usize, supersampling: usize, peak: &mut f32) -> [f32; MAX_SPECTRUM_BARS] {
+    let spectrum = &audio_frame.spectrum;
+    let nyquist = audio_frame.sample_rate.max(1.0) / 2.0;
+    let bin_hz = nyquist / spectrum.len() as f32;
+    let low = MIN_FREQUENCY_HZ.min(nyquist / 2.0);
+    let ratio = nyquist / low;
+
+    let mut magnitudes = [0.0f32; MAX_SPECTRUM_BARS];
+    for (bar, magnitude) in magnitudes.iter_mut().enumerate().take(num_bars) {
+        let start_hz = low * ratio.powf(bar as f32 / num_bars as f32);
+        let end_hz = low * ratio.powf((bar + 1) as f32 / num_bars as f32);
+
+        if supersampling <= 1 {
+            // Every bar covers at least one bin, so narrow low bars repeat the nearest bin
+            let first = ((start_hz / bin_hz) as usize).clamp(1, spectrum.len() - 1);
+            let last = ((end_hz / bin_hz).ceil() as usize).clamp(first + 1, spectrum.len());
+            *magnitude = spectrum[first..last].iter().copied().fold(0.0, f32::max);
+        } else {
+            // Wide bars still catch peaks between the samples through the bins they contain
+            let first = ((start_hz / bin_hz).ceil() as usize).max(1);
+            let last = ((end_hz / bin_hz).floor() as usize + 1).min(spectrum.len());
+            let in_bar = spectrum.get(first..last).unwrap_or_default().iter().copied().fold(0.0, f32::max);
+            *magnitude = (0..supersampling)
+                .map(|point| {
+                    let fraction = (bar as f32 + (point as f32 + 0.5) / supersampling as f32) / num_bars as f32;
+                    catmull_rom(spectrum, low * ratio.powf(fraction) / bin_hz)
+                })
+                .fold(in_bar, f32::max);
+        }
+    }
+
+    // Reference level jumps up instantly and decays slowly, so quiet passages read as quiet
+    let frame_peak = magnitudes.iter().copied().fold(0.0, f32::max);
+    *peak = (*peak * PEAK_DECAY).max(frame_peak).max(PEAK_FLOOR);
+
+    let mut levels = [0.0f32; MAX_SPECTRUM_BARS];
+    for (level, &magnitude) in levels.iter_mut().zip(&magnitudes).take(num_bars) {
+        let db = 20.0 * (magnitude / *peak).max(1e-6).log10();
+        *level = (1.0 + db / DYNAMIC_RANGE_DB).clamp(0.0, 1.0);
+    }
+    levels
+}
+
+/// The spectrum at fractional bin `position`, by Catmull-Rom interpolation of the bins around
+/// it (the DC bin is left out, as in the per-bin bars)
+fn catmull_rom(spectrum: &[f32], position: f32) -> f32 {
+    let last = spectrum.len() as isize - 1;
+    let index = position.floor() as isize;
+    let t = position - index as f32;
+    let bin = |offset: isize| spectrum[(index + offset).clamp(1, last) as usize];
+    let (p0, p1, p2, p3) = (bin(-1), bin(0), bin(1), bin(2));
+    let value = 0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+        + (3.0 * (p1 - p2) + p3 - p0) * t * t * t);
+    value.max(0.0) // The curve can overshoot below zero next to a sharp peak
+}
+
+/// Rows of history in the scrolling spectral flux view
+pub const FLUX_HISTORY_ROWS: usize = 48;
+const FLUX_GAIN: f32 = 5.0; // A 12 dB rise in a bin draws at full brightness
+
+/// Spectral flux debug data as laid out for the shader (`@group(0) @binding(2)`): the last
+/// two spectra plus a ring of per-bin flux rows, each packed four bins to a `vec4`
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FluxDebugUniforms {
+    pub num_bins: f32, // 0 = no spectrum available
+    pub head_row: f32, // History row holding the newest flux
+    pub _padding: [f32; 2],
+    pub previous: [[f32; 4]; MAX_SPECTRUM_BARS / 4],
+    pub current: [[f32; 4]; MAX_SPECTRUM_BARS / 4],
+    pub history: [[[f32; 4]; MAX_SPECTRUM_BARS / 4]; FLUX_HISTORY_ROWS],
+}
+
+/// Per-bin spectral flux for the `flux_debug` view: how much each log-spaced band rose
+/// (positive) or fell (negative) since the last frame, with a scrolling history.
+///
+/// Uses unsmoothed levels so the display shows the frame-to-frame change the onset
+/// detector works from, rather than the spectralizer's fall-off.
+#[derive(Debug, Clone)]
+pub struct FluxDebug {
+    previous: [f32; MAX_SPECTRUM_BARS],
+    current: [f32; MAX_SPECTRUM_BARS],
+    history: [[f32; MAX_SPECTRUM_BARS]; FLUX_HISTORY_ROWS],
+    head_row: usize,
+    peak: f32,
+    active: bool,
+}
+
+impl FluxDebug {
+    pub fn new() -> Self {
+        Self {
+            previous: [0.0; MAX_SPECTRUM_BARS],
+            current: [0.0; MAX_SPECTRUM_BARS],
+            history: [[0.0; MAX_SPECTRUM_BARS]; FLUX_HISTORY_ROWS],
+            head_row: 0,
+            peak: PEAK_FLOOR,
+            active: false,
+        }
+    }
+
+    /// Push the frame's spectrum and record its flux as the newest history row.
+    /// Frames without a spectrum leave the view inactive.
+    pub fn update(&mut self, audio_frame: &AudioFrame) {
+        self.active = audio_frame.spectrum.len() >= 2;
+        if !self.active {
+            return;
+        }
+
+        self.previous = self.current;
+        self.current = log_levels(audio_frame, MAX_SPECTRUM_BARS, 1, &mut self.peak);
+
+        self.head_row = (self.head_row + 1) % FLUX_HISTORY_ROWS;
+        for (bin, flux) in self.history[self.head_row].iter_mut().enumerate() {
+            *flux = ((self.current[bin] - self.previous[bin]) * FLUX_GAIN).clamp(-1.0, 1.0);
+        }
+    }
+
+    pub fn to_uniforms(&self) -> FluxDebugUniforms {
+        let mut uniforms = FluxDebugUniforms {
+            num_bins: if self.active { MAX_SPECTRUM_BARS as f32 } else { 0.0 },
+            head_row: self.head_row as f32,
+            _padding: [0.0; 2],
+            previous: [[0.0; 4]; MAX_SPECTRUM_BARS / 4],
+            current: [[0.0; 4]; MAX_SPECTRUM_BARS / 4],
+            history: [[[0.0; 4]; MAX_SPECTRUM_BARS / 4]; FLUX_HISTORY_ROWS],
+        };
+        for bin in 0..MAX_SPECTRUM_BARS {
+            uniforms.previous[bin / 4][bin % 4] = self.previous[bin];
+            uniforms.current[bin / 4][bin % 4] = self.current[bin];
+            for (row, flux) in self.history.iter().enumerate() {
+                uniforms.history[row][bin / 4][bin % 4] = flux[bin];
+            }
+        }
+        uniforms
+    }
+}
+
+impl Default for FluxDebug {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolation_passes_through_the_bins() {
+        let spectrum = [5.0, 0.2, 0.9, 0.0, 0.6, 1.0, 0.3, 0.0];
+        for (bin, &level) in spectrum.iter().enumerate().skip(1) {
+            assert_eq!(catmull_rom(&spectrum, bin as f32), level, "bin {}", bin);
+        }
+        // Between bins it stays on a curve through its neighbours, and never goes negative
+        // next to the drop after a peak
+        for step in 10..70 {
+            let value = catmull_rom(&spectrum, step as f32 / 10.0);
+            assert!((0.0..=1.2).contains(&value), "{} at bin {}", value, step as f32 / 10.0);
+        }
+        let mut without_dc = spectrum;
+        without_dc[0] = 0.0;
+        assert_eq!(catmull_rom(&spectrum, 0.5), catmull_rom(&without_dc, 0.5), "the DC bin is left out");
+    }
+
+    #[test]
+    fn test_supersampled_bars_stay_in_range() {
+        let mut frame = AudioFrame {
+            sample_rate: 44100.0,
+            spectrum: (0..256).map(|bin| if bin % 16 == 3 { 40.0 } else { 0.5 / (1.0 + bin as f32) }).collect(),
+            ..AudioFrame::default()
+        };
+        for supersampling in [1, DEFAULT_SPECTRUM_SUPERSAMPLING, MAX_SPECTRUM_SUPERSAMPLING] {
+            let mut bars = SpectrumBars::new();
+            bars.update(&frame, 32, supersampling);
+            let uniforms = bars.to_uniforms(32);
+            let values: Vec<f32> = uniforms.bars.iter().flatten().copied().collect();
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)), "{:?}", values);
+            assert_eq!(values.iter().copied().fold(0.0, f32::max), 1.0, "the loudest bar reaches the top");
+        }
+
+        // A flat spectrum interpolates to itself, so every bar is the same height
+        frame.spectrum = vec![3.0; 256];
+        let mut bars = SpectrumBars::new();
+        bars.update(&frame, 32, DEFAULT_SPECTRUM_SUPERSAMPLING);
+        assert!(bars.to_uniforms(32).bars.iter().flatten().take(32).all(|&value| value == 1.0));
+    }
+}
@@ -5,6 +5,12 @@ pub struct TextureManager {
     bind_groups: HashMap<String, wgpu::BindGroup>,
 }
 
+impl Default for TextureManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TextureManager {
     pub fn new() -> Self {
         Self {
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use clap::Parser;
 use log::info;
 use std::sync::Arc;
 use winit::{
@@ -8,27 +9,58 @@ use winit::{
     window::WindowBuilder,
 };
 
-mod graphics;
-mod ui;
-mod audio;
-mod effects;
+use arrvee::{audio, effects, graphics, ui};
 
 use graphics::GraphicsEngine;
 use ui::UserInterface;
 use audio::AudioFrame;
+use effects::effect_pool::EffectPool;
+
+#[derive(Parser)]
+#[command(name = "arrvee-graphics-test")]
+#[command(about = "Arrvee Music Visualizer - Graphics and UI Test")]
+struct Args {
+    /// Resize the window every frame to stress surface and UI resize handling
+    #[arg(long)]
+    resize_stress: bool,
+
+    /// Window width in pixels (at least 320; default 800)
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Window height in pixels (at least 240; default 600)
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Window title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// File the auto-blend pool checkboxes are loaded from and saved to
+    #[arg(long, value_name = "FILE", default_value = effects::effect_pool::DEFAULT_EFFECT_POOL_PATH)]
+    effect_pool: String,
+}
 
 fn main() -> Result<()> {
     env_logger::init();
+    let args = Args::parse();
+    graphics::crash_report::install("arrvee-graphics-test");
+    let window_size = graphics::engine::window_size(args.width, args.height, (800, 600))?;
     info!("Starting Graphics Test");
+    if args.resize_stress {
+        info!("📐 Resize stress: resizing the window every frame");
+    }
 
     let event_loop = EventLoop::new()?;
     let window = Arc::new(WindowBuilder::new()
-        .with_title("Arrvee Graphics Test")
-        .with_inner_size(winit::dpi::LogicalSize::new(800, 600))
+        .with_title(args.title.as_deref().unwrap_or("Arrvee Graphics Test"))
+        .with_inner_size(window_size)
         .build(&event_loop)?);
 
-    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window))?;
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, wgpu::Backends::all()))?;
+    EffectPool::load(&args.effect_pool)?.apply(graphics_engine.psychedelic_manager_mut());
     let mut ui = UserInterface::new(&window, &graphics_engine);
+    let mut stress_frame: u32 = 0;
 
     info!("Graphics test initialized successfully");
 
@@ -49,6 +81,10 @@ fn main() -> Result<()> {
                         info!("Escape pressed");
                         elwt.exit();
                     }
+                    if event.physical_key == PhysicalKey::Code(KeyCode::F11)
+                        && event.state == ElementState::Pressed {
+                        graphics::engine::toggle_fullscreen(&window_clone);
+                    }
                 }
                 WindowEvent::Resized(physical_size) => {
                     graphics_engine.resize(physical_size);
@@ -58,6 +94,7 @@ fn main() -> Result<()> {
                     let fake_audio = AudioFrame {
                         sample_rate: 44100.0,
                         spectrum: vec![0.1; 512],
+                        mel_bands: Vec::new(),
                         time_domain: vec![0.1; 1024],
                         frequency_bands: audio::FrequencyBands {
                             bass: 0.3,
@@ -79,15 +116,36 @@ fn main() -> Result<()> {
                         dynamic_range: 0.6,
                     };
 
-                    if let Err(e) = graphics_engine.render(&fake_audio, &window_clone) {
+                    let result = graphics_engine.render_with_overlay(&fake_audio, &window_clone, |encoder, view, device, queue, size| {
+                        ui.render(encoder, view, device, queue, &window_clone, size)
+                    });
+                    if let Err(e) = result {
                         log::error!("Render error: {}", e);
                     }
+                    if ui.apply_effect_toggles(graphics_engine.psychedelic_manager_mut()) {
+                        let pool = EffectPool::from_manager(graphics_engine.psychedelic_manager());
+                        match pool.save(&args.effect_pool) {
+                            Ok(()) => info!("💾 Auto-blend pool saved to {}", args.effect_pool),
+                            Err(e) => log::warn!("⚠️  Could not save the auto-blend pool: {}", e),
+                        }
+                    }
+                    ui.apply_beat_flash(&mut graphics_engine);
+                    ui.apply_solo_band(&mut graphics_engine);
+                    ui.apply_kaleidoscope(&mut graphics_engine);
                 }
                 _ => {
+                    graphics_engine.handle_mouse_event(&event);
                     ui.handle_event(&event, &window_clone);
                 }
             },
             Event::AboutToWait => {
+                if args.resize_stress {
+                    // Sweep through awkward sizes (odd, tiny, larger than the start) one per frame
+                    stress_frame = stress_frame.wrapping_add(1);
+                    let width = 64 + (stress_frame * 37) % 1200;
+                    let height = 48 + (stress_frame * 53) % 900;
+                    let _ = window_clone.request_inner_size(winit::dpi::PhysicalSize::new(width, height));
+                }
                 window_clone.request_redraw();
             }
             _ => {}
@@ -0,0 +1,28 @@
+//! Arrvee: real-time audio analysis and the psychedelic wgpu visualizer it drives.
+//!
+//! The binaries (`arrvee`, `audio-test`, `synchronized-test`, the analysis and prescan tools)
+//! are thin wrappers over these modules; other crates can use them the same way:
+//!
+//! ```no_run
+//! let (samples, sample_rate) = arrvee::audio::decode_file_to_mono("track.wav", Default::default())?;
+//! for frame in arrvee::audio::analyze_buffer(&samples, sample_rate, 512) {
+//!     println!("bass {:.2} beat {}", frame.frequency_bands.bass, frame.beat_detected);
+//! }
+//! # Ok::<(), arrvee::ArrveeError>(())
+//! ```
+
+pub mod audio;
+pub mod control;
+pub mod effects;
+pub mod error;
+pub mod graphics;
+pub mod logging;
+pub mod ui;
+
+#[cfg(test)]
+mod test_support;
+
+pub use audio::{AudioFrame, AudioPlayback, FrequencyBands, PrescanData, PrescanProcessor, SynchronizedPlayback};
+pub use effects::PsychedelicManager;
+pub use error::ArrveeError;
+pub use graphics::GraphicsEngine;
@@ -0,0 +1,80 @@
+use log::LevelFilter;
+use serde_json::{Map, Value};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Log target for periodic progress lines (silenced by `--quiet`)
+pub const PROGRESS_TARGET: &str = "arrvee::progress";
+/// Log target for structured key events (only emitted with `--json-logs`)
+pub const EVENT_TARGET: &str = "arrvee::event";
+
+static JSON_LOGS: AtomicBool = AtomicBool::new(false);
+
+/// Logger setup for the command-line tools.
+///
+/// - `quiet`: drop periodic progress lines, keep everything else
+/// - `json_logs`: one JSON object per line (timestamp, level, message or event fields),
+///   defaulting to `info` level so key events are visible without `RUST_LOG`
+pub fn init(quiet: bool, json_logs: bool) {
+    let default_filter = if json_logs { "info" } else { "error" };
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter));
+
+    if quiet {
+        builder.filter_module(PROGRESS_TARGET, LevelFilter::Off);
+    }
+
+    if json_logs {
+        JSON_LOGS.store(true, Ordering::Relaxed);
+        builder.format(|buf, record| {
+            let mut object = Map::new();
+            object.insert("timestamp".into(), buf.timestamp().to_string().into());
+            object.insert("level".into(), record.level().to_string().into());
+
+            let message = record.args().to_string();
+            let structured = record.target() == EVENT_TARGET || record.target() == PROGRESS_TARGET;
+            match serde_json::from_str::<Value>(&message) {
+                Ok(Value::Object(fields)) if structured => object.extend(fields),
+                _ => {
+                    object.insert("target".into(), record.target().into());
+                    object.insert("message".into(), message.into());
+                }
+            }
+
+            writeln!(buf, "{}", Value::Object(object))
+        });
+    } else {
+        // Key events duplicate the human-readable lines, so only JSON mode shows them
+        builder.filter_module(EVENT_TARGET, LevelFilter::Off);
+    }
+
+    builder.init();
+}
+
+/// Whether structured JSON logging is active
+#[allow(dead_code)]
+pub fn json_enabled() -> bool {
+    JSON_LOGS.load(Ordering::Relaxed)
+}
+
+/// Emit a structured key event (e.g. "start", "complete") with extra fields
+pub fn event(name: &str, fields: Value) {
+    log::info!(target: EVENT_TARGET, "{}", with_event_name(name, fields));
+}
+
+/// Emit a periodic progress update: structured in JSON mode, `message` otherwise
+pub fn progress(message: &str, fields: Value) {
+    if json_enabled() {
+        log::info!(target: PROGRESS_TARGET, "{}", with_event_name("progress", fields));
+    } else {
+        log::info!(target: PROGRESS_TARGET, "{}", message);
+    }
+}
+
+fn with_event_name(name: &str, fields: Value) -> Value {
+    let mut object = match fields {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    object.insert("event".into(), name.into());
+    Value::Object(object)
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use clap::Parser;
 use log::info;
 use std::sync::Arc;
 use winit::{
@@ -8,18 +9,31 @@ use winit::{
     window::WindowBuilder,
 };
 
-mod audio;
-mod graphics;
-mod ui;
-mod effects;
+use arrvee::{audio, effects, graphics, ui};
+use arrvee::control::ControlChannel;
 
 use audio::AudioPlayback;
 use graphics::GraphicsEngine;
 use ui::UserInterface;
 
+#[derive(Parser)]
+#[command(name = "arrvee")]
+#[command(about = "Arrvee Music Visualizer")]
+struct Args {
+    /// Accept line commands on stdin (`effect fractal_madness`, `palette 3`, `smoothing 0.5`, `volume 0.2`, ...)
+    #[arg(long)]
+    control_stdin: bool,
+
+    /// Accept the same commands from TCP clients on 127.0.0.1:<PORT>
+    #[arg(long, value_name = "PORT")]
+    control_tcp: Option<u16>,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
     env_logger::init();
+    graphics::crash_report::install("arrvee-visualizer");
     info!("Starting Arrvee Music Visualizer");
 
     let event_loop = EventLoop::new()?;
@@ -28,8 +42,10 @@ async fn main() -> Result<()> {
         .with_inner_size(winit::dpi::LogicalSize::new(1200, 800))
         .build(&event_loop)?);
 
-    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window))?;
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, wgpu::Backends::all()))?;
     let mut audio_playback = AudioPlayback::new()?;
+    effects::effect_pool::EffectPool::load(effects::effect_pool::DEFAULT_EFFECT_POOL_PATH)?
+        .apply(graphics_engine.psychedelic_manager_mut());
     let mut ui = UserInterface::new(&window, &graphics_engine);
 
     // Load sample audio file
@@ -38,6 +54,14 @@ async fn main() -> Result<()> {
 
     info!("Visualizer initialized successfully");
 
+    let control = ControlChannel::new();
+    if args.control_stdin {
+        control.listen_stdin();
+    }
+    if let Some(port) = args.control_tcp {
+        control.listen_tcp(port)?;
+    }
+
     let window_clone = Arc::clone(&window);
     event_loop.run(move |event, elwt| {
         match event {
@@ -55,6 +79,10 @@ async fn main() -> Result<()> {
                         info!("Escape pressed");
                         elwt.exit();
                     }
+                    if event.physical_key == PhysicalKey::Code(KeyCode::F11)
+                        && event.state == ElementState::Pressed {
+                        graphics::engine::toggle_fullscreen(&window_clone);
+                    }
                 }
                 WindowEvent::Resized(physical_size) => {
                     graphics_engine.resize(physical_size);
@@ -66,10 +94,14 @@ async fn main() -> Result<()> {
                     }
                 }
                 _ => {
+                    graphics_engine.handle_mouse_event(&event);
                     ui.handle_event(&event, &window_clone);
                 }
             },
             Event::AboutToWait => {
+                for command in control.poll() {
+                    command.apply(&mut graphics_engine, &mut audio_playback);
+                }
                 window_clone.request_redraw();
             }
             _ => {}
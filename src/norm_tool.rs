@@ -0,0 +1,76 @@
+use anyhow::Result;
+use clap::Parser;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use arrvee::audio;
+
+use audio::feature_normalizer::{NormalizationParameters, OBSERVED_FEATURE_NAMES};
+
+#[derive(Parser)]
+#[command(name = "arrvee-normtool")]
+#[command(about = "Fit normalization parameters to the raw feature peaks of audio-analyzer results")]
+struct Args {
+    /// Analysis JSON files from `audio-analyzer` (one per track of the corpus)
+    #[arg(required = true)]
+    analysis_files: Vec<String>,
+
+    /// Parameters file to write, for `audio-test --normalization` / `prescan-tool --normalization`
+    #[arg(long, short, default_value = "arrvee_norm.json")]
+    output: String,
+
+    /// Multiplier over the loudest track's peak, so loud passages don't pin at 1.0 (1.2 = 20%)
+    #[arg(long, default_value = "1.2")]
+    headroom: f32,
+}
+
+/// The parts of an analysis JSON this tool reads
+#[derive(Deserialize)]
+struct AnalysisPeaks {
+    #[serde(default)]
+    schema_version: u32,
+    #[serde(default)]
+    raw_feature_peaks: HashMap<String, f32>,
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+    if !(args.headroom.is_finite() && args.headroom > 0.0) {
+        return Err(anyhow::anyhow!("--headroom must be positive, got {}", args.headroom));
+    }
+
+    // The loudest track sets each ceiling, so no track of the corpus clips beyond the headroom
+    let mut corpus_peaks: HashMap<String, f32> = HashMap::new();
+    for path in &args.analysis_files {
+        let analysis: AnalysisPeaks = serde_json::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+        if analysis.raw_feature_peaks.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} has no raw_feature_peaks (schema version {}); re-run audio-analyzer on the track",
+                path, analysis.schema_version));
+        }
+        for (name, peak) in analysis.raw_feature_peaks {
+            let entry = corpus_peaks.entry(name).or_insert(0.0);
+            *entry = entry.max(peak);
+        }
+    }
+
+    let parameters = NormalizationParameters::from_peaks(&corpus_peaks, args.headroom);
+    let fitted = serde_json::to_value(&parameters)?;
+    let defaults = serde_json::to_value(NormalizationParameters::default())?;
+
+    println!("=== NORMALIZATION FROM {} TRACK(S) ({:.0}% headroom) ===", args.analysis_files.len(), (args.headroom - 1.0) * 100.0);
+    println!("{:<20} {:>14} {:>14}", "Feature", "Default max", "Fitted max");
+    for name in OBSERVED_FEATURE_NAMES {
+        let field = format!("{}_max", name);
+        let note = if corpus_peaks.get(name).is_some_and(|peak| *peak > 0.0) { "" } else { "  (no signal, default kept)" };
+        println!("{:<20} {:>14.6e} {:>14.6e}{}", name, defaults[&field].as_f64().unwrap_or_default(),
+                 fitted[&field].as_f64().unwrap_or_default(), note);
+    }
+
+    parameters.save(&args.output)?;
+    println!();
+    println!("💾 Wrote {}; load it with `audio-test <file> --normalization {}`", args.output, args.output);
+    Ok(())
+}
@@ -2,9 +2,8 @@ use anyhow::Result;
 use clap::Parser;
 use log::info;
 
-mod audio;
-mod graphics;
-mod effects;
+use arrvee::{audio, logging};
+use arrvee::ui::batch_progress::{format_seconds, BatchProgress};
 use audio::{
     PrescanProcessor, ArvFormat,
     AudioAnalyzer, CpuAudioAnalyzer, NewGpuAudioAnalyzer,
@@ -16,8 +15,22 @@ use audio::{
 #[command(about = "Pre-scan audio files for real-time synchronized visualization")]
 struct Args {
     /// Audio file to pre-scan (MP3, WAV, M4A, OGG, etc.)
-    #[arg()]
-    input_file: String,
+    #[arg(required_unless_present = "batch", conflicts_with = "batch")]
+    input_file: Option<String>,
+
+    /// Pre-scan every audio file under DIR (recursively), writing each one's prescan data next
+    /// to it with the `--format` extension (e.g. `track.mp3` -> `track.arv`); `-o` is ignored
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["dump_spectrum", "spectrogram"])]
+    batch: Option<String>,
+
+    /// With --batch, re-scan files that already have prescan data instead of skipping them
+    #[arg(long, requires = "batch")]
+    force: bool,
+
+    /// Files pre-scanned at once with --batch (default: one per CPU core). Only the first
+    /// worker uses the GPU; the others analyze on the CPU
+    #[arg(short, long, requires = "batch")]
+    jobs: Option<usize>,
 
     /// Output file for prescan data (JSON or ARV format)
     #[arg(short, long, default_value = "prescan_data.arv")]
@@ -31,24 +44,239 @@ struct Args {
     #[arg(long, default_value = "44100")]
     sample_rate: u32,
 
-    /// Analysis chunk size (smaller = more precise, larger = faster)
+    /// Analysis chunk size (smaller = more precise, larger = faster). GPU analysis needs a power
+    /// of two of at least 64; other sizes fall back to the CPU analyzer
     #[arg(long, default_value = "512")]
     chunk_size: usize,
+
+    /// FFT size in samples (a power of two), independent of the chunk (hop) size, e.g. 4096
+    /// for finer bass resolution with 512-sample timing (CPU only; defaults to the chunk size)
+    #[arg(long)]
+    fft_size: Option<usize>,
+
+    /// Beat-locked frames: estimate the tempo, then re-analyze with the hop set to a fixed
+    /// number of frames per beat, aligned to the beat grid
+    #[arg(long)]
+    beat_locked: bool,
+
+    /// Frames per beat for --beat-locked (default: the power of two nearest the chunk size)
+    #[arg(long, requires = "beat_locked")]
+    frames_per_beat: Option<u32>,
+
+    /// Stereo downmix for analysis: average, rms (level-preserving sum), left, right, mid (L+R) or side (L-R)
+    #[arg(long, default_value = "average")]
+    mono_mix: String,
+
+    /// If the file's channels are out of phase (one polarity-inverted), invert the right
+    /// channel before the downmix so bass and vocals don't cancel
+    #[arg(long)]
+    fix_phase: bool,
+
+    /// Reload the saved file and check it against the in-memory analysis
+    #[arg(long)]
+    verify: bool,
+
+    /// Normalization parameters JSON (e.g. from `audio-analyzer --apply-suggestions`)
+    #[arg(long, value_name = "FILE")]
+    normalization: Option<String>,
+
+    /// Normalized beat strength (0.0-1.0) above which a frame counts as a beat
+    #[arg(long, default_value_t = audio::feature_normalizer::DEFAULT_BEAT_THRESHOLD)]
+    beat_threshold: f32,
+
+    /// How slowly the BPM follows tempo changes: 0 takes the median of recent beats as-is,
+    /// values near 1 change slowly (CPU analyzer)
+    #[arg(long, default_value_t = audio::tempo_detector::DEFAULT_BPM_SMOOTHING)]
+    bpm_smoothing: f32,
+
+    /// Apply spectral whitening before band extraction (evens out bass-heavy mixes; CPU only)
+    #[arg(long)]
+    whiten: bool,
+
+    /// Average bands in dB instead of linear magnitude (livelier treble/presence; CPU only)
+    #[arg(long)]
+    log_magnitude: bool,
+
+    /// Also export a band spectrogram (time vs. frequency heatmap) as a PNG
+    #[arg(long, value_name = "OUT.png")]
+    spectrogram: Option<String>,
+
+    /// Print the full spectrum and every raw and normalized feature of the chunk at `--at`,
+    /// then exit without writing prescan data (CPU analyzer)
+    #[arg(long, requires = "at")]
+    dump_spectrum: bool,
+
+    /// Time in seconds of the chunk to inspect with --dump-spectrum
+    #[arg(long, value_name = "SECONDS", requires = "dump_spectrum")]
+    at: Option<f32>,
+
+    /// Suppress the periodic progress logs
+    #[arg(long)]
+    quiet: bool,
+
+    /// Emit logs as JSON lines (one object per line) for scripting and CI
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
     let args = Args::parse();
+    logging::init(args.quiet, args.json_logs);
 
     info!("Arrvee Pre-scan Tool");
-    info!("Input file: {}", args.input_file);
-    info!("Output file: {}", args.output);
-    info!("Sample rate: {}Hz, Chunk size: {}", args.sample_rate, args.chunk_size);
+    match &args.batch {
+        Some(directory) => info!("Batch directory: {}", directory),
+        None => {
+            info!("Input file: {}", args.input_file.as_deref().unwrap_or_default());
+            info!("Output file: {}", args.output);
+        }
+    }
+    info!("Sample rate: {}Hz, Chunk size: {}, FFT size: {}",
+          args.sample_rate, args.chunk_size, args.fft_size.unwrap_or(args.chunk_size));
+    logging::event("start", serde_json::json!({
+        "tool": "prescan",
+        "input": args.input_file,
+        "output": args.output,
+        "batch": args.batch,
+        "format": args.format,
+        "sample_rate": args.sample_rate,
+        "chunk_size": args.chunk_size,
+        "fft_size": args.fft_size.unwrap_or(args.chunk_size),
+        "beat_threshold": args.beat_threshold,
+        "bpm_smoothing": args.bpm_smoothing,
+        "normalization": args.normalization,
+        "beat_locked": args.beat_locked,
+        "mono_mix": args.mono_mix,
+        "fix_phase": args.fix_phase,
+        "log_magnitude": args.log_magnitude,
+        "dump_spectrum_at": args.at,
+    }));
+
+    if let Some(directory) = &args.batch {
+        return prescan_directory(&args, directory).await;
+    }
+    let input = args.input_file.as_deref().expect("clap requires an input file without --batch");
+
+    if let Some(at) = args.at.filter(|_| args.dump_spectrum) {
+        return dump_spectrum(&args, input, at).await;
+    }
+
+    prescan_file(&args, input, &args.output, false).await?;
+
+    info!("\n✅ Pre-scan complete! You can now use this data for perfectly synchronized real-time visualization.");
+    info!("💡 Tip: Use the synchronized playback mode in the visualizer for authentic real-time responsiveness.");
+
+    Ok(())
+}
+
+/// `--batch`: pre-scan each audio file under `directory` into a prescan file beside it, skipping
+/// those that already have one unless `--force`. Files are spread over `--jobs` worker threads;
+/// a failed file is reported and the rest carry on, and the run fails at the end if any did.
+async fn prescan_directory(args: &Args, directory: &str) -> Result<()> {
+    let files = audio::find_audio_files(directory)
+        .map_err(|e| anyhow::anyhow!("Could not read directory {}: {}", directory, e))?;
+    let extension = if args.format.to_lowercase() == "arv" { "arv" } else { "json" };
+    println!("📂 {} audio files under {}", files.len(), directory);
+
+    // Outputs are named after the file stem, so `track.mp3` and `track.flac` would overwrite
+    // each other's prescan data: fail all of them rather than keep whichever finished last
+    let mut claims: std::collections::HashMap<std::path::PathBuf, Vec<&std::path::PathBuf>> = std::collections::HashMap::new();
+    for path in &files {
+        claims.entry(path.with_extension(extension)).or_default().push(path);
+    }
+
+    let mut pending = Vec::new();
+    let mut collisions = Vec::new();
+    for (index, path) in files.iter().enumerate() {
+        let output = path.with_extension(extension);
+        let claimants = &claims[&output];
+        if claimants.len() > 1 {
+            let others: Vec<String> = claimants.iter()
+                .filter(|&&other| other != path)
+                .map(|other| other.display().to_string())
+                .collect();
+            println!("❌ [{}/{}] {}: {} would also be written for {} (rename one, or pre-scan it on its own with -o)",
+                     index + 1, files.len(), path.display(), output.display(), others.join(", "));
+            collisions.push(path.to_string_lossy().to_string());
+        } else if !args.force && output.exists() {
+            println!("⏭️  [{}/{}] {}: {} already exists (--force to re-scan)",
+                     index + 1, files.len(), path.display(), output.display());
+        } else {
+            pending.push((index, path, output));
+        }
+    }
+    let skipped = files.len() - pending.len() - collisions.len();
+
+    let jobs = args.jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |cores| cores.get()))
+        .clamp(1, pending.len().max(1));
+    if jobs > 1 {
+        println!("🧵 {} files on {} workers (the first tries the GPU, the others analyze on the CPU)", pending.len(), jobs);
+    }
+
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let queued = pending.len();
+    for file in pending {
+        sender.send(file).expect("the receiver outlives the queue");
+    }
+    drop(sender);
+
+    let file_count = files.len();
+    let progress = std::sync::Mutex::new(BatchProgress::new(queued, !args.quiet && !args.json_logs));
+    std::thread::scope(|scope| {
+        for worker in 0..jobs {
+            let (receiver, progress) = (receiver.clone(), &progress);
+            // One GPU analyzer at a time: concurrent workers go straight to the CPU
+            let cpu_only = worker > 0;
+            scope.spawn(move || {
+                for (index, path, output) in receiver {
+                    let (input, output) = (path.to_string_lossy(), output.to_string_lossy());
+                    let tag = format!("[{}/{}]", index + 1, file_count);
+                    let result = pollster::block_on(prescan_file(args, &input, &output, cpu_only));
+                    let mut progress = progress.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    match result {
+                        Ok(()) => progress.succeeded(&format!("✅ {} {} -> {}", tag, input, output)),
+                        Err(e) => progress.failed(&format!("❌ {} {}: {}", tag, input, e), &input),
+                    }
+                }
+            });
+        }
+    });
+    let progress = progress.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut failed = progress.failures;
+    failed.extend(collisions);
+    failed.sort();
+
+    println!();
+    println!("=== BATCH SUMMARY ===");
+    println!("{} pre-scanned, {} skipped, {} failed in {}",
+             progress.succeeded, skipped, failed.len(), format_seconds(progress.started.elapsed().as_secs_f32()));
+    for input in &failed {
+        println!("  ❌ {}", input);
+    }
+    logging::event("batch_complete", serde_json::json!({
+        "tool": "prescan",
+        "directory": directory,
+        "files": files.len(),
+        "jobs": jobs,
+        "succeeded": progress.succeeded,
+        "skipped": skipped,
+        "failed": failed,
+    }));
+
+    if !failed.is_empty() {
+        return Err(anyhow::anyhow!("{} of {} files failed to pre-scan", failed.len(), files.len() - skipped));
+    }
+    Ok(())
+}
 
+/// Pre-scan `input` and save it to `output` in the `--format` format, with the optional
+/// verification and spectrogram. `cpu_only` skips the GPU attempt.
+async fn prescan_file(args: &Args, input: &str, output: &str, cpu_only: bool) -> Result<()> {
     // Pre-scan the audio file using unified architecture
     info!("Starting pre-scan analysis...");
-    let prescan_data = prescan_with_unified_architecture(&args).await?;
+    let prescan_data = prescan_with_unified_architecture(args, input, cpu_only).await?;
 
     // Display statistics
     info!("\n=== PRE-SCAN RESULTS ===");
@@ -63,6 +291,9 @@ async fn main() -> Result<()> {
     info!("Dominant frequency range: {}", prescan_data.statistics.dominant_frequency_range);
     info!("Energy profile: {}", prescan_data.statistics.energy_profile);
     info!("Complexity score: {:.3}", prescan_data.statistics.complexity_score);
+    let stereo_phase = &prescan_data.statistics.stereo_phase;
+    info!("Stereo phase: {:?} (L/R correlation {:.2}{})", stereo_phase.relation, stereo_phase.correlation,
+          if stereo_phase.corrected { ", right channel inverted" } else { "" });
 
     // Peak values for calibration
     info!("\n=== PEAK VALUES (for calibration) ===");
@@ -75,18 +306,37 @@ async fn main() -> Result<()> {
     info!("Peak onset: {:.6}", prescan_data.statistics.peak_onset);
 
     // Save results in requested format
-    info!("Saving prescan data to: {} (format: {})", args.output, args.format);
+    info!("Saving prescan data to: {} (format: {})", output, args.format);
 
     let file_size = if args.format.to_lowercase() == "arv" {
-        ArvFormat::save_arv(&prescan_data, &args.output)?;
-        std::fs::metadata(&args.output)?.len()
+        ArvFormat::save_arv(&prescan_data, output)?;
+        std::fs::metadata(output)?.len()
     } else {
-        PrescanProcessor::save_prescan_data(&prescan_data, &args.output)?;
-        std::fs::metadata(&args.output)?.len()
+        PrescanProcessor::save_prescan_data(&prescan_data, output)?;
+        std::fs::metadata(output)?.len()
     };
 
     info!("Prescan data saved successfully ({:.1} KB)", file_size as f64 / 1024.0);
 
+    // Optionally reload what we just wrote and make sure it round-trips
+    if args.verify {
+        info!("Verifying saved prescan data...");
+        match verify_saved_output(&prescan_data, output, args.format.to_lowercase() == "arv") {
+            Ok(checked) => info!("✅ Verification passed: {} frames reloaded, {} spot-checked within tolerance",
+                                 prescan_data.frames.len(), checked),
+            Err(e) => {
+                log::error!("❌ Verification failed: {}", e);
+                return Err(e);
+            }
+        }
+    }
+
+    if let Some(spectrogram_path) = &args.spectrogram {
+        info!("Rendering spectrogram to: {}", spectrogram_path);
+        audio::spectrogram::SpectrogramExporter::default().export(&prescan_data, spectrogram_path)?;
+        info!("🖼️  Spectrogram saved");
+    }
+
     // Show compression ratio if ARV format
     if args.format.to_lowercase() == "arv" && std::path::Path::new("sample_prescan.json").exists() {
         let json_size = std::fs::metadata("sample_prescan.json")?.len();
@@ -94,51 +344,118 @@ async fn main() -> Result<()> {
         info!("Compression ratio: {:.1}% smaller than JSON", compression * 100.0);
     }
 
-    info!("\n✅ Pre-scan complete! You can now use this data for perfectly synchronized real-time visualization.");
-    info!("💡 Tip: Use the synchronized playback mode in the visualizer for authentic real-time responsiveness.");
+    logging::event("complete", serde_json::json!({
+        "tool": "prescan",
+        "output": output,
+        "file_size_bytes": file_size,
+        "frames": prescan_data.frames.len(),
+        "duration_seconds": prescan_data.file_info.duration_seconds,
+        "frame_rate": prescan_data.file_info.frame_rate,
+        "total_beats": prescan_data.statistics.total_beats,
+        "average_bpm": prescan_data.statistics.average_bpm,
+        "energy_profile": prescan_data.statistics.energy_profile,
+        "complexity_score": prescan_data.statistics.complexity_score,
+        "stereo_phase": prescan_data.statistics.stereo_phase,
+    }));
 
     Ok(())
 }
 
 /// Unified prescan function using transparent GPU-first with CPU fallback architecture
 /// Automatically tries GPU acceleration, falls back to CPU if unavailable
-async fn prescan_with_unified_architecture(args: &Args) -> Result<audio::PrescanData> {
-    use audio::prescan::{PrescanFrame, FileInfo, AnalysisStatistics};
-    use audio::{FrequencyBands, FeatureNormalizer};
-    use rodio::{Decoder, Source};
-    use std::fs::File;
-    use std::io::BufReader;
+async fn prescan_with_unified_architecture(args: &Args, input: &str, cpu_only: bool) -> Result<audio::PrescanData> {
+    use audio::prescan::FileInfo;
 
     info!("Loading audio file...");
 
-    // Load audio file
-    let file = BufReader::new(File::open(&args.input_file)?);
-    let source = Decoder::new(file)?;
-    let channels = source.channels();
-    let samples: Vec<i16> = source.convert_samples().collect();
-
-    // Convert to f32 and mix to mono
-    let audio_buffer: Vec<f32> = samples
-        .chunks_exact(channels as usize)
-        .map(|chunk| {
-            let sum: f32 = chunk.iter().map(|&s| s as f32 / 32768.0).sum();
-            sum / channels as f32
-        })
-        .collect();
+    // Load audio file and mix to mono f32
+    let (audio_buffer, _, stereo_phase) = audio::decode_file_to_mono_checked(input, args.mono_mix.parse()?, args.fix_phase)?;
 
     let total_samples = audio_buffer.len();
-    let duration_seconds = total_samples as f32 / args.sample_rate as f32;
-    let frame_rate = args.sample_rate as f32 / args.chunk_size as f32;
+    let sample_rate = args.sample_rate as f32;
+    let duration_seconds = total_samples as f32 / sample_rate;
+    let mut frame_rate = sample_rate / args.chunk_size as f32;
+    let mut time_offset_seconds = 0.0;
 
     info!("Loaded {} samples ({:.2}s) for analysis", total_samples, duration_seconds);
 
-    // Try GPU first, fall back to CPU automatically
-    let mut analyzer: Box<dyn AudioAnalyzer + Send> = {
+    let mut analyzer = create_analyzer(args, cpu_only).await?;
+    info!("Using {} analyzer", analyzer.analyzer_type());
+
+    // Process entire file chunk by chunk
+    let positions: Vec<usize> = (0..)
+        .map(|k| k * args.chunk_size)
+        .take_while(|&position| position + args.chunk_size <= total_samples)
+        .collect();
+    let (mut frames, mut statistics) = analyze_positions(args, analyzer.as_mut(), &audio_buffer, &positions, duration_seconds).await?;
+
+    // Beat-locked mode: re-analyze on a grid of beat subdivisions found in the whole buffer
+    if args.beat_locked {
+        match audio::beat_grid::estimate_beat_grid(&audio_buffer, sample_rate) {
+            Some(grid) => {
+                let frames_per_beat = args.frames_per_beat
+                    .unwrap_or_else(|| grid.frames_per_beat_near(args.chunk_size, sample_rate))
+                    .max(1);
+                frame_rate = grid.frame_rate(frames_per_beat);
+                time_offset_seconds = grid.offset_seconds(frames_per_beat);
+                info!("🥁 Beat grid: {:.2} BPM, first beat at {:.3}s; {} frames per beat ({:.1} samples hop)",
+                      grid.bpm, grid.first_beat_seconds, frames_per_beat, sample_rate / frame_rate);
+
+                let positions = grid.frame_positions(frames_per_beat, sample_rate, args.chunk_size, total_samples);
+                let mut analyzer = create_analyzer(args, cpu_only).await?;
+                analyzer.set_chunk_interval(1.0 / frame_rate);
+                (frames, statistics) = analyze_positions(args, analyzer.as_mut(), &audio_buffer, &positions, duration_seconds).await?;
+                // Grid timestamps rather than rounded sample positions, so frames sit exactly on subdivisions
+                for (index, frame) in frames.iter_mut().enumerate() {
+                    frame.timestamp = time_offset_seconds + index as f32 / frame_rate;
+                }
+                statistics.average_bpm = grid.bpm;
+            }
+            None => log::warn!("⚠️  No tempo found for a beat grid; keeping fixed {}-sample frames", args.chunk_size),
+        }
+    }
+
+    // Classify content
+    classify_unified_content(&mut statistics, &frames);
+    statistics.stereo_phase = stereo_phase;
+
+    info!("{} analysis complete: {} frames, {} beats, {:.1} BPM average",
+          analyzer.analyzer_type(), frames.len(), statistics.total_beats, statistics.average_bpm);
+
+    Ok(audio::PrescanData {
+        file_info: FileInfo {
+            filename: input.to_string(),
+            duration_seconds,
+            sample_rate,
+            total_samples,
+            frame_rate,
+            chunk_size: args.chunk_size,
+            time_offset_seconds,
+        },
+        frames,
+        statistics,
+    })
+}
+
+/// Try GPU first, fall back to CPU automatically. Whitening, log magnitudes and a separate
+/// FFT size are only implemented on the CPU path, so they skip the GPU attempt, as does `cpu_only`.
+async fn create_analyzer(args: &Args, cpu_only: bool) -> Result<Box<dyn AudioAnalyzer + Send>> {
+    let fft_size = args.fft_size.filter(|&size| size != args.chunk_size);
+    let mut analyzer: Box<dyn AudioAnalyzer + Send> = if cpu_only || args.whiten || args.log_magnitude || fft_size.is_some() {
+        Box::new(create_cpu_analyzer(args)?)
+    } else {
         info!("Attempting GPU initialization...");
         match NewGpuAudioAnalyzer::new_standalone(args.sample_rate as f32, args.chunk_size).await {
             Ok(gpu_analyzer) => {
                 info!("✅ GPU analyzer initialized successfully");
-                Box::new(gpu_analyzer)
+                Box::new(audio::FallbackAnalyzer::new(
+                    Box::new(gpu_analyzer),
+                    Box::new(CpuAudioAnalyzer::new(args.sample_rate as f32, args.chunk_size)?),
+                ))
+            }
+            Err(e) if e.is::<audio::ComputeUnavailable>() => {
+                info!("⚠️  GPU compute unavailable on this adapter; using CPU analysis. ({})", e);
+                Box::new(CpuAudioAnalyzer::new(args.sample_rate as f32, args.chunk_size)?)
             }
             Err(e) => {
                 info!("⚠️  GPU initialization failed: {}. Falling back to CPU.", e);
@@ -146,20 +463,135 @@ async fn prescan_with_unified_architecture(args: &Args) -> Result<audio::Prescan
             }
         }
     };
+    analyzer.set_bpm_smoothing(args.bpm_smoothing);
+    Ok(analyzer)
+}
 
-    info!("Using {} analyzer", analyzer.analyzer_type());
+/// CPU analyzer with the requested whitening, log-magnitude and FFT size options
+fn create_cpu_analyzer(args: &Args) -> Result<CpuAudioAnalyzer> {
+    let mut cpu_analyzer = CpuAudioAnalyzer::new(args.sample_rate as f32, args.chunk_size)?;
+    if args.whiten {
+        info!("🎚️  Spectral whitening enabled (using CPU analyzer)");
+        cpu_analyzer.set_spectral_whitening(true);
+    }
+    if args.log_magnitude {
+        info!("📈 Log-magnitude bands enabled (using CPU analyzer)");
+        cpu_analyzer.set_log_magnitude(true);
+    }
+    if let Some(size) = args.fft_size.filter(|&size| size != args.chunk_size) {
+        cpu_analyzer.set_fft_size(size)?;
+        info!("📐 {}-point FFT with a {}-sample hop ({:.1} Hz bins, using CPU analyzer)",
+              size, args.chunk_size, cpu_analyzer.frequency_resolution());
+    }
+    Ok(cpu_analyzer)
+}
 
-    // Initialize feature normalizer
-    let mut normalizer = FeatureNormalizer::new();
+/// Normalizer for `--normalization` (or the log-magnitude preset) with `--beat-threshold`
+fn create_normalizer(args: &Args) -> Result<audio::FeatureNormalizer> {
+    use audio::FeatureNormalizer;
+    use audio::feature_normalizer::NormalizationParameters;
 
-    // Process entire file chunk by chunk
-    let mut frames = Vec::new();
+    let mut normalizer = match &args.normalization {
+        Some(path) => FeatureNormalizer::with_parameters(NormalizationParameters::load(path)?),
+        None if args.log_magnitude => FeatureNormalizer::with_parameters(NormalizationParameters::log_magnitude()),
+        None => FeatureNormalizer::new(),
+    };
+    normalizer.set_beat_threshold(args.beat_threshold);
+    Ok(normalizer)
+}
+
+const DUMP_WARMUP_SECONDS: f32 = 5.0; // Analyzed before the inspected chunk so flux, onsets, beats and BPM have history
+
+/// `--dump-spectrum`: analyze the run-up to `at` and print a breakdown of the chunk there, the
+/// same chunk a full pre-scan would produce at that time
+async fn dump_spectrum(args: &Args, input: &str, at: f32) -> Result<()> {
+    let (audio_buffer, _, _) = audio::decode_file_to_mono_checked(input, args.mono_mix.parse()?, args.fix_phase)?;
+    let sample_rate = args.sample_rate as f32;
+    let chunk_size = args.chunk_size;
+    let frames_in_file = audio_buffer.len() / chunk_size;
+    if at < 0.0 || frames_in_file == 0 || (at * sample_rate) as usize / chunk_size >= frames_in_file {
+        return Err(anyhow::anyhow!("--at {:.3}s is outside {} ({:.2}s)",
+                                   at, input, audio_buffer.len() as f32 / sample_rate));
+    }
+
+    let target_frame = (at * sample_rate) as usize / chunk_size;
+    let first_frame = target_frame.saturating_sub((DUMP_WARMUP_SECONDS * sample_rate) as usize / chunk_size);
+    let mut analyzer = create_cpu_analyzer(args)?;
+    let mut normalizer = create_normalizer(args)?;
+    let mut features = None;
+    for frame in first_frame..=target_frame {
+        let chunk = &audio_buffer[frame * chunk_size..(frame + 1) * chunk_size];
+        let raw = analyzer.analyze_chunk(chunk).await?;
+        let normalized = normalizer.normalize(&raw);
+        features = Some((raw, normalized));
+    }
+    let (raw, normalized) = features.expect("at least the target chunk is analyzed");
+    let spectrum = analyzer.last_spectrum().unwrap_or_default();
+
+    let start = target_frame * chunk_size;
+    println!("=== {} at {:.3}s ===", input, at);
+    println!("Chunk {}: samples {}..{} ({:.3}s-{:.3}s), {} frames of run-up",
+             target_frame, start, start + chunk_size, start as f32 / sample_rate,
+             (start + chunk_size) as f32 / sample_rate, target_frame - first_frame);
+
+    // dBFS against a full-scale sine (a Hann-windowed sine of amplitude 1 peaks at chunk_size / 4)
+    let bin_hz = sample_rate / 2.0 / spectrum.len().max(1) as f32;
+    let full_scale = chunk_size as f32 / 4.0;
+    println!();
+    println!("--- Spectrum ({} bins, {:.1} Hz each) ---", spectrum.len(), bin_hz);
+    println!("{:>5} {:>9} {:>12} {:>7}", "bin", "Hz", "magnitude", "dBFS");
+    for (bin, &magnitude) in spectrum.iter().enumerate() {
+        let dbfs = audio::level_meter::to_dbfs(magnitude / full_scale);
+        let bar = "#".repeat(((dbfs + 96.0) / 96.0 * 30.0).max(0.0) as usize);
+        println!("{:>5} {:>9.1} {:>12.6} {:>7.1} {}", bin, bin as f32 * bin_hz, magnitude, dbfs, bar);
+    }
+
+    println!();
+    println!("--- Features (raw → normalized) ---");
+    let rows = [
+        ("sub_bass", raw.sub_bass, normalized.sub_bass),
+        ("bass", raw.bass, normalized.bass),
+        ("mid", raw.mid, normalized.mid),
+        ("treble", raw.treble, normalized.treble),
+        ("presence", raw.presence, normalized.presence),
+        ("spectral_centroid", raw.spectral_centroid, normalized.spectral_centroid),
+        ("spectral_rolloff", raw.spectral_rolloff, normalized.spectral_rolloff),
+        ("spectral_flux", raw.spectral_flux, normalized.spectral_flux),
+        ("zero_crossing_rate", raw.zero_crossing_rate, normalized.zero_crossing_rate),
+        ("onset_strength", raw.onset_strength, normalized.onset_strength),
+        ("beat_strength", raw.beat_strength, normalized.beat_strength),
+        ("estimated_bpm", raw.estimated_bpm, normalized.estimated_bpm),
+        ("volume", raw.volume, normalized.volume),
+        ("dynamic_range", raw.dynamic_range, normalized.dynamic_range),
+        ("pitch_confidence", raw.pitch_confidence, normalized.pitch_confidence),
+    ];
+    for (name, raw_value, normalized_value) in rows {
+        println!("{:<19} {:>14.6e} → {:.4}", name, raw_value, normalized_value);
+    }
+    println!("{:<19} {} (threshold {:.2})", "beat_detected", normalized.beat_detected, normalizer.beat_threshold());
+
+    Ok(())
+}
+
+/// Analyze one chunk at each sample position, returning the frames and their peak/beat statistics
+async fn analyze_positions(
+    args: &Args,
+    analyzer: &mut (dyn AudioAnalyzer + Send),
+    audio_buffer: &[f32],
+    positions: &[usize],
+    duration_seconds: f32,
+) -> Result<(Vec<audio::prescan::PrescanFrame>, audio::prescan::AnalysisStatistics)> {
+    use audio::prescan::{PrescanFrame, AnalysisStatistics};
+    use audio::FrequencyBands;
+
+    let mut normalizer = create_normalizer(args)?;
+
+    let mut frames = Vec::with_capacity(positions.len());
     let mut statistics = AnalysisStatistics::default();
-    let mut sample_pos = 0;
     let mut beat_count = 0u32;
     let mut bpm_values = Vec::new();
 
-    while sample_pos + args.chunk_size <= total_samples {
+    for &sample_pos in positions {
         let chunk = &audio_buffer[sample_pos..sample_pos + args.chunk_size];
         let raw_features = analyzer.analyze_chunk(chunk).await?;
         let normalized_features = normalizer.normalize(&raw_features);
@@ -175,7 +607,7 @@ async fn prescan_with_unified_architecture(args: &Args) -> Result<audio::Prescan
                 treble: normalized_features.treble,
                 presence: normalized_features.presence,
             },
-            beat_detected: normalized_features.beat_strength > 0.3,
+            beat_detected: normalized_features.beat_detected,
             beat_strength: normalized_features.beat_strength,
             estimated_bpm: normalized_features.estimated_bpm,
             spectral_centroid: normalized_features.spectral_centroid,
@@ -192,11 +624,16 @@ async fn prescan_with_unified_architecture(args: &Args) -> Result<audio::Prescan
         update_unified_statistics(&mut statistics, &normalized_features, &mut beat_count, &mut bpm_values);
 
         frames.push(prescan_frame);
-        sample_pos += args.chunk_size;
 
         if frames.len() % 1000 == 0 {
-            info!("Pre-scanned {} frames ({:.1}s of {:.1}s)",
-                  frames.len(), timestamp, duration_seconds);
+            logging::progress(
+                &format!("Pre-scanned {} frames ({:.1}s of {:.1}s)", frames.len(), timestamp, duration_seconds),
+                serde_json::json!({
+                    "frames": frames.len(),
+                    "position_seconds": timestamp,
+                    "duration_seconds": duration_seconds,
+                }),
+            );
         }
     }
 
@@ -210,24 +647,80 @@ async fn prescan_with_unified_architecture(args: &Args) -> Result<audio::Prescan
         );
     }
 
-    // Classify content
-    classify_unified_content(&mut statistics, &frames);
+    Ok((frames, statistics))
+}
 
-    info!("{} analysis complete: {} frames, {} beats, {:.1} BPM average",
-          analyzer.analyzer_type(), frames.len(), beat_count, statistics.average_bpm);
+/// Reload a saved prescan file and compare it against the data that was written.
+///
+/// Checks the frame count and spot-checks a handful of evenly spaced frames. ARV only
+/// stores a subset of features with 16-bit (8-bit for beat strength) quantization, so
+/// tolerances are looser and unstored fields are skipped. Returns the number of frames checked.
+fn verify_saved_output(original: &audio::PrescanData, path: &str, is_arv: bool) -> Result<usize> {
+    let reloaded = if is_arv {
+        ArvFormat::load_arv(path)?
+    } else {
+        PrescanProcessor::load_prescan_data(path)?
+    };
 
-    Ok(audio::PrescanData {
-        file_info: FileInfo {
-            filename: args.input_file.clone(),
-            duration_seconds,
-            sample_rate: args.sample_rate as f32,
-            total_samples,
-            frame_rate,
-            chunk_size: args.chunk_size,
-        },
-        frames,
-        statistics,
-    })
+    if reloaded.frames.len() != original.frames.len() {
+        return Err(anyhow::anyhow!("Frame count mismatch: wrote {}, reloaded {}",
+                                   original.frames.len(), reloaded.frames.len()));
+    }
+
+    if original.frames.is_empty() {
+        return Ok(0);
+    }
+
+    // First, last, and a few evenly spaced frames in between
+    let last = original.frames.len() - 1;
+    let mut indices: Vec<usize> = (0..=4).map(|i| last * i / 4).collect();
+    indices.dedup();
+
+    let (float_tolerance, beat_tolerance) = if is_arv { (0.001, 0.05) } else { (1e-5, 1e-5) };
+
+    for &index in &indices {
+        let expected = &original.frames[index];
+        let actual = &reloaded.frames[index];
+
+        let mut checks = vec![
+            ("timestamp", expected.timestamp, actual.timestamp, 0.001),
+            ("sub_bass", expected.frequency_bands.sub_bass, actual.frequency_bands.sub_bass, float_tolerance),
+            ("bass", expected.frequency_bands.bass, actual.frequency_bands.bass, float_tolerance),
+            ("mid", expected.frequency_bands.mid, actual.frequency_bands.mid, float_tolerance),
+            ("treble", expected.frequency_bands.treble, actual.frequency_bands.treble, float_tolerance),
+            ("presence", expected.frequency_bands.presence, actual.frequency_bands.presence, float_tolerance),
+            ("spectral_centroid", expected.spectral_centroid, actual.spectral_centroid, float_tolerance),
+            ("pitch_confidence", expected.pitch_confidence, actual.pitch_confidence, float_tolerance),
+            ("onset_strength", expected.onset_strength, actual.onset_strength, float_tolerance),
+            ("beat_strength", expected.beat_strength, actual.beat_strength, beat_tolerance),
+        ];
+
+        // JSON stores every field, so check the ones ARV drops as well
+        if !is_arv {
+            checks.extend([
+                ("estimated_bpm", expected.estimated_bpm, actual.estimated_bpm, float_tolerance),
+                ("spectral_rolloff", expected.spectral_rolloff, actual.spectral_rolloff, float_tolerance),
+                ("zero_crossing_rate", expected.zero_crossing_rate, actual.zero_crossing_rate, float_tolerance),
+                ("spectral_flux", expected.spectral_flux, actual.spectral_flux, float_tolerance),
+                ("dynamic_range", expected.dynamic_range, actual.dynamic_range, float_tolerance),
+                ("volume", expected.volume, actual.volume, float_tolerance),
+            ]);
+        }
+
+        for (name, expected_value, actual_value, tolerance) in checks {
+            if (expected_value - actual_value).abs() > tolerance {
+                return Err(anyhow::anyhow!("Frame {} field '{}' differs: wrote {:.6}, reloaded {:.6}",
+                                           index, name, expected_value, actual_value));
+            }
+        }
+
+        if expected.beat_detected != actual.beat_detected {
+            return Err(anyhow::anyhow!("Frame {} beat flag differs: wrote {}, reloaded {}",
+                                       index, expected.beat_detected, actual.beat_detected));
+        }
+    }
+
+    Ok(indices.len())
 }
 
 fn update_unified_statistics(
@@ -245,8 +738,8 @@ fn update_unified_statistics(
     stats.peak_spectral_flux = stats.peak_spectral_flux.max(features.spectral_flux);
     stats.peak_onset = stats.peak_onset.max(features.onset_strength);
 
-    // Track beats and BPM
-    if features.beat_strength > 0.3 {
+    // Track beats and BPM (same threshold as the per-frame beat_detected flag)
+    if features.beat_detected {
         *beat_count += 1;
         if features.estimated_bpm > 60.0 && features.estimated_bpm < 200.0 {
             bpm_values.push(features.estimated_bpm);
@@ -2,47 +2,49 @@ use anyhow::Result;
 use clap::Parser;
 use log::info;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use winit::{
     event::{ElementState, Event, WindowEvent},
     event_loop::EventLoop,
     keyboard::{KeyCode, PhysicalKey},
-    window::WindowBuilder,
+    window::{Window, WindowBuilder},
 };
 
-mod graphics;
-mod audio;
-mod effects;
+use arrvee::{audio, effects, graphics, ui};
+use arrvee::control::ControlChannel;
 
 use graphics::GraphicsEngine;
-use audio::{AudioPlayback, AudioFrame, ArvFormat, SynchronizedPlayback};
+use ui::band_grid::BandGridOverlay;
+use ui::refresh_throttle::RefreshThrottle;
+use ui::now_playing::{NowPlayingOverlay, OverlayPosition};
+use effects::program::EffectProgram;
+use audio::{AudioPlayback, AudioFrame, ArvFormat, PrescanData, PrescanProcessor, SynchronizedPlayback};
+use audio::loop_region::LoopRegion;
+use audio::solo_band::SoloBand;
 
 struct DebugOverlay {
     show_overlay: bool,
     volume_control: f32,
-    frame_count: u32,
+    refresh: RefreshThrottle, // Redraws at `--debug-hz`
     last_sync_info: String,
 }
 
 impl DebugOverlay {
-    fn new() -> Self {
+    fn new(refresh_hz: f32) -> Self {
         Self {
             show_overlay: true,
             volume_control: 0.1, // 10% default volume
-            frame_count: 0,
+            refresh: RefreshThrottle::new(refresh_hz),
             last_sync_info: String::new(),
         }
     }
 
-    fn render_debug_info(&mut self, audio_frame: &AudioFrame, graphics_engine: &graphics::GraphicsEngine, sync_info: &str) {
+    fn render_debug_info(&mut self, audio_frame: &AudioFrame, graphics_engine: &graphics::GraphicsEngine, sync_info: &str, sensitivity: f32) {
         if !self.show_overlay {
             return;
         }
 
-        self.frame_count += 1;
-
-        // Only update display every 30 frames (roughly twice per second) to reduce spam
-        if self.frame_count % 30 != 0 {
+        if !self.refresh.ready(Instant::now()) {
             return;
         }
 
@@ -88,6 +90,7 @@ impl DebugOverlay {
                  current_palette,
                  graphics_engine.smoothing_factor);
 
+        println!("║   Sensitivity: {:>5.2}x                                         ║", sensitivity);
         println!("║   Sync: {:<48} ║", self.last_sync_info);
 
         println!("║                                                               ║");
@@ -103,6 +106,7 @@ impl DebugOverlay {
                     "fractal_madness" => "Fractal Madness",
                     "spectralizer_bars" => "Spectralizer",
                     "parametric_waves" => "Parametric Waves",
+                    "flux_debug" => "Flux Debug",
                     _ => effect
                 };
                 println!("║   {:<15}: {:>6.3} ■{:<15}                    ║",
@@ -121,8 +125,11 @@ impl DebugOverlay {
         println!("║                                                               ║");
         println!("║ 🎮 CONTROLS                                                   ║");
         println!("║   P: Palette | [/]: Smoothing | Q/W/E/R/T: Projection       ║");
-        println!("║   1-7: Effects | 0: Auto | D: Toggle Debug | Space: Pause   ║");
-        println!("║   +/-: Volume | S: Show Sync Info | ESC: Exit               ║");
+        println!("║   1-8: Effects | 0: Auto | D: Toggle Debug | Space: Pause   ║");
+        println!("║   F: Freeze Visuals | F11: Fullscreen                       ║");
+        println!("║   B: Fade to Black/In | PgUp/PgDn: Master Intensity         ║");
+        println!("║   U: Dump Uniforms to JSON (reload with --load-uniforms)    ║");
+        println!("║   +/-: Volume | ,/.: Sensitivity | ESC: Exit                ║");
         println!("╚═══════════════════════════════════════════════════════════════╝");
     }
 
@@ -144,29 +151,224 @@ struct Args {
     #[arg(default_value = "sample.m4a")]
     audio_file: String,
 
-    /// ARV prescan data file
+    /// Prescan data: an ARV file, prescan JSON, or a CSV/JSON feature stream from another tool
     #[arg(short, long, default_value = "sample_prescan.arv")]
     arv_file: String,
 
     /// Show developer overlay with analysis stats
     #[arg(long, short)]
     debug: bool,
+
+    /// Developer overlay refreshes per second (independent of the render frame rate)
+    #[arg(long, value_name = "HZ", default_value_t = 2.0, value_parser = ui::refresh_throttle::parse_refresh_hz)]
+    debug_hz: f32,
+
+    /// Graphics backend: vulkan, metal, dx12, gl, or auto
+    #[arg(long, default_value = "auto")]
+    backend: String,
+
+    /// Playback speed (0.25-4.0; e.g. 0.5 for half-speed ambient; pitch follows)
+    #[arg(long, default_value = "1.0")]
+    speed: f32,
+
+    /// Open with the audio paused (visuals idle) until Space, e.g. while a screen recorder gets ready
+    #[arg(long, overrides_with = "autoplay")]
+    paused: bool,
+
+    /// Start playing as soon as the window opens (the default; overrides an earlier `--paused`)
+    #[arg(long, overrides_with = "paused")]
+    autoplay: bool,
+
+    /// Seek this many seconds into the track before starting
+    #[arg(long, value_name = "SECONDS")]
+    start_at: Option<f32>,
+
+    /// Loop between two points, START-END in seconds (e.g. 32-48.5); I/O set them live, L clears
+    #[arg(long, value_name = "START-END")]
+    loop_region: Option<String>,
+
+    /// Start in borderless fullscreen, optionally on a specific monitor (`--fullscreen=1`)
+    #[arg(long, value_name = "MONITOR", num_args = 0..=1, require_equals = true)]
+    fullscreen: Option<Option<usize>>,
+
+    /// Show a "now playing" caption ("Artist — Title" from the file's tags, else the file name) in a
+    /// corner: --now-playing=top-left, top-right, bottom-left (default) or bottom-right; N toggles it
+    #[arg(long, value_name = "POSITION", num_args = 0..=1, default_missing_value = "bottom-left", require_equals = true)]
+    now_playing: Option<String>,
+
+    /// Window width in pixels (at least 320; default is the tool's usual size)
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Window height in pixels (at least 240)
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Window title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Seconds for a full master fade in/out (B to fade to black and back)
+    #[arg(long, default_value = "2.0")]
+    fade_time: f32,
+
+    /// Render a fixed uniforms dump (written with U) instead of audio-driven values
+    #[arg(long, value_name = "FILE")]
+    load_uniforms: Option<String>,
+
+    /// Look frames up this many milliseconds ahead of the playback clock to compensate for
+    /// audio/display pipeline latency (negative values delay the visuals instead)
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    latency_offset: f32,
+
+    /// Use the nearest earlier prescan frame instead of interpolating between frames
+    #[arg(long)]
+    nearest_frame: bool,
+
+    /// Start in a manual effect instead of auto-blend (e.g. `fractal_madness`)
+    #[arg(long)]
+    effect: Option<String>,
+
+    /// Starting color palette: index 0-5 or name (e.g. `deep-ocean`)
+    #[arg(long)]
+    palette: Option<String>,
+
+    /// Starting projection: auto, sphere, cylinder, torus, flat
+    #[arg(long)]
+    projection: Option<String>,
+
+    /// Leave effects out of auto-blend (comma-separated or repeated, e.g. `particle_swarm`)
+    #[arg(long, value_delimiter = ',')]
+    disable_effect: Vec<String>,
+
+    /// Invert the auto-blend: visuals calm down when the music is intense and come alive in quiet passages
+    #[arg(long)]
+    invert_response: bool,
+
+    /// Multiplier on how fast auto-blend crossfades between effects (0.1 = slow, cinematic blends
+    /// over seconds; 4 = snappy switching)
+    #[arg(long, default_value = "1.0")]
+    transition_speed: f32,
+
+    /// Drive the visuals from one band alone: sub-bass, bass, mid, treble or presence (Y cycles it)
+    #[arg(long, value_name = "BAND")]
+    solo_band: Option<String>,
+
+    /// Analysis profile for the playback's real-time analysis: full, or lite (less CPU); the
+    /// visuals come from the prescan either way
+    #[arg(long, default_value = "full")]
+    profile: String,
+
+    /// Accept line commands on stdin (`effect fractal_madness`, `palette 3`, `smoothing 0.5`, `volume 0.2`, ...)
+    #[arg(long)]
+    control_stdin: bool,
+
+    /// Accept the same commands from TCP clients on 127.0.0.1:<PORT>
+    #[arg(long, value_name = "PORT")]
+    control_tcp: Option<u16>,
+
+    /// Pulse the whole screen on each beat at this strength (0-1), over any effect; rate-limited to 3 flashes/s
+    #[arg(long, value_name = "INTENSITY", num_args = 0..=1, default_missing_value = "0.5")]
+    beat_flash: Option<f32>,
+
+    /// Motion trails: share of each frame kept into the next (0 = off, up to 0.98)
+    #[arg(long, value_name = "AMOUNT", default_value_t = 0.0)]
+    feedback: f32,
+
+    /// Fixed number of mirrored kaleidoscope segments (2-32) instead of following pitch and onsets
+    #[arg(long, value_name = "COUNT")]
+    kaleidoscope_segments: Option<u32>,
+
+    /// Blend width of the kaleidoscope seams, as a share of a segment (0 = hard mirror lines)
+    #[arg(long, value_name = "AMOUNT", default_value_t = graphics::engine::DEFAULT_KALEIDOSCOPE_SEAM_SOFTNESS)]
+    kaleidoscope_softness: f32,
+
+    /// Scale the kaleidoscope segment count with the tempo (half at 60 BPM, double at 240)
+    #[arg(long)]
+    kaleidoscope_bpm_sync: bool,
+
+    /// Size of the particle swarm's bursts on onsets and beats (0 = no bursts, up to 3)
+    #[arg(long, value_name = "RATE", default_value_t = 1.0)]
+    particle_bursts: f32,
+
+    /// Calmer visuals for motion-sensitive viewers: slower animation and crossfades, a gentler
+    /// camera, no particle bursts, and auto-blend favouring plasma and waves
+    #[arg(long)]
+    reduced_motion: bool,
+
+    /// Keep the camera still in the 3D projections instead of rolling and zooming with the music
+    #[arg(long)]
+    still_camera: bool,
+
+    /// Background behind unlit areas, as hex (e.g. `#102030`)
+    #[arg(long, value_name = "HEX")]
+    bg_color: Option<String>,
+
+    /// See-through background for compositing in OBS or over the desktop (needs a compositor)
+    #[arg(long, conflicts_with = "bg_color")]
+    transparent: bool,
+
+    /// Highlight tonemapping for bright blends: off (clip), reinhard or aces
+    #[arg(long, default_value = "off")]
+    tonemap: String,
+
+    /// Window output color space: srgb or linear
+    #[arg(long, default_value = "srgb")]
+    output_color_space: String,
+
+    /// JSON effect program that steps through effects on a schedule (overrides auto-blend)
+    #[arg(long, value_name = "FILE")]
+    effect_program: Option<String>,
+
+    /// Log every frame's effect weights to a CSV that `--replay-weights` can play back
+    #[arg(long, value_name = "FILE")]
+    record_weights: Option<String>,
+
+    /// Drive the effect weights from a `--record-weights` log instead of auto-blend
+    #[arg(long, value_name = "FILE")]
+    replay_weights: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     let args = Args::parse();
+    graphics::crash_report::install("arrvee-sync-test");
+    graphics::crash_report::set_audio_file(&args.audio_file);
+    let backends = graphics::engine::parse_backends(&args.backend)?;
+    let window_size = graphics::engine::window_size(args.width, args.height, (1200, 800))?;
+
+    // Parse up front so a typo fails before a window is opened
+    let start_effect = args.effect.as_deref().map(effects::psychedelic_manager::parse_effect_name).transpose()?;
+    let start_palette = args.palette.as_deref().map(graphics::engine::parse_palette).transpose()?;
+    let start_projection = args.projection.as_deref().map(graphics::engine::parse_projection).transpose()?;
+    let disabled_effects = args.disable_effect.iter()
+        .map(|name| effects::psychedelic_manager::parse_effect_name(name))
+        .collect::<Result<Vec<_>>>()?;
+    let tonemap = graphics::engine::parse_tonemap(&args.tonemap)?;
+    let output_color_space: graphics::engine::OutputColorSpace = args.output_color_space.parse()?;
+    let bg_color = args.bg_color.as_deref().map(graphics::engine::parse_hex_color).transpose()?;
+    let analysis_profile: audio::AnalysisProfile = args.profile.parse()?;
+    let effect_program = args.effect_program.as_deref().map(EffectProgram::load).transpose()?;
 
     info!("Starting Synchronized Audio Visualization Test");
     info!("Audio file: {}", args.audio_file);
     info!("ARV data: {}", args.arv_file);
     info!("Debug overlay: {}", args.debug);
+    if args.latency_offset != 0.0 {
+        info!("⏱️  Latency offset: {:+.0}ms", args.latency_offset);
+    }
+    let latency_offset_seconds = args.latency_offset / 1000.0;
+    let nearest_frame = args.nearest_frame;
 
     // Load synchronized playback data
-    info!("Loading ARV prescan data...");
-    let prescan_data = ArvFormat::load_arv(&args.arv_file)?;
+    info!("Loading prescan data...");
+    let prescan_data = load_sync_data(&args.arv_file)?;
     let mut synchronized_playback = SynchronizedPlayback::new(prescan_data);
+    graphics::crash_report::set_analysis_config(format!(
+        "prescan {} ({}Hz, chunk size {}), {:?} profile, latency offset {:+.0}ms, speed {:.2}x",
+        args.arv_file, synchronized_playback.get_file_info().sample_rate, synchronized_playback.get_file_info().chunk_size,
+        analysis_profile, args.latency_offset, args.speed));
 
     info!("Loaded synchronized data:");
     info!("  Duration: {:.1}s", synchronized_playback.get_file_info().duration_seconds);
@@ -178,25 +380,112 @@ async fn main() -> Result<()> {
 
     let event_loop = EventLoop::new()?;
     let window = Arc::new(WindowBuilder::new()
-        .with_title("Arrvee Synchronized Playback Test")
-        .with_inner_size(winit::dpi::LogicalSize::new(1200, 800))
+        .with_title(args.title.as_deref().unwrap_or("Arrvee Synchronized Playback Test"))
+        .with_inner_size(window_size)
+        .with_transparent(args.transparent)
+        .with_fullscreen(args.fullscreen.map(|monitor| graphics::engine::borderless_fullscreen(&event_loop, monitor)))
         .build(&event_loop)?);
 
-    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window))?;
+    let mut graphics_engine = pollster::block_on(GraphicsEngine::new(&window, backends))?;
+    graphics_engine.set_master_fade_time(args.fade_time);
+    if let Some(color) = bg_color {
+        graphics_engine.set_clear_color(color);
+    }
+    if args.transparent {
+        graphics_engine.set_transparent(true);
+    }
+    let profile = graphics_engine.psychedelic_manager_mut().apply_profile(synchronized_playback.get_statistics());
+    graphics_engine.palette_index = profile.palette_index;
+    info!("🎨 Starting with {} (palette {}) for this track's profile", profile.effect, profile.palette_index as i32);
+    if let Some(effect) = start_effect {
+        graphics_engine.psychedelic_manager_mut().set_manual_effect(Some(effect.to_string()));
+        info!("🎆 Starting in manual effect: {}", effect);
+    }
+    if let Some(palette) = start_palette {
+        graphics_engine.palette_index = palette;
+    }
+    for effect in &disabled_effects {
+        graphics_engine.psychedelic_manager_mut().set_effect_enabled(effect, false);
+    }
+    if !disabled_effects.is_empty() {
+        info!("🚫 Auto-blend skips: {}", disabled_effects.join(", "));
+    }
+    if args.invert_response {
+        graphics_engine.psychedelic_manager_mut().set_invert_response(true);
+        info!("🙃 Inverted response: quiet passages drive the effects");
+    }
+    graphics_engine.psychedelic_manager_mut().set_transition_speed_multiplier(args.transition_speed)?;
+    if args.transition_speed != 1.0 {
+        info!("🌊 Effect transitions at {:.2}x speed", args.transition_speed);
+    }
+    if let Some(band) = args.solo_band.as_deref().map(str::parse::<SoloBand>).transpose()? {
+        info!("🎯 Solo band: {}", band.name());
+        graphics_engine.set_solo_band(Some(band));
+    }
+    if let Some(projection) = start_projection {
+        graphics_engine.projection_mode = projection;
+    }
+    if let Some(intensity) = args.beat_flash {
+        graphics_engine.set_beat_flash_enabled(true);
+        graphics_engine.set_beat_flash_intensity(intensity);
+        info!("💥 Beat flash at {:.0}% intensity", graphics_engine.beat_flash().intensity() * 100.0);
+    }
+    if args.feedback > 0.0 {
+        graphics_engine.set_feedback(args.feedback);
+        info!("🌀 Frame feedback at {:.2}", graphics_engine.feedback());
+    }
+    graphics_engine.set_kaleidoscope_segments(args.kaleidoscope_segments);
+    graphics_engine.set_kaleidoscope_seam_softness(args.kaleidoscope_softness);
+    graphics_engine.set_kaleidoscope_bpm_sync(args.kaleidoscope_bpm_sync);
+    graphics_engine.set_particle_emission_rate(args.particle_bursts);
+    if args.reduced_motion {
+        graphics_engine.set_reduced_motion(true);
+        info!("🐢 Reduced motion: animation at {:.0}% speed, no particle bursts, gentle effects favoured",
+              graphics::engine::REDUCED_MOTION_SCALE * 100.0);
+    }
+    if let Some(segments) = graphics_engine.kaleidoscope_segments() {
+        info!("🔷 Kaleidoscope: {} segments{}", segments, if args.kaleidoscope_bpm_sync { ", scaled with the tempo" } else { "" });
+    }
+    graphics_engine.set_camera_motion(!args.still_camera);
+    graphics_engine.tonemap_mode = tonemap;
+    graphics_engine.set_output_color_space(output_color_space)?;
+    if let Some(program) = effect_program {
+        info!("🎬 Running effect program from {}", args.effect_program.as_deref().unwrap_or_default());
+        graphics_engine.psychedelic_manager_mut().set_program(Some(program));
+    }
+    if let Some(path) = &args.replay_weights {
+        graphics_engine.replay_weights(path)?;
+    }
+    if let Some(path) = &args.record_weights {
+        graphics_engine.record_weights(path)?;
+    }
+    if let Some(path) = &args.load_uniforms {
+        graphics_engine.load_uniforms(path)?;
+    }
     let mut shutdown_requested = false;
     let mut audio_playback = AudioPlayback::new()?;
+    audio_playback.set_analysis_profile(analysis_profile);
     let mut debug_overlay = if args.debug {
-        Some(DebugOverlay::new())
+        Some(DebugOverlay::new(args.debug_hz))
     } else {
         None
     };
 
-    let mut paused = false;
+    // Wall-clock seconds into playback when paused, so the analysis lookup holds still
+    let mut paused_at: Option<Duration> = None;
     let mut playback_start_time = Instant::now();
 
     // Load and start playing the specified audio file
     info!("Loading {}...", args.audio_file);
     audio_playback.load_file(&args.audio_file).await?;
+    let now_playing_text = audio_playback.metadata().map_or_else(|| args.audio_file.clone(), |metadata| metadata.display_text());
+    let now_playing_position = args.now_playing.as_deref().map(str::parse::<OverlayPosition>).transpose()?;
+    let mut now_playing = NowPlayingOverlay::new(&graphics_engine.device, graphics_engine.config.format, window.scale_factor() as f32,
+                                                 now_playing_text.clone(), now_playing_position.unwrap_or_default());
+    now_playing.set_visible(now_playing_position.is_some());
+    let mut band_grid = BandGridOverlay::new(&graphics_engine.device, graphics_engine.config.format,
+                                             window.scale_factor() as f32, OverlayPosition::BottomRight);
+    info!("🎵 Now playing: {}", now_playing_text);
 
     // Set initial volume
     let initial_volume = if let Some(debug) = &debug_overlay {
@@ -205,12 +494,41 @@ async fn main() -> Result<()> {
         0.1
     };
     audio_playback.set_volume(initial_volume);
+    if args.speed != 1.0 {
+        audio_playback.set_speed(args.speed);
+        synchronized_playback.set_speed(audio_playback.speed(), 0.0);
+        info!("⏩ Playback speed {:.2}x", audio_playback.speed());
+    }
 
-    audio_playback.play();
+    let mut loop_region = match &args.loop_region {
+        Some(text) => LoopRegion::parse(text)?,
+        None => LoopRegion::new(),
+    };
+    let start_at = args.start_at.or(loop_region.bounds().map(|(start, _)| start));
+    let start_paused = args.paused && !args.autoplay;
+    audio_playback.start(start_at, start_paused)?;
+    if let Some(start) = start_at {
+        synchronized_playback.seek(start, 0.0);
+    }
+    if start_paused {
+        paused_at = Some(Duration::ZERO);
+    }
+    if let Some((start, end)) = loop_region.bounds() {
+        info!("🔁 Looping {:.2}s-{:.2}s", start, end);
+    }
+    let mut last_track_time = start_at.unwrap_or(0.0);
     info!("Audio playback started at {:.0}% volume with synchronized analysis", initial_volume * 100.0);
 
     info!("Synchronized visualization test initialized successfully");
 
+    let control = ControlChannel::new();
+    if args.control_stdin {
+        control.listen_stdin();
+    }
+    if let Some(port) = args.control_tcp {
+        control.listen_tcp(port)?;
+    }
+
     let window_clone = Arc::clone(&window);
     event_loop.run(move |event, elwt| {
         match event {
@@ -237,18 +555,58 @@ async fn main() -> Result<()> {
                                 info!("Cleanup complete");
                                 elwt.exit();
                             }
+                            PhysicalKey::Code(KeyCode::F11) => {
+                                graphics::engine::toggle_fullscreen(&window_clone);
+                            }
                             PhysicalKey::Code(KeyCode::Space) => {
-                                if paused {
+                                if let Some(elapsed) = paused_at.take() {
                                     audio_playback.play();
-                                    playback_start_time = Instant::now() - playback_start_time.elapsed();
-                                    paused = false;
+                                    playback_start_time = Instant::now() - elapsed;
                                     info!("Audio resumed");
                                 } else {
                                     audio_playback.pause();
-                                    paused = true;
+                                    paused_at = Some(playback_start_time.elapsed());
                                     info!("Audio paused");
                                 }
                             }
+                            // Freeze visuals independently of audio
+                            PhysicalKey::Code(KeyCode::KeyF) => {
+                                let frozen = !graphics_engine.is_time_paused();
+                                graphics_engine.set_time_paused(frozen);
+                                info!("{}", if frozen { "❄️ Visuals frozen" } else { "▶️ Visuals resumed" });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyU) => {
+                                let timestamp = std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .map_or(0, |elapsed| elapsed.as_secs());
+                                if let Err(e) = graphics_engine.dump_uniforms(format!("uniforms_{}.json", timestamp)) {
+                                    log::error!("Failed to dump uniforms: {}", e);
+                                }
+                            }
+                            PhysicalKey::Code(KeyCode::KeyN) => {
+                                now_playing.set_visible(!now_playing.is_visible());
+                            }
+                            // Band grid: FFT bins and band edges over the spectralizer
+                            PhysicalKey::Code(KeyCode::KeyA) => {
+                                let enabled = !graphics_engine.band_grid();
+                                graphics_engine.set_band_grid(enabled);
+                                band_grid.set_visible(enabled);
+                                info!("📏 Band grid {}", if enabled { "on" } else { "off" });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyY) => {
+                                let band = SoloBand::cycle(graphics_engine.solo_band());
+                                graphics_engine.set_solo_band(band);
+                                info!("🎯 Solo band: {}", band.map_or("off (full mix)", SoloBand::name));
+                            }
+                            PhysicalKey::Code(KeyCode::KeyB) => {
+                                let target = graphics_engine.toggle_master_fade();
+                                info!("{}", if target > 0.0 { "🌅 Fading in" } else { "🌑 Fading to black" });
+                            }
+                            PhysicalKey::Code(KeyCode::PageUp) | PhysicalKey::Code(KeyCode::PageDown) => {
+                                let step = if event.physical_key == PhysicalKey::Code(KeyCode::PageUp) { 0.25 } else { -0.25 };
+                                let target = graphics_engine.step_master_fade(step);
+                                info!("🎚️ Master intensity → {:.0}%", target * 100.0);
+                            }
                             PhysicalKey::Code(KeyCode::KeyD) => {
                                 if let Some(debug) = &mut debug_overlay {
                                     debug.toggle_overlay();
@@ -269,6 +627,15 @@ async fn main() -> Result<()> {
                                     info!("Volume decreased to {:.1}%", new_volume * 100.0);
                                 }
                             }
+                            // Sensitivity controls (scales the prescanned features at render time)
+                            PhysicalKey::Code(KeyCode::Period) => {
+                                let new_sensitivity = audio_playback.adjust_sensitivity(0.1);
+                                info!("🎚️ Sensitivity increased to {:.2}x", new_sensitivity);
+                            }
+                            PhysicalKey::Code(KeyCode::Comma) => {
+                                let new_sensitivity = audio_playback.adjust_sensitivity(-0.1);
+                                info!("🎚️ Sensitivity decreased to {:.2}x", new_sensitivity);
+                            }
                             // Effect switching controls
                             PhysicalKey::Code(KeyCode::Digit1) => {
                                 graphics_engine.psychedelic_manager_mut().set_manual_effect(Some("llama_plasma".to_string()));
@@ -298,6 +665,10 @@ async fn main() -> Result<()> {
                                 graphics_engine.psychedelic_manager_mut().set_manual_effect(Some("parametric_waves".to_string()));
                                 info!("🌈 Effect switched to: Parametric Waves");
                             }
+                            PhysicalKey::Code(KeyCode::Digit8) => {
+                                graphics_engine.psychedelic_manager_mut().set_manual_effect(Some("flux_debug".to_string()));
+                                info!("🌈 Effect switched to: Flux Debug");
+                            }
                             PhysicalKey::Code(KeyCode::Digit0) => {
                                 graphics_engine.psychedelic_manager_mut().set_manual_effect(None);
                                 info!("🌈 Effect switched to: Auto-Blend Mode");
@@ -339,10 +710,30 @@ async fn main() -> Result<()> {
                                 graphics_engine.smoothing_factor = (graphics_engine.smoothing_factor + 0.1).min(2.0);
                                 info!("🎛️ Smoothing: {:.1}", graphics_engine.smoothing_factor);
                             }
+                            // A/B loop points at the current playback position
+                            PhysicalKey::Code(KeyCode::KeyI) => {
+                                loop_region.set_in(last_track_time);
+                                info!("🔁 Loop in at {:.2}s", last_track_time);
+                            }
+                            PhysicalKey::Code(KeyCode::KeyO) => {
+                                if loop_region.set_out(last_track_time) {
+                                    info!("🔁 Loop out at {:.2}s", last_track_time);
+                                } else {
+                                    log::warn!("⚠️ Loop out at {:.2}s must be at least {}s after the in point",
+                                               last_track_time, audio::loop_region::MIN_LOOP_SECONDS);
+                                }
+                            }
+                            PhysicalKey::Code(KeyCode::KeyL) => {
+                                loop_region.clear();
+                                info!("🔁 Loop cleared");
+                            }
                             _ => {}
                         }
                     }
                 }
+                WindowEvent::CursorMoved { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::MouseInput { .. } => {
+                    graphics_engine.handle_mouse_event(&event);
+                }
                 WindowEvent::Resized(physical_size) => {
                     graphics_engine.resize(physical_size);
                 }
@@ -352,18 +743,39 @@ async fn main() -> Result<()> {
                     }
 
                     // Get current playback time and synchronized frame
-                    let current_time = if paused {
-                        playback_start_time.elapsed().as_secs_f32()
-                    } else {
-                        playback_start_time.elapsed().as_secs_f32()
-                    };
+                    let wall_time = paused_at.unwrap_or_else(|| playback_start_time.elapsed()).as_secs_f32();
+                    let mut current_time = synchronized_playback.track_time(wall_time);
+                    if let Some(start) = loop_region.wrap(current_time) {
+                        match audio_playback.seek(start) {
+                            Ok(()) => {
+                                synchronized_playback.seek(start, wall_time);
+                                current_time = synchronized_playback.track_time(wall_time);
+                            }
+                            Err(e) => {
+                                log::warn!("⚠️ Loop disabled: {}", e);
+                                loop_region.clear();
+                            }
+                        }
+                    }
+                    last_track_time = current_time;
+                    let duration = synchronized_playback.get_file_info().duration_seconds.max(f32::EPSILON);
+                    graphics_engine.set_loop_markers(loop_region.in_point().map(|point| point / duration),
+                                                     loop_region.out_point().map(|point| point / duration),
+                                                     current_time / duration);
 
                     let file_info_sample_rate = synchronized_playback.get_file_info().sample_rate;
-                    let _sync_info = if let Some(sync_frame) = synchronized_playback.get_synchronized_frame(current_time) {
+                    let lookup_time = (current_time + latency_offset_seconds).max(0.0);
+                    let sync_frame = if nearest_frame {
+                        synchronized_playback.get_synchronized_frame(lookup_time).cloned()
+                    } else {
+                        synchronized_playback.get_interpolated_frame(lookup_time)
+                    };
+                    let _sync_info = if let Some(sync_frame) = sync_frame {
                         // Convert prescan frame to AudioFrame for rendering
-                        let audio_data = AudioFrame {
+                        let mut audio_data = AudioFrame {
                             sample_rate: file_info_sample_rate,
                             spectrum: vec![0.0; 512], // Not used in rendering
+                            mel_bands: Vec::new(),
                             time_domain: vec![0.0; 1024], // Not used in rendering
                             frequency_bands: sync_frame.frequency_bands.clone(),
                             beat_detected: sync_frame.beat_detected,
@@ -378,21 +790,16 @@ async fn main() -> Result<()> {
                             estimated_bpm: sync_frame.estimated_bpm,
                             dynamic_range: sync_frame.dynamic_range,
                         };
+                        audio_data.apply_sensitivity(audio_playback.get_sensitivity());
 
                         let sync_status = format!("T={:.2}s Frame@{:.3}s Perfect", current_time, sync_frame.timestamp);
 
-                        // Render debug overlay if enabled
-                        static mut FRAME_COUNT: u32 = 0;
-                        unsafe {
-                            FRAME_COUNT += 1;
-                            if FRAME_COUNT % 30 == 0 {
-                                if let Some(debug) = &mut debug_overlay {
-                                    debug.render_debug_info(&audio_data, &graphics_engine, &sync_status);
-                                }
-                            }
+                        // Render debug overlay if enabled (it throttles itself to --debug-hz)
+                        if let Some(debug) = &mut debug_overlay {
+                            debug.render_debug_info(&audio_data, &graphics_engine, &sync_status, audio_playback.get_sensitivity());
                         }
 
-                        if let Err(e) = graphics_engine.render(&audio_data, &window_clone) {
+                        if let Err(e) = render_frame(&mut graphics_engine, &audio_data, &window_clone, &mut now_playing, &mut band_grid) {
                             log::error!("Render error: {}", e);
                         }
 
@@ -402,7 +809,7 @@ async fn main() -> Result<()> {
 
                         // Use default frame when out of sync
                         let default_frame = AudioFrame::default();
-                        if let Err(e) = graphics_engine.render(&default_frame, &window_clone) {
+                        if let Err(e) = render_frame(&mut graphics_engine, &default_frame, &window_clone, &mut now_playing, &mut band_grid) {
                             log::error!("Render error: {}", e);
                         }
 
@@ -418,6 +825,9 @@ async fn main() -> Result<()> {
                 _ => {}
             },
             Event::AboutToWait => {
+                for command in control.poll() {
+                    command.apply(&mut graphics_engine, &mut audio_playback);
+                }
                 window_clone.request_redraw();
             }
             _ => {}
@@ -425,4 +835,33 @@ async fn main() -> Result<()> {
     })?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Load synchronization data by extension: ARV, prescan-tool JSON, or an external
+/// feature stream (CSV, or JSON that isn't a prescan dump)
+fn load_sync_data(path: &str) -> Result<PrescanData> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "csv" => audio::feature_import::import_feature_stream(path),
+        // Report both failures: a damaged prescan dump shouldn't read as a bad feature stream
+        "json" => PrescanProcessor::load_prescan_data(path).or_else(|prescan_error| {
+            audio::feature_import::import_feature_stream(path).map_err(|import_error| anyhow::anyhow!(
+                "{} is neither prescan data ({}) nor a feature stream ({})", path, prescan_error, import_error))
+        }),
+        _ => Ok(ArvFormat::load_arv(path)?),
+    }
+}
+
+/// Render a frame with the now-playing caption and band grid legend over it
+fn render_frame(graphics_engine: &mut GraphicsEngine, audio_frame: &AudioFrame, window: &Window,
+                now_playing: &mut NowPlayingOverlay, band_grid: &mut BandGridOverlay) -> Result<()> {
+    let band_edges = graphics_engine.band_edges();
+    graphics_engine.render_with_overlay(audio_frame, window, |encoder, view, device, queue, size| {
+        now_playing.render(encoder, view, device, queue, size)?;
+        band_grid.render(encoder, view, device, queue, size, &band_edges)
+    })
+}
@@ -0,0 +1,75 @@
+//! Helpers shared by the unit tests
+
+use std::path::{Path, PathBuf};
+
+/// A per-process scratch path in the system temp directory (`arrvee_<pid>_<name>`), removed
+/// when dropped, so a failing assertion doesn't leave it behind
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    /// A file path; nothing is created until the test writes it
+    pub fn file(name: &str) -> Self {
+        let path = Self(std::env::temp_dir().join(format!("arrvee_{}_{}", std::process::id(), name)));
+        path.remove();
+        path
+    }
+
+    /// A fresh, empty directory
+    pub fn dir(name: &str) -> Self {
+        let path = Self::file(name);
+        std::fs::create_dir_all(&path.0).unwrap();
+        path
+    }
+
+    fn remove(&self) {
+        if self.0.is_dir() {
+            std::fs::remove_dir_all(&self.0).ok();
+        } else {
+            std::fs::remove_file(&self.0).ok();
+        }
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+impl std::ops::Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_paths_are_removed_on_drop() {
+        let file = TempPath::file("support.txt");
+        std::fs::write(&file, "scratch").unwrap();
+        let file_path = file.to_path_buf();
+        drop(file);
+        assert!(!file_path.exists());
+
+        let directory = TempPath::dir("support_dir");
+        std::fs::write(directory.join("inside.txt"), "scratch").unwrap();
+        let directory_path = directory.to_path_buf();
+        // Also when a test panics partway
+        assert!(std::panic::catch_unwind(move || {
+            let _directory = directory;
+            panic!("assertion failed");
+        }).is_err());
+        assert!(!directory_path.exists());
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+
+use crate::graphics::spectrum::BandEdge;
+use super::now_playing::OverlayPosition;
+use super::overlay::EguiOverlay;
+
+/// Colors of the band-edge lines, low to high (matching `band_grid_overlay` in the shader)
+pub const BAND_EDGE_COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(255, 77, 77),
+    egui::Color32::from_rgb(255, 204, 51),
+    egui::Color32::from_rgb(77, 255, 102),
+    egui::Color32::from_rgb(77, 178, 255),
+];
+
+/// Legend for the band grid: each edge's frequency, the bands it splits, the FFT bin it falls
+/// in and where it lands across the spectralizer, in its line's color. Listed rather than
+/// pinned to the lines, since the 3D projections bend them.
+#[allow(dead_code)]
+pub struct BandGridOverlay {
+    overlay: EguiOverlay,
+    position: OverlayPosition,
+    visible: bool,
+}
+
+#[allow(dead_code)]
+impl BandGridOverlay {
+    pub fn new(device: &Device, format: TextureFormat, scale_factor: f32, position: OverlayPosition) -> Self {
+        Self {
+            overlay: EguiOverlay::new(device, format, scale_factor),
+            position,
+            visible: false,
+        }
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Draw the legend for `edges` over `target`, which is `target_size` pixels
+    pub fn render(&mut self, encoder: &mut CommandEncoder, target: &TextureView, device: &Device, queue: &Queue,
+                  target_size: PhysicalSize<u32>, edges: &[BandEdge; 4]) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let (align, offset) = self.position.anchor();
+        self.overlay.render(encoder, target, device, queue, target_size, |ctx| {
+            egui::Area::new(egui::Id::new("band_grid"))
+                .anchor(align, offset)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha(170))
+                        .rounding(6.0)
+                        .inner_margin(egui::Margin::same(10.0))
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new("Band edges").strong().color(egui::Color32::WHITE));
+                            for (edge, color) in edges.iter().zip(BAND_EDGE_COLORS) {
+                                ui.label(egui::RichText::new(edge_label(edge)).monospace().color(color));
+                            }
+                        });
+                });
+        })
+    }
+}
+
+/// One legend row, e.g. ` 250 Hz  bass | mid         bin 5.8     x 0.32`
+fn edge_label(edge: &BandEdge) -> String {
+    let hz = if edge.hz >= 1000.0 { format!("{:.0} kHz", edge.hz / 1000.0) } else { format!("{:.0} Hz", edge.hz) };
+    let bands = format!("{} | {}", edge.below.name(), edge.above.name());
+    let bin = edge.bin.map_or_else(|| "no spectrum".to_string(), |bin| format!("bin {:.1}", bin));
+    let x = edge.x.map_or_else(|| "off screen".to_string(), |x| format!("x {:.2}", x));
+    format!("{:>7}  {:<18} {:<11} {}", hz, bands, bin, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioFrame;
+    use crate::graphics::spectrum::{SpectrumAxis, SpectrumBars};
+
+    #[test]
+    fn test_band_edges_land_on_the_spectralizer_axis() {
+        // A 512-bin spectrum at 44.1 kHz: log bars from 30 Hz to Nyquist, ~43 Hz per bin
+        let mut bars = SpectrumBars::new();
+        let frame = AudioFrame { spectrum: vec![0.5; 512], ..AudioFrame::default() };
+        bars.update(&frame, 64, 1);
+        assert_eq!(bars.axis(), SpectrumAxis::Log { low_hz: 30.0, high_hz: 22050.0 });
+
+        let edges = bars.band_edges();
+        assert_eq!(edges.map(|edge| edge.hz), [60.0, 250.0, 2000.0, 8000.0]);
+        let xs = edges.map(|edge| edge.x.unwrap());
+        assert!(xs.windows(2).all(|pair| pair[0] < pair[1]));
+        // 60 Hz is one octave up a ~9.5-octave axis
+        assert!((xs[0] - 0.105).abs() < 0.01, "60 Hz at x {}", xs[0]);
+        assert!((edges[1].bin.unwrap() - 5.8).abs() < 0.1);
+        assert_eq!(edge_label(&edges[1]), " 250 Hz  bass | mid         bin 5.8     x 0.32");
+
+        // Without a spectrum the bands sit side by side, the edges between them
+        let mut bars = SpectrumBars::new();
+        bars.update(&AudioFrame { spectrum: Vec::new(), ..AudioFrame::default() }, 64, 1);
+        assert_eq!(bars.axis(), SpectrumAxis::Bands);
+        assert_eq!(bars.band_edges().map(|edge| edge.x.unwrap()), [0.2, 0.4, 0.6, 0.8]);
+        assert_eq!(bars.band_edges()[0].bin, None);
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::Instant;
+
+/// Width of the `BatchProgress` bar, in characters
+pub const PROGRESS_BAR_WIDTH: usize = 30;
+
+/// Overall batch progress: each finished file's line prints above a bar of files done,
+/// failures and time left, redrawn on stderr (when it is a terminal)
+pub struct BatchProgress {
+    total: usize,
+    pub succeeded: usize,
+    /// Inputs that failed, in the order they finished
+    pub failures: Vec<String>,
+    pub started: Instant,
+    draw_bar: bool,
+}
+
+impl BatchProgress {
+    /// `show_bar` asks for the bar; it is only drawn when stderr is a terminal and there is
+    /// something to do
+    pub fn new(total: usize, show_bar: bool) -> Self {
+        use std::io::IsTerminal;
+        let progress = Self {
+            total,
+            succeeded: 0,
+            failures: Vec::new(),
+            started: Instant::now(),
+            draw_bar: show_bar && total > 0 && std::io::stderr().is_terminal(),
+        };
+        progress.redraw(None);
+        progress
+    }
+
+    pub fn succeeded(&mut self, line: &str) {
+        self.succeeded += 1;
+        self.redraw(Some(line));
+    }
+
+    pub fn failed(&mut self, line: &str, input: &str) {
+        self.failures.push(input.to_string());
+        self.redraw(Some(line));
+    }
+
+    /// Print `line` (if any) above the bar, then the bar itself
+    fn redraw(&self, line: Option<&str>) {
+        if !self.draw_bar {
+            if let Some(line) = line {
+                println!("{}", line);
+            }
+            return;
+        }
+        eprint!("\r\x1b[2K");
+        if let Some(line) = line {
+            println!("{}", line);
+        }
+        eprint!("{}", self.status_line(self.started.elapsed().as_secs_f32()));
+        if self.succeeded + self.failures.len() >= self.total {
+            eprintln!();
+        }
+    }
+
+    /// The bar and counts after `elapsed` seconds, with an estimate of the time left while
+    /// files are still running
+    fn status_line(&self, elapsed: f32) -> String {
+        let done = self.succeeded + self.failures.len();
+        let remaining = if done == 0 || done >= self.total {
+            String::new()
+        } else {
+            format!(", ~{} left", format_seconds(elapsed / done as f32 * (self.total - done) as f32))
+        };
+        format!("[{}] {}/{} files, {} failed, {}{}",
+                progress_bar(done, self.total, PROGRESS_BAR_WIDTH),
+                done, self.total, self.failures.len(), format_seconds(elapsed), remaining)
+    }
+}
+
+/// `width` characters, filled in proportion to `done` of `total` (empty when there is nothing
+/// to do, full once `done` reaches `total`)
+pub fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done.min(total) * width).checked_div(total).unwrap_or(0);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Seconds as `1m05s` (or `42s` under a minute)
+pub fn format_seconds(seconds: f32) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_format_as_minutes_and_seconds() {
+        assert_eq!(format_seconds(0.0), "0s");
+        assert_eq!(format_seconds(42.4), "42s");
+        assert_eq!(format_seconds(59.6), "1m00s");
+        assert_eq!(format_seconds(65.0), "1m05s");
+        assert_eq!(format_seconds(3600.0), "60m00s");
+        assert_eq!(format_seconds(-3.0), "0s", "a negative estimate saturates");
+    }
+
+    #[test]
+    fn test_bar_fills_with_the_files_done() {
+        let filled = |bar: &str| bar.chars().filter(|&c| c == '█').count();
+        for (done, total, expected) in [(0, 10, 0), (5, 10, 5), (1, 3, 3), (10, 10, 10), (12, 10, 10), (0, 0, 0), (3, 0, 0)] {
+            let bar = progress_bar(done, total, 10);
+            assert_eq!(bar.chars().count(), 10, "{}/{}", done, total);
+            assert_eq!(filled(&bar), expected, "{}/{}", done, total);
+        }
+
+        let mut progress = BatchProgress::new(4, false);
+        assert_eq!(progress.status_line(0.0), format!("[{}] 0/4 files, 0 failed, 0s", "░".repeat(PROGRESS_BAR_WIDTH)));
+        progress.succeeded("done");
+        progress.failed("failed", "b.mp3");
+        assert!(progress.status_line(20.0).ends_with("2/4 files, 1 failed, 20s, ~20s left"));
+        assert_eq!(progress.failures, ["b.mp3"]);
+
+        let empty = BatchProgress::new(0, true);
+        assert_eq!(empty.status_line(1.0), format!("[{}] 0/0 files, 0 failed, 1s", "░".repeat(PROGRESS_BAR_WIDTH)));
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+
+use crate::audio::level_meter::ChannelLevel;
+use super::now_playing::OverlayPosition;
+use super::overlay::EguiOverlay;
+
+/// Bottom of the meter scale; quieter levels read as an empty bar
+pub const METER_FLOOR_DBFS: f32 = -60.0;
+
+const BAR_SIZE: egui::Vec2 = egui::vec2(240.0, 12.0);
+const SAFE_COLOR: egui::Color32 = egui::Color32::from_rgb(70, 210, 90);
+const HOT_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 200, 50); // From -12 dBFS
+const OVER_COLOR: egui::Color32 = egui::Color32::from_rgb(235, 60, 50);  // From -3 dBFS
+const PEAK_COLOR: egui::Color32 = egui::Color32::WHITE;
+
+/// Classic L/R meter over the visuals: a bar per source channel filled to its RMS level
+/// (dBFS, `METER_FLOOR_DBFS` to 0), a tick at the held peak, the readouts, and a clip flag
+#[allow(dead_code)]
+pub struct LevelMeterOverlay {
+    overlay: EguiOverlay,
+    position: OverlayPosition,
+    visible: bool,
+}
+
+#[allow(dead_code)]
+impl LevelMeterOverlay {
+    pub fn new(device: &Device, format: TextureFormat, scale_factor: f32, position: OverlayPosition) -> Self {
+        Self {
+            overlay: EguiOverlay::new(device, format, scale_factor),
+            position,
+            visible: false,
+        }
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Draw meters for `channels` (from `AudioPlayback::levels`) over `target`, which is
+    /// `target_size` pixels
+    pub fn render(&mut self, encoder: &mut CommandEncoder, target: &TextureView, device: &Device, queue: &Queue,
+                  target_size: PhysicalSize<u32>, channels: &[ChannelLevel]) -> Result<()> {
+        if !self.visible || channels.is_empty() {
+            return Ok(());
+        }
+
+        let (align, offset) = self.position.anchor();
+        self.overlay.render(encoder, target, device, queue, target_size, |ctx| {
+            egui::Area::new(egui::Id::new("level_meter"))
+                .anchor(align, offset)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha(170))
+                        .rounding(6.0)
+                        .inner_margin(egui::Margin::same(10.0))
+                        .show(ui, |ui| draw_meters(ui, channels));
+                });
+        })
+    }
+}
+
+/// "M" for mono, "L"/"R" for stereo, else the channel's number
+pub fn channel_label(channels: usize, index: usize) -> String {
+    match (channels, index) {
+        (1, _) => "M".to_string(),
+        (2, 0) => "L".to_string(),
+        (2, 1) => "R".to_string(),
+        _ => (index + 1).to_string(),
+    }
+}
+
+/// Where `dbfs` falls along the meter, 0 at `METER_FLOOR_DBFS` to 1 at full scale
+pub fn meter_fraction(dbfs: f32) -> f32 {
+    ((dbfs - METER_FLOOR_DBFS) / -METER_FLOOR_DBFS).clamp(0.0, 1.0)
+}
+
+fn draw_meters(ui: &mut egui::Ui, channels: &[ChannelLevel]) {
+    ui.label(egui::RichText::new("Levels (dBFS)").strong().color(egui::Color32::WHITE));
+    for (index, level) in channels.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(channel_label(channels.len(), index)).monospace().color(egui::Color32::WHITE));
+            draw_bar(ui, level);
+            ui.label(egui::RichText::new(format!("{:>6.1} | pk {:>6.1}", level.rms_dbfs, level.held_peak_dbfs))
+                .monospace().color(egui::Color32::LIGHT_GRAY));
+            let clip = if level.clipped { OVER_COLOR } else { egui::Color32::from_gray(60) };
+            ui.label(egui::RichText::new("CLIP").monospace().strong().color(clip));
+        });
+    }
+}
+
+fn draw_bar(ui: &mut egui::Ui, level: &ChannelLevel) {
+    let (rect, _) = ui.allocate_exact_size(BAR_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(35));
+
+    // Fill in the three zones up to the RMS level, so the color shows how hot it runs
+    let x = |dbfs: f32| rect.left() + meter_fraction(dbfs) * rect.width();
+    let fill = x(level.rms_dbfs);
+    for (from, to, color) in [(METER_FLOOR_DBFS, -12.0, SAFE_COLOR), (-12.0, -3.0, HOT_COLOR), (-3.0, 0.0, OVER_COLOR)] {
+        let (left, right) = (x(from), x(to).min(fill));
+        if right > left {
+            painter.rect_filled(egui::Rect::from_x_y_ranges(left..=right, rect.y_range()), 0.0, color);
+        }
+    }
+
+    if level.held_peak_dbfs > METER_FLOOR_DBFS {
+        let peak = x(level.held_peak_dbfs).min(rect.right() - 1.0);
+        painter.vline(peak, rect.y_range(), egui::Stroke::new(2.0, PEAK_COLOR));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::level_meter::LevelMeter;
+
+    #[test]
+    fn test_meters_fill_to_the_level_and_mark_the_peak() {
+        assert_eq!(meter_fraction(0.0), 1.0);
+        assert_eq!(meter_fraction(-30.0), 0.5);
+        assert_eq!(meter_fraction(-96.0), 0.0);
+        assert_eq!(meter_fraction(3.0), 1.0);
+        assert_eq!((channel_label(1, 0), channel_label(2, 1), channel_label(6, 4)), ("M".into(), "R".into(), "5".into()));
+
+        // Left clipping at full scale, right at -6 dBFS (RMS and peak)
+        let mut meter = LevelMeter::new();
+        let block: Vec<f32> = (0..735).flat_map(|_| [1.0, 0.5]).collect();
+        meter.update(&block, 2, 1.0 / 60.0);
+        let [left, right] = [meter.channels()[0], meter.channels()[1]];
+        assert!(left.clipped && !right.clipped);
+
+        let context = egui::Context::default();
+        let output = context.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| draw_bar(ui, &right));
+        });
+        let shapes: Vec<&egui::Shape> = output.shapes.iter().map(|clipped| &clipped.shape).collect();
+        let fills: Vec<egui::Rect> = shapes.iter()
+            .filter_map(|shape| match shape {
+                egui::Shape::Rect(rect) if [SAFE_COLOR, HOT_COLOR, OVER_COLOR].contains(&rect.fill) => Some(rect.rect),
+                _ => None,
+            })
+            .collect();
+        // -6 dBFS fills the safe and hot zones, stopping 90% of the way along
+        assert_eq!(fills.len(), 2);
+        let background = shapes.iter()
+            .find_map(|shape| match shape {
+                egui::Shape::Rect(rect) if rect.fill == egui::Color32::from_gray(35) => Some(rect.rect),
+                _ => None,
+            })
+            .unwrap();
+        let filled = (fills[1].right() - background.left()) / background.width();
+        assert!((filled - 0.9).abs() < 0.01, "filled to {}", filled);
+        // One tick marks the held peak
+        let peak_ticks = shapes.iter().filter(|shape| matches!(shape, egui::Shape::LineSegment { .. })).count();
+        assert_eq!(peak_ticks, 1);
+    }
+}
@@ -1,18 +1,51 @@
+pub mod band_grid;
+pub mod batch_progress;
+pub mod level_meter;
+pub mod now_playing;
+pub mod overlay;
+pub mod refresh_throttle;
+pub mod smoothing_scope;
+
 use anyhow::Result;
 use egui_wgpu::Renderer;
 use egui_winit::State;
 use wgpu::{CommandEncoder, Device, Queue, TextureView};
-use winit::{event::WindowEvent, window::Window};
+use winit::{dpi::PhysicalSize, event::WindowEvent, window::Window};
 
+use crate::audio::solo_band::SoloBand;
+use crate::effects::psychedelic_manager::{PsychedelicManager, EFFECT_NAMES};
+use crate::graphics::engine::KALEIDOSCOPE_SEGMENT_RANGE;
 use crate::graphics::GraphicsEngine;
 
 pub struct UserInterface {
     context: egui::Context,
     state: State,
     renderer: Renderer,
+    /// Size of the target the UI was last drawn into
+    size: PhysicalSize<u32>,
+    controls: UiControls,
+}
+
+/// The values the controls window edits, kept between frames
+struct UiControls {
     show_controls: bool,
     volume: f32,
     selected_preset: usize,
+    /// Auto-blend pool checkboxes, in `EFFECT_NAMES` order
+    auto_blend_effects: [bool; EFFECT_NAMES.len()],
+    invert_response: bool,
+    transition_speed: f32,
+    beat_flash_enabled: bool,
+    beat_flash_intensity: f32,
+    solo_band: Option<SoloBand>,
+    kaleidoscope: KaleidoscopeControls,
+}
+
+/// Kaleidoscope symmetry controls, mirroring the engine's settings
+struct KaleidoscopeControls {
+    segments: u32, // 0 = audio-driven
+    seam_softness: f32,
+    bpm_sync: bool,
 }
 
 impl UserInterface {
@@ -38,9 +71,25 @@ impl UserInterface {
             context,
             state: egui_state,
             renderer,
-            show_controls: true,
-            volume: 0.1,
-            selected_preset: 0,
+            size: window.inner_size(),
+            controls: UiControls {
+                show_controls: true,
+                volume: 0.1,
+                selected_preset: 0,
+                auto_blend_effects: std::array::from_fn(|index| {
+                    graphics_engine.psychedelic_manager.is_effect_enabled(EFFECT_NAMES[index])
+                }),
+                invert_response: graphics_engine.psychedelic_manager.is_response_inverted(),
+                transition_speed: graphics_engine.psychedelic_manager.transition_speed_multiplier(),
+                beat_flash_enabled: graphics_engine.beat_flash().is_enabled(),
+                beat_flash_intensity: graphics_engine.beat_flash().intensity(),
+                solo_band: graphics_engine.solo_band(),
+                kaleidoscope: KaleidoscopeControls {
+                    segments: graphics_engine.kaleidoscope_segments().unwrap_or(0),
+                    seam_softness: graphics_engine.kaleidoscope_seam_softness(),
+                    bpm_sync: graphics_engine.kaleidoscope_bpm_sync(),
+                },
+            },
         }
     }
 
@@ -48,6 +97,8 @@ impl UserInterface {
         let _ = self.state.on_window_event(window, event);
     }
 
+    /// Draw the UI over `target`, which is `target_size` pixels (the surface texture's size,
+    /// not a cached window size: during a rapid resize the two can differ for a frame)
     pub fn render(
         &mut self,
         encoder: &mut CommandEncoder,
@@ -55,27 +106,36 @@ impl UserInterface {
         device: &Device,
         queue: &Queue,
         window: &Window,
+        target_size: PhysicalSize<u32>,
     ) -> Result<()> {
+        if target_size.width == 0 || target_size.height == 0 {
+            return Ok(()); // Minimized: nothing to draw into
+        }
+        if target_size != self.size {
+            log::debug!("UI target resized to {}x{}", target_size.width, target_size.height);
+            self.size = target_size;
+        }
+
         let raw_input = self.state.take_egui_input(window);
 
-        let show_controls = &mut self.show_controls;
-        let volume = &mut self.volume;
-        let selected_preset = &mut self.selected_preset;
+        let controls = &mut self.controls;
 
         let full_output = self.context.run(raw_input, |ctx| {
-            Self::ui_content(ctx, show_controls, volume, selected_preset);
+            Self::ui_content(ctx, controls);
         });
 
         self.state.handle_platform_output(window, full_output.platform_output);
 
         let tris = self.context.tessellate(full_output.shapes, full_output.pixels_per_point);
 
+        // New and updated textures must be uploaded before the buffers and pass that use them
         for (id, image_delta) in &full_output.textures_delta.set {
             self.renderer.update_texture(device, queue, *id, image_delta);
         }
 
+        // Rebuilt every frame so scissor rects are clipped to the real target size
         let screen_descriptor = egui_wgpu::ScreenDescriptor {
-            size_in_pixels: [device.limits().max_texture_dimension_2d; 2],
+            size_in_pixels: [self.size.width, self.size.height],
             pixels_per_point: full_output.pixels_per_point,
         };
 
@@ -100,6 +160,7 @@ impl UserInterface {
             self.renderer.render(&mut render_pass, &tris, &screen_descriptor);
         }
 
+        // Freed only after this frame's pass has recorded its last use of them
         for id in &full_output.textures_delta.free {
             self.renderer.free_texture(id);
         }
@@ -107,8 +168,8 @@ impl UserInterface {
         Ok(())
     }
 
-    fn ui_content(ctx: &egui::Context, show_controls: &mut bool, volume: &mut f32, selected_preset: &mut usize) {
-        if *show_controls {
+    fn ui_content(ctx: &egui::Context, controls: &mut UiControls) {
+        if controls.show_controls {
             egui::Window::new("Arrvee Controls")
                 .default_pos([10.0, 10.0])
                 .default_size([300.0, 200.0])
@@ -119,16 +180,66 @@ impl UserInterface {
 
                     ui.horizontal(|ui| {
                         ui.label("Volume:");
-                        ui.add(egui::Slider::new(volume, 0.0..=1.0));
+                        ui.add(egui::Slider::new(&mut controls.volume, 0.0..=1.0));
                     });
 
                     ui.separator();
 
                     ui.label("Presets:");
-                    ui.radio_value(selected_preset, 0, "Plasma Dreams");
-                    ui.radio_value(selected_preset, 1, "Spectrum Bars");
-                    ui.radio_value(selected_preset, 2, "Radial Waves");
-                    ui.radio_value(selected_preset, 3, "Beat Sync");
+                    ui.radio_value(&mut controls.selected_preset, 0, "Plasma Dreams");
+                    ui.radio_value(&mut controls.selected_preset, 1, "Spectrum Bars");
+                    ui.radio_value(&mut controls.selected_preset, 2, "Radial Waves");
+                    ui.radio_value(&mut controls.selected_preset, 3, "Beat Sync");
+
+                    ui.separator();
+
+                    ui.collapsing("Auto-blend effects", |ui| {
+                        for (enabled, name) in controls.auto_blend_effects.iter_mut().zip(EFFECT_NAMES) {
+                            ui.checkbox(enabled, name);
+                        }
+                        ui.checkbox(&mut controls.invert_response, "Invert response (quiet = busy)");
+                        ui.horizontal(|ui| {
+                            ui.label("Transition speed:");
+                            ui.add(egui::Slider::new(&mut controls.transition_speed, 0.05..=4.0).logarithmic(true).suffix("x"));
+                        });
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut controls.beat_flash_enabled, "Beat flash");
+                        ui.add_enabled(controls.beat_flash_enabled, egui::Slider::new(&mut controls.beat_flash_intensity, 0.0..=1.0));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Solo band:");
+                        egui::ComboBox::from_id_source("solo_band")
+                            .selected_text(controls.solo_band.map_or("Off (full mix)", SoloBand::name))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut controls.solo_band, None, "Off (full mix)");
+                                for band in SoloBand::ALL {
+                                    ui.selectable_value(&mut controls.solo_band, Some(band), band.name());
+                                }
+                            });
+                    });
+
+                    ui.collapsing("Kaleidoscope", |ui| {
+                        let kaleidoscope = &mut controls.kaleidoscope;
+                        let (min, max) = KALEIDOSCOPE_SEGMENT_RANGE;
+                        let mut fixed = kaleidoscope.segments > 0;
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut fixed, "Fixed segments");
+                            ui.add_enabled(fixed, egui::Slider::new(&mut kaleidoscope.segments, min..=max));
+                        });
+                        kaleidoscope.segments = match (fixed, kaleidoscope.segments) {
+                            (false, _) => 0,
+                            (true, 0) => 6,
+                            (true, segments) => segments,
+                        };
+                        ui.horizontal(|ui| {
+                            ui.label("Seam softness:");
+                            ui.add(egui::Slider::new(&mut kaleidoscope.seam_softness, 0.0..=1.0));
+                        });
+                        ui.checkbox(&mut kaleidoscope.bpm_sync, "Scale segments with tempo");
+                    });
 
                     ui.separator();
 
@@ -150,7 +261,7 @@ impl UserInterface {
 
                     ui.separator();
 
-                    ui.checkbox(show_controls, "Show Controls");
+                    ui.checkbox(&mut controls.show_controls, "Show Controls");
 
                     ui.separator();
 
@@ -159,15 +270,54 @@ impl UserInterface {
         }
 
         if ctx.input(|i| i.key_pressed(egui::Key::F1)) {
-            *show_controls = !*show_controls;
+            controls.show_controls = !controls.show_controls;
         }
     }
 
+    #[allow(dead_code)]
     pub fn volume(&self) -> f32 {
-        self.volume
+        self.controls.volume
     }
 
+    #[allow(dead_code)]
     pub fn selected_preset(&self) -> usize {
-        self.selected_preset
+        self.controls.selected_preset
+    }
+
+    /// Apply the auto-blend controls (effect pool, inverted response, transition speed) to the
+    /// effect manager. Returns true when the pool changed, for the caller to save it.
+    #[allow(dead_code)]
+    pub fn apply_effect_toggles(&self, manager: &mut PsychedelicManager) -> bool {
+        let mut pool_changed = false;
+        for (&enabled, name) in self.controls.auto_blend_effects.iter().zip(EFFECT_NAMES) {
+            pool_changed |= manager.is_effect_enabled(name) != enabled;
+            manager.set_effect_enabled(name, enabled);
+        }
+        manager.set_invert_response(self.controls.invert_response);
+        manager.set_transition_speed_multiplier(self.controls.transition_speed).ok(); // The slider only reaches positive speeds
+        pool_changed
+    }
+
+    /// Apply the beat flash toggle and slider to the engine
+    #[allow(dead_code)]
+    pub fn apply_beat_flash(&self, graphics_engine: &mut GraphicsEngine) {
+        graphics_engine.set_beat_flash_enabled(self.controls.beat_flash_enabled);
+        graphics_engine.set_beat_flash_intensity(self.controls.beat_flash_intensity);
+    }
+
+    /// Apply the solo band selection to the engine
+    #[allow(dead_code)]
+    pub fn apply_solo_band(&self, graphics_engine: &mut GraphicsEngine) {
+        graphics_engine.set_solo_band(self.controls.solo_band);
+    }
+
+    /// Apply the kaleidoscope segment, seam softness and tempo controls to the engine
+    #[allow(dead_code)]
+    pub fn apply_kaleidoscope(&self, graphics_engine: &mut GraphicsEngine) {
+        let kaleidoscope = &self.controls.kaleidoscope;
+        let segments = kaleidoscope.segments;
+        graphics_engine.set_kaleidoscope_segments((segments > 0).then_some(segments));
+        graphics_engine.set_kaleidoscope_seam_softness(kaleidoscope.seam_softness);
+        graphics_engine.set_kaleidoscope_bpm_sync(kaleidoscope.bpm_sync);
     }
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use std::str::FromStr;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+use winit::window::Window;
+
+use crate::audio::AudioFrame;
+use crate::graphics::GraphicsEngine;
+use super::overlay::EguiOverlay;
+
+/// Corner of the window the now-playing caption sits in
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OverlayPosition {
+    TopLeft,
+    TopRight,
+    #[default]
+    BottomLeft,
+    BottomRight,
+}
+
+impl FromStr for OverlayPosition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            other => Err(anyhow::anyhow!(
+                "Unknown overlay position '{}'. Use: top-left, top-right, bottom-left, bottom-right", other
+            )),
+        }
+    }
+}
+
+impl OverlayPosition {
+    pub(crate) fn anchor(self) -> (egui::Align2, egui::Vec2) {
+        const MARGIN: f32 = 24.0;
+        match self {
+            Self::TopLeft => (egui::Align2::LEFT_TOP, egui::vec2(MARGIN, MARGIN)),
+            Self::TopRight => (egui::Align2::RIGHT_TOP, egui::vec2(-MARGIN, MARGIN)),
+            Self::BottomLeft => (egui::Align2::LEFT_BOTTOM, egui::vec2(MARGIN, -MARGIN)),
+            Self::BottomRight => (egui::Align2::RIGHT_BOTTOM, egui::vec2(-MARGIN, -MARGIN)),
+        }
+    }
+}
+
+/// "Now playing" caption (e.g. "Artist — Title") drawn over the visuals for streams and
+/// recordings
+#[allow(dead_code)]
+pub struct NowPlayingOverlay {
+    overlay: EguiOverlay,
+    text: String,
+    position: OverlayPosition,
+    visible: bool,
+}
+
+#[allow(dead_code)]
+impl NowPlayingOverlay {
+    /// `format` is the surface format it draws into; `scale_factor` the window's (HiDPI) scale
+    pub fn new(device: &Device, format: TextureFormat, scale_factor: f32, text: String, position: OverlayPosition) -> Self {
+        Self {
+            overlay: EguiOverlay::new(device, format, scale_factor),
+            text,
+            position,
+            visible: true,
+        }
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_position(&mut self, position: OverlayPosition) {
+        self.position = position;
+    }
+
+    /// Render a frame of `graphics_engine` with the caption on top (while it's showing)
+    pub fn render_frame(&mut self, graphics_engine: &mut GraphicsEngine, audio_frame: &AudioFrame, window: &Window) -> Result<()> {
+        graphics_engine.render_with_overlay(audio_frame, window, |encoder, view, device, queue, size| {
+            self.render(encoder, view, device, queue, size)
+        })
+    }
+
+    /// Draw the caption over `target`, which is `target_size` pixels (see `UserInterface::render`)
+    pub fn render(&mut self, encoder: &mut CommandEncoder, target: &TextureView, device: &Device, queue: &Queue,
+                  target_size: PhysicalSize<u32>) -> Result<()> {
+        if !self.visible || self.text.is_empty() {
+            return Ok(());
+        }
+
+        let (align, offset) = self.position.anchor();
+        let text = &self.text;
+        self.overlay.render(encoder, target, device, queue, target_size, |ctx| {
+            egui::Area::new(egui::Id::new("now_playing"))
+                .anchor(align, offset)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha(150))
+                        .rounding(6.0)
+                        .inner_margin(egui::Margin::symmetric(14.0, 8.0))
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(text).size(22.0).color(egui::Color32::WHITE));
+                        });
+                });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_positions_parse() {
+        assert_eq!("top-right".parse::<OverlayPosition>().unwrap(), OverlayPosition::TopRight);
+        assert_eq!("Bottom_Left".parse::<OverlayPosition>().unwrap(), OverlayPosition::BottomLeft);
+        assert!("middle".parse::<OverlayPosition>().is_err());
+        assert_eq!(OverlayPosition::default(), OverlayPosition::BottomLeft);
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use egui_wgpu::Renderer;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+
+/// egui context and renderer for a display-only overlay (captions, scopes) drawn over the
+/// finished frame; takes no input, so it needs no winit event plumbing
+pub struct EguiOverlay {
+    context: egui::Context,
+    renderer: Renderer,
+    scale_factor: f32,
+}
+
+impl EguiOverlay {
+    /// `format` is the surface format it draws into; `scale_factor` the window's (HiDPI) scale
+    pub fn new(device: &Device, format: TextureFormat, scale_factor: f32) -> Self {
+        Self {
+            context: egui::Context::default(),
+            renderer: Renderer::new(device, format, None, 1),
+            scale_factor: scale_factor.max(0.5),
+        }
+    }
+
+    /// Run `build_ui` and draw the result over `target`, which is `target_size` pixels
+    pub fn render(&mut self, encoder: &mut CommandEncoder, target: &TextureView, device: &Device, queue: &Queue,
+                  target_size: PhysicalSize<u32>, build_ui: impl FnMut(&egui::Context)) -> Result<()> {
+        if target_size.width == 0 || target_size.height == 0 {
+            return Ok(());
+        }
+
+        let mut raw_input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(target_size.width as f32, target_size.height as f32) / self.scale_factor,
+            )),
+            ..Default::default()
+        };
+        raw_input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(self.scale_factor);
+
+        let full_output = self.context.run(raw_input, build_ui);
+
+        let tris = self.context.tessellate(full_output.shapes, full_output.pixels_per_point);
+        for (id, image_delta) in &full_output.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, image_delta);
+        }
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [target_size.width, target_size.height],
+            pixels_per_point: full_output.pixels_per_point,
+        };
+        self.renderer.update_buffers(device, queue, encoder, &tris, &screen_descriptor);
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("egui overlay pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            self.renderer.render(&mut render_pass, &tris, &screen_descriptor);
+        }
+
+        for id in &full_output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+
+        Ok(())
+    }
+}
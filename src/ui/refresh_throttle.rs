@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+/// Slowest refresh rate a `RefreshThrottle` runs at
+pub const MIN_REFRESH_HZ: f32 = 0.1;
+/// Fastest refresh rate a `RefreshThrottle` runs at
+pub const MAX_REFRESH_HZ: f32 = 60.0;
+
+/// clap value parser for `--debug-hz`: a finite rate above 0 (clamped to
+/// `MIN_REFRESH_HZ`-`MAX_REFRESH_HZ` by the throttle)
+pub fn parse_refresh_hz(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(hz) if hz.is_finite() && hz > 0.0 => Ok(hz),
+        _ => Err(format!("'{}' is not a refresh rate; expected a number of times per second above 0", value)),
+    }
+}
+
+/// Limits a redraw (e.g. the terminal debug overlays) to a fixed rate against the clock, so it
+/// doesn't follow the render frame rate. A late frame moves the schedule on rather than
+/// bursting to catch up.
+#[derive(Debug, Clone)]
+pub struct RefreshThrottle {
+    interval: Duration,
+    next_refresh: Option<Instant>,
+}
+
+impl RefreshThrottle {
+    /// `refresh_hz` is clamped to `MIN_REFRESH_HZ`-`MAX_REFRESH_HZ` (NaN counts as the minimum)
+    pub fn new(refresh_hz: f32) -> Self {
+        let refresh_hz = if refresh_hz.is_nan() { MIN_REFRESH_HZ } else { refresh_hz.clamp(MIN_REFRESH_HZ, MAX_REFRESH_HZ) };
+        Self {
+            interval: Duration::from_secs_f32(1.0 / refresh_hz),
+            next_refresh: None,
+        }
+    }
+
+    /// Whether to redraw at `now`; true on the first call, then once per interval
+    pub fn ready(&mut self, now: Instant) -> bool {
+        match self.next_refresh {
+            Some(next) if now < next => return false,
+            Some(next) => {
+                let next = next + self.interval;
+                self.next_refresh = Some(if next > now { next } else { now + self.interval });
+            }
+            None => self.next_refresh = Some(now + self.interval),
+        }
+        true
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_keeps_its_rate_and_rejects_bad_rates() {
+        let mut throttle = RefreshThrottle::new(2.0);
+        let start = Instant::now();
+        let at = |seconds: f32| start + Duration::from_secs_f32(seconds);
+        assert!(throttle.ready(at(0.0)));
+        assert!(!throttle.ready(at(0.3)));
+        assert!(throttle.ready(at(0.55)));
+        assert!(!throttle.ready(at(0.9)), "the schedule holds its phase");
+        assert!(throttle.ready(at(1.0)));
+        // A long stall refreshes once, then carries on a full interval later
+        assert!(throttle.ready(at(5.2)));
+        assert!(!throttle.ready(at(5.5)));
+        assert!(throttle.ready(at(5.75)));
+
+        assert_eq!(RefreshThrottle::new(1000.0).interval(), Duration::from_secs_f32(1.0 / MAX_REFRESH_HZ));
+        assert_eq!(RefreshThrottle::new(f32::NAN).interval(), Duration::from_secs_f32(1.0 / MIN_REFRESH_HZ));
+
+        assert_eq!(parse_refresh_hz("2.5"), Ok(2.5));
+        for bad in ["nan", "inf", "0", "-1", "fast", ""] {
+            assert!(parse_refresh_hz(bad).is_err(), "{} was accepted", bad);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+
+use crate::graphics::smoothing_scope::SmoothingScope;
+use super::now_playing::OverlayPosition;
+use super::overlay::EguiOverlay;
+
+const PLOT_SIZE: egui::Vec2 = egui::vec2(360.0, 120.0);
+const RAW_COLOR: egui::Color32 = egui::Color32::from_gray(150);
+const SMOOTHED_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 220, 255);
+
+/// Line plot of a `SmoothingScope`: the raw feature in grey with the smoothed one over it,
+/// newest at the right, so the envelope's lag behind each hit is visible while tuning
+#[allow(dead_code)]
+pub struct SmoothingScopeOverlay {
+    overlay: EguiOverlay,
+    position: OverlayPosition,
+    detail: String,
+    visible: bool,
+}
+
+#[allow(dead_code)]
+impl SmoothingScopeOverlay {
+    pub fn new(device: &Device, format: TextureFormat, scale_factor: f32, position: OverlayPosition) -> Self {
+        Self {
+            overlay: EguiOverlay::new(device, format, scale_factor),
+            position,
+            detail: String::new(),
+            visible: false,
+        }
+    }
+
+    /// Line under the plot's title, e.g. the traced feature's attack/release times
+    pub fn set_detail(&mut self, detail: String) {
+        self.detail = detail;
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Draw `scope` over `target`, which is `target_size` pixels
+    pub fn render(&mut self, encoder: &mut CommandEncoder, target: &TextureView, device: &Device, queue: &Queue,
+                  target_size: PhysicalSize<u32>, scope: &SmoothingScope) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let (align, offset) = self.position.anchor();
+        let detail = &self.detail;
+        self.overlay.render(encoder, target, device, queue, target_size, |ctx| {
+            egui::Area::new(egui::Id::new("smoothing_scope"))
+                .anchor(align, offset)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha(170))
+                        .rounding(6.0)
+                        .inner_margin(egui::Margin::same(10.0))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(format!("Smoothing scope: {}", scope.feature().name()))
+                                    .strong().color(egui::Color32::WHITE));
+                                ui.label(egui::RichText::new("raw").color(RAW_COLOR));
+                                ui.label(egui::RichText::new("smoothed").color(SMOOTHED_COLOR));
+                            });
+                            if !detail.is_empty() {
+                                ui.label(egui::RichText::new(detail).small().color(egui::Color32::LIGHT_GRAY));
+                            }
+                            draw_traces(ui, scope);
+                        });
+                });
+        })
+    }
+}
+
+fn draw_traces(ui: &mut egui::Ui, scope: &SmoothingScope) {
+    let (rect, _) = ui.allocate_exact_size(PLOT_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::from_gray(70)));
+    painter.hline(rect.x_range(), rect.center().y, egui::Stroke::new(1.0, egui::Color32::from_gray(45)));
+
+    // Fill from the right so the newest frame always sits at the edge
+    let step = rect.width() / (scope.capacity() - 1) as f32;
+    let start = scope.capacity() - scope.len();
+    let point = |index: usize, value: f32| {
+        egui::pos2(rect.left() + (start + index) as f32 * step, rect.bottom() - value.clamp(0.0, 1.0) * rect.height())
+    };
+    let raw: Vec<_> = scope.samples().enumerate().map(|(index, (raw, _))| point(index, raw)).collect();
+    let smoothed: Vec<_> = scope.samples().enumerate().map(|(index, (_, smoothed))| point(index, smoothed)).collect();
+    painter.add(egui::Shape::line(raw, egui::Stroke::new(1.0, RAW_COLOR)));
+    painter.add(egui::Shape::line(smoothed, egui::Stroke::new(2.0, SMOOTHED_COLOR)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioFrame;
+    use crate::graphics::smoothing_scope::{ScopeFeature, SCOPE_FRAMES};
+
+    #[test]
+    fn test_partial_scope_draws_both_traces_at_the_right_edge() {
+        let mut scope = SmoothingScope::new(ScopeFeature::Volume, SCOPE_FRAMES);
+        for volume in [0.0, 1.0, 0.5] {
+            let frame = AudioFrame { volume, ..AudioFrame::default() };
+            scope.push(&frame, &frame);
+        }
+
+        let context = egui::Context::default();
+        let output = context.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| draw_traces(ui, &scope));
+        });
+        let lines: Vec<_> = output.shapes.iter()
+            .filter_map(|clipped| match &clipped.shape {
+                egui::Shape::Path(path) if !path.closed => Some(path.points.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|points| points.len() == 3));
+        // Newest sample last, drawn at the plot's right edge
+        assert!(lines[0][2].x > lines[0][0].x);
+    }
+}
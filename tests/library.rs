@@ -0,0 +1,20 @@
+//! Uses Arrvee the way another crate would, through the `arrvee` library only
+
+use arrvee::audio::test_signal::{TestSignal, TestSignalConfig};
+use arrvee::audio::{analyze_buffer, ArvFormat};
+use arrvee::ArrveeError;
+
+#[test]
+fn test_analysis_api_is_usable_from_another_crate() {
+    let config = TestSignalConfig { signal: TestSignal::ClickTrack, duration_seconds: 4.0, ..Default::default() };
+    let frames = analyze_buffer(&config.generate(), config.sample_rate, 512);
+
+    assert_eq!(frames.len(), (4.0 * 44100.0) as usize / 512);
+    assert!(frames.iter().any(|frame| frame.beat_detected), "a 120 BPM click track should register beats");
+    assert!(frames.iter().all(|frame| (0.0..=1.0).contains(&frame.volume)));
+
+    match ArvFormat::load_arv("does-not-exist.arv") {
+        Err(ArrveeError::Io { path, .. }) => assert!(path.ends_with("does-not-exist.arv")),
+        other => panic!("expected an Io error, got {:?}", other.map(|_| ())),
+    }
+}